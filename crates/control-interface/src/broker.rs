@@ -139,6 +139,31 @@ pub mod v1 {
                 prefix(topic_prefix, lattice, CTL_API_VERSION_1)
             )
         }
+
+        pub fn drain_host(topic_prefix: &Option<String>, lattice: &str, host_id: &str) -> String {
+            format!(
+                "{}.host.drain.{host_id}",
+                prefix(topic_prefix, lattice, CTL_API_VERSION_1)
+            )
+        }
+
+        pub fn prune_cache(topic_prefix: &Option<String>, lattice: &str, host_id: &str) -> String {
+            format!(
+                "{}.cache.prune.{host_id}",
+                prefix(topic_prefix, lattice, CTL_API_VERSION_1)
+            )
+        }
+
+        pub fn pull_artifacts(
+            topic_prefix: &Option<String>,
+            lattice: &str,
+            host_id: &str,
+        ) -> String {
+            format!(
+                "{}.cache.pull.{host_id}",
+                prefix(topic_prefix, lattice, CTL_API_VERSION_1)
+            )
+        }
     }
 
     pub mod queries {
@@ -171,6 +196,17 @@ pub mod v1 {
             )
         }
 
+        pub fn cached_artifacts(
+            topic_prefix: &Option<String>,
+            lattice: &str,
+            host_id: &str,
+        ) -> String {
+            format!(
+                "{}.cache.get.{host_id}",
+                prefix(topic_prefix, lattice, CTL_API_VERSION_1)
+            )
+        }
+
         pub fn hosts(topic_prefix: &Option<String>, lattice: &str) -> String {
             format!(
                 "{}.host.ping",
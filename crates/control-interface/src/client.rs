@@ -13,12 +13,13 @@ use tokio::sync::mpsc::Receiver;
 use tracing::{debug, error, instrument, trace};
 
 use crate::types::ctl::{
-    CtlResponse, ScaleComponentCommand, StartProviderCommand, StopHostCommand, StopProviderCommand,
-    UpdateComponentCommand,
+    CtlResponse, DrainHostCommand, PruneCacheCommand, PullArtifactsCommand, ScaleComponentCommand,
+    StartProviderCommand, StopHostCommand, StopProviderCommand, UpdateComponentCommand,
 };
+use crate::types::event::WasmbusEvent;
 use crate::types::host::{Host, HostInventory, HostLabel};
 use crate::types::link::Link;
-use crate::types::registry::RegistryCredential;
+use crate::types::registry::{CachedArtifact, RegistryCredential};
 use crate::types::rpc::{
     ComponentAuctionAck, ComponentAuctionRequest, DeleteInterfaceLinkDefinitionRequest,
     ProviderAuctionAck, ProviderAuctionRequest,
@@ -742,6 +743,124 @@ impl Client {
         }
     }
 
+    /// Issue a command to a host instructing that it drain: stop accepting new invocations, wait
+    /// for in-flight invocations to finish and stop its providers. The host keeps running
+    /// afterwards and can be stopped with [`Client::stop_host`].
+    ///
+    /// The target host will acknowledge receipt of the command before it starts draining. Progress
+    /// is reported with the "host drain started", "host drain progress" and "host drained" events.
+    ///
+    /// # Arguments
+    ///
+    /// * `host_id` - ID of the host to drain
+    /// * `timeout_ms` - (optional) amount of time to wait for in-flight invocations to finish
+    ///
+    #[instrument(level = "debug", skip_all)]
+    pub async fn drain_host(
+        &self,
+        host_id: &str,
+        timeout_ms: Option<u64>,
+    ) -> Result<CtlResponse<()>> {
+        let host_id = IdentifierKind::is_host_id(host_id)?;
+        let subject =
+            broker::v1::commands::drain_host(&self.topic_prefix, &self.lattice, host_id.as_str());
+        debug!("drain_host:request {}", &subject);
+        let bytes = json_serialize(DrainHostCommand {
+            host_id,
+            timeout: timeout_ms,
+        })?;
+
+        match self.request_timeout(subject, bytes, self.timeout).await {
+            Ok(msg) => Ok(json_deserialize(&msg.payload)?),
+            Err(e) => Err(format!("Did not receive drain host acknowledgement: {e}").into()),
+        }
+    }
+
+    /// Retrieves the artifacts in the OCI artifact cache of the given host, most recently used
+    /// first
+    #[instrument(level = "debug", skip_all)]
+    pub async fn get_cached_artifacts(
+        &self,
+        host_id: &str,
+    ) -> Result<CtlResponse<Vec<CachedArtifact>>> {
+        let subject = broker::v1::queries::cached_artifacts(
+            &self.topic_prefix,
+            &self.lattice,
+            IdentifierKind::is_host_id(host_id)?.as_str(),
+        );
+        debug!("get_cached_artifacts:request {}", &subject);
+        match self.request_timeout(subject, vec![], self.timeout).await {
+            Ok(msg) => Ok(json_deserialize(&msg.payload)?),
+            Err(e) => Err(format!("Did not receive cached artifacts from target host: {e}").into()),
+        }
+    }
+
+    /// Issue a command to a host instructing that it remove artifacts from its OCI artifact
+    /// cache. Unless `all` is set, artifacts of running components and providers are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `host_id` - ID of the host whose cache to prune
+    /// * `all` - whether to also remove artifacts of running components and providers
+    ///
+    /// Returns the removed artifacts
+    #[instrument(level = "debug", skip_all)]
+    pub async fn prune_cache(
+        &self,
+        host_id: &str,
+        all: bool,
+    ) -> Result<CtlResponse<Vec<CachedArtifact>>> {
+        let host_id = IdentifierKind::is_host_id(host_id)?;
+        let subject =
+            broker::v1::commands::prune_cache(&self.topic_prefix, &self.lattice, host_id.as_str());
+        debug!("prune_cache:request {}", &subject);
+        let bytes = json_serialize(PruneCacheCommand { host_id, all })?;
+
+        match self.request_timeout(subject, bytes, self.timeout).await {
+            Ok(msg) => Ok(json_deserialize(&msg.payload)?),
+            Err(e) => Err(format!("Did not receive prune cache acknowledgement: {e}").into()),
+        }
+    }
+
+    /// Issue a command to a host instructing that it pull OCI artifacts into its cache ahead of
+    /// their use. Unlike most commands, the host responds once the artifacts have been pulled.
+    ///
+    /// # Arguments
+    ///
+    /// * `host_id` - ID of the host to pull the artifacts to
+    /// * `references` - OCI references of the artifacts to pull
+    /// * `timeout_ms` - (optional) amount of time to wait for the pull to complete, defaults to
+    ///   the timeout of the client
+    ///
+    /// Returns the cached artifacts
+    #[instrument(level = "debug", skip_all)]
+    pub async fn pull_artifacts(
+        &self,
+        host_id: &str,
+        references: Vec<String>,
+        timeout_ms: Option<u64>,
+    ) -> Result<CtlResponse<Vec<CachedArtifact>>> {
+        let host_id = IdentifierKind::is_host_id(host_id)?;
+        let subject = broker::v1::commands::pull_artifacts(
+            &self.topic_prefix,
+            &self.lattice,
+            host_id.as_str(),
+        );
+        debug!("pull_artifacts:request {}", &subject);
+        let bytes = json_serialize(
+            PullArtifactsCommand::builder()
+                .host_id(&host_id)
+                .references(references)
+                .build()?,
+        )?;
+        let timeout = timeout_ms.map_or(self.timeout, Duration::from_millis);
+
+        match self.request_timeout(subject, bytes, timeout).await {
+            Ok(msg) => Ok(json_deserialize(&msg.payload)?),
+            Err(e) => Err(format!("Did not receive pull artifacts response: {e}").into()),
+        }
+    }
+
     /// Publish a message and wait for a response
     async fn publish_and_wait<D: DeserializeOwned>(
         &self,
@@ -822,6 +941,54 @@ impl Client {
         });
         Ok(receiver)
     }
+
+    /// Returns the receiver end of a channel that subscribes to the lattice event stream and
+    /// decodes the events into [`WasmbusEvent`]s.
+    ///
+    /// This behaves like [`Client::events_receiver`], except that events which are not known
+    /// [`WasmbusEvent`]s are logged and skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use wasmcloud_control_interface::{Client, ClientBuilder, WasmbusEvent};
+    /// async {
+    ///   let nc = async_nats::connect("127.0.0.1:4222").await.unwrap();
+    ///   let client = ClientBuilder::new(nc).build();
+    ///   let mut receiver = client.typed_events_receiver(vec!["component_scaled".to_string()]).await.unwrap();
+    ///   while let Some(WasmbusEvent::ComponentScaled(evt)) = receiver.recv().await {
+    ///       println!("Component {} scaled to {}", evt.component_id(), evt.max_instances());
+    ///   }
+    /// };
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `event_types` - List of types of events to listen for
+    ///
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn typed_events_receiver(
+        &self,
+        event_types: Vec<String>,
+    ) -> Result<Receiver<WasmbusEvent>> {
+        let mut events = self.events_receiver(event_types).await?;
+        let (sender, receiver) = tokio::sync::mpsc::channel(5000);
+        tokio::spawn(async move {
+            while let Some(evt) = events.recv().await {
+                let evt = match WasmbusEvent::try_from(&evt) {
+                    Ok(evt) => evt,
+                    Err(err) => {
+                        error!(?err, "failed to decode wasmbus event");
+                        continue;
+                    }
+                };
+                let Ok(()) = sender.send(evt).await else {
+                    break;
+                };
+            }
+        });
+        Ok(receiver)
+    }
 }
 
 /// Collect `T` values until timeout has elapsed
@@ -28,6 +28,7 @@ pub use client::{Client, ClientBuilder};
 mod types;
 pub use types::component::*;
 pub use types::ctl::*;
+pub use types::event::*;
 pub use types::host::*;
 pub use types::link::*;
 pub use types::provider::*;
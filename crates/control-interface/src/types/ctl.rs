@@ -442,6 +442,205 @@ impl StopHostCommandBuilder {
     }
 }
 
+/// A command sent to request that the given host drain, i.e. stop accepting invocations, wait for
+/// in-flight invocations to finish and stop its providers
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct DrainHostCommand {
+    /// The ID of the target host
+    #[serde(default)]
+    pub(crate) host_id: String,
+    /// An optional timeout in milliseconds to wait for in-flight invocations to finish
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) timeout: Option<u64>,
+}
+
+impl DrainHostCommand {
+    #[must_use]
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    #[must_use]
+    pub fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
+
+    #[must_use]
+    pub fn builder() -> DrainHostCommandBuilder {
+        DrainHostCommandBuilder::default()
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct DrainHostCommandBuilder {
+    host_id: Option<String>,
+    timeout: Option<u64>,
+}
+
+impl DrainHostCommandBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn host_id(mut self, v: &str) -> Self {
+        self.host_id = Some(v.into());
+        self
+    }
+
+    #[must_use]
+    pub fn timeout(mut self, v: u64) -> Self {
+        self.timeout = Some(v);
+        self
+    }
+
+    pub fn build(self) -> Result<DrainHostCommand> {
+        Ok(DrainHostCommand {
+            host_id: self
+                .host_id
+                .ok_or_else(|| "host id is required for draining host".to_string())?,
+            timeout: self.timeout,
+        })
+    }
+}
+
+/// A command sent to request that the given host remove artifacts from its OCI artifact cache
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct PruneCacheCommand {
+    /// The ID of the target host
+    #[serde(default)]
+    pub(crate) host_id: String,
+    /// Whether to also remove artifacts of running components and providers
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) all: bool,
+}
+
+impl PruneCacheCommand {
+    #[must_use]
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    #[must_use]
+    pub fn all(&self) -> bool {
+        self.all
+    }
+
+    #[must_use]
+    pub fn builder() -> PruneCacheCommandBuilder {
+        PruneCacheCommandBuilder::default()
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct PruneCacheCommandBuilder {
+    host_id: Option<String>,
+    all: bool,
+}
+
+impl PruneCacheCommandBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn host_id(mut self, v: &str) -> Self {
+        self.host_id = Some(v.into());
+        self
+    }
+
+    #[must_use]
+    pub fn all(mut self, v: bool) -> Self {
+        self.all = v;
+        self
+    }
+
+    pub fn build(self) -> Result<PruneCacheCommand> {
+        Ok(PruneCacheCommand {
+            host_id: self
+                .host_id
+                .ok_or_else(|| "host id is required for pruning cache".to_string())?,
+            all: self.all,
+        })
+    }
+}
+
+/// A command sent to request that the given host pull OCI artifacts into its cache ahead of their
+/// use
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct PullArtifactsCommand {
+    /// The ID of the target host
+    #[serde(default)]
+    pub(crate) host_id: String,
+    /// The OCI references of the artifacts to pull
+    #[serde(default)]
+    pub(crate) references: Vec<String>,
+}
+
+impl PullArtifactsCommand {
+    #[must_use]
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    #[must_use]
+    pub fn references(&self) -> &[String] {
+        &self.references
+    }
+
+    #[must_use]
+    pub fn builder() -> PullArtifactsCommandBuilder {
+        PullArtifactsCommandBuilder::default()
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct PullArtifactsCommandBuilder {
+    host_id: Option<String>,
+    references: Vec<String>,
+}
+
+impl PullArtifactsCommandBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn host_id(mut self, v: &str) -> Self {
+        self.host_id = Some(v.into());
+        self
+    }
+
+    #[must_use]
+    pub fn references(mut self, v: Vec<String>) -> Self {
+        self.references = v;
+        self
+    }
+
+    pub fn build(self) -> Result<PullArtifactsCommand> {
+        if self.references.is_empty() {
+            return Err("at least one reference is required for pulling artifacts"
+                .to_string()
+                .into());
+        }
+        Ok(PullArtifactsCommand {
+            host_id: self
+                .host_id
+                .ok_or_else(|| "host id is required for pulling artifacts".to_string())?,
+            references: self.references,
+        })
+    }
+}
+
 /// A request to stop the given provider on the indicated host
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[non_exhaustive]
@@ -619,8 +818,8 @@ mod tests {
     use std::collections::BTreeMap;
 
     use super::{
-        ScaleComponentCommand, StartProviderCommand, StopHostCommand, StopProviderCommand,
-        UpdateComponentCommand,
+        DrainHostCommand, PruneCacheCommand, PullArtifactsCommand, ScaleComponentCommand,
+        StartProviderCommand, StopHostCommand, StopProviderCommand, UpdateComponentCommand,
     };
 
     #[test]
@@ -685,6 +884,55 @@ mod tests {
         )
     }
 
+    #[test]
+    fn drain_host_command_builder() {
+        assert_eq!(
+            DrainHostCommand {
+                host_id: "host_id".into(),
+                timeout: Some(1),
+            },
+            DrainHostCommand::builder()
+                .host_id("host_id")
+                .timeout(1)
+                .build()
+                .unwrap()
+        );
+        assert!(DrainHostCommand::builder().build().is_err());
+    }
+
+    #[test]
+    fn cache_command_builders() {
+        assert_eq!(
+            PruneCacheCommand {
+                host_id: "host_id".into(),
+                all: true,
+            },
+            PruneCacheCommand::builder()
+                .host_id("host_id")
+                .all(true)
+                .build()
+                .unwrap()
+        );
+        assert!(PruneCacheCommand::builder().build().is_err());
+        assert_eq!(
+            PullArtifactsCommand {
+                host_id: "host_id".into(),
+                references: vec!["ghcr.io/wasmcloud/components/http-hello-world-rust:0.1.0".into()],
+            },
+            PullArtifactsCommand::builder()
+                .host_id("host_id")
+                .references(vec![
+                    "ghcr.io/wasmcloud/components/http-hello-world-rust:0.1.0".into()
+                ])
+                .build()
+                .unwrap()
+        );
+        assert!(PullArtifactsCommand::builder()
+            .host_id("host_id")
+            .build()
+            .is_err());
+    }
+
     #[test]
     fn stop_provider_command_builder() {
         assert_eq!(
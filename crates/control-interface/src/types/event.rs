@@ -0,0 +1,1227 @@
+//! Data types of the events published by wasmCloud hosts on a lattice
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{bail, Context as _};
+use cloudevents::{AttributesReader as _, Data, Event};
+use serde::{Deserialize, Serialize};
+
+use crate::types::host::HostInventory;
+use crate::types::link::Link;
+
+/// Version of the [`WasmbusEvent`] payloads published by hosts
+pub const WASMBUS_EVENT_VERSION: &str = "v1";
+
+/// Name of the CloudEvents extension attribute carrying the [`WASMBUS_EVENT_VERSION`]
+pub const WASMBUS_EVENT_VERSION_EXTENSION: &str = "wasmbuseventversion";
+
+/// Prefix of the CloudEvents type of all [`WasmbusEvent`]s
+pub const WASMBUS_EVENT_TYPE_PREFIX: &str = "com.wasmcloud.lattice.";
+
+/// An event published by a wasmCloud host.
+///
+/// The serialized form is adjacently tagged, e.g. `{"type": "config_set", "data": {...}}`, where
+/// `type` is the [name](WasmbusEvent::name) of the event and `data` is the payload published on
+/// the lattice.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum WasmbusEvent {
+    /// A component was scaled
+    ComponentScaled(ComponentScaled),
+    /// A component failed to scale
+    ComponentScaleFailed(ComponentScaleFailed),
+    /// A provider was started
+    ProviderStarted(ProviderStarted),
+    /// A provider failed to start
+    ProviderStartFailed(ProviderStartFailed),
+    /// A provider was stopped
+    ProviderStopped(ProviderStopped),
+    /// A link was put
+    LinkdefSet(Link),
+    /// A link failed to be put
+    LinkdefSetFailed(LinkdefSetFailed),
+    /// A link was deleted
+    LinkdefDeleted(LinkdefDeleted),
+    /// A named config was put
+    ConfigSet(ConfigChanged),
+    /// A named config was deleted
+    ConfigDeleted(ConfigChanged),
+    /// A provider passed its health check after previously failing it
+    HealthCheckPassed(HealthCheck),
+    /// A provider failed its health check after previously passing it
+    HealthCheckFailed(HealthCheck),
+    /// A provider reported the same health check result as before
+    HealthCheckStatus(HealthCheck),
+    /// A host started
+    HostStarted(HostStarted),
+    /// A host stopped
+    HostStopped(HostStopped),
+    /// A host started draining
+    HostDrainStarted(HostDrainStatus),
+    /// The number of in-flight invocations of a draining host changed
+    HostDrainProgress(HostDrainStatus),
+    /// A host finished draining
+    HostDrained(HostDrainStatus),
+    /// A host reported its inventory
+    HostHeartbeat(HostInventory),
+    /// The labels of a host changed
+    LabelsChanged(LabelsChanged),
+}
+
+impl WasmbusEvent {
+    /// Returns the name of the event, e.g. `component_scaled`, which is the suffix of the
+    /// CloudEvents type and NATS subject the event is published on
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ComponentScaled(..) => "component_scaled",
+            Self::ComponentScaleFailed(..) => "component_scale_failed",
+            Self::ProviderStarted(..) => "provider_started",
+            Self::ProviderStartFailed(..) => "provider_start_failed",
+            Self::ProviderStopped(..) => "provider_stopped",
+            Self::LinkdefSet(..) => "linkdef_set",
+            Self::LinkdefSetFailed(..) => "linkdef_set_failed",
+            Self::LinkdefDeleted(..) => "linkdef_deleted",
+            Self::ConfigSet(..) => "config_set",
+            Self::ConfigDeleted(..) => "config_deleted",
+            Self::HealthCheckPassed(..) => "health_check_passed",
+            Self::HealthCheckFailed(..) => "health_check_failed",
+            Self::HealthCheckStatus(..) => "health_check_status",
+            Self::HostStarted(..) => "host_started",
+            Self::HostStopped(..) => "host_stopped",
+            Self::HostDrainStarted(..) => "host_drain_started",
+            Self::HostDrainProgress(..) => "host_drain_progress",
+            Self::HostDrained(..) => "host_drained",
+            Self::HostHeartbeat(..) => "host_heartbeat",
+            Self::LabelsChanged(..) => "labels_changed",
+        }
+    }
+
+    /// Returns the CloudEvents type of the event, e.g. `com.wasmcloud.lattice.component_scaled`
+    #[must_use]
+    pub fn event_type(&self) -> String {
+        format!("{WASMBUS_EVENT_TYPE_PREFIX}{}", self.name())
+    }
+
+    /// Returns the payload of the event
+    ///
+    /// # Errors
+    ///
+    /// Fails if the payload cannot be serialized
+    pub fn data(&self) -> serde_json::Result<serde_json::Value> {
+        Ok(match serde_json::to_value(self)? {
+            serde_json::Value::Object(mut event) => {
+                event.remove("data").unwrap_or(serde_json::Value::Null)
+            }
+            _ => serde_json::Value::Null,
+        })
+    }
+
+    /// Parses an event from its [name](WasmbusEvent::name) and payload
+    ///
+    /// # Errors
+    ///
+    /// Fails if the event is unknown or the payload does not match the event
+    pub fn from_parts(name: &str, data: serde_json::Value) -> serde_json::Result<Self> {
+        serde_json::from_value(serde_json::json!({
+            "type": name,
+            "data": data,
+        }))
+    }
+}
+
+impl TryFrom<&Event> for WasmbusEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(event: &Event) -> anyhow::Result<Self> {
+        let Some(name) = event.ty().strip_prefix(WASMBUS_EVENT_TYPE_PREFIX) else {
+            bail!("`{}` is not a wasmbus event type", event.ty());
+        };
+        if let Some(version) = event.extension(WASMBUS_EVENT_VERSION_EXTENSION) {
+            let version = version.to_string();
+            if version != WASMBUS_EVENT_VERSION {
+                bail!("unsupported wasmbus event version `{version}`");
+            }
+        }
+        let data = match event.data() {
+            Some(Data::Json(data)) => data.clone(),
+            Some(Data::String(data)) => {
+                serde_json::from_str(data).context("failed to parse event data")?
+            }
+            Some(Data::Binary(data)) => {
+                serde_json::from_slice(data).context("failed to parse event data")?
+            }
+            None => serde_json::Value::Null,
+        };
+        Self::from_parts(name, data).with_context(|| format!("failed to decode `{name}` event"))
+    }
+}
+
+/// Summary of the claims of a component included in events
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct ComponentClaims {
+    /// Issuer of the claims
+    pub(crate) issuer: String,
+    /// Call alias of the component
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) call_alias: Option<String>,
+    /// Tags of the component
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) tags: Option<Vec<String>>,
+    /// Name of the component
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) name: Option<String>,
+    /// Version of the component
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) version: Option<String>,
+    /// Revision of the component
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) revision: Option<i32>,
+    /// Human-readable time before which the claims are not valid
+    pub(crate) not_before_human: String,
+    /// Human-readable time after which the claims are not valid
+    pub(crate) expires_human: String,
+}
+
+impl ComponentClaims {
+    /// Create [`ComponentClaims`] from the issuer and validity of the claims
+    #[must_use]
+    pub fn new(
+        issuer: impl Into<String>,
+        not_before_human: impl Into<String>,
+        expires_human: impl Into<String>,
+    ) -> Self {
+        Self {
+            issuer: issuer.into(),
+            not_before_human: not_before_human.into(),
+            expires_human: expires_human.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the call alias of the component
+    #[must_use]
+    pub fn with_call_alias(mut self, call_alias: Option<String>) -> Self {
+        self.call_alias = call_alias;
+        self
+    }
+
+    /// Set the tags of the component
+    #[must_use]
+    pub fn with_tags(mut self, tags: Option<Vec<String>>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Set the name of the component
+    #[must_use]
+    pub fn with_name(mut self, name: Option<String>) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Set the version of the component
+    #[must_use]
+    pub fn with_version(mut self, version: Option<String>) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Set the revision of the component
+    #[must_use]
+    pub fn with_revision(mut self, revision: Option<i32>) -> Self {
+        self.revision = revision;
+        self
+    }
+
+    /// Get the issuer of the claims
+    #[must_use]
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// Get the call alias of the component
+    #[must_use]
+    pub fn call_alias(&self) -> Option<&str> {
+        self.call_alias.as_deref()
+    }
+
+    /// Get the tags of the component
+    #[must_use]
+    pub fn tags(&self) -> Option<&Vec<String>> {
+        self.tags.as_ref()
+    }
+
+    /// Get the name of the component
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get the version of the component
+    #[must_use]
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Get the revision of the component
+    #[must_use]
+    pub fn revision(&self) -> Option<i32> {
+        self.revision
+    }
+
+    /// Get the human-readable time before which the claims are not valid
+    #[must_use]
+    pub fn not_before_human(&self) -> &str {
+        &self.not_before_human
+    }
+
+    /// Get the human-readable time after which the claims are not valid
+    #[must_use]
+    pub fn expires_human(&self) -> &str {
+        &self.expires_human
+    }
+}
+
+/// Payload of [`WasmbusEvent::ComponentScaled`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct ComponentScaled {
+    /// Public key of the component, if it is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) public_key: Option<String>,
+    /// Claims of the component, if it is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) claims: Option<ComponentClaims>,
+    /// Annotations of the component
+    #[serde(default)]
+    pub(crate) annotations: BTreeMap<String, String>,
+    /// ID of the host the component was scaled on
+    pub(crate) host_id: String,
+    /// Image reference of the component
+    pub(crate) image_ref: String,
+    /// Maximum number of concurrent instances of the component
+    pub(crate) max_instances: usize,
+    /// ID of the component
+    pub(crate) component_id: String,
+}
+
+impl ComponentScaled {
+    /// Create a [`ComponentScaled`] payload for a component scaled to `max_instances` on a host
+    #[must_use]
+    pub fn new(
+        host_id: impl Into<String>,
+        component_id: impl Into<String>,
+        image_ref: impl Into<String>,
+        max_instances: usize,
+    ) -> Self {
+        Self {
+            host_id: host_id.into(),
+            component_id: component_id.into(),
+            image_ref: image_ref.into(),
+            max_instances,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the public key and claims of a signed component
+    #[must_use]
+    pub fn with_claims(mut self, public_key: impl Into<String>, claims: ComponentClaims) -> Self {
+        self.public_key = Some(public_key.into());
+        self.claims = Some(claims);
+        self
+    }
+
+    /// Set the annotations of the component
+    #[must_use]
+    pub fn with_annotations(mut self, annotations: BTreeMap<String, String>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Get the public key of the component, if it is signed
+    #[must_use]
+    pub fn public_key(&self) -> Option<&str> {
+        self.public_key.as_deref()
+    }
+
+    /// Get the claims of the component, if it is signed
+    #[must_use]
+    pub fn claims(&self) -> Option<&ComponentClaims> {
+        self.claims.as_ref()
+    }
+
+    /// Get the annotations of the component
+    #[must_use]
+    pub fn annotations(&self) -> &BTreeMap<String, String> {
+        &self.annotations
+    }
+
+    /// Get the ID of the host the component was scaled on
+    #[must_use]
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    /// Get the image reference of the component
+    #[must_use]
+    pub fn image_ref(&self) -> &str {
+        &self.image_ref
+    }
+
+    /// Get the maximum number of concurrent instances of the component
+    #[must_use]
+    pub fn max_instances(&self) -> usize {
+        self.max_instances
+    }
+
+    /// Get the ID of the component
+    #[must_use]
+    pub fn component_id(&self) -> &str {
+        &self.component_id
+    }
+}
+
+/// Payload of [`WasmbusEvent::ComponentScaleFailed`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct ComponentScaleFailed {
+    /// Public key of the component, if it is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) public_key: Option<String>,
+    /// ID of the component
+    pub(crate) component_id: String,
+    /// Annotations of the component
+    #[serde(default)]
+    pub(crate) annotations: BTreeMap<String, String>,
+    /// ID of the host the component failed to scale on
+    pub(crate) host_id: String,
+    /// Image reference of the component
+    pub(crate) image_ref: String,
+    /// Requested maximum number of concurrent instances of the component
+    pub(crate) max_instances: u32,
+    /// Reason the component failed to scale
+    pub(crate) error: String,
+}
+
+impl ComponentScaleFailed {
+    /// Create a [`ComponentScaleFailed`] payload for a component that failed to scale with `error`
+    #[must_use]
+    pub fn new(
+        host_id: impl Into<String>,
+        component_id: impl Into<String>,
+        image_ref: impl Into<String>,
+        max_instances: u32,
+        error: impl Into<String>,
+    ) -> Self {
+        Self {
+            host_id: host_id.into(),
+            component_id: component_id.into(),
+            image_ref: image_ref.into(),
+            max_instances,
+            error: error.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the public key of the component, if it is signed
+    #[must_use]
+    pub fn with_public_key(mut self, public_key: impl Into<String>) -> Self {
+        self.public_key = Some(public_key.into());
+        self
+    }
+
+    /// Set the annotations of the component
+    #[must_use]
+    pub fn with_annotations(mut self, annotations: BTreeMap<String, String>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Get the public key of the component, if it is signed
+    #[must_use]
+    pub fn public_key(&self) -> Option<&str> {
+        self.public_key.as_deref()
+    }
+
+    /// Get the ID of the component
+    #[must_use]
+    pub fn component_id(&self) -> &str {
+        &self.component_id
+    }
+
+    /// Get the annotations of the component
+    #[must_use]
+    pub fn annotations(&self) -> &BTreeMap<String, String> {
+        &self.annotations
+    }
+
+    /// Get the ID of the host the component failed to scale on
+    #[must_use]
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    /// Get the image reference of the component
+    #[must_use]
+    pub fn image_ref(&self) -> &str {
+        &self.image_ref
+    }
+
+    /// Get the requested maximum number of concurrent instances of the component
+    #[must_use]
+    pub fn max_instances(&self) -> u32 {
+        self.max_instances
+    }
+
+    /// Get the reason the component failed to scale
+    #[must_use]
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+}
+
+/// Summary of the claims of a provider included in events
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct ProviderClaims {
+    /// Issuer of the claims
+    pub(crate) issuer: String,
+    /// Tags of the provider, which are always empty
+    #[serde(default)]
+    pub(crate) tags: Option<Vec<String>>,
+    /// Name of the provider
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    /// Version of the provider
+    #[serde(default)]
+    pub(crate) version: Option<String>,
+    /// Human-readable time before which the claims are not valid
+    pub(crate) not_before_human: String,
+    /// Human-readable time after which the claims are not valid
+    pub(crate) expires_human: String,
+}
+
+impl ProviderClaims {
+    /// Create [`ProviderClaims`] from the issuer and validity of the claims
+    #[must_use]
+    pub fn new(
+        issuer: impl Into<String>,
+        not_before_human: impl Into<String>,
+        expires_human: impl Into<String>,
+    ) -> Self {
+        Self {
+            issuer: issuer.into(),
+            not_before_human: not_before_human.into(),
+            expires_human: expires_human.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the name of the provider
+    #[must_use]
+    pub fn with_name(mut self, name: Option<String>) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Set the version of the provider
+    #[must_use]
+    pub fn with_version(mut self, version: Option<String>) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Get the issuer of the claims
+    #[must_use]
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// Get the name of the provider
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get the version of the provider
+    #[must_use]
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Get the human-readable time before which the claims are not valid
+    #[must_use]
+    pub fn not_before_human(&self) -> &str {
+        &self.not_before_human
+    }
+
+    /// Get the human-readable time after which the claims are not valid
+    #[must_use]
+    pub fn expires_human(&self) -> &str {
+        &self.expires_human
+    }
+}
+
+/// Payload of [`WasmbusEvent::ProviderStarted`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct ProviderStarted {
+    /// ID of the host the provider was started on
+    pub(crate) host_id: String,
+    /// Image reference of the provider
+    pub(crate) image_ref: String,
+    /// ID of the provider
+    pub(crate) provider_id: String,
+    /// Annotations of the provider
+    #[serde(default)]
+    pub(crate) annotations: BTreeMap<String, String>,
+    /// Claims of the provider, if it is signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) claims: Option<ProviderClaims>,
+    // TODO(#1548): remove these fields when we don't depend on them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) instance_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) public_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) link_name: Option<String>,
+}
+
+impl ProviderStarted {
+    /// Create a [`ProviderStarted`] payload for a provider started on a host
+    #[must_use]
+    pub fn new(
+        host_id: impl Into<String>,
+        provider_id: impl Into<String>,
+        image_ref: impl Into<String>,
+    ) -> Self {
+        Self {
+            host_id: host_id.into(),
+            provider_id: provider_id.into(),
+            image_ref: image_ref.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the claims of a signed provider
+    #[must_use]
+    pub fn with_claims(mut self, claims: ProviderClaims) -> Self {
+        self.instance_id = Some(self.provider_id.clone());
+        self.public_key = Some(self.provider_id.clone());
+        self.link_name = Some("default".into());
+        self.claims = Some(claims);
+        self
+    }
+
+    /// Set the annotations of the provider
+    #[must_use]
+    pub fn with_annotations(mut self, annotations: BTreeMap<String, String>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Get the ID of the host the provider was started on
+    #[must_use]
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    /// Get the image reference of the provider
+    #[must_use]
+    pub fn image_ref(&self) -> &str {
+        &self.image_ref
+    }
+
+    /// Get the ID of the provider
+    #[must_use]
+    pub fn provider_id(&self) -> &str {
+        &self.provider_id
+    }
+
+    /// Get the annotations of the provider
+    #[must_use]
+    pub fn annotations(&self) -> &BTreeMap<String, String> {
+        &self.annotations
+    }
+
+    /// Get the claims of the provider, if it is signed
+    #[must_use]
+    pub fn claims(&self) -> Option<&ProviderClaims> {
+        self.claims.as_ref()
+    }
+}
+
+/// Payload of [`WasmbusEvent::ProviderStartFailed`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct ProviderStartFailed {
+    /// Image reference of the provider
+    pub(crate) provider_ref: String,
+    /// ID of the provider
+    pub(crate) provider_id: String,
+    /// ID of the host the provider failed to start on
+    pub(crate) host_id: String,
+    /// Reason the provider failed to start
+    pub(crate) error: String,
+    // TODO(#1548): remove this field when we don't depend on it
+    #[serde(default)]
+    pub(crate) link_name: String,
+}
+
+impl ProviderStartFailed {
+    /// Create a [`ProviderStartFailed`] payload for a provider that failed to start with `error`
+    #[must_use]
+    pub fn new(
+        host_id: impl Into<String>,
+        provider_id: impl Into<String>,
+        provider_ref: impl Into<String>,
+        error: impl Into<String>,
+    ) -> Self {
+        Self {
+            host_id: host_id.into(),
+            provider_id: provider_id.into(),
+            provider_ref: provider_ref.into(),
+            error: error.into(),
+            link_name: "default".into(),
+        }
+    }
+
+    /// Get the image reference of the provider
+    #[must_use]
+    pub fn provider_ref(&self) -> &str {
+        &self.provider_ref
+    }
+
+    /// Get the ID of the provider
+    #[must_use]
+    pub fn provider_id(&self) -> &str {
+        &self.provider_id
+    }
+
+    /// Get the ID of the host the provider failed to start on
+    #[must_use]
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    /// Get the reason the provider failed to start
+    #[must_use]
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+}
+
+/// Payload of [`WasmbusEvent::ProviderStopped`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct ProviderStopped {
+    /// ID of the host the provider was stopped on
+    pub(crate) host_id: String,
+    /// ID of the provider
+    pub(crate) provider_id: String,
+    /// Annotations of the provider
+    #[serde(default)]
+    pub(crate) annotations: BTreeMap<String, String>,
+    /// Reason the provider was stopped
+    pub(crate) reason: String,
+    // TODO(#1548): remove these fields when we don't depend on them
+    #[serde(default)]
+    pub(crate) instance_id: String,
+    #[serde(default)]
+    pub(crate) public_key: String,
+    #[serde(default)]
+    pub(crate) link_name: String,
+}
+
+impl ProviderStopped {
+    /// Create a [`ProviderStopped`] payload for a provider stopped on a host for `reason`
+    #[must_use]
+    pub fn new(
+        host_id: impl Into<String>,
+        provider_id: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        let provider_id = provider_id.into();
+        Self {
+            host_id: host_id.into(),
+            instance_id: provider_id.clone(),
+            public_key: provider_id.clone(),
+            provider_id,
+            reason: reason.into(),
+            link_name: "default".into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the annotations of the provider
+    #[must_use]
+    pub fn with_annotations(mut self, annotations: BTreeMap<String, String>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Get the ID of the host the provider was stopped on
+    #[must_use]
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    /// Get the ID of the provider
+    #[must_use]
+    pub fn provider_id(&self) -> &str {
+        &self.provider_id
+    }
+
+    /// Get the annotations of the provider
+    #[must_use]
+    pub fn annotations(&self) -> &BTreeMap<String, String> {
+        &self.annotations
+    }
+
+    /// Get the reason the provider was stopped
+    #[must_use]
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+/// Payload of [`WasmbusEvent::LinkdefSetFailed`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct LinkdefSetFailed {
+    /// The link that failed to be put
+    #[serde(flatten)]
+    pub(crate) link: Link,
+    /// Reason the link failed to be put
+    pub(crate) error: String,
+}
+
+impl LinkdefSetFailed {
+    /// Create a [`LinkdefSetFailed`] payload for a link that failed to be put with `error`
+    #[must_use]
+    pub fn new(link: Link, error: impl Into<String>) -> Self {
+        Self {
+            link,
+            error: error.into(),
+        }
+    }
+
+    /// Get the link that failed to be put
+    #[must_use]
+    pub fn link(&self) -> &Link {
+        &self.link
+    }
+
+    /// Get the reason the link failed to be put
+    #[must_use]
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+}
+
+/// Payload of [`WasmbusEvent::LinkdefDeleted`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct LinkdefDeleted {
+    /// Source identifier of the link
+    pub(crate) source_id: String,
+    /// Target of the link, if the link existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) target: Option<String>,
+    /// Name of the link
+    pub(crate) name: String,
+    /// WIT namespace of the link
+    pub(crate) wit_namespace: String,
+    /// WIT package of the link
+    pub(crate) wit_package: String,
+    /// WIT interfaces of the link, if the link existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) interfaces: Option<Vec<String>>,
+}
+
+impl LinkdefDeleted {
+    /// Create a [`LinkdefDeleted`] payload identifying the deleted link
+    #[must_use]
+    pub fn new(
+        source_id: impl Into<String>,
+        name: impl Into<String>,
+        wit_namespace: impl Into<String>,
+        wit_package: impl Into<String>,
+    ) -> Self {
+        Self {
+            source_id: source_id.into(),
+            name: name.into(),
+            wit_namespace: wit_namespace.into(),
+            wit_package: wit_package.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the target and interfaces of the deleted link, which are only known if it existed
+    #[must_use]
+    pub fn with_target(mut self, target: impl Into<String>, interfaces: Vec<String>) -> Self {
+        self.target = Some(target.into());
+        self.interfaces = Some(interfaces);
+        self
+    }
+
+    /// Get the source identifier of the link
+    #[must_use]
+    pub fn source_id(&self) -> &str {
+        &self.source_id
+    }
+
+    /// Get the target of the link, if the link existed
+    #[must_use]
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// Get the name of the link
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the WIT namespace of the link
+    #[must_use]
+    pub fn wit_namespace(&self) -> &str {
+        &self.wit_namespace
+    }
+
+    /// Get the WIT package of the link
+    #[must_use]
+    pub fn wit_package(&self) -> &str {
+        &self.wit_package
+    }
+
+    /// Get the WIT interfaces of the link, if the link existed
+    #[must_use]
+    pub fn interfaces(&self) -> Option<&Vec<String>> {
+        self.interfaces.as_ref()
+    }
+}
+
+/// Payload of [`WasmbusEvent::ConfigSet`] and [`WasmbusEvent::ConfigDeleted`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct ConfigChanged {
+    /// Name of the config
+    pub(crate) config_name: String,
+}
+
+impl ConfigChanged {
+    /// Create a [`ConfigChanged`] payload for the named config
+    #[must_use]
+    pub fn new(config_name: impl Into<String>) -> Self {
+        Self {
+            config_name: config_name.into(),
+        }
+    }
+
+    /// Get the name of the config
+    #[must_use]
+    pub fn config_name(&self) -> &str {
+        &self.config_name
+    }
+}
+
+/// Payload of the provider health check events
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct HealthCheck {
+    /// ID of the host running the provider
+    pub(crate) host_id: String,
+    /// ID of the provider
+    pub(crate) provider_id: String,
+}
+
+impl HealthCheck {
+    /// Create a [`HealthCheck`] payload for a provider running on a host
+    #[must_use]
+    pub fn new(host_id: impl Into<String>, provider_id: impl Into<String>) -> Self {
+        Self {
+            host_id: host_id.into(),
+            provider_id: provider_id.into(),
+        }
+    }
+
+    /// Get the ID of the host running the provider
+    #[must_use]
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    /// Get the ID of the provider
+    #[must_use]
+    pub fn provider_id(&self) -> &str {
+        &self.provider_id
+    }
+}
+
+/// Payload of [`WasmbusEvent::HostStarted`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct HostStarted {
+    /// ID of the host
+    pub(crate) id: String,
+    /// Human-readable friendly name of the host
+    pub(crate) friendly_name: String,
+    /// Labels of the host
+    #[serde(default)]
+    pub(crate) labels: BTreeMap<String, String>,
+    /// Uptime of the host in seconds
+    #[serde(default)]
+    pub(crate) uptime_seconds: u64,
+    /// Version of the host
+    pub(crate) version: String,
+}
+
+impl HostStarted {
+    /// Create a [`HostStarted`] payload for a host that has just started
+    #[must_use]
+    pub fn new(
+        id: impl Into<String>,
+        friendly_name: impl Into<String>,
+        version: impl Into<String>,
+        labels: BTreeMap<String, String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            friendly_name: friendly_name.into(),
+            version: version.into(),
+            labels,
+            uptime_seconds: 0,
+        }
+    }
+
+    /// Get the ID of the host
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the human-readable friendly name of the host
+    #[must_use]
+    pub fn friendly_name(&self) -> &str {
+        &self.friendly_name
+    }
+
+    /// Get the labels of the host
+    #[must_use]
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    /// Get the uptime of the host in seconds
+    #[must_use]
+    pub fn uptime_seconds(&self) -> u64 {
+        self.uptime_seconds
+    }
+
+    /// Get the version of the host
+    #[must_use]
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+}
+
+/// Payload of [`WasmbusEvent::HostStopped`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct HostStopped {
+    /// Labels of the host
+    #[serde(default)]
+    pub(crate) labels: BTreeMap<String, String>,
+}
+
+impl HostStopped {
+    /// Create a [`HostStopped`] payload with the labels of the host
+    #[must_use]
+    pub fn new(labels: BTreeMap<String, String>) -> Self {
+        Self { labels }
+    }
+
+    /// Get the labels of the host
+    #[must_use]
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+}
+
+/// Payload of [`WasmbusEvent::HostDrainStarted`], [`WasmbusEvent::HostDrainProgress`] and
+/// [`WasmbusEvent::HostDrained`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct HostDrainStatus {
+    /// ID of the host
+    pub(crate) host_id: String,
+    /// Number of in-flight invocations of each component with any
+    #[serde(default)]
+    pub(crate) in_flight: BTreeMap<String, u64>,
+    /// Whether the host stopped waiting for in-flight invocations because the drain timed out
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) timed_out: bool,
+}
+
+impl HostDrainStatus {
+    /// Create a [`HostDrainStatus`] payload with the in-flight invocations of each component
+    #[must_use]
+    pub fn new(host_id: impl Into<String>, in_flight: BTreeMap<String, u64>) -> Self {
+        Self {
+            host_id: host_id.into(),
+            in_flight,
+            timed_out: false,
+        }
+    }
+
+    /// Set whether the host stopped waiting for in-flight invocations because the drain timed out
+    #[must_use]
+    pub fn with_timed_out(mut self, timed_out: bool) -> Self {
+        self.timed_out = timed_out;
+        self
+    }
+
+    /// Get the ID of the host
+    #[must_use]
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    /// Get the number of in-flight invocations of each component with any
+    #[must_use]
+    pub fn in_flight(&self) -> &BTreeMap<String, u64> {
+        &self.in_flight
+    }
+
+    /// Get whether the host stopped waiting for in-flight invocations because the drain timed out
+    #[must_use]
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+}
+
+/// Payload of [`WasmbusEvent::LabelsChanged`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct LabelsChanged {
+    /// ID of the host
+    pub(crate) host_id: String,
+    /// New labels of the host
+    pub(crate) labels: HashMap<String, String>,
+}
+
+impl LabelsChanged {
+    /// Create a [`LabelsChanged`] payload with the new labels of a host
+    #[must_use]
+    pub fn new(host_id: impl Into<String>, labels: HashMap<String, String>) -> Self {
+        Self {
+            host_id: host_id.into(),
+            labels,
+        }
+    }
+
+    /// Get the ID of the host
+    #[must_use]
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    /// Get the new labels of the host
+    #[must_use]
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use cloudevents::{EventBuilder as _, EventBuilderV10};
+
+    use super::*;
+
+    #[test]
+    fn wasmbus_event_round_trip() {
+        let link = Link::builder()
+            .source_id("source")
+            .target("target")
+            .name("default")
+            .wit_namespace("wasi")
+            .wit_package("http")
+            .interfaces(vec!["outgoing-handler".into()])
+            .build()
+            .unwrap();
+        let events = [
+            WasmbusEvent::ComponentScaled(
+                ComponentScaled::new("host", "component", "ghcr.io/component:0.1.0", 5)
+                    .with_claims(
+                        "MCOMPONENT",
+                        ComponentClaims::new("ISSUER", "never", "never")
+                            .with_name(Some("component".into())),
+                    )
+                    .with_annotations(BTreeMap::from([("a".into(), "b".into())])),
+            ),
+            WasmbusEvent::ProviderStopped(ProviderStopped::new("host", "provider", "stop")),
+            WasmbusEvent::LinkdefSet(link.clone()),
+            WasmbusEvent::LinkdefSetFailed(LinkdefSetFailed::new(link, "denied")),
+            WasmbusEvent::ConfigDeleted(ConfigChanged::new("config")),
+            WasmbusEvent::HostHeartbeat(HostInventory::default()),
+            WasmbusEvent::HostDrainProgress(HostDrainStatus::new(
+                "host",
+                BTreeMap::from([("component".into(), 2)]),
+            )),
+            WasmbusEvent::HostDrained(HostDrainStatus::default().with_timed_out(true)),
+        ];
+        for event in events {
+            let encoded = serde_json::to_string(&event).unwrap();
+            assert_eq!(
+                serde_json::from_str::<WasmbusEvent>(&encoded).unwrap(),
+                event
+            );
+            let data = event.data().unwrap();
+            assert_eq!(
+                WasmbusEvent::from_parts(event.name(), data.clone()).unwrap(),
+                event
+            );
+
+            let cloud_event = EventBuilderV10::new()
+                .id("id")
+                .source("host")
+                .ty(event.event_type())
+                .extension(WASMBUS_EVENT_VERSION_EXTENSION, WASMBUS_EVENT_VERSION)
+                .data("application/json", data)
+                .build()
+                .unwrap();
+            assert_eq!(WasmbusEvent::try_from(&cloud_event).unwrap(), event);
+        }
+    }
+
+    #[test]
+    fn wasmbus_event_data_matches_published_payload() {
+        let event =
+            WasmbusEvent::LinkdefDeleted(LinkdefDeleted::new("source", "default", "wasi", "http"));
+        assert_eq!(
+            event.data().unwrap(),
+            serde_json::json!({
+                "source_id": "source",
+                "name": "default",
+                "wit_namespace": "wasi",
+                "wit_package": "http",
+            })
+        );
+        let cloud_event = EventBuilderV10::new()
+            .id("id")
+            .source("host")
+            .ty(event.event_type())
+            .extension(WASMBUS_EVENT_VERSION_EXTENSION, "v0")
+            .data("application/json", event.data().unwrap())
+            .build()
+            .unwrap();
+        assert!(WasmbusEvent::try_from(&cloud_event).is_err());
+    }
+}
@@ -243,6 +243,10 @@ pub struct HostInventory {
     /// The host uptime in seconds
     #[serde(default)]
     pub(crate) uptime_seconds: u64,
+
+    /// Resource usage and quota of each tenant on the host, keyed by tenant name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) tenant_quotas: BTreeMap<String, TenantQuotaUsage>,
 }
 
 impl HostInventory {
@@ -286,6 +290,11 @@ impl HostInventory {
         self.uptime_seconds
     }
 
+    /// Get the resource usage and quota of each tenant on the host
+    pub fn tenant_quotas(&self) -> &BTreeMap<String, TenantQuotaUsage> {
+        &self.tenant_quotas
+    }
+
     #[must_use]
     pub fn builder() -> HostInventoryBuilder {
         HostInventoryBuilder::default()
@@ -303,6 +312,7 @@ pub struct HostInventoryBuilder {
     version: Option<String>,
     uptime_human: Option<String>,
     uptime_seconds: Option<u64>,
+    tenant_quotas: Option<BTreeMap<String, TenantQuotaUsage>>,
 }
 
 impl HostInventoryBuilder {
@@ -354,6 +364,12 @@ impl HostInventoryBuilder {
         self
     }
 
+    #[must_use]
+    pub fn tenant_quotas(mut self, v: BTreeMap<String, TenantQuotaUsage>) -> Self {
+        self.tenant_quotas = Some(v);
+        self
+    }
+
     pub fn build(self) -> Result<HostInventory> {
         Ok(HostInventory {
             components: self.components.unwrap_or_default(),
@@ -374,10 +390,104 @@ impl HostInventoryBuilder {
             uptime_seconds: self
                 .uptime_seconds
                 .ok_or_else(|| "uptime_seconds is required".to_string())?,
+            tenant_quotas: self.tenant_quotas.unwrap_or_default(),
         })
     }
 }
 
+/// Resources used by the components and providers of a tenant on a host, along with the quota
+/// of the tenant on that host, if any
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct TenantQuotaUsage {
+    /// Total maximum number of instances of the components of the tenant
+    #[serde(default)]
+    pub(crate) instances: u64,
+
+    /// Total linear memory in bytes the instances of the components of the tenant may allocate
+    #[serde(default)]
+    pub(crate) memory_bytes: u64,
+
+    /// Number of providers of the tenant
+    #[serde(default)]
+    pub(crate) providers: u64,
+
+    /// Maximum number of component instances allowed for the tenant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_instances: Option<u64>,
+
+    /// Maximum linear memory in bytes allowed for the tenant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_memory_bytes: Option<u64>,
+
+    /// Maximum number of providers allowed for the tenant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_providers: Option<u64>,
+}
+
+impl TenantQuotaUsage {
+    /// Create a [`TenantQuotaUsage`] from the resources used by a tenant, without a quota
+    #[must_use]
+    pub fn new(instances: u64, memory_bytes: u64, providers: u64) -> Self {
+        Self {
+            instances,
+            memory_bytes,
+            providers,
+            ..Default::default()
+        }
+    }
+
+    /// Set the quota of the tenant
+    #[must_use]
+    pub fn with_quota(
+        mut self,
+        max_instances: Option<u64>,
+        max_memory_bytes: Option<u64>,
+        max_providers: Option<u64>,
+    ) -> Self {
+        self.max_instances = max_instances;
+        self.max_memory_bytes = max_memory_bytes;
+        self.max_providers = max_providers;
+        self
+    }
+
+    /// Get the total maximum number of instances of the components of the tenant
+    #[must_use]
+    pub fn instances(&self) -> u64 {
+        self.instances
+    }
+
+    /// Get the total linear memory in bytes the components of the tenant may allocate
+    #[must_use]
+    pub fn memory_bytes(&self) -> u64 {
+        self.memory_bytes
+    }
+
+    /// Get the number of providers of the tenant
+    #[must_use]
+    pub fn providers(&self) -> u64 {
+        self.providers
+    }
+
+    /// Get the maximum number of component instances allowed for the tenant, if limited
+    #[must_use]
+    pub fn max_instances(&self) -> Option<u64> {
+        self.max_instances
+    }
+
+    /// Get the maximum linear memory in bytes allowed for the tenant, if limited
+    #[must_use]
+    pub fn max_memory_bytes(&self) -> Option<u64> {
+        self.max_memory_bytes
+    }
+
+    /// Get the maximum number of providers allowed for the tenant, if limited
+    #[must_use]
+    pub fn max_providers(&self) -> Option<u64> {
+        self.max_providers
+    }
+}
+
 /// A label on a given host (ex. "arch=amd64")
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[non_exhaustive]
@@ -435,7 +545,7 @@ mod tests {
 
     use crate::{ComponentDescription, ProviderDescription};
 
-    use super::{Host, HostInventory};
+    use super::{Host, HostInventory, TenantQuotaUsage};
 
     #[test]
     fn host_builder() {
@@ -479,7 +589,18 @@ mod tests {
                 labels: BTreeMap::from([("a".into(), "b".into())]),
                 version: "1.0.0".into(),
                 uptime_human: "t".into(),
-                uptime_seconds: 1
+                uptime_seconds: 1,
+                tenant_quotas: BTreeMap::from([(
+                    "team-a".into(),
+                    TenantQuotaUsage {
+                        instances: 2,
+                        memory_bytes: 1024,
+                        providers: 1,
+                        max_instances: Some(4),
+                        max_memory_bytes: None,
+                        max_providers: Some(1),
+                    }
+                )]),
             },
             HostInventory::builder()
                 .components(Vec::from([ComponentDescription::default()]))
@@ -490,6 +611,10 @@ mod tests {
                 .version("1.0.0".into())
                 .uptime_human("t".into())
                 .uptime_seconds(1)
+                .tenant_quotas(BTreeMap::from([(
+                    "team-a".into(),
+                    TenantQuotaUsage::new(2, 1024, 1).with_quota(Some(4), None, Some(1))
+                )]))
                 .build()
                 .unwrap()
         )
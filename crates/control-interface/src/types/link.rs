@@ -33,6 +33,13 @@ pub struct Link {
     /// List of named configurations to provide to the target upon request
     #[serde(default)]
     pub(crate) target_config: Vec<String>,
+    /// Policy applied by the source host to invocations of the target over this link
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) invocation_policy: Option<InvocationPolicy>,
+    /// Targets invocations over the link are split between, in proportion to their weights. If
+    /// set, the primary `target` must be one of them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) weighted_targets: Vec<WeightedTarget>,
 }
 
 impl Link {
@@ -76,6 +83,16 @@ impl Link {
         &self.target_config
     }
 
+    #[must_use]
+    pub fn invocation_policy(&self) -> Option<&InvocationPolicy> {
+        self.invocation_policy.as_ref()
+    }
+
+    #[must_use]
+    pub fn weighted_targets(&self) -> &Vec<WeightedTarget> {
+        &self.weighted_targets
+    }
+
     #[must_use]
     pub fn builder() -> LinkBuilder {
         LinkBuilder::default()
@@ -94,6 +111,8 @@ pub struct LinkBuilder {
     interfaces: Option<Vec<String>>,
     source_config: Option<Vec<String>>,
     target_config: Option<Vec<String>>,
+    invocation_policy: Option<InvocationPolicy>,
+    weighted_targets: Option<Vec<WeightedTarget>>,
 }
 
 impl LinkBuilder {
@@ -145,6 +164,18 @@ impl LinkBuilder {
         self
     }
 
+    #[must_use]
+    pub fn invocation_policy(mut self, v: InvocationPolicy) -> Self {
+        self.invocation_policy = Some(v);
+        self
+    }
+
+    #[must_use]
+    pub fn weighted_targets(mut self, v: Vec<WeightedTarget>) -> Self {
+        self.weighted_targets = Some(v);
+        self
+    }
+
     pub fn build(self) -> crate::Result<Link> {
         Ok(Link {
             source_id: self
@@ -165,6 +196,170 @@ impl LinkBuilder {
             interfaces: self.interfaces.unwrap_or_default(),
             source_config: self.source_config.unwrap_or_default(),
             target_config: self.target_config.unwrap_or_default(),
+            invocation_policy: self.invocation_policy,
+            weighted_targets: self.weighted_targets.unwrap_or_default(),
+        })
+    }
+}
+
+/// A target of a [`Link`], which receives a share of the invocations over the link in proportion
+/// to its weight
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize, Hash)]
+#[non_exhaustive]
+pub struct WeightedTarget {
+    /// Target of the link, which can be a unique identifier or (future) a routing group
+    pub(crate) target: String,
+    /// Weight of the target relative to the other targets of the link
+    pub(crate) weight: u32,
+}
+
+impl WeightedTarget {
+    #[must_use]
+    pub fn new(target: &str, weight: u32) -> Self {
+        Self {
+            target: target.into(),
+            weight,
+        }
+    }
+
+    #[must_use]
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    #[must_use]
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+}
+
+/// Policy applied to invocations made over a [`Link`], controlling timeouts, retries and
+/// circuit breaking of calls from the source to the target.
+///
+/// All settings are optional, unset settings fall back to the defaults of the host
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize, Hash)]
+#[non_exhaustive]
+pub struct InvocationPolicy {
+    /// Timeout of an invocation, in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) timeout_ms: Option<u64>,
+    /// Maximum number of times a failed invocation of an idempotent function is retried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_retries: Option<u32>,
+    /// Delay before the first retry, in milliseconds, which is doubled for each subsequent retry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) retry_backoff_ms: Option<u64>,
+    /// Whether all functions of the linked interfaces are idempotent and may be retried. If not
+    /// set, only well-known idempotent functions, like `wasi:keyvalue/store.get`, are retried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) idempotent: Option<bool>,
+    /// Number of consecutive failed invocations after which the circuit breaker opens and
+    /// invocations fail fast. Circuit breaking is disabled if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) circuit_breaker_threshold: Option<u32>,
+    /// Time after which an open circuit breaker lets a single invocation through to probe the
+    /// target, in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) circuit_breaker_reset_ms: Option<u64>,
+}
+
+impl InvocationPolicy {
+    #[must_use]
+    pub fn timeout_ms(&self) -> Option<u64> {
+        self.timeout_ms
+    }
+
+    #[must_use]
+    pub fn max_retries(&self) -> Option<u32> {
+        self.max_retries
+    }
+
+    #[must_use]
+    pub fn retry_backoff_ms(&self) -> Option<u64> {
+        self.retry_backoff_ms
+    }
+
+    #[must_use]
+    pub fn idempotent(&self) -> Option<bool> {
+        self.idempotent
+    }
+
+    #[must_use]
+    pub fn circuit_breaker_threshold(&self) -> Option<u32> {
+        self.circuit_breaker_threshold
+    }
+
+    #[must_use]
+    pub fn circuit_breaker_reset_ms(&self) -> Option<u64> {
+        self.circuit_breaker_reset_ms
+    }
+
+    #[must_use]
+    pub fn builder() -> InvocationPolicyBuilder {
+        InvocationPolicyBuilder::default()
+    }
+}
+
+/// Builder that produces [`InvocationPolicy`]s
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct InvocationPolicyBuilder {
+    timeout_ms: Option<u64>,
+    max_retries: Option<u32>,
+    retry_backoff_ms: Option<u64>,
+    idempotent: Option<bool>,
+    circuit_breaker_threshold: Option<u32>,
+    circuit_breaker_reset_ms: Option<u64>,
+}
+
+impl InvocationPolicyBuilder {
+    #[must_use]
+    pub fn timeout_ms(mut self, v: u64) -> Self {
+        self.timeout_ms = Some(v);
+        self
+    }
+
+    #[must_use]
+    pub fn max_retries(mut self, v: u32) -> Self {
+        self.max_retries = Some(v);
+        self
+    }
+
+    #[must_use]
+    pub fn retry_backoff_ms(mut self, v: u64) -> Self {
+        self.retry_backoff_ms = Some(v);
+        self
+    }
+
+    #[must_use]
+    pub fn idempotent(mut self, v: bool) -> Self {
+        self.idempotent = Some(v);
+        self
+    }
+
+    #[must_use]
+    pub fn circuit_breaker_threshold(mut self, v: u32) -> Self {
+        self.circuit_breaker_threshold = Some(v);
+        self
+    }
+
+    #[must_use]
+    pub fn circuit_breaker_reset_ms(mut self, v: u64) -> Self {
+        self.circuit_breaker_reset_ms = Some(v);
+        self
+    }
+
+    pub fn build(self) -> crate::Result<InvocationPolicy> {
+        if self.circuit_breaker_threshold == Some(0) {
+            return Err("circuit breaker threshold must be greater than zero".into());
+        }
+        Ok(InvocationPolicy {
+            timeout_ms: self.timeout_ms,
+            max_retries: self.max_retries,
+            retry_backoff_ms: self.retry_backoff_ms,
+            idempotent: self.idempotent,
+            circuit_breaker_threshold: self.circuit_breaker_threshold,
+            circuit_breaker_reset_ms: self.circuit_breaker_reset_ms,
         })
     }
 }
@@ -177,7 +372,7 @@ pub(crate) fn default_link_name() -> String {
 #[cfg(test)]
 mod tests {
 
-    use super::Link;
+    use super::{InvocationPolicy, Link, WeightedTarget};
 
     #[test]
     fn link_builder() {
@@ -190,7 +385,17 @@ mod tests {
                 wit_package: "wit_package".into(),
                 interfaces: vec!["i".into()],
                 source_config: vec!["sc".into()],
-                target_config: vec!["tc".into()]
+                target_config: vec!["tc".into()],
+                invocation_policy: Some(InvocationPolicy {
+                    timeout_ms: Some(500),
+                    max_retries: Some(2),
+                    circuit_breaker_threshold: Some(5),
+                    ..Default::default()
+                }),
+                weighted_targets: vec![
+                    WeightedTarget::new("target", 90),
+                    WeightedTarget::new("canary", 10)
+                ],
             },
             Link::builder()
                 .source_id("source_id")
@@ -201,6 +406,18 @@ mod tests {
                 .interfaces(vec!["i".into()])
                 .source_config(vec!["sc".into()])
                 .target_config(vec!["tc".into()])
+                .invocation_policy(
+                    InvocationPolicy::builder()
+                        .timeout_ms(500)
+                        .max_retries(2)
+                        .circuit_breaker_threshold(5)
+                        .build()
+                        .unwrap()
+                )
+                .weighted_targets(vec![
+                    WeightedTarget::new("target", 90),
+                    WeightedTarget::new("canary", 10)
+                ])
                 .build()
                 .unwrap()
         );
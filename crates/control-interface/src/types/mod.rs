@@ -2,6 +2,7 @@
 
 pub mod component;
 pub mod ctl;
+pub mod event;
 pub mod host;
 pub mod link;
 pub mod provider;
@@ -67,6 +67,58 @@ impl RegistryCredential {
     }
 }
 
+/// An artifact in the OCI artifact cache of a host
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct CachedArtifact {
+    /// The manifest digest of the artifact
+    pub(crate) digest: String,
+    /// The references resolving to the artifact
+    #[serde(default)]
+    pub(crate) references: Vec<String>,
+    /// The size of the artifact in bytes
+    pub(crate) size: u64,
+    /// When the artifact was last used, in seconds since the Unix epoch
+    pub(crate) last_used: u64,
+}
+
+impl CachedArtifact {
+    #[must_use]
+    pub fn new(
+        digest: impl Into<String>,
+        references: Vec<String>,
+        size: u64,
+        last_used: u64,
+    ) -> Self {
+        Self {
+            digest: digest.into(),
+            references,
+            size,
+            last_used,
+        }
+    }
+
+    #[must_use]
+    pub fn digest(&self) -> &str {
+        &self.digest
+    }
+
+    #[must_use]
+    pub fn references(&self) -> &[String] {
+        &self.references
+    }
+
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    #[must_use]
+    pub fn last_used(&self) -> u64 {
+        self.last_used
+    }
+}
+
 /// Helper for creating the default registry type
 fn default_registry_type() -> String {
     "oci".to_string()
@@ -0,0 +1,134 @@
+//! Validation of named configuration against the JSON Schema embedded by provider authors in the
+//! claims of capability providers. This module requires the `config-schema` feature to be enabled
+use core::fmt;
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use jsonschema::JSONSchema;
+use serde::{Deserialize, Serialize};
+
+/// Violation of a configuration schema
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigViolation {
+    /// JSON pointer to the offending value, e.g. `/port`, or an empty string if the configuration
+    /// as a whole is invalid, for example because a required key is missing
+    pub path: String,
+    /// Description of the violation
+    pub message: String,
+}
+
+impl fmt::Display for ConfigViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "`/`: {}", self.message)
+        } else {
+            write!(f, "`{}`: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Validate `config` against the JSON `schema`, returning all violations found.
+///
+/// Configuration values are always strings, so `config` is validated as a JSON object mapping
+/// keys to string values. Secrets must not be passed in `config`, since violation messages may
+/// contain the offending values.
+///
+/// # Errors
+///
+/// Returns an error if `schema` is not a valid JSON Schema
+pub fn validate_config(
+    schema: &serde_json::Value,
+    config: &HashMap<String, String>,
+) -> Result<Vec<ConfigViolation>> {
+    let schema =
+        JSONSchema::compile(schema).map_err(|err| anyhow!("invalid config schema: {err}"))?;
+    let config = serde_json::Value::Object(
+        config
+            .iter()
+            .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+            .collect(),
+    );
+    let Err(errors) = schema.validate(&config) else {
+        return Ok(Vec::default());
+    };
+    let mut violations: Vec<_> = errors
+        .map(|err| ConfigViolation {
+            path: err.instance_path.to_string(),
+            message: err.to_string(),
+        })
+        .collect();
+    violations.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(violations)
+}
+
+/// Validate `config` against the JSON `schema` as in [validate_config], ignoring the keys the
+/// schema requires. Used for configuration, which may be completed by other configuration, for
+/// example provider configuration, which is completed by the configuration of links.
+///
+/// # Errors
+///
+/// Returns an error if `schema` is not a valid JSON Schema
+pub fn validate_partial_config(
+    schema: &serde_json::Value,
+    config: &HashMap<String, String>,
+) -> Result<Vec<ConfigViolation>> {
+    let mut schema = schema.clone();
+    if let Some(schema) = schema.as_object_mut() {
+        schema.remove("required");
+    }
+    validate_config(&schema, config)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn validates_config() -> Result<()> {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "port": { "type": "string", "pattern": "^[0-9]+$" },
+                "mode": { "enum": ["fast", "safe"] },
+            },
+            "required": ["port"],
+            "additionalProperties": false,
+        });
+        let config = HashMap::from([("port".into(), "8080".into())]);
+        assert_eq!(validate_config(&schema, &config)?, []);
+
+        let config = HashMap::from([
+            ("port".into(), "http".into()),
+            ("mode".into(), "slow".into()),
+        ]);
+        let violations = validate_config(&schema, &config)?;
+        let paths: Vec<_> = violations.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(paths, ["/mode", "/port"]);
+
+        let violations = validate_config(&schema, &HashMap::from([("x".into(), "y".into())]))?;
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().all(|v| v.path.is_empty()));
+        assert!(violations[0].to_string().starts_with("`/`: "));
+
+        assert!(validate_config(&json!({ "type": 42 }), &config).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn validates_partial_config() -> Result<()> {
+        let schema = json!({
+            "type": "object",
+            "properties": { "port": { "type": "string", "pattern": "^[0-9]+$" } },
+            "required": ["port", "host"],
+        });
+        assert_eq!(validate_partial_config(&schema, &HashMap::default())?, []);
+        let violations =
+            validate_partial_config(&schema, &HashMap::from([("port".into(), "http".into())]))?;
+        let paths: Vec<_> = violations.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(paths, ["/port"]);
+        Ok(())
+    }
+}
@@ -67,6 +67,14 @@ pub struct ServiceSettings {
     pub tls_cert_file: Option<String>,
     #[serde(default)]
     pub tls_priv_key_file: Option<String>,
+    /// path to X.509 CA bundle used to verify client certificates. Must be PEM-encoded.
+    /// If set, clients authenticate with mutual TLS
+    #[serde(default)]
+    pub tls_client_ca_file: Option<String>,
+    /// Whether clients must present a certificate, if `tls_client_ca_file` is set.
+    /// Defaults to [`TlsClientAuth::Required`]
+    #[serde(default)]
+    pub tls_client_auth: Option<TlsClientAuth>,
     /// Rpc timeout - how long (milliseconds) to wait for component's response
     /// before returning a status 503 to the http client
     /// If not set, uses the system-wide rpc timeout
@@ -81,6 +89,26 @@ pub struct ServiceSettings {
     pub cors: Cors,
     #[serde(default)]
     pub disable_keepalive: Option<bool>,
+    // request limits
+    /// Number of requests per second a single client may sustain, refilling its token bucket.
+    /// If not set, requests are not rate limited
+    #[serde(default)]
+    pub rate_limit_per_second: Option<u32>,
+    /// Number of requests a single client may burst before being rate limited.
+    /// If not set, defaults to `rate_limit_per_second`
+    #[serde(default)]
+    pub rate_limit_burst: Option<u32>,
+    /// Header identifying the client for rate limiting. If not set, or not present in a request,
+    /// clients are identified by IP address
+    #[serde(default)]
+    pub rate_limit_key_header: Option<String>,
+    /// Maximum size of a request body in bytes, larger requests are rejected with status 413
+    #[serde(default)]
+    pub max_request_body_bytes: Option<u64>,
+    /// Maximum number of requests handled concurrently, further requests are rejected with
+    /// status 503
+    #[serde(default)]
+    pub max_concurrent_requests: Option<usize>,
 }
 
 impl Default for ServiceSettings {
@@ -95,12 +123,19 @@ impl Default for ServiceSettings {
             cors_max_age_secs: Some(CORS_DEFAULT_MAX_AGE_SECS),
             tls_cert_file: None,
             tls_priv_key_file: None,
+            tls_client_ca_file: None,
+            tls_client_auth: None,
             timeout_ms: None,
             cache_control: None,
             readonly_mode: Some(false),
             tls: Tls::default(),
             cors: Cors::default(),
             disable_keepalive: None,
+            rate_limit_per_second: None,
+            rate_limit_burst: None,
+            rate_limit_key_header: None,
+            max_request_body_bytes: None,
+            max_concurrent_requests: None,
         }
     }
 }
@@ -119,6 +154,8 @@ impl ServiceSettings {
                 timeout_ms: s.timeout_ms,
                 tls_cert_file: s.tls_cert_file.or(s.tls.cert_file),
                 tls_priv_key_file: s.tls_priv_key_file.or(s.tls.priv_key_file),
+                tls_client_ca_file: s.tls_client_ca_file,
+                tls_client_auth: s.tls_client_auth,
                 cors_allowed_origins: s.cors_allowed_origins.or(s.cors.allowed_origins),
                 cors_allowed_headers: s.cors_allowed_headers.or(s.cors.allowed_headers),
                 cors_allowed_methods: s.cors_allowed_methods.or(s.cors.allowed_methods),
@@ -127,6 +164,11 @@ impl ServiceSettings {
                 tls: Tls::default(),
                 cors: Cors::default(),
                 disable_keepalive: s.disable_keepalive,
+                rate_limit_per_second: s.rate_limit_per_second,
+                rate_limit_burst: s.rate_limit_burst,
+                rate_limit_key_header: s.rate_limit_key_header,
+                max_request_body_bytes: s.max_request_body_bytes,
+                max_concurrent_requests: s.max_concurrent_requests,
             })
             .map_err(|e| HttpServerError::Settings(format!("invalid json: {e}")))
     }
@@ -162,6 +204,24 @@ impl ServiceSettings {
                 }
             }
         }
+        match (&self.tls_client_ca_file, &self.tls_client_auth) {
+            (None, None) => {}
+            (None, Some(_)) => {
+                errors
+                    .push("'tls_client_auth' requires 'tls_client_ca_file' to be set".to_string());
+            }
+            (Some(ca_file), _) => {
+                if self.tls_cert_file.is_none() {
+                    errors.push(
+                        "for mutual tls, 'tls_cert_file' and 'tls_priv_key_file' must be set"
+                            .to_string(),
+                    );
+                }
+                if !Path::new(ca_file).is_file() {
+                    errors.push(format!("missing tls_client_ca_file '{ca_file}'"));
+                }
+            }
+        }
         if let Some(ref methods) = self.cors_allowed_methods {
             for m in &methods.0 {
                 if http::Method::try_from(m.as_str()).is_err() {
@@ -174,6 +234,21 @@ impl ServiceSettings {
                 errors.push(format!("Invalid Cache Control header : '{cache_control}'"));
             }
         }
+        match (self.rate_limit_per_second, self.rate_limit_burst) {
+            (Some(0), _) => errors.push("'rate_limit_per_second' must be non-zero".to_string()),
+            (_, Some(0)) => errors.push("'rate_limit_burst' must be non-zero".to_string()),
+            (None, Some(_)) => errors
+                .push("'rate_limit_burst' requires 'rate_limit_per_second' to be set".to_string()),
+            _ => {}
+        }
+        if let Some(header) = self.rate_limit_key_header.as_ref() {
+            if http::HeaderName::from_str(header).is_err() {
+                errors.push(format!("Invalid rate limit key header : '{header}'"));
+            }
+        }
+        if self.max_concurrent_requests == Some(0) {
+            errors.push("'max_concurrent_requests' must be non-zero".to_string());
+        }
         if !errors.is_empty() {
             Err(HttpServerError::Settings(format!(
                 "\nInvalid httpserver settings: \n{}\n",
@@ -267,6 +342,15 @@ pub fn load_settings(
     if let Some(tls_priv_key_file) = values.get(&UniCase::new("tls_priv_key_file")) {
         settings.tls_priv_key_file = Some(tls_priv_key_file.to_string());
     }
+    if let Some(tls_client_ca_file) = values.get(&UniCase::new("tls_client_ca_file")) {
+        settings.tls_client_ca_file = Some(tls_client_ca_file.to_string());
+    }
+    if let Some(tls_client_auth) = values.get(&UniCase::new("tls_client_auth")) {
+        let client_auth = tls_client_auth.parse().map_err(|_| {
+            HttpServerError::InvalidParameter(format!("Invalid tls_client_auth: {tls_client_auth}"))
+        })?;
+        settings.tls_client_auth = Some(client_auth);
+    }
 
     // CORS
     if let Some(cors_allowed_origins) = values.get(&UniCase::new("cors_allowed_origins")) {
@@ -299,6 +383,35 @@ pub fn load_settings(
         settings.disable_keepalive = Some(disable_keepalive.parse().unwrap_or(false));
     }
 
+    // Request limits
+    if let Some(rate_limit_per_second) = values.get(&UniCase::new("rate_limit_per_second")) {
+        let rate: u32 = rate_limit_per_second.parse().map_err(|_| {
+            HttpServerError::InvalidParameter("Invalid rate_limit_per_second".to_string())
+        })?;
+        settings.rate_limit_per_second = Some(rate);
+    }
+    if let Some(rate_limit_burst) = values.get(&UniCase::new("rate_limit_burst")) {
+        let burst: u32 = rate_limit_burst.parse().map_err(|_| {
+            HttpServerError::InvalidParameter("Invalid rate_limit_burst".to_string())
+        })?;
+        settings.rate_limit_burst = Some(burst);
+    }
+    if let Some(rate_limit_key_header) = values.get(&UniCase::new("rate_limit_key_header")) {
+        settings.rate_limit_key_header = Some(rate_limit_key_header.to_string());
+    }
+    if let Some(max_request_body_bytes) = values.get(&UniCase::new("max_request_body_bytes")) {
+        let max: u64 = max_request_body_bytes.parse().map_err(|_| {
+            HttpServerError::InvalidParameter("Invalid max_request_body_bytes".to_string())
+        })?;
+        settings.max_request_body_bytes = Some(max);
+    }
+    if let Some(max_concurrent_requests) = values.get(&UniCase::new("max_concurrent_requests")) {
+        let max: usize = max_concurrent_requests.parse().map_err(|_| {
+            HttpServerError::InvalidParameter("Invalid max_concurrent_requests".to_string())
+        })?;
+        settings.max_concurrent_requests = Some(max);
+    }
+
     settings.validate()?;
     Ok(settings)
 }
@@ -310,6 +423,32 @@ pub struct Tls {
     pub priv_key_file: Option<String>,
 }
 
+/// Client certificate requirement of a mutual TLS listener
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TlsClientAuth {
+    /// Clients must present a certificate signed by the client CA
+    #[default]
+    Required,
+    /// Clients may present a certificate signed by the client CA, or none at all
+    Optional,
+}
+
+impl FromStr for TlsClientAuth {
+    type Err = std::io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "required" => Ok(Self::Required),
+            "optional" => Ok(Self::Optional),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("{s} is not a valid client auth mode, expected `required` or `optional`"),
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Cors {
     pub allowed_origins: Option<AllowedOrigins>,
@@ -529,7 +668,9 @@ where
 mod test {
     use std::str::FromStr;
 
-    use super::{CorsOrigin, ServiceSettings};
+    use std::collections::HashMap;
+
+    use super::{load_settings, CorsOrigin, ServiceSettings, TlsClientAuth};
 
     const GOOD_ORIGINS: &[&str] = &[
         // origins that should be parsed correctly
@@ -592,6 +733,76 @@ mod test {
         );
     }
 
+    #[test]
+    fn settings_limits() {
+        let values = HashMap::from([
+            ("rate_limit_per_second".to_string(), "10".to_string()),
+            ("Rate_Limit_Burst".to_string(), "20".to_string()),
+            ("rate_limit_key_header".to_string(), "x-api-key".to_string()),
+            ("max_request_body_bytes".to_string(), "1024".to_string()),
+            ("max_concurrent_requests".to_string(), "5".to_string()),
+        ]);
+        let s = load_settings(None, &values).expect("load limits");
+        assert_eq!(s.rate_limit_per_second, Some(10));
+        assert_eq!(s.rate_limit_burst, Some(20));
+        assert_eq!(s.rate_limit_key_header.as_deref(), Some("x-api-key"));
+        assert_eq!(s.max_request_body_bytes, Some(1024));
+        assert_eq!(s.max_concurrent_requests, Some(5));
+
+        let s = ServiceSettings::from_json(r#"{"rate_limit_per_second": 3}"#).expect("parse_json");
+        assert_eq!(s.rate_limit_per_second, Some(3));
+        assert_eq!(s.rate_limit_burst, None);
+
+        for (key, value) in [
+            ("rate_limit_per_second", "0"),
+            ("rate_limit_burst", "5"),
+            ("rate_limit_key_header", "not a header"),
+            ("max_concurrent_requests", "0"),
+            ("max_request_body_bytes", "-1"),
+        ] {
+            let values = HashMap::from([(key.to_string(), value.to_string())]);
+            assert!(
+                load_settings(None, &values).is_err(),
+                "{key}={value} (expect err)"
+            );
+        }
+    }
+
+    #[test]
+    fn settings_client_auth() {
+        let ca_file = std::env::current_exe().expect("current executable");
+        let ca_file = ca_file.to_string_lossy();
+        let values = HashMap::from([
+            ("tls_cert_file".to_string(), ca_file.to_string()),
+            ("tls_priv_key_file".to_string(), ca_file.to_string()),
+            ("tls_client_ca_file".to_string(), ca_file.to_string()),
+            ("tls_client_auth".to_string(), "Optional".to_string()),
+        ]);
+        let s = load_settings(None, &values).expect("load client auth");
+        assert_eq!(s.tls_client_ca_file.as_deref(), Some(ca_file.as_ref()));
+        assert_eq!(s.tls_client_auth, Some(TlsClientAuth::Optional));
+
+        for values in [
+            vec![("tls_client_auth", "required")],
+            vec![("tls_client_ca_file", ca_file.as_ref())],
+            vec![
+                ("tls_cert_file", ca_file.as_ref()),
+                ("tls_priv_key_file", ca_file.as_ref()),
+                ("tls_client_ca_file", ca_file.as_ref()),
+                ("tls_client_auth", "sometimes"),
+            ],
+        ] {
+            let values = values
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            assert!(
+                load_settings(None, &values).is_err(),
+                "{values:?} (expect err)"
+            );
+        }
+    }
+
     #[test]
     fn origins_deserialize() {
         // test CorsOrigin
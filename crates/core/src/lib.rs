@@ -17,6 +17,10 @@ pub use otel::*;
 pub mod oci;
 #[cfg(feature = "oci")]
 pub use oci::*;
+#[cfg(feature = "oci")]
+pub mod oci_cache;
+#[cfg(feature = "oci")]
+pub mod signature;
 
 pub mod par;
 pub use par::*;
@@ -38,6 +42,9 @@ pub mod http;
 #[cfg(feature = "messaging")]
 pub mod messaging;
 
+#[cfg(feature = "config-schema")]
+pub mod config_schema;
+
 #[cfg(feature = "http-client-common")]
 pub mod http_client;
 
@@ -1,17 +1,17 @@
-use std::env::temp_dir;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use anyhow::{bail, Context as _};
+use anyhow::{bail, ensure, Context as _};
 use oci_client::client::ClientProtocol;
-use oci_client::client::ImageData;
 use oci_client::Reference;
 use oci_wasm::WASM_LAYER_MEDIA_TYPE;
 use oci_wasm::WASM_MANIFEST_MEDIA_TYPE;
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
+use tracing::warn;
 use wascap::jwt;
 
+use crate::oci_cache::OciCache;
+use crate::signature::SignatureVerifier;
 use crate::RegistryConfig;
 use crate::{tls, UseParFileCache};
 
@@ -37,6 +37,8 @@ pub struct OciFetcher {
     allow_latest: bool,
     allow_insecure: bool,
     auth: oci_client::secrets::RegistryAuth,
+    signature_public_keys: Vec<PathBuf>,
+    cache: OciCache,
 }
 
 impl Default for OciFetcher {
@@ -46,6 +48,8 @@ impl Default for OciFetcher {
             allow_latest: false,
             allow_insecure: false,
             auth: oci_client::secrets::RegistryAuth::Anonymous,
+            signature_public_keys: Vec::default(),
+            cache: OciCache::default(),
         }
     }
 }
@@ -57,6 +61,7 @@ impl From<&RegistryConfig> for OciFetcher {
             allow_latest,
             allow_insecure,
             additional_ca_paths,
+            signature_public_keys,
             ..
         }: &RegistryConfig,
     ) -> Self {
@@ -65,6 +70,8 @@ impl From<&RegistryConfig> for OciFetcher {
             allow_latest: *allow_latest,
             allow_insecure: *allow_insecure,
             additional_ca_paths: additional_ca_paths.clone(),
+            signature_public_keys: signature_public_keys.clone(),
+            cache: OciCache::default(),
         }
     }
 }
@@ -76,6 +83,7 @@ impl From<RegistryConfig> for OciFetcher {
             allow_latest,
             allow_insecure,
             additional_ca_paths,
+            signature_public_keys,
             ..
         }: RegistryConfig,
     ) -> Self {
@@ -84,48 +92,21 @@ impl From<RegistryConfig> for OciFetcher {
             allow_latest,
             allow_insecure,
             additional_ca_paths,
+            signature_public_keys,
+            cache: OciCache::default(),
         }
     }
 }
 
 /// Default directory in which OCI artifacts are cached
 pub async fn oci_cache_dir() -> anyhow::Result<PathBuf> {
-    let path = temp_dir().join("wasmcloud_ocicache");
+    let path = OciCache::default().dir().to_path_buf();
     if !fs::try_exists(&path).await? {
         fs::create_dir_all(&path).await?;
     }
     Ok(path)
 }
 
-#[allow(unused)]
-async fn cache_oci_image(
-    image: ImageData,
-    cache_filepath: impl AsRef<Path>,
-    digest_filepath: impl AsRef<Path>,
-) -> std::io::Result<()> {
-    let mut cache_file = fs::File::create(cache_filepath).await?;
-    let content = image
-        .layers
-        .into_iter()
-        .flat_map(|l| l.data)
-        .collect::<Vec<_>>();
-    cache_file.write_all(&content).await?;
-    cache_file.flush().await?;
-    if let Some(digest) = image.digest {
-        let mut digest_file = fs::File::create(digest_filepath).await?;
-        digest_file.write_all(digest.as_bytes()).await?;
-        digest_file.flush().await?;
-    }
-    Ok(())
-}
-
-fn prune_filepath(img: &str) -> String {
-    let mut img = img.replace(':', "_");
-    img = img.replace('/', "_");
-    img = img.replace('.', "_");
-    img
-}
-
 /// A type to indicate whether there was a cache hit or miss when loading artifacts
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CacheResult {
@@ -134,8 +115,9 @@ pub enum CacheResult {
 }
 
 impl OciFetcher {
-    /// Fetch an OCI artifact to a path and return that path. Returns the path and whether or not
-    /// there was a cache hit/miss
+    /// Fetch an OCI artifact to a cache in `output_dir`, bounded by the maximum size of the cache
+    /// of this fetcher, and return its path. Returns the path and whether or not there was a cache
+    /// hit/miss
     pub async fn fetch_path(
         &self,
         output_dir: impl AsRef<Path>,
@@ -143,17 +125,36 @@ impl OciFetcher {
         accepted_media_types: Vec<&str>,
         cache: OciArtifactCacheUpdate,
     ) -> anyhow::Result<(PathBuf, CacheResult)> {
-        let output_dir = output_dir.as_ref();
-        let img = img.as_ref().to_lowercase(); // the OCI spec does not allow for capital letters in references
-        if !self.allow_latest && img.ends_with(":latest") {
+        let oci_cache = OciCache::new(output_dir.as_ref(), self.cache.max_size());
+        self.fetch_to_cache(&oci_cache, img, accepted_media_types, cache)
+            .await
+    }
+
+    /// Fetch an OCI artifact to the cache of this fetcher and return its path. Returns the path
+    /// and whether or not there was a cache hit/miss
+    pub async fn fetch_cached(
+        &self,
+        img: impl AsRef<str>,
+        accepted_media_types: Vec<&str>,
+        cache: OciArtifactCacheUpdate,
+    ) -> anyhow::Result<(PathBuf, CacheResult)> {
+        self.fetch_to_cache(&self.cache, img, accepted_media_types, cache)
+            .await
+    }
+
+    async fn fetch_to_cache(
+        &self,
+        oci_cache: &OciCache,
+        img: impl AsRef<str>,
+        accepted_media_types: Vec<&str>,
+        cache: OciArtifactCacheUpdate,
+    ) -> anyhow::Result<(PathBuf, CacheResult)> {
+        let img_ref = img.as_ref().to_lowercase(); // the OCI spec does not allow for capital letters in references
+        if !self.allow_latest && img_ref.ends_with(":latest") {
             bail!("fetching images tagged 'latest' is currently prohibited in this host. This option can be overridden with WASMCLOUD_OCI_ALLOW_LATEST")
         }
-        let pruned_filepath = prune_filepath(&img);
-        let cache_file = output_dir.join(&pruned_filepath);
-        let mut digest_file = output_dir.join(&pruned_filepath).clone();
-        digest_file.set_extension("digest");
 
-        let img = Reference::from_str(&img)?;
+        let img = Reference::from_str(&img_ref)?;
 
         let protocol = if self.allow_insecure {
             ClientProtocol::HttpsExcept(vec![img.registry().to_string()])
@@ -178,21 +179,46 @@ impl OciFetcher {
             ..Default::default()
         });
 
-        // In case of a cache miss where the file does not exist, pull a fresh OCI Image
-        if fs::metadata(&cache_file).await.is_ok() {
-            let (_, oci_digest) = c
-                .pull_manifest(&img, &self.auth)
+        let (_, oci_digest) = c
+            .pull_manifest(&img, &self.auth)
+            .await
+            .context("failed to fetch OCI manifest")?;
+
+        // If signatures are required, verify the signature of the current manifest digest before
+        // using either the cache or a fresh pull, which must then match the verified digest
+        if !self.signature_public_keys.is_empty() {
+            let verifier = SignatureVerifier::load(&self.signature_public_keys)
+                .context("failed to load signature public keys")?;
+            verifier
+                .verify(&c, &self.auth, &img, &oci_digest)
                 .await
-                .context("failed to fetch OCI manifest")?;
-            // If the digest file doesn't exist that is ok, we just unwrap to an empty string
-            let file_digest = fs::read_to_string(&digest_file).await.unwrap_or_default();
-            if !oci_digest.is_empty() && !file_digest.is_empty() && file_digest == oci_digest {
-                return Ok((cache_file, CacheResult::Hit));
+                .context("artifact signature verification failed")?;
+        }
+
+        // Artifacts are cached by digest, so a reference is a cache hit if it resolved to the
+        // same digest when it was last cached. Artifacts cached under other references are reused,
+        // but reported as a miss, since the reference changed.
+        if let OciArtifactCacheUpdate::Update = cache {
+            if let Some(path) = oci_cache.get(&oci_digest).await {
+                if oci_cache.lookup(&img_ref).await.as_ref() == Some(&oci_digest) {
+                    return Ok((path, CacheResult::Hit));
+                }
+                oci_cache
+                    .tag(&img_ref, &oci_digest)
+                    .await
+                    .context("failed to cache OCI reference")?;
+                return Ok((path, CacheResult::Miss));
             }
         }
 
+        // Pull by the resolved digest, so that a tag moved after verification cannot substitute
+        // unverified content
         let imgdata = c
-            .pull(&img, &self.auth, accepted_media_types)
+            .pull(
+                &img.clone_with_digest(oci_digest.clone()),
+                &self.auth,
+                accepted_media_types,
+            )
             .await
             .context("failed to fetch OCI bytes")?;
         // As a client, we should reject invalid OCI artifacts
@@ -208,14 +234,36 @@ impl OciFetcher {
                 imgdata.layers.len()
             )
         }
-        // Update the OCI artifact cache if specified
-        if let OciArtifactCacheUpdate::Update = cache {
-            cache_oci_image(imgdata, &cache_file, digest_file)
-                .await
-                .context("failed to cache OCI bytes")?;
+        let digest = match imgdata.digest.as_deref() {
+            Some(digest) => digest,
+            None if self.signature_public_keys.is_empty() => &oci_digest,
+            None => bail!(
+                "artifact digest is unknown, unable to match it against its verified signature"
+            ),
+        };
+        ensure!(
+            self.signature_public_keys.is_empty() || digest == oci_digest,
+            "artifact digest does not match the digest of its verified signature"
+        );
+        let content = imgdata
+            .layers
+            .iter()
+            .flat_map(|l| l.data.iter().copied())
+            .collect::<Vec<_>>();
+        // Only record the reference of the artifact if the cache is to be updated
+        let reference = match cache {
+            OciArtifactCacheUpdate::Update => Some(img_ref.as_str()),
+            OciArtifactCacheUpdate::Ignore => None,
+        };
+        let path = oci_cache
+            .insert(reference, digest, &content)
+            .await
+            .context("failed to cache OCI bytes")?;
+        if let Err(err) = oci_cache.evict(Some(digest)).await {
+            warn!(?err, "failed to evict artifacts from OCI cache");
         }
 
-        Ok((cache_file, CacheResult::Miss))
+        Ok((path, CacheResult::Miss))
     }
 
     /// Fetch component from OCI
@@ -225,8 +273,7 @@ impl OciFetcher {
     /// Returns an error if either fetching fails or reading the fetched OCI path fails
     pub async fn fetch_component(&self, oci_ref: impl AsRef<str>) -> anyhow::Result<Vec<u8>> {
         let (path, _) = self
-            .fetch_path(
-                oci_cache_dir().await?,
+            .fetch_cached(
                 oci_ref,
                 vec![WASM_MEDIA_TYPE, OCI_MEDIA_TYPE, WASM_LAYER_MEDIA_TYPE],
                 OciArtifactCacheUpdate::Update,
@@ -249,8 +296,7 @@ impl OciFetcher {
         host_id: impl AsRef<str>,
     ) -> anyhow::Result<(PathBuf, Option<jwt::Token<jwt::CapabilityProvider>>)> {
         let (path, cache) = self
-            .fetch_path(
-                oci_cache_dir().await?,
+            .fetch_cached(
                 oci_ref.as_ref(),
                 vec![PROVIDER_ARCHIVE_MEDIA_TYPE, OCI_MEDIA_TYPE],
                 OciArtifactCacheUpdate::Update,
@@ -266,6 +312,35 @@ impl OciFetcher {
             .with_context(|| format!("failed to read `{}`", path.display()))
     }
 
+    /// Fetch a component or provider from OCI to the cache ahead of its use, returning its path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching fails
+    pub async fn prefetch(&self, oci_ref: impl AsRef<str>) -> anyhow::Result<PathBuf> {
+        let (path, _) = self
+            .fetch_cached(
+                oci_ref,
+                vec![
+                    WASM_MEDIA_TYPE,
+                    OCI_MEDIA_TYPE,
+                    WASM_LAYER_MEDIA_TYPE,
+                    PROVIDER_ARCHIVE_MEDIA_TYPE,
+                ],
+                OciArtifactCacheUpdate::Update,
+            )
+            .await
+            .context("failed to fetch OCI path")?;
+        Ok(path)
+    }
+
+    /// Used to set the cache that artifacts are fetched to
+    #[must_use]
+    pub fn with_cache(mut self, cache: OciCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
     /// Used to set additional CA paths that will be used as part of fetching components and providers
     pub fn with_additional_ca_paths(mut self, paths: &[impl AsRef<Path>]) -> Self {
         self.additional_ca_paths = paths.iter().map(AsRef::as_ref).map(PathBuf::from).collect();
@@ -0,0 +1,416 @@
+//! Managed cache of OCI artifacts. This module requires the `oci` feature to be enabled
+//!
+//! Artifacts are stored once per manifest digest in the `blobs` directory of the cache, so that
+//! references with different tags resolving to the same digest share their content. The `refs`
+//! directory maps each reference to the digest it last resolved to. If the cache has a maximum
+//! size, the least recently used artifacts are evicted when it is exceeded.
+
+use std::cmp::Reverse;
+use std::env::temp_dir;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tracing::{debug, warn};
+
+/// Name of the default cache directory in the temporary directory
+const DEFAULT_CACHE_DIR_NAME: &str = "wasmcloud_ocicache";
+
+/// Counter making names of temporary files unique within the process
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Contents of a file in the `refs` directory
+#[derive(Deserialize, Serialize)]
+struct CachedRef {
+    reference: String,
+    digest: String,
+}
+
+/// An artifact in an [`OciCache`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheEntry {
+    /// The manifest digest of the artifact
+    pub digest: String,
+    /// The references resolving to the artifact, sorted
+    pub references: Vec<String>,
+    /// The size of the artifact in bytes
+    pub size: u64,
+    /// When the artifact was last fetched or used
+    pub last_used: SystemTime,
+    /// The path to the artifact in the cache
+    pub path: PathBuf,
+}
+
+/// A managed OCI artifact cache in a directory, with an optional maximum size in bytes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OciCache {
+    dir: PathBuf,
+    max_size: Option<u64>,
+}
+
+impl Default for OciCache {
+    fn default() -> Self {
+        Self::new(temp_dir().join(DEFAULT_CACHE_DIR_NAME), None)
+    }
+}
+
+/// Replace characters in `s` which are not allowed or meaningful in file names
+fn normalize_for_filename(s: &str) -> String {
+    s.replace([':', '/', '.', '@'], "_")
+}
+
+impl OciCache {
+    /// Construct a cache in `dir`, which is created on first use. If `max_size` is set, the least
+    /// recently used artifacts are evicted once the cache exceeds it.
+    pub fn new(dir: impl Into<PathBuf>, max_size: Option<u64>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_size,
+        }
+    }
+
+    /// Returns the directory of the cache
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the maximum size of the cache in bytes, if it is bounded
+    #[must_use]
+    pub fn max_size(&self) -> Option<u64> {
+        self.max_size
+    }
+
+    fn blobs_dir(&self) -> PathBuf {
+        self.dir.join("blobs")
+    }
+
+    fn refs_dir(&self) -> PathBuf {
+        self.dir.join("refs")
+    }
+
+    fn blob_path(&self, digest: &str) -> PathBuf {
+        self.blobs_dir().join(normalize_for_filename(digest))
+    }
+
+    fn ref_path(&self, reference: &str) -> PathBuf {
+        self.refs_dir()
+            .join(normalize_for_filename(reference))
+            .with_extension("json")
+    }
+
+    /// Returns the digest `reference` resolved to when it was last cached, if any
+    pub async fn lookup(&self, reference: &str) -> Option<String> {
+        let data = fs::read(self.ref_path(reference)).await.ok()?;
+        let cached = serde_json::from_slice::<CachedRef>(&data).ok()?;
+        (cached.reference == reference).then_some(cached.digest)
+    }
+
+    /// Returns the path to the artifact with manifest digest `digest`, if it is cached, marking
+    /// it as used
+    pub async fn get(&self, digest: &str) -> Option<PathBuf> {
+        let path = self.blob_path(digest);
+        let file = fs::OpenOptions::new().write(true).open(&path).await.ok()?;
+        if let Err(err) = file.into_std().await.set_modified(SystemTime::now()) {
+            debug!(?err, path = %path.display(), "failed to mark cached artifact as used");
+        }
+        Some(path)
+    }
+
+    /// Record that `reference` resolves to the cached artifact with manifest digest `digest`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reference cannot be written to the cache
+    pub async fn tag(&self, reference: &str, digest: &str) -> Result<()> {
+        fs::create_dir_all(self.refs_dir())
+            .await
+            .context("failed to create cache refs directory")?;
+        let data = serde_json::to_vec(&CachedRef {
+            reference: reference.to_string(),
+            digest: digest.to_string(),
+        })
+        .context("failed to encode cached reference")?;
+        write_atomic(&self.ref_path(reference), &data).await
+    }
+
+    /// Store `content`, the artifact with manifest digest `digest`, in the cache, returning its
+    /// path. Artifacts already cached are not written again. If `reference` is set, it is
+    /// recorded to resolve to the artifact.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the artifact cannot be written to the cache
+    pub async fn insert(
+        &self,
+        reference: Option<&str>,
+        digest: &str,
+        content: &[u8],
+    ) -> Result<PathBuf> {
+        let path = match self.get(digest).await {
+            Some(path) => path,
+            None => {
+                fs::create_dir_all(self.blobs_dir())
+                    .await
+                    .context("failed to create cache blobs directory")?;
+                let path = self.blob_path(digest);
+                write_atomic(&path, content).await?;
+                path
+            }
+        };
+        if let Some(reference) = reference {
+            self.tag(reference, digest).await?;
+        }
+        Ok(path)
+    }
+
+    /// List the cached artifacts, most recently used first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory cannot be read
+    pub async fn list(&self) -> Result<Vec<CacheEntry>> {
+        let mut entries = Vec::new();
+        let mut blobs = match fs::read_dir(self.blobs_dir()).await {
+            Ok(blobs) => blobs,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(entries),
+            Err(err) => return Err(err).context("failed to read cache blobs directory"),
+        };
+        let refs = self.refs().await?;
+        while let Some(blob) = blobs
+            .next_entry()
+            .await
+            .context("failed to read cache blobs directory")?
+        {
+            let name = blob.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            // Skip artifacts being written
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(metadata) = blob.metadata().await else {
+                continue;
+            };
+            let mut digest = None;
+            let mut references = Vec::new();
+            for cached in &refs {
+                if normalize_for_filename(&cached.digest) == name {
+                    digest = Some(cached.digest.clone());
+                    references.push(cached.reference.clone());
+                }
+            }
+            references.sort();
+            entries.push(CacheEntry {
+                digest: digest.unwrap_or_else(|| name.to_string()),
+                references,
+                size: metadata.len(),
+                last_used: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                path: blob.path(),
+            });
+        }
+        entries.sort_by_key(|entry| Reverse(entry.last_used));
+        Ok(entries)
+    }
+
+    async fn refs(&self) -> Result<Vec<CachedRef>> {
+        let mut refs = Vec::new();
+        let mut dir = match fs::read_dir(self.refs_dir()).await {
+            Ok(dir) => dir,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(refs),
+            Err(err) => return Err(err).context("failed to read cache refs directory"),
+        };
+        while let Some(entry) = dir
+            .next_entry()
+            .await
+            .context("failed to read cache refs directory")?
+        {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(data) = fs::read(entry.path()).await else {
+                continue;
+            };
+            match serde_json::from_slice::<CachedRef>(&data) {
+                Ok(cached) => refs.push(cached),
+                Err(err) => warn!(?err, path = %entry.path().display(), "invalid cached reference"),
+            }
+        }
+        Ok(refs)
+    }
+
+    /// Remove `entry` and the references resolving to it from the cache
+    async fn remove(&self, entry: &CacheEntry) -> Result<()> {
+        for reference in &entry.references {
+            remove_file(&self.ref_path(reference)).await?;
+        }
+        remove_file(&entry.path).await
+    }
+
+    /// Remove all cached artifacts none of whose references satisfy `keep`, returning the removed
+    /// artifacts
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache cannot be read or an artifact cannot be removed
+    pub async fn prune(&self, keep: impl Fn(&str) -> bool) -> Result<Vec<CacheEntry>> {
+        let mut removed = Vec::new();
+        for entry in self.list().await? {
+            if entry.references.iter().any(|reference| keep(reference)) {
+                continue;
+            }
+            self.remove(&entry).await?;
+            removed.push(entry);
+        }
+        Ok(removed)
+    }
+
+    /// Evict the least recently used artifacts until the cache does not exceed its maximum size,
+    /// returning the evicted artifacts. The artifact with digest `retain` is never evicted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache cannot be read or an artifact cannot be removed
+    pub async fn evict(&self, retain: Option<&str>) -> Result<Vec<CacheEntry>> {
+        let Some(max_size) = self.max_size else {
+            return Ok(Vec::default());
+        };
+        let mut entries = self.list().await?;
+        let mut size = entries.iter().map(|entry| entry.size).sum::<u64>();
+        let retain = retain.map(|digest| self.blob_path(digest));
+        let mut evicted = Vec::new();
+        // Entries are sorted by most recent use, so evict from the back
+        while size > max_size {
+            let Some(i) = entries
+                .iter()
+                .rposition(|entry| Some(&entry.path) != retain.as_ref())
+            else {
+                warn!(size, max_size, "OCI cache exceeds its maximum size");
+                break;
+            };
+            let entry = entries.remove(i);
+            debug!(
+                digest = entry.digest,
+                size = entry.size,
+                "evicting cached artifact"
+            );
+            self.remove(&entry).await?;
+            size = size.saturating_sub(entry.size);
+            evicted.push(entry);
+        }
+        Ok(evicted)
+    }
+}
+
+/// Write `data` to `path` by renaming a temporary file, so that concurrent readers never observe
+/// a partially written file
+async fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let name = path
+        .file_name()
+        .context("cache path has no file name")?
+        .to_string_lossy();
+    let tmp = path.with_file_name(format!(
+        ".{name}.{}.{}",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&tmp, data)
+        .await
+        .with_context(|| format!("failed to write `{}`", tmp.display()))?;
+    fs::rename(&tmp, path)
+        .await
+        .with_context(|| format!("failed to write `{}`", path.display()))
+}
+
+async fn remove_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path).await {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).with_context(|| format!("failed to remove `{}`", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::time::Duration;
+
+    const DIGEST_A: &str = "sha256:aaaa";
+    const DIGEST_B: &str = "sha256:bbbb";
+
+    async fn set_last_used(cache: &OciCache, digest: &str, secs: u64) -> Result<()> {
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .open(cache.blob_path(digest))
+            .await?;
+        file.into_std()
+            .await
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn caches_artifacts_by_digest() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache = OciCache::new(dir.path(), Some(10));
+        assert!(cache.list().await?.is_empty());
+
+        let path = cache
+            .insert(Some("ghcr.io/acme/a:0.1.0"), DIGEST_A, b"aaaaaa")
+            .await?;
+        assert_eq!(fs::read(&path).await?, b"aaaaaa");
+        assert_eq!(
+            cache.lookup("ghcr.io/acme/a:0.1.0").await.as_deref(),
+            Some(DIGEST_A)
+        );
+        // Tags resolving to the same digest share the cached artifact
+        assert_eq!(
+            cache
+                .insert(Some("ghcr.io/acme/a:latest"), DIGEST_A, b"aaaaaa")
+                .await?,
+            path
+        );
+        let entries = cache.list().await?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].references,
+            ["ghcr.io/acme/a:0.1.0", "ghcr.io/acme/a:latest"]
+        );
+        assert_eq!(entries[0].size, 6);
+
+        // Exceeding the maximum size evicts the least recently used artifact
+        set_last_used(&cache, DIGEST_A, 1).await?;
+        cache
+            .insert(Some("ghcr.io/acme/b:0.1.0"), DIGEST_B, b"bbbbbb")
+            .await?;
+        let evicted = cache.evict(Some(DIGEST_B)).await?;
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].digest, DIGEST_A);
+        assert!(cache.get(DIGEST_A).await.is_none());
+        assert!(cache.lookup("ghcr.io/acme/a:0.1.0").await.is_none());
+        assert_eq!(
+            cache.lookup("ghcr.io/acme/b:0.1.0").await.as_deref(),
+            Some(DIGEST_B)
+        );
+
+        // Pruning keeps artifacts with a kept reference
+        cache
+            .insert(Some("ghcr.io/acme/a:0.1.0"), DIGEST_A, b"aa")
+            .await?;
+        let removed = cache
+            .prune(|reference| reference == "ghcr.io/acme/a:0.1.0")
+            .await?;
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].digest, DIGEST_B);
+        let entries = cache.list().await?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].digest, DIGEST_A);
+        Ok(())
+    }
+}
@@ -56,7 +56,7 @@ impl From<RegistryAuth> for oci_client::secrets::RegistryAuth {
 }
 
 /// Credentials for a registry containing wasmCloud artifacts
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 #[non_exhaustive]
 pub struct RegistryConfig {
     /// The type of the registry (only OCI is supported at this time)
@@ -69,6 +69,9 @@ pub struct RegistryConfig {
     pub(crate) allow_insecure: bool,
     /// Additional CAs to include in the OCI client configuration
     pub(crate) additional_ca_paths: Vec<PathBuf>,
+    /// Paths to PEM encoded public keys. If any are set, artifacts must have a cosign-compatible
+    /// signature valid for one of the keys. Only valid for OCI registries
+    pub(crate) signature_public_keys: Vec<PathBuf>,
 }
 
 /// Builder for constructing a [`RegistryConfig`]
@@ -83,6 +86,7 @@ pub struct RegistryConfigBuilder {
     allow_latest: Option<bool>,
     allow_insecure: Option<bool>,
     additional_ca_paths: Option<Vec<PathBuf>>,
+    signature_public_keys: Option<Vec<PathBuf>>,
 }
 
 impl RegistryConfigBuilder {
@@ -111,6 +115,11 @@ impl RegistryConfigBuilder {
         self
     }
 
+    pub fn signature_public_keys(mut self, keys: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.signature_public_keys = Some(keys.into_iter().collect::<Vec<PathBuf>>());
+        self
+    }
+
     pub fn build(self) -> Result<RegistryConfig> {
         let allow_insecure = self.allow_insecure.unwrap_or_default();
         Ok(RegistryConfig {
@@ -123,6 +132,7 @@ impl RegistryConfigBuilder {
             allow_latest: self.allow_latest.unwrap_or_default(),
             allow_insecure,
             additional_ca_paths: self.additional_ca_paths.unwrap_or_default(),
+            signature_public_keys: self.signature_public_keys.unwrap_or_default(),
         })
     }
 }
@@ -167,4 +177,12 @@ impl RegistryConfig {
     pub fn set_additional_ca_paths(&mut self, value: Vec<PathBuf>) {
         self.additional_ca_paths = value;
     }
+
+    pub fn signature_public_keys(&self) -> &Vec<PathBuf> {
+        &self.signature_public_keys
+    }
+
+    pub fn set_signature_public_keys(&mut self, value: Vec<PathBuf>) {
+        self.signature_public_keys = value;
+    }
 }
@@ -0,0 +1,310 @@
+//! Verification of cosign-compatible detached signatures of OCI artifacts using local public keys.
+//! This module requires the `oci` feature to be enabled
+//!
+//! Signatures are looked up the way `cosign sign --key` stores them: in an artifact in the same
+//! repository tagged `<algorithm>-<hex>.sig` after the manifest digest of the signed artifact.
+//! Each layer of the signature artifact contains a simple signing payload referencing the signed
+//! digest, with the base64 encoded ECDSA P-256 signature of the payload in its
+//! `dev.cosignproject.cosign/signature` annotation. An artifact is verified if any signature of
+//! it is valid for any of the trusted public keys.
+
+use std::path::Path;
+
+use anyhow::{bail, ensure, Context as _, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use oci_client::client::ImageLayer;
+use oci_client::secrets::RegistryAuth;
+use oci_client::{Client, Reference};
+use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_ASN1};
+use serde::Deserialize;
+use tracing::debug;
+
+/// Media type of the layers of a cosign signature artifact
+pub const SIMPLE_SIGNING_MEDIA_TYPE: &str = "application/vnd.dev.cosign.simplesigning.v1+json";
+
+/// Annotation of a signature layer containing the base64 encoded signature of its payload
+pub const SIGNATURE_ANNOTATION: &str = "dev.cosignproject.cosign/signature";
+
+/// The `type` of cosign simple signing payloads
+const SIMPLE_SIGNING_TYPE: &str = "cosign container image signature";
+
+/// DER prefix of the SubjectPublicKeyInfo of an ECDSA P-256 public key, followed by the
+/// uncompressed point
+const P256_SPKI_PREFIX: &[u8] = &[
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+#[derive(Deserialize)]
+struct SimpleSigning {
+    critical: Critical,
+}
+
+#[derive(Deserialize)]
+struct Critical {
+    image: SignedImage,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+#[derive(Deserialize)]
+struct SignedImage {
+    #[serde(rename = "docker-manifest-digest")]
+    digest: String,
+}
+
+/// Verifier of detached signatures of OCI artifacts
+#[derive(Clone, Debug, Default)]
+pub struct SignatureVerifier {
+    /// Uncompressed ECDSA P-256 points of the trusted public keys
+    keys: Vec<Vec<u8>>,
+}
+
+impl SignatureVerifier {
+    /// Construct a verifier trusting the PEM encoded ECDSA P-256 public keys in `pem`, as
+    /// generated by `cosign generate-key-pair`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pem` contains no public keys or a key is not an ECDSA P-256 key
+    pub fn from_pem(pem: &[u8]) -> Result<Self> {
+        let mut keys = Vec::new();
+        for item in rustls_pemfile::read_all(&mut &*pem) {
+            let rustls_pemfile::Item::SubjectPublicKeyInfo(spki) =
+                item.context("failed to parse PEM")?
+            else {
+                continue;
+            };
+            let point = spki
+                .as_ref()
+                .strip_prefix(P256_SPKI_PREFIX)
+                .context("public key is not an ECDSA P-256 key")?;
+            keys.push(point.to_vec());
+        }
+        ensure!(!keys.is_empty(), "no public keys found in PEM");
+        Ok(Self { keys })
+    }
+
+    /// Construct a verifier trusting the public keys in the PEM files at `paths`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the files cannot be read or contains invalid public keys
+    pub fn load(paths: impl IntoIterator<Item = impl AsRef<Path>>) -> Result<Self> {
+        let mut keys = Vec::new();
+        for path in paths {
+            let path = path.as_ref();
+            let pem = std::fs::read(path)
+                .with_context(|| format!("failed to read public key `{}`", path.display()))?;
+            let verifier = Self::from_pem(&pem)
+                .with_context(|| format!("invalid public key `{}`", path.display()))?;
+            keys.extend(verifier.keys);
+        }
+        Ok(Self { keys })
+    }
+
+    /// Returns whether the verifier trusts no public keys
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Verify that the signature `signature`, base64 encoded, of the simple signing `payload` is
+    /// valid for one of the trusted keys and that the payload signs the manifest digest `digest`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature is invalid or the payload does not sign `digest`
+    pub fn verify_payload(&self, payload: &[u8], signature: &str, digest: &str) -> Result<()> {
+        let signature = STANDARD
+            .decode(signature.trim())
+            .context("failed to decode signature")?;
+        ensure!(
+            self.keys.iter().any(|key| {
+                UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, key)
+                    .verify(payload, &signature)
+                    .is_ok()
+            }),
+            "signature is not valid for any of the trusted public keys"
+        );
+        let payload = serde_json::from_slice::<SimpleSigning>(payload)
+            .context("failed to parse signature payload")?;
+        ensure!(
+            payload.critical.ty == SIMPLE_SIGNING_TYPE,
+            "unsupported signature payload type `{}`",
+            payload.critical.ty
+        );
+        ensure!(
+            payload.critical.image.digest == digest,
+            "signature is for digest `{}`, but the artifact has digest `{digest}`",
+            payload.critical.image.digest
+        );
+        Ok(())
+    }
+
+    /// Verify that any of the signature `layers` of an artifact is valid for the manifest digest
+    /// `digest`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if none of the layers contains a valid signature
+    pub fn verify_layers(&self, layers: &[ImageLayer], digest: &str) -> Result<()> {
+        let mut last_err = None;
+        for layer in layers {
+            if layer.media_type != SIMPLE_SIGNING_MEDIA_TYPE {
+                continue;
+            }
+            let Some(signature) = layer
+                .annotations
+                .as_ref()
+                .and_then(|annotations| annotations.get(SIGNATURE_ANNOTATION))
+            else {
+                continue;
+            };
+            match self.verify_payload(&layer.data, signature, digest) {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    debug!(?err, "signature verification failed");
+                    last_err = Some(err);
+                }
+            }
+        }
+        match last_err {
+            Some(err) => Err(err),
+            None => bail!("no signatures found"),
+        }
+    }
+
+    /// Fetch the signatures of the artifact `image` with manifest digest `digest` using `client`
+    /// and verify that any of them is valid
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the artifact is unsigned or none of its signatures are valid
+    pub async fn verify(
+        &self,
+        client: &Client,
+        auth: &RegistryAuth,
+        image: &Reference,
+        digest: &str,
+    ) -> Result<()> {
+        let signatures = signature_reference(image, digest)?;
+        let signatures = client
+            .pull(&signatures, auth, vec![SIMPLE_SIGNING_MEDIA_TYPE])
+            .await
+            .with_context(|| {
+                format!("failed to fetch signatures of `{image}`, the artifact may be unsigned")
+            })?;
+        self.verify_layers(&signatures.layers, digest)
+            .with_context(|| format!("failed to verify signature of `{image}`"))
+    }
+}
+
+/// Returns the reference of the cosign signature artifact of `image` with manifest digest `digest`
+///
+/// # Errors
+///
+/// Returns an error if `digest` is not of the form `<algorithm>:<hex>`
+pub fn signature_reference(image: &Reference, digest: &str) -> Result<Reference> {
+    let (algorithm, hex) = digest
+        .split_once(':')
+        .with_context(|| format!("invalid digest `{digest}`"))?;
+    Ok(Reference::with_tag(
+        image.registry().to_string(),
+        image.repository().to_string(),
+        format!("{algorithm}-{hex}.sig"),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair as _, ECDSA_P256_SHA256_ASN1_SIGNING};
+
+    const DIGEST: &str = "sha256:4f3c0c4d07bd6b0f56b1c6dc2f1d6ba1a4b8d0ab2f6e5c7a4c03eb3a0c1b5e2d";
+
+    fn key_pair() -> Result<(EcdsaKeyPair, Vec<u8>)> {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng)
+            .map_err(|_| anyhow::anyhow!("failed to generate key"))?;
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+            .map_err(|_| anyhow::anyhow!("failed to parse key"))?;
+        let spki = [P256_SPKI_PREFIX, key.public_key().as_ref()].concat();
+        let pem = format!(
+            "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+            STANDARD.encode(spki)
+        );
+        Ok((key, pem.into_bytes()))
+    }
+
+    fn signature_layer(key: &EcdsaKeyPair, digest: &str) -> Result<ImageLayer> {
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "critical": {
+                "identity": { "docker-reference": "ghcr.io/wasmcloud/http-hello-world" },
+                "image": { "docker-manifest-digest": digest },
+                "type": SIMPLE_SIGNING_TYPE,
+            },
+            "optional": null,
+        }))?;
+        let signature = key
+            .sign(&SystemRandom::new(), &payload)
+            .map_err(|_| anyhow::anyhow!("failed to sign payload"))?;
+        Ok(ImageLayer::new(
+            payload,
+            SIMPLE_SIGNING_MEDIA_TYPE.to_string(),
+            Some(BTreeMap::from([(
+                SIGNATURE_ANNOTATION.to_string(),
+                STANDARD.encode(signature),
+            )])),
+        ))
+    }
+
+    #[test]
+    fn verifies_signatures() -> Result<()> {
+        let (key, pem) = key_pair()?;
+        let (other_key, other_pem) = key_pair()?;
+        let verifier = SignatureVerifier::from_pem(&pem)?;
+
+        verifier.verify_layers(&[signature_layer(&key, DIGEST)?], DIGEST)?;
+        verifier.verify_layers(
+            &[
+                signature_layer(&other_key, DIGEST)?,
+                signature_layer(&key, DIGEST)?,
+            ],
+            DIGEST,
+        )?;
+        // Signatures of other keys, signatures of other digests and missing signatures are rejected
+        assert!(verifier
+            .verify_layers(&[signature_layer(&other_key, DIGEST)?], DIGEST)
+            .is_err());
+        let mismatched = "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+        let err = verifier
+            .verify_layers(&[signature_layer(&key, mismatched)?], DIGEST)
+            .expect_err("signature of other digest should be rejected");
+        assert!(err.to_string().contains("but the artifact has digest"));
+        assert!(verifier.verify_layers(&[], DIGEST).is_err());
+
+        let verifier = SignatureVerifier::from_pem(&[pem, other_pem].concat())?;
+        verifier.verify_layers(&[signature_layer(&other_key, DIGEST)?], DIGEST)?;
+        assert!(SignatureVerifier::from_pem(b"").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn signature_references() -> Result<()> {
+        let image = Reference::try_from("ghcr.io/wasmcloud/http-hello-world:0.1.0")?;
+        assert_eq!(
+            signature_reference(&image, DIGEST)?.whole(),
+            format!(
+                "ghcr.io/wasmcloud/http-hello-world:sha256-{}.sig",
+                DIGEST.trim_start_matches("sha256:")
+            )
+        );
+        Ok(())
+    }
+}
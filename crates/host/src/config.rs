@@ -1,26 +1,146 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use anyhow::Context as _;
+use futures::StreamExt as _;
 use tokio::sync::watch::{self, Receiver};
+use tracing::{error, warn};
 
-use crate::store::{DefaultStore, StoreManager};
+use crate::store::{DefaultStore, FileStore, StoreEvent, StoreManager, WatchUnsupported};
 
 #[async_trait::async_trait]
 /// A trait for managing a config store which can be watched to receive updates to the config
 pub trait ConfigManager: StoreManager {
     /// Watches a config by name and returns a receiver that will be notified when the config changes
     ///
-    /// The default implementation returns a receiver that will never receive any updates.
+    /// The default implementation is built on [StoreManager::watch], so it receives updates from
+    /// any store that reports its changes. For stores that do not support watching, it returns a
+    /// receiver that will never receive any updates.
     async fn watch(&self, name: &str) -> anyhow::Result<Receiver<HashMap<String, String>>> {
-        let config = match self.get(name).await {
+        // Start watching before the initial read so no update in between is missed
+        let watcher = match StoreManager::watch(self, name).await {
+            Ok(watcher) => Some(watcher),
+            Err(err) if err.is::<WatchUnsupported>() => None,
+            Err(err) => return Err(err.context("Failed to watch config")),
+        };
+        let config: HashMap<String, String> = match self.get(name).await {
             Ok(Some(data)) => serde_json::from_slice(&data)
                 .context("Data corruption error, unable to decode data from store")?,
             Ok(None) => return Err(anyhow::anyhow!("Config {} does not exist", name)),
             Err(e) => return Err(anyhow::anyhow!("Error fetching config {}: {}", name, e)),
         };
-        Ok(watch::channel(config).1)
+
+        let Some(mut watcher) = watcher else {
+            return Ok(watch::channel(config).1);
+        };
+
+        let (tx, rx) = watch::channel(config);
+        // Since we're starting a task, we need to own this data
+        let name = name.to_owned();
+
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    event = watcher.next() => event,
+                    () = tx.closed() => {
+                        warn!(%name, "config watch channel closed, aborting watch");
+                        return;
+                    }
+                };
+                match event {
+                    // The watch is on a prefix, so skip configs that merely share this name as a prefix
+                    Some(Ok(event)) if event.key() != name => continue,
+                    Some(Ok(StoreEvent::Delete { .. })) => {
+                        // NOTE(thomastaylor312): We should probably do something and notify something up
+                        // the chain if we get a delete or purge event of a config that is still being used.
+                        // For now we just zero it out
+                        tx.send_replace(HashMap::new());
+                    }
+                    Some(Ok(StoreEvent::Put { value, .. })) => {
+                        let config: HashMap<String, String> = match serde_json::from_slice(&value) {
+                            Ok(config) => config,
+                            Err(e) => {
+                                error!(%name, error = %e, "Error decoding config from store during watch");
+                                continue;
+                            }
+                        };
+                        tx.send_if_modified(|current| {
+                            if current == &config {
+                                false
+                            } else {
+                                *current = config;
+                                true
+                            }
+                        });
+                    }
+                    Some(Err(e)) => {
+                        error!(%name, error = %e, "Error reading from watcher for config. Will wait for next entry");
+                    }
+                    None => {
+                        error!(%name, "Watcher for config has closed");
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(rx)
     }
 }
 
-/// A default implementation of the config manager that does not watch for updates
+/// The in-memory store notifies config watchers of changes made through it
 impl ConfigManager for DefaultStore {}
+
+/// The file store notifies config watchers of changes made through it
+impl ConfigManager for FileStore {}
+
+/// Any store can be used for config, which is watched through [StoreManager::watch]
+impl ConfigManager for Arc<dyn StoreManager> {}
+
+#[cfg(test)]
+mod test {
+    use bytes::Bytes;
+    use tokio::sync::RwLock;
+
+    use super::*;
+
+    /// A store implementing only the required methods of [StoreManager]
+    #[derive(Default)]
+    struct MinimalStore(RwLock<HashMap<String, Bytes>>);
+
+    #[async_trait::async_trait]
+    impl StoreManager for MinimalStore {
+        async fn get(&self, key: &str) -> anyhow::Result<Option<Bytes>> {
+            Ok(self.0.read().await.get(key).cloned())
+        }
+
+        async fn put(&self, key: &str, value: Bytes) -> anyhow::Result<()> {
+            self.0.write().await.insert(key.to_string(), value);
+            Ok(())
+        }
+
+        async fn del(&self, key: &str) -> anyhow::Result<()> {
+            self.0.write().await.remove(key);
+            Ok(())
+        }
+    }
+
+    impl ConfigManager for MinimalStore {}
+
+    #[tokio::test]
+    async fn watches_stores_without_watch_support() -> anyhow::Result<()> {
+        let store = MinimalStore::default();
+        store
+            .put("greeting", Bytes::from_static(br#"{"message":"hello"}"#))
+            .await?;
+        let rx = ConfigManager::watch(&store, "greeting")
+            .await
+            .context("failed to watch config")?;
+        assert_eq!(
+            rx.borrow().get("message").map(String::as_str),
+            Some("hello")
+        );
+        assert!(ConfigManager::watch(&store, "missing").await.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,451 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Context as _;
+use cloudevents::{EventBuilder as _, EventBuilderV10};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use ulid::Ulid;
+use uuid::Uuid;
+use wascap::jwt;
+use wasmcloud_control_interface::{
+    ComponentClaims, ComponentScaleFailed, ComponentScaled, ConfigChanged, HealthCheck,
+    HostDrainStatus, HostInventory, HostStarted, HostStopped, LabelsChanged, Link, LinkdefDeleted,
+    LinkdefSetFailed, ProviderClaims, ProviderStartFailed, ProviderStarted, ProviderStopped,
+    WASMBUS_EVENT_VERSION, WASMBUS_EVENT_VERSION_EXTENSION,
+};
+
+pub use wasmcloud_control_interface::WasmbusEvent;
+
+/// Webhook implementation of [EventPublisher], delivering events as CloudEvents over HTTP
+pub mod webhook;
+
+/// A trait for publishing wasmbus events. This can be implemented by any transport or bus
+/// implementation that can send the serialized event to the appropriate destination.
+#[async_trait::async_trait]
+pub trait EventPublisher: Send + Sync {
+    /// Publish an event that occurred in the host. [WasmbusEvent::name] is the type of event being
+    /// published, and [WasmbusEvent::data] is the payload of the event. It's up to the
+    /// implementation to determine how to handle events. By default, this is a no-op.
+    async fn publish_event(&self, _event: WasmbusEvent) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Deliver the events published so far, called once when the host stops after the final
+    /// event has been published. By default, this is a no-op.
+    async fn flush(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// A default implementation of the EventPublisher trait that does nothing.
+/// This is useful for testing or when no event publishing is required.
+#[derive(Default)]
+pub struct DefaultEventPublisher {}
+impl EventPublisher for DefaultEventPublisher {}
+
+/// Wraps `event` in a CloudEvents envelope built from `event_builder`, which carries the source
+pub(crate) fn cloud_event(
+    event_builder: &EventBuilderV10,
+    event: &WasmbusEvent,
+) -> anyhow::Result<cloudevents::Event> {
+    let data = event.data().context("failed to serialize event data")?;
+    let now = OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .context("failed to format current time")?;
+    event_builder
+        .clone()
+        .ty(event.event_type())
+        .id(Uuid::from_u128(Ulid::new().into()).to_string())
+        .time(now)
+        .extension(WASMBUS_EVENT_VERSION_EXTENSION, WASMBUS_EVENT_VERSION)
+        .data("application/json", data)
+        .build()
+        .context("failed to build cloud event")
+}
+
+fn format_component_claims(claims: &jwt::Claims<jwt::Component>) -> ComponentClaims {
+    let not_before_human = claims
+        .not_before
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| "never".to_string());
+    let expires_human = claims
+        .expires
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| "never".to_string());
+    let summary = ComponentClaims::new(&claims.issuer, not_before_human, expires_human);
+    if let Some(component) = &claims.metadata {
+        summary
+            .with_call_alias(component.call_alias.clone())
+            .with_tags(component.tags.clone())
+            .with_name(component.name.clone())
+            .with_version(component.ver.clone())
+            .with_revision(component.rev)
+    } else {
+        summary
+    }
+}
+
+/// Generates an event for when a component is scaled
+///
+/// # Arguments
+/// * `claims` - Optional component claims
+/// * `annotations` - Key-value pairs of metadata annotations
+/// * `host_id` - ID of the host where scaling occurred
+/// * `max_instances` - Maximum number of instances to scale to
+/// * `image_ref` - Reference to the component image
+/// * `component_id` - Unique identifier for the component
+///
+/// # Returns
+/// Event containing scaling details and component metadata
+pub fn component_scaled(
+    claims: Option<&jwt::Claims<jwt::Component>>,
+    annotations: &BTreeMap<String, String>,
+    host_id: impl AsRef<str>,
+    max_instances: impl Into<usize>,
+    image_ref: impl AsRef<str>,
+    component_id: impl AsRef<str>,
+) -> WasmbusEvent {
+    let event = ComponentScaled::new(
+        host_id.as_ref(),
+        component_id.as_ref(),
+        image_ref.as_ref(),
+        max_instances.into(),
+    )
+    .with_annotations(annotations.clone());
+    WasmbusEvent::ComponentScaled(if let Some(claims) = claims {
+        event.with_claims(&claims.subject, format_component_claims(claims))
+    } else {
+        event
+    })
+}
+
+/// Generates an event for when component scaling fails
+///
+/// # Arguments
+/// * `claims` - Optional component claims
+/// * `annotations` - Key-value pairs of metadata annotations
+/// * `host_id` - ID of the host where scaling failed
+/// * `image_ref` - Reference to the component image
+/// * `component_id` - Unique identifier for the component
+/// * `max_instances` - Target number of instances that failed to scale
+/// * `error` - The error that caused the scaling failure
+///
+/// # Returns
+/// Event containing scaling failure details and error information
+pub fn component_scale_failed(
+    claims: Option<&jwt::Claims<jwt::Component>>,
+    annotations: &BTreeMap<String, String>,
+    host_id: impl AsRef<str>,
+    image_ref: impl AsRef<str>,
+    component_id: impl AsRef<str>,
+    max_instances: u32,
+    error: &anyhow::Error,
+) -> WasmbusEvent {
+    let event = ComponentScaleFailed::new(
+        host_id.as_ref(),
+        component_id.as_ref(),
+        image_ref.as_ref(),
+        max_instances,
+        format!("{error:#}"),
+    )
+    .with_annotations(annotations.clone());
+    WasmbusEvent::ComponentScaleFailed(if let Some(claims) = claims {
+        event.with_public_key(&claims.subject)
+    } else {
+        event
+    })
+}
+
+/// Generates an event for when a link definition is set
+///
+/// # Arguments
+/// * `link` - Link definition containing source, target, and interface information
+///
+/// # Returns
+/// Event containing complete link definition details
+pub fn linkdef_set(link: &Link) -> WasmbusEvent {
+    WasmbusEvent::LinkdefSet(link.clone())
+}
+
+/// Generates an event for when setting a link definition fails
+///
+/// # Arguments
+/// * `link` - Link definition that failed to be set
+/// * `error` - The error that caused the link definition failure
+///
+/// # Returns
+/// Event containing link definition details and error information
+pub fn linkdef_set_failed(link: &Link, error: &anyhow::Error) -> WasmbusEvent {
+    WasmbusEvent::LinkdefSetFailed(LinkdefSetFailed::new(link.clone(), format!("{error:#}")))
+}
+
+/// Generates an event for when a link definition is deleted
+///
+/// # Arguments
+/// * `source_id` - ID of the source component
+/// * `target` - Optional target component ID
+/// * `name` - Name of the link
+/// * `wit_namespace` - WIT namespace for the link
+/// * `wit_package` - WIT package name
+/// * `interfaces` - Optional list of interface names
+///
+/// # Returns
+/// Event containing link deletion details
+pub fn linkdef_deleted(
+    source_id: impl AsRef<str>,
+    target: Option<&String>,
+    name: impl AsRef<str>,
+    wit_namespace: impl AsRef<str>,
+    wit_package: impl AsRef<str>,
+    interfaces: Option<&Vec<String>>,
+) -> WasmbusEvent {
+    let event = LinkdefDeleted::new(
+        source_id.as_ref(),
+        name.as_ref(),
+        wit_namespace.as_ref(),
+        wit_package.as_ref(),
+    );
+    // Target and interfaces aren't known if the link didn't exist, so we omit them from the
+    // event data in that case.
+    WasmbusEvent::LinkdefDeleted(
+        if let (Some(target), Some(interfaces)) = (target, interfaces) {
+            event.with_target(target, interfaces.clone())
+        } else {
+            event
+        },
+    )
+}
+
+/// Generates an event for when a provider starts
+///
+/// # Arguments
+/// * `claims` - Optional capability provider claims
+/// * `annotations` - Key-value pairs of metadata annotations
+/// * `host_id` - ID of the host where provider started
+/// * `image_ref` - Reference to the provider image
+/// * `provider_id` - Unique identifier for the provider
+///
+/// # Returns
+/// Event containing provider startup details and metadata
+pub fn provider_started(
+    claims: Option<&jwt::Claims<jwt::CapabilityProvider>>,
+    annotations: &BTreeMap<String, String>,
+    host_id: impl AsRef<str>,
+    image_ref: impl AsRef<str>,
+    provider_id: impl AsRef<str>,
+) -> WasmbusEvent {
+    let event = ProviderStarted::new(host_id.as_ref(), provider_id.as_ref(), image_ref.as_ref())
+        .with_annotations(annotations.clone());
+    WasmbusEvent::ProviderStarted(if let Some(claims) = claims {
+        let not_before_human = claims
+            .not_before
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "never".to_string());
+        let expires_human = claims
+            .expires
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "never".to_string());
+        let metadata = claims.metadata.as_ref();
+        event.with_claims(
+            ProviderClaims::new(&claims.issuer, not_before_human, expires_human)
+                .with_name(metadata.and_then(|jwt::CapabilityProvider { name, .. }| name.clone()))
+                .with_version(metadata.and_then(|jwt::CapabilityProvider { ver, .. }| ver.clone())),
+        )
+    } else {
+        event
+    })
+}
+
+/// Generates an event for when a provider fails to start
+///
+/// # Arguments
+/// * `provider_ref` - Reference to the provider image
+/// * `provider_id` - Unique identifier for the provider
+/// * `host_id` - ID of the host where start failed
+/// * `error` - The error that caused the start failure
+///
+/// # Returns
+/// Event containing provider start failure details
+pub fn provider_start_failed(
+    provider_ref: impl AsRef<str>,
+    provider_id: impl AsRef<str>,
+    host_id: impl AsRef<str>,
+    error: &anyhow::Error,
+) -> WasmbusEvent {
+    WasmbusEvent::ProviderStartFailed(ProviderStartFailed::new(
+        host_id.as_ref(),
+        provider_id.as_ref(),
+        provider_ref.as_ref(),
+        format!("{error:#}"),
+    ))
+}
+
+/// Generates an event for when a provider stops
+///
+/// # Arguments
+/// * `annotations` - Key-value pairs of metadata annotations
+/// * `host_id` - ID of the host where provider stopped
+/// * `provider_id` - Unique identifier for the provider
+/// * `reason` - Reason for stopping the provider
+///
+/// # Returns
+/// Event containing provider stop details
+pub fn provider_stopped(
+    annotations: &BTreeMap<String, String>,
+    host_id: impl AsRef<str>,
+    provider_id: impl AsRef<str>,
+    reason: impl AsRef<str>,
+) -> WasmbusEvent {
+    WasmbusEvent::ProviderStopped(
+        ProviderStopped::new(host_id.as_ref(), provider_id.as_ref(), reason.as_ref())
+            .with_annotations(annotations.clone()),
+    )
+}
+
+/// Generates an event payload for provider health checks, which is published as
+/// [WasmbusEvent::HealthCheckPassed], [WasmbusEvent::HealthCheckFailed] or
+/// [WasmbusEvent::HealthCheckStatus]
+///
+/// # Arguments
+/// * `host_id` - ID of the host performing the health check
+/// * `provider_id` - Unique identifier for the provider being checked
+///
+/// # Returns
+/// Payload containing health check details
+pub fn provider_health_check(
+    host_id: impl AsRef<str>,
+    provider_id: impl AsRef<str>,
+) -> HealthCheck {
+    HealthCheck::new(host_id.as_ref(), provider_id.as_ref())
+}
+
+/// Generates an event for when a config is set
+///
+/// # Arguments
+/// * `config_name` - Name of the configuration being set
+///
+/// # Returns
+/// Event containing config set details
+pub fn config_set(config_name: impl AsRef<str>) -> WasmbusEvent {
+    WasmbusEvent::ConfigSet(ConfigChanged::new(config_name.as_ref()))
+}
+
+/// Generates an event for when a config is deleted
+///
+/// # Arguments
+/// * `config_name` - Name of the configuration being deleted
+///
+/// # Returns
+/// Event containing config deletion details
+pub fn config_deleted(config_name: impl AsRef<str>) -> WasmbusEvent {
+    WasmbusEvent::ConfigDeleted(ConfigChanged::new(config_name.as_ref()))
+}
+
+/// Generates an event for when host labels are changed
+///
+/// # Arguments
+/// * `host_id` - ID of the host whose labels changed
+/// * `labels` - New set of labels as key-value pairs
+///
+/// # Returns
+/// Event containing updated label information
+pub fn labels_changed(
+    host_id: impl AsRef<str>,
+    labels: impl Into<HashMap<String, String>>,
+) -> WasmbusEvent {
+    WasmbusEvent::LabelsChanged(LabelsChanged::new(host_id.as_ref(), labels.into()))
+}
+
+/// Generates an event for when a host starts
+///
+/// # Arguments
+/// * `host_id` - ID of the host
+/// * `friendly_name` - Human-readable name of the host
+/// * `version` - Version of the host
+/// * `labels` - Labels of the host
+///
+/// # Returns
+/// Event containing host startup details
+pub fn host_started(
+    host_id: impl AsRef<str>,
+    friendly_name: impl AsRef<str>,
+    version: impl AsRef<str>,
+    labels: BTreeMap<String, String>,
+) -> WasmbusEvent {
+    WasmbusEvent::HostStarted(HostStarted::new(
+        host_id.as_ref(),
+        friendly_name.as_ref(),
+        version.as_ref(),
+        labels,
+    ))
+}
+
+/// Generates an event for when a host stops
+///
+/// # Arguments
+/// * `labels` - Labels of the host
+///
+/// # Returns
+/// Event containing the labels of the stopped host
+pub fn host_stopped(labels: BTreeMap<String, String>) -> WasmbusEvent {
+    WasmbusEvent::HostStopped(HostStopped::new(labels))
+}
+
+/// Generates an event for when a host starts draining
+///
+/// # Arguments
+/// * `host_id` - ID of the host
+/// * `in_flight` - Number of in-flight invocations of each component
+///
+/// # Returns
+/// Event containing the in-flight invocations of the host
+pub fn host_drain_started(
+    host_id: impl AsRef<str>,
+    in_flight: BTreeMap<String, u64>,
+) -> WasmbusEvent {
+    WasmbusEvent::HostDrainStarted(HostDrainStatus::new(host_id.as_ref(), in_flight))
+}
+
+/// Generates an event for when the in-flight invocations of a draining host change
+///
+/// # Arguments
+/// * `host_id` - ID of the host
+/// * `in_flight` - Number of in-flight invocations of each component
+///
+/// # Returns
+/// Event containing the in-flight invocations of the host
+pub fn host_drain_progress(
+    host_id: impl AsRef<str>,
+    in_flight: BTreeMap<String, u64>,
+) -> WasmbusEvent {
+    WasmbusEvent::HostDrainProgress(HostDrainStatus::new(host_id.as_ref(), in_flight))
+}
+
+/// Generates an event for when a host finishes draining
+///
+/// # Arguments
+/// * `host_id` - ID of the host
+/// * `in_flight` - Number of invocations of each component still in flight
+/// * `timed_out` - Whether the host stopped waiting for in-flight invocations
+///
+/// # Returns
+/// Event containing the invocations of the host still in flight
+pub fn host_drained(
+    host_id: impl AsRef<str>,
+    in_flight: BTreeMap<String, u64>,
+    timed_out: bool,
+) -> WasmbusEvent {
+    WasmbusEvent::HostDrained(
+        HostDrainStatus::new(host_id.as_ref(), in_flight).with_timed_out(timed_out),
+    )
+}
+
+/// Generates an event for the periodic host heartbeat
+///
+/// # Arguments
+/// * `inventory` - Current inventory of the host
+///
+/// # Returns
+/// Event containing the host inventory
+pub fn host_heartbeat(inventory: HostInventory) -> WasmbusEvent {
+    WasmbusEvent::HostHeartbeat(inventory)
+}
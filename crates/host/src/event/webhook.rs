@@ -0,0 +1,437 @@
+//! Webhook implementation of the wasmCloud [crate::event::EventPublisher] extension trait
+
+use core::pin::pin;
+use core::time::Duration;
+
+use std::sync::Arc;
+
+use anyhow::{bail, ensure, Context as _};
+use bytes::Bytes;
+use cloudevents::{EventBuilder as _, EventBuilderV10};
+use futures::future::join_all;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::StatusCode;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout, timeout_at, Instant};
+use tracing::{debug, error, instrument, warn};
+use url::Url;
+
+use crate::event::{cloud_event, EventPublisher, WasmbusEvent};
+
+/// Default maximum number of events delivered in a single request
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// Default time to wait for more events to fill a batch before delivering it
+pub const DEFAULT_BATCH_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Default maximum number of events buffered for delivery
+pub const DEFAULT_QUEUE_SIZE: usize = 10_000;
+
+/// Default number of times delivery of a batch is retried
+pub const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// Default delay before the first retry of a batch, which doubles on each further retry
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Default maximum delay between retries of a batch
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Default timeout of a single delivery request
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time to wait for buffered events to be delivered when the host stops
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Content type of a single event in the CloudEvents HTTP structured content mode
+const CONTENT_TYPE_EVENT: &str = "application/cloudevents+json";
+
+/// Content type of a batch of events in the CloudEvents HTTP batched content mode
+const CONTENT_TYPE_BATCH: &str = "application/cloudevents-batch+json";
+
+/// Configuration of a [WebhookEventPublisher]
+#[derive(Clone, Debug)]
+pub struct WebhookConfig {
+    /// URLs every event is delivered to
+    pub urls: Vec<Url>,
+    /// Maximum number of events delivered in a single request
+    pub batch_size: usize,
+    /// Time to wait for more events to fill a batch before delivering it
+    pub batch_timeout: Duration,
+    /// Maximum number of events buffered for delivery. Events published while the queue is full
+    /// are dropped.
+    pub queue_size: usize,
+    /// Number of times delivery of a batch to a URL is retried before the batch is dropped
+    pub max_retries: u32,
+    /// Delay before the first retry of a batch, which doubles on each further retry
+    pub initial_backoff: Duration,
+    /// Maximum delay between retries of a batch
+    pub max_backoff: Duration,
+    /// Timeout of a single delivery request
+    pub request_timeout: Duration,
+    /// Time to wait for buffered events to be delivered when the host stops. Events still
+    /// buffered afterwards are dropped.
+    pub drain_timeout: Duration,
+}
+
+impl WebhookConfig {
+    /// Returns a configuration delivering events to `urls` with default settings
+    pub fn new(urls: Vec<Url>) -> Self {
+        Self {
+            urls,
+            batch_size: DEFAULT_BATCH_SIZE,
+            batch_timeout: DEFAULT_BATCH_TIMEOUT,
+            queue_size: DEFAULT_QUEUE_SIZE,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+        }
+    }
+}
+
+/// Webhook implementation of the wasmCloud [crate::event::EventPublisher] extension trait,
+/// POSTing events as CloudEvents to one or more URLs.
+///
+/// Published events are buffered in a bounded queue and delivered in the background, in batches
+/// using the CloudEvents HTTP batched content mode. Failed deliveries are retried with exponential
+/// backoff. When the host stops, [EventPublisher::flush] delivers the buffered events within the
+/// configured drain timeout. Delivery of the remaining events continues after the publisher is
+/// dropped.
+pub struct WebhookEventPublisher {
+    event_builder: EventBuilderV10,
+    queue: mpsc::Sender<cloudevents::Event>,
+    shutdown: Arc<Notify>,
+    delivery: Mutex<Option<JoinHandle<()>>>,
+    drain_timeout: Duration,
+}
+
+impl WebhookEventPublisher {
+    /// Create a new webhook event publisher and start delivering events in the background.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source of the event, typically the host ID.
+    /// * `config` - The URLs to deliver events to and how to deliver them.
+    pub fn new(source: String, config: WebhookConfig) -> anyhow::Result<Self> {
+        ensure!(
+            !config.urls.is_empty(),
+            "at least one webhook URL is required"
+        );
+        ensure!(config.batch_size > 0, "webhook batch size must be non-zero");
+        ensure!(config.queue_size > 0, "webhook queue size must be non-zero");
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .build()
+            .context("failed to build webhook HTTP client")?;
+        let (queue, events) = mpsc::channel(config.queue_size);
+        let shutdown = Arc::default();
+        let drain_timeout = config.drain_timeout;
+        let delivery = tokio::spawn(deliver(client, config, events, Arc::clone(&shutdown)));
+        Ok(Self {
+            event_builder: EventBuilderV10::new().source(source),
+            queue,
+            shutdown,
+            delivery: Mutex::new(Some(delivery)),
+            drain_timeout,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl EventPublisher for WebhookEventPublisher {
+    #[instrument(skip_all, fields(name = event.name()))]
+    async fn publish_event(&self, event: WasmbusEvent) -> anyhow::Result<()> {
+        let ev = cloud_event(&self.event_builder, &event)?;
+        match self.queue.try_send(ev) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(..)) => {
+                warn!(
+                    event = event.name(),
+                    "webhook event queue is full, dropping event"
+                );
+                Ok(())
+            }
+            Err(TrySendError::Closed(..)) => bail!("webhook event delivery has stopped"),
+        }
+    }
+
+    #[instrument(skip_all)]
+    async fn flush(&self) -> anyhow::Result<()> {
+        let Some(mut delivery) = self.delivery.lock().await.take() else {
+            return Ok(());
+        };
+        self.shutdown.notify_one();
+        match timeout(self.drain_timeout, &mut delivery).await {
+            Ok(res) => res.context("webhook event delivery failed"),
+            Err(..) => {
+                delivery.abort();
+                warn!(
+                    timeout = ?self.drain_timeout,
+                    "timed out delivering buffered webhook events, dropping them"
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Delivers batches of `events` to all configured URLs until all publishers are dropped or
+/// `shutdown` is notified, after which the buffered events are delivered and no more are accepted
+async fn deliver(
+    client: reqwest::Client,
+    config: WebhookConfig,
+    mut events: mpsc::Receiver<cloudevents::Event>,
+    shutdown: Arc<Notify>,
+) {
+    let mut batch = Vec::with_capacity(config.batch_size);
+    let mut shutdown = pin!(shutdown.notified());
+    let mut draining = false;
+    loop {
+        // Once draining, the queue is closed and receiving returns the buffered events, or none
+        // once the queue is empty, without waiting
+        let n = tokio::select! {
+            n = events.recv_many(&mut batch, config.batch_size) => n,
+            () = &mut shutdown, if !draining => {
+                debug!("draining webhook event queue");
+                draining = true;
+                events.close();
+                continue;
+            }
+        };
+        if n == 0 {
+            break;
+        }
+        let deadline = Instant::now() + config.batch_timeout;
+        while batch.len() < config.batch_size {
+            if draining {
+                // The queue is closed, so there are no more events to wait for
+                match events.try_recv() {
+                    Ok(ev) => batch.push(ev),
+                    Err(..) => break,
+                }
+                continue;
+            }
+            let limit = config.batch_size - batch.len();
+            let res = tokio::select! {
+                res = timeout_at(deadline, events.recv_many(&mut batch, limit)) => res,
+                () = &mut shutdown, if !draining => {
+                    debug!("draining webhook event queue");
+                    draining = true;
+                    events.close();
+                    continue;
+                }
+            };
+            match res {
+                Ok(0) | Err(..) => break,
+                Ok(..) => {}
+            }
+        }
+
+        let body = if let [ev] = batch.as_slice() {
+            serde_json::to_vec(ev).map(|body| (CONTENT_TYPE_EVENT, Bytes::from(body)))
+        } else {
+            serde_json::to_vec(&batch).map(|body| (CONTENT_TYPE_BATCH, Bytes::from(body)))
+        };
+        match body {
+            Ok((content_type, body)) => {
+                join_all(config.urls.iter().map(|url| {
+                    deliver_batch(
+                        &client,
+                        &config,
+                        url,
+                        content_type,
+                        body.clone(),
+                        batch.len(),
+                    )
+                }))
+                .await;
+            }
+            Err(err) => error!(?err, "failed to serialize webhook events"),
+        }
+        batch.clear();
+    }
+    debug!("webhook event delivery stopped");
+}
+
+/// Delivers a serialized batch of `count` events to `url`, retrying with exponential backoff
+#[instrument(level = "debug", skip(client, config, body))]
+async fn deliver_batch(
+    client: &reqwest::Client,
+    config: &WebhookConfig,
+    url: &Url,
+    content_type: &'static str,
+    body: Bytes,
+    count: usize,
+) {
+    let mut backoff = config.initial_backoff;
+    for attempt in 0..=config.max_retries {
+        if attempt > 0 {
+            sleep(backoff).await;
+            backoff = backoff.saturating_mul(2).min(config.max_backoff);
+        }
+        match client
+            .post(url.clone())
+            .header(CONTENT_TYPE, content_type)
+            .body(body.clone())
+            .send()
+            .await
+        {
+            Ok(res) if res.status().is_success() => return,
+            Ok(res) if !is_retryable(res.status()) => {
+                error!(status = %res.status(), "webhook rejected events, dropping them");
+                return;
+            }
+            Ok(res) => debug!(status = %res.status(), attempt, "webhook failed to accept events"),
+            Err(err) => debug!(?err, attempt, "failed to deliver events to webhook"),
+        }
+    }
+    error!(
+        retries = config.max_retries,
+        "failed to deliver events to webhook, dropping them"
+    );
+}
+
+/// Returns whether a request failing with `status` may succeed when retried
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use http_body_util::BodyExt as _;
+    use hyper_util::rt::TokioIo;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use wasmcloud_control_interface::ConfigChanged;
+
+    use super::*;
+
+    /// Serves a webhook on a local port, sending the content type and body of every accepted
+    /// request. Requests fail until `failures` requests have been made.
+    async fn serve(
+        failures: usize,
+    ) -> anyhow::Result<(
+        Url,
+        Arc<AtomicUsize>,
+        mpsc::UnboundedReceiver<(String, Bytes)>,
+    )> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = Url::parse(&format!("http://{}/events", listener.local_addr()?))?;
+        let (bodies_tx, bodies_rx) = mpsc::unbounded_channel();
+        let attempts = Arc::new(AtomicUsize::default());
+        tokio::spawn({
+            let attempts = Arc::clone(&attempts);
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let bodies_tx = bodies_tx.clone();
+                    let attempts = Arc::clone(&attempts);
+                    tokio::spawn(hyper::server::conn::http1::Builder::new().serve_connection(
+                        TokioIo::new(stream),
+                        hyper::service::service_fn(
+                            move |req: http::Request<hyper::body::Incoming>| {
+                                let bodies_tx = bodies_tx.clone();
+                                let attempts = Arc::clone(&attempts);
+                                async move {
+                                    let content_type = req.headers()[http::header::CONTENT_TYPE]
+                                        .to_str()?
+                                        .to_string();
+                                    let body = req.into_body().collect().await?.to_bytes();
+                                    let status =
+                                        if attempts.fetch_add(1, Ordering::Relaxed) < failures {
+                                            http::StatusCode::SERVICE_UNAVAILABLE
+                                        } else {
+                                            bodies_tx.send((content_type, body))?;
+                                            http::StatusCode::ACCEPTED
+                                        };
+                                    let mut res =
+                                        http::Response::new(http_body_util::Empty::<Bytes>::new());
+                                    *res.status_mut() = status;
+                                    anyhow::Ok(res)
+                                }
+                            },
+                        ),
+                    ));
+                }
+            }
+        });
+        Ok((url, attempts, bodies_rx))
+    }
+
+    #[tokio::test]
+    async fn delivers_batches_with_retries() -> anyhow::Result<()> {
+        // Fail the first delivery to exercise retries
+        let (url, attempts, mut bodies_rx) = serve(1).await?;
+
+        let publisher = WebhookEventPublisher::new(
+            "host".into(),
+            WebhookConfig {
+                batch_timeout: Duration::from_millis(100),
+                initial_backoff: Duration::from_millis(10),
+                ..WebhookConfig::new(vec![url])
+            },
+        )?;
+        publisher
+            .publish_event(WasmbusEvent::ConfigSet(ConfigChanged::new("a")))
+            .await?;
+        publisher
+            .publish_event(WasmbusEvent::ConfigDeleted(ConfigChanged::new("a")))
+            .await?;
+
+        let (content_type, body) = bodies_rx.recv().await.context("no events delivered")?;
+        assert_eq!(content_type, CONTENT_TYPE_BATCH);
+        let events: Vec<cloudevents::Event> = serde_json::from_slice(&body)?;
+        let events = events
+            .iter()
+            .map(WasmbusEvent::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(
+            events,
+            [
+                WasmbusEvent::ConfigSet(ConfigChanged::new("a")),
+                WasmbusEvent::ConfigDeleted(ConfigChanged::new("a")),
+            ]
+        );
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn flush_delivers_buffered_events() -> anyhow::Result<()> {
+        let (url, _, mut bodies_rx) = serve(0).await?;
+
+        // The batch timeout would delay delivery far beyond the test without the flush
+        let publisher = WebhookEventPublisher::new(
+            "host".into(),
+            WebhookConfig {
+                batch_timeout: Duration::from_secs(600),
+                ..WebhookConfig::new(vec![url])
+            },
+        )?;
+        publisher
+            .publish_event(WasmbusEvent::ConfigSet(ConfigChanged::new("a")))
+            .await?;
+        tokio::time::timeout(Duration::from_secs(5), publisher.flush())
+            .await
+            .context("flush timed out")??;
+
+        let (content_type, body) = bodies_rx.try_recv().context("no events delivered")?;
+        assert_eq!(content_type, CONTENT_TYPE_EVENT);
+        let event: cloudevents::Event = serde_json::from_slice(&body)?;
+        assert_eq!(
+            WasmbusEvent::try_from(&event)?,
+            WasmbusEvent::ConfigSet(ConfigChanged::new("a"))
+        );
+        assert!(publisher
+            .publish_event(WasmbusEvent::ConfigDeleted(ConfigChanged::new("a")))
+            .await
+            .is_err());
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@
 
 /// [crate::config::ConfigManager] trait for managing a config store which can be watched to receive
 /// updates to the config. This is a supertrait of [crate::store::StoreManager] and is implemented
-/// by [crate::store::DefaultStore].
+/// by [crate::store::DefaultStore] and [crate::store::FileStore].
 pub mod config;
 
 /// [crate::event::EventPublisher] trait for receiving and publishing events from the host
@@ -21,7 +21,8 @@ pub mod metrics;
 pub mod oci;
 
 /// [crate::policy::PolicyManager] trait for layering additional security policies on top of the
-/// wasmCloud host
+/// wasmCloud host, a local rule-file based implementation in [crate::policy::local] and decorators
+/// for caching and auditing decisions
 pub mod policy;
 
 /// [crate::registry::RegistryCredentialExt] extension trait for converting registry credentials
@@ -137,6 +138,73 @@ impl ResourceRef<'_> {
     }
 }
 
+/// Construct an [`OciFetcher`] for an OCI reference from the registry configuration of its
+/// registry, falling back to the default configuration, fetching to the configured OCI cache.
+fn oci_fetcher(
+    oci_ref: &ResourceRef<'_>,
+    default_config: &oci::Config,
+    registry_config: &HashMap<String, RegistryConfig>,
+) -> OciFetcher {
+    oci_ref
+        .authority()
+        .and_then(|authority| registry_config.get(authority))
+        .map(OciFetcher::from)
+        .unwrap_or_else(|| {
+            OciFetcher::from(
+                RegistryConfig::builder()
+                    .reg_type(RegistryType::Oci)
+                    .additional_ca_paths(default_config.additional_ca_paths.clone())
+                    .allow_latest(default_config.allow_latest)
+                    .allow_insecure(
+                        oci_ref
+                            .authority()
+                            .map(|authority| {
+                                default_config
+                                    .allowed_insecure
+                                    .contains(&authority.to_string())
+                            })
+                            .unwrap_or(false),
+                    )
+                    .signature_public_keys(
+                        oci_ref
+                            .authority()
+                            .and_then(|authority| {
+                                default_config.signature_public_keys.get(authority)
+                            })
+                            .cloned()
+                            .unwrap_or_default(),
+                    )
+                    .auth(RegistryAuth::Anonymous)
+                    .build()
+                    .unwrap_or_default(),
+            )
+        })
+        .with_additional_ca_paths(&default_config.additional_ca_paths)
+        .with_cache(default_config.cache())
+}
+
+/// Pull an artifact from an OCI reference into the OCI cache ahead of its use.
+#[instrument(level = "debug", skip(default_config, registry_config))]
+pub(crate) async fn pull_artifact(
+    artifact_ref: &str,
+    default_config: &oci::Config,
+    registry_config: &HashMap<String, RegistryConfig>,
+) -> anyhow::Result<PathBuf> {
+    match ResourceRef::try_from(artifact_ref)? {
+        ref oci_ref @ ResourceRef::Oci(artifact_ref) => {
+            oci_fetcher(oci_ref, default_config, registry_config)
+                .prefetch(artifact_ref)
+                .await
+                .with_context(|| {
+                    format!("failed to pull artifact under OCI reference `{artifact_ref}`")
+                })
+        }
+        ResourceRef::File(..) | ResourceRef::Builtin(..) => {
+            bail!("only artifacts under OCI references can be pulled")
+        }
+    }
+}
+
 /// Fetch an component from a reference.
 #[instrument(level = "debug", skip(default_config, registry_config))]
 pub async fn fetch_component(
@@ -155,37 +223,14 @@ pub async fn fetch_component(
                 .await
                 .context("failed to read component")
         }
-        ref oci_ref @ ResourceRef::Oci(component_ref) => oci_ref
-            .authority()
-            .and_then(|authority| registry_config.get(authority))
-            .map(OciFetcher::from)
-            .unwrap_or_else(|| {
-                OciFetcher::from(
-                    RegistryConfig::builder()
-                        .reg_type(RegistryType::Oci)
-                        .additional_ca_paths(default_config.additional_ca_paths.clone())
-                        .allow_latest(default_config.allow_latest)
-                        .allow_insecure(
-                            oci_ref
-                                .authority()
-                                .map(|authority| {
-                                    default_config
-                                        .allowed_insecure
-                                        .contains(&authority.to_string())
-                                })
-                                .unwrap_or(false),
-                        )
-                        .auth(RegistryAuth::Anonymous)
-                        .build()
-                        .unwrap_or_default(),
-                )
-            })
-            .with_additional_ca_paths(&default_config.additional_ca_paths)
-            .fetch_component(component_ref)
-            .await
-            .with_context(|| {
-                format!("failed to fetch component under OCI reference `{component_ref}`")
-            }),
+        ref oci_ref @ ResourceRef::Oci(component_ref) => {
+            oci_fetcher(oci_ref, default_config, registry_config)
+                .fetch_component(component_ref)
+                .await
+                .with_context(|| {
+                    format!("failed to fetch component under OCI reference `{component_ref}`")
+                })
+        }
         ResourceRef::Builtin(..) => bail!("nothing to fetch for a builtin"),
     }
 }
@@ -214,37 +259,14 @@ pub(crate) async fn fetch_provider(
             .await
             .context("failed to read provider")
         }
-        oci_ref @ ResourceRef::Oci(provider_ref) => oci_ref
-            .authority()
-            .and_then(|authority| registry_config.get(authority))
-            .map(OciFetcher::from)
-            .unwrap_or_else(|| {
-                OciFetcher::from(
-                    RegistryConfig::builder()
-                        .reg_type(RegistryType::Oci)
-                        .additional_ca_paths(default_config.additional_ca_paths.clone())
-                        .allow_latest(default_config.allow_latest)
-                        .allow_insecure(
-                            oci_ref
-                                .authority()
-                                .map(|authority| {
-                                    default_config
-                                        .allowed_insecure
-                                        .contains(&authority.to_string())
-                                })
-                                .unwrap_or(false),
-                        )
-                        .auth(RegistryAuth::Anonymous)
-                        .build()
-                        .unwrap_or_default(),
-                )
-            })
-            .with_additional_ca_paths(&default_config.additional_ca_paths)
-            .fetch_provider(provider_ref, host_id)
-            .await
-            .with_context(|| {
-                format!("failed to fetch provider under OCI reference `{provider_ref}`")
-            }),
+        oci_ref @ ResourceRef::Oci(provider_ref) => {
+            oci_fetcher(oci_ref, default_config, registry_config)
+                .fetch_provider(provider_ref, host_id)
+                .await
+                .with_context(|| {
+                    format!("failed to fetch provider under OCI reference `{provider_ref}`")
+                })
+        }
         ResourceRef::Builtin(..) => bail!("nothing to fetch for a builtin"),
     }
 }
@@ -23,6 +23,16 @@ pub struct HostMetrics {
     pub component_active_instances: UpDownCounter<i64>,
     /// The maximum number of instances of a component.
     pub component_max_instances: Gauge<u64>,
+    /// The amount of fuel consumed by component invocations, if fuel metering is enabled.
+    pub component_fuel_consumed: Counter<u64>,
+    /// The count of the number of component invocations served by a pre-instantiated instance.
+    pub component_instance_pool_hits: Counter<u64>,
+    /// The count of the number of component invocations, which found the instance pool empty.
+    pub component_instance_pool_misses: Counter<u64>,
+    /// The state of the circuit breaker of a link, where 0 is closed, 1 is open and 2 is half-open.
+    pub link_circuit_breaker_state: Gauge<u64>,
+    /// The count of the number of times the circuit breaker of a link opened.
+    pub link_circuit_breaker_trips: Counter<u64>,
 
     /// The total amount of available system memory in bytes.
     pub system_total_memory_bytes: ObservableGauge<u64>,
@@ -104,6 +114,33 @@ impl HostMetrics {
             .with_description("Maximum number of component instances")
             .build();
 
+        let component_fuel_consumed = meter
+            .u64_counter("wasmcloud_host.component.fuel_consumed")
+            .with_description("Amount of fuel consumed by component invocations")
+            .build();
+
+        let component_instance_pool_hits = meter
+            .u64_counter("wasmcloud_host.component.instance_pool.hits")
+            .with_description("Number of invocations served by a pre-instantiated instance")
+            .build();
+
+        let component_instance_pool_misses = meter
+            .u64_counter("wasmcloud_host.component.instance_pool.misses")
+            .with_description("Number of invocations which found the instance pool empty")
+            .build();
+
+        let link_circuit_breaker_state = meter
+            .u64_gauge("wasmcloud_host.link.circuit_breaker.state")
+            .with_description(
+                "State of link circuit breakers (0 = closed, 1 = open, 2 = half-open)",
+            )
+            .build();
+
+        let link_circuit_breaker_trips = meter
+            .u64_counter("wasmcloud_host.link.circuit_breaker.trips")
+            .with_description("Number of times link circuit breakers opened")
+            .build();
+
         let mut system = System::new();
         // Get the initial metrics
         system.refresh_memory();
@@ -177,6 +214,11 @@ impl HostMetrics {
             component_errors: component_error_count,
             component_active_instances,
             component_max_instances,
+            component_fuel_consumed,
+            component_instance_pool_hits,
+            component_instance_pool_misses,
+            link_circuit_breaker_state,
+            link_circuit_breaker_trips,
             system_total_memory_bytes: system_memory_total_bytes,
             system_used_memory_bytes: system_memory_used_bytes,
             system_cpu_usage,
@@ -215,4 +257,37 @@ impl HostMetrics {
             self.component_errors.add(1, attributes);
         }
     }
+
+    /// Record the amount of fuel consumed by invocations of a component.
+    pub(crate) fn record_fuel_consumed(&self, fuel: u64, attributes: &[KeyValue]) {
+        self.component_fuel_consumed.add(fuel, attributes);
+    }
+
+    /// Record the hits and misses of the instance pool of a component.
+    pub(crate) fn record_instance_pool_stats(
+        &self,
+        hits: u64,
+        misses: u64,
+        attributes: &[KeyValue],
+    ) {
+        if hits > 0 {
+            self.component_instance_pool_hits.add(hits, attributes);
+        }
+        if misses > 0 {
+            self.component_instance_pool_misses.add(misses, attributes);
+        }
+    }
+
+    /// Set the state of the circuit breaker of a link, counting a trip if it opened.
+    pub(crate) fn set_circuit_breaker_state(
+        &self,
+        state: u64,
+        opened: bool,
+        attributes: &[KeyValue],
+    ) {
+        self.link_circuit_breaker_state.record(state, attributes);
+        if opened {
+            self.link_circuit_breaker_trips.add(1, attributes);
+        }
+    }
 }
@@ -2,6 +2,7 @@
 
 use std::{
     collections::{BTreeMap, HashMap},
+    path::PathBuf,
     sync::Arc,
     time::Duration,
 };
@@ -17,8 +18,13 @@ use wasmcloud_core::RegistryConfig;
 
 use crate::{
     event::EventPublisher,
-    nats::{event::NatsEventPublisher, policy::NatsPolicyManager, secrets::NatsSecretsManager},
+    nats::{
+        event::NatsEventPublisher,
+        policy::{watch_policy_changes, NatsPolicyManager},
+        secrets::NatsSecretsManager,
+    },
     oci,
+    policy::{cache::CachingPolicyManager, local::LocalPolicyManager},
     registry::{merge_registry_config, RegistryCredentialExt as _, SupplementalConfig},
     secrets::SecretsManager,
     store::StoreManager,
@@ -51,6 +57,9 @@ pub struct NatsHostBuilder {
     config_store: Arc<dyn StoreManager>,
     data_store: Store,
     policy_manager: Option<Arc<dyn PolicyManager>>,
+    /// The NATS policy manager, if configured, so that its decision cache can be replaced by one
+    /// expiring decisions in [Self::with_policy_cache]
+    nats_policy_manager: Option<NatsPolicyManager>,
     secrets_manager: Option<Arc<dyn SecretsManager>>,
     event_publisher: Option<Arc<dyn EventPublisher>>,
 }
@@ -104,6 +113,7 @@ impl NatsHostBuilder {
             config_store: Arc::new(config_data),
             data_store,
             policy_manager: None,
+            nats_policy_manager: None,
             secrets_manager: None,
             event_publisher: None,
             enable_component_auction,
@@ -134,7 +144,62 @@ impl NatsHostBuilder {
         .await?;
 
         Ok(NatsHostBuilder {
-            policy_manager: Some(Arc::new(policy_manager)),
+            policy_manager: Some(Arc::new(policy_manager.clone())),
+            nats_policy_manager: Some(policy_manager),
+            ..self
+        })
+    }
+
+    /// Setup the local, file-based policy manager for the host. The rule file is watched for changes
+    /// and reloaded every `reload_interval`, see [crate::policy::local] for the file format.
+    pub async fn with_local_policy_manager(
+        self,
+        policy_file: PathBuf,
+        reload_interval: Option<Duration>,
+    ) -> anyhow::Result<Self> {
+        let policy_manager = LocalPolicyManager::load(policy_file, reload_interval).await?;
+
+        Ok(NatsHostBuilder {
+            policy_manager: Some(policy_manager),
+            nats_policy_manager: None,
+            ..self
+        })
+    }
+
+    /// Cache the decisions of the configured policy manager for `ttl`. If `policy_changes_topic`
+    /// is set, messages on it override or invalidate cached decisions, see
+    /// [watch_policy_changes]. The decisions of the [NatsPolicyManager] are no longer cached
+    /// indefinitely, but expire after `ttl`. Has no effect if no policy manager is configured or
+    /// the policy manager already caches its decisions.
+    pub async fn with_policy_cache(
+        self,
+        ttl: Duration,
+        policy_changes_topic: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let policy_manager = if let Some(nats_policy_manager) = &self.nats_policy_manager {
+            Some(Arc::new(nats_policy_manager.clone().without_decision_cache()) as _)
+        } else {
+            self.policy_manager.clone()
+        };
+        let Some(policy_manager) = policy_manager
+            .as_ref()
+            .and_then(|policy_manager| CachingPolicyManager::wrap(policy_manager, ttl))
+        else {
+            return Ok(self);
+        };
+        if let Some(policy_changes_topic) = policy_changes_topic {
+            let policy_changes = watch_policy_changes(
+                &self.ctl_nats,
+                policy_changes_topic,
+                Arc::downgrade(&policy_manager),
+            )
+            .await?;
+            policy_manager.set_policy_changes(policy_changes);
+        }
+
+        Ok(NatsHostBuilder {
+            policy_manager: Some(policy_manager),
+            nats_policy_manager: None,
             ..self
         })
     }
@@ -70,6 +70,9 @@ impl Queue {
             Either::Left(nats.subscribe(format!(
                 "{topic_prefix}.{CTL_API_VERSION_1}.{lattice}.host.*.{host_id}"
             ))),
+            Either::Left(nats.subscribe(format!(
+                "{topic_prefix}.{CTL_API_VERSION_1}.{lattice}.cache.*.{host_id}"
+            ))),
             Either::Right(nats.queue_subscribe(
                 format!("{topic_prefix}.{CTL_API_VERSION_1}.{lattice}.config.>"),
                 format!("{topic_prefix}.{CTL_API_VERSION_1}.{lattice}.config"),
@@ -172,6 +175,27 @@ impl crate::wasmbus::Host {
                 .await
                 .map(Some)
                 .map(serialize_ctl_response),
+            (Some("host"), Some("drain"), Some(host_id), None) => Arc::clone(&self)
+                .handle_drain_host(message.payload, host_id)
+                .await
+                .map(Some)
+                .map(serialize_ctl_response),
+            // Cache commands
+            (Some("cache"), Some("get"), Some(_host_id), None) => self
+                .handle_cached_artifacts()
+                .await
+                .map(Some)
+                .map(serialize_ctl_response),
+            (Some("cache"), Some("prune"), Some(host_id), None) => self
+                .handle_prune_cache(message.payload, host_id)
+                .await
+                .map(Some)
+                .map(serialize_ctl_response),
+            (Some("cache"), Some("pull"), Some(host_id), None) => self
+                .handle_pull_artifacts(message.payload, host_id)
+                .await
+                .map(Some)
+                .map(serialize_ctl_response),
             // Claims commands
             (Some("claims"), Some("get"), None, None) => self
                 .handle_claims()
@@ -2,12 +2,9 @@
 
 use anyhow::Context;
 use cloudevents::{EventBuilder, EventBuilderV10};
-use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use tracing::{instrument, warn};
-use ulid::Ulid;
-use uuid::Uuid;
 
-use crate::event::EventPublisher;
+use crate::event::{cloud_event, EventPublisher, WasmbusEvent};
 
 /// NATS implementation of the wasmCloud [crate::event::EventPublisher] extension trait,
 /// sending events to the NATS message bus with a CloudEvents payload envelope.
@@ -36,20 +33,10 @@ impl NatsEventPublisher {
 
 #[async_trait::async_trait]
 impl EventPublisher for NatsEventPublisher {
-    #[instrument(skip(self, data))]
-    async fn publish_event(&self, name: &str, data: serde_json::Value) -> anyhow::Result<()> {
-        let now = OffsetDateTime::now_utc()
-            .format(&Rfc3339)
-            .context("failed to format current time")?;
-        let ev = self
-            .event_builder
-            .clone()
-            .ty(format!("com.wasmcloud.lattice.{name}"))
-            .id(Uuid::from_u128(Ulid::new().into()).to_string())
-            .time(now)
-            .data("application/json", data)
-            .build()
-            .context("failed to build cloud event")?;
+    #[instrument(skip_all, fields(name = event.name()))]
+    async fn publish_event(&self, event: WasmbusEvent) -> anyhow::Result<()> {
+        let name = event.name();
+        let ev = cloud_event(&self.event_builder, &event)?;
         let ev = serde_json::to_vec(&ev).context("failed to serialize event")?;
         let max_payload = self.ctl_nats.server_info().max_payload;
         let lattice = &self.lattice;
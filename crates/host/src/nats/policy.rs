@@ -4,7 +4,7 @@
 use core::time::Duration;
 
 use std::collections::{BTreeMap, HashMap};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 use anyhow::Context;
 use futures::{
@@ -18,10 +18,10 @@ use ulid::Ulid;
 use uuid::Uuid;
 use wascap::jwt;
 
+use crate::policy::cache::CachingPolicyManager;
 use crate::policy::{
     ComponentInformation, HostInfo, PerformInvocationRequest, PolicyClaims, PolicyManager,
-    ProviderInformation, Request, RequestBody, RequestKey, RequestKind, Response,
-    POLICY_TYPE_VERSION,
+    ProviderInformation, Request, RequestBody, RequestKey, Response, POLICY_TYPE_VERSION,
 };
 
 /// Encapsulates making requests for policy decisions, and receiving updated decisions
@@ -33,6 +33,7 @@ pub struct NatsPolicyManager {
     policy_timeout: Duration,
     decision_cache: Arc<RwLock<HashMap<RequestKey, Response>>>,
     request_to_key: Arc<RwLock<HashMap<String, RequestKey>>>,
+    cache_decisions: bool,
     /// An abort handle for the policy changes subscription
     pub policy_changes: AbortHandle,
 }
@@ -58,6 +59,7 @@ impl NatsPolicyManager {
             policy_timeout: policy_timeout.unwrap_or(DEFAULT_POLICY_TIMEOUT),
             decision_cache: Arc::default(),
             request_to_key: Arc::default(),
+            cache_decisions: true,
             policy_changes: policy_changes_abort,
         };
 
@@ -83,6 +85,16 @@ impl NatsPolicyManager {
         Ok(manager)
     }
 
+    /// Stop caching decisions and applying overrides received on the policy changes topic, so that
+    /// the manager can be wrapped in a [CachingPolicyManager] expiring decisions after a TTL
+    pub fn without_decision_cache(self) -> Self {
+        self.policy_changes.abort();
+        Self {
+            cache_decisions: false,
+            ..self
+        }
+    }
+
     /// Sends a policy request to the policy server and caches the response
     #[instrument(level = "trace", skip_all)]
     pub async fn evaluate_action(&self, request: RequestBody) -> anyhow::Result<Response> {
@@ -95,16 +107,13 @@ impl NatsPolicyManager {
             });
         };
 
-        let kind = match request {
-            RequestBody::StartComponent(_) => RequestKind::StartComponent,
-            RequestBody::StartProvider(_) => RequestKind::StartProvider,
-            RequestBody::PerformInvocation(_) => RequestKind::PerformInvocation,
-            RequestBody::Unknown => RequestKind::Unknown,
-        };
+        let kind = request.kind();
         let cache_key = (&request).into();
-        if let Some(entry) = self.decision_cache.read().await.get(&cache_key) {
-            trace!(?cache_key, ?entry, "using cached policy decision");
-            return Ok(entry.clone());
+        if self.cache_decisions {
+            if let Some(entry) = self.decision_cache.read().await.get(&cache_key) {
+                trace!(?cache_key, ?entry, "using cached policy decision");
+                return Ok(entry.clone());
+            }
         }
 
         let request_id = Uuid::from_u128(Ulid::new().into()).to_string();
@@ -127,6 +136,9 @@ impl NatsPolicyManager {
             .context("policy request failed")?;
         let decision = serde_json::from_slice::<Response>(&res.payload)
             .context("failed to deserialize policy response")?;
+        if !self.cache_decisions {
+            return Ok(decision);
+        }
 
         self.decision_cache
             .write()
@@ -173,6 +185,52 @@ impl NatsPolicyManager {
     }
 }
 
+/// Subscribe to `policy_changes_topic` and apply the received messages to `cache`.
+///
+/// A message containing a [Response] for a request known to the cache overrides the cached
+/// decision for that request. Any other message invalidates all cached decisions, so that they
+/// are re-evaluated on next use. The subscription ends once `cache` is dropped.
+#[instrument(skip(nats, cache))]
+pub async fn watch_policy_changes(
+    nats: &async_nats::Client,
+    policy_changes_topic: String,
+    cache: Weak<CachingPolicyManager>,
+) -> anyhow::Result<AbortHandle> {
+    let mut policy_changes = nats
+        .subscribe(policy_changes_topic)
+        .await
+        .context("failed to subscribe to policy changes")?;
+    let (abort, abort_reg) = AbortHandle::new_pair();
+    spawn(Abortable::new(
+        async move {
+            while let Some(msg) = policy_changes.next().await {
+                let Some(cache) = cache.upgrade() else {
+                    break;
+                };
+                match serde_json::from_slice::<Response>(&msg.payload) {
+                    Ok(response) => {
+                        let request_id = response.request_id.clone();
+                        if cache.override_decision(response).await {
+                            debug!(request_id, "overrode cached policy decision");
+                            continue;
+                        }
+                        debug!(
+                            request_id,
+                            "received policy change for unknown request id, invalidating cache"
+                        );
+                    }
+                    Err(err) => {
+                        debug!(?err, "received policy change, invalidating cache");
+                    }
+                }
+                cache.invalidate_all().await;
+            }
+        },
+        abort_reg,
+    ));
+    Ok(abort)
+}
+
 #[async_trait::async_trait]
 impl PolicyManager for NatsPolicyManager {
     #[instrument(level = "trace", skip_all)]
@@ -196,6 +254,10 @@ impl PolicyManager for NatsPolicyManager {
             .await
     }
 
+    fn caches_decisions(&self) -> bool {
+        self.cache_decisions
+    }
+
     /// Use the policy manager to evaluate whether a provider may be started
     #[instrument(level = "trace", skip_all)]
     async fn evaluate_start_provider(
@@ -226,18 +288,28 @@ impl PolicyManager for NatsPolicyManager {
         interface: String,
         function: String,
     ) -> anyhow::Result<Response> {
-        let request = PerformInvocationRequest {
-            interface,
-            function,
-            target: ComponentInformation {
-                component_id: component_id.to_string(),
-                image_ref: image_ref.to_string(),
-                max_instances: 0,
-                annotations: annotations.clone(),
-                claims: claims.map(PolicyClaims::from),
-            },
-        };
-        self.evaluate_action(RequestBody::PerformInvocation(request))
+        self.evaluate_perform_invocation_request(
+            &PerformInvocationRequest::new(
+                component_id,
+                image_ref,
+                annotations,
+                claims,
+                interface,
+                function,
+            ),
+            claims,
+        )
+        .await
+    }
+
+    /// Use the policy manager to evaluate whether a component may be invoked, taking its caller into account
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_perform_invocation_request(
+        &self,
+        request: &PerformInvocationRequest,
+        _claims: Option<&jwt::Claims<jwt::Component>>,
+    ) -> anyhow::Result<Response> {
+        self.evaluate_action(RequestBody::PerformInvocation(request.clone()))
             .await
     }
 }
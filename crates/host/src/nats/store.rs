@@ -1,20 +1,17 @@
 //! Implementation of the [crate::config::ConfigManager] and [crate::store::StoreManager] traits for NATS JetStream KV [Store].
 
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
 use anyhow::{anyhow, ensure, Context as _};
 use async_nats::jetstream::kv::{Entry as KvEntry, Operation, Store};
 use bytes::Bytes;
 use futures::{StreamExt as _, TryStreamExt as _};
-use tokio::{
-    sync::watch::{self, Receiver},
-    task::JoinSet,
-};
+use tokio::task::JoinSet;
 use tracing::{debug, error, instrument, warn};
 
 use crate::{
     config::ConfigManager,
-    store::StoreManager,
+    store::{StoreEvent, StoreManager, StoreWatch},
     wasmbus::{
         claims::{Claims, StoredClaims},
         ComponentSpecification,
@@ -44,76 +41,45 @@ impl StoreManager for Store {
             .await
             .map_err(|err| anyhow::anyhow!("Failed to delete config: {}", err))
     }
-}
-
-#[async_trait::async_trait]
-impl ConfigManager for Store {
-    /// Watch the key in the JetStream bucket for changes. This will return a channel that will
-    /// receive updates to the config as they happen.
-    async fn watch(&self, name: &str) -> anyhow::Result<Receiver<HashMap<String, String>>> {
-        let config: HashMap<String, String> = match self.get(name).await {
-            Ok(Some(data)) => serde_json::from_slice(&data)
-                .context("Data corruption error, unable to decode data from store")?,
-            Ok(None) => return Err(anyhow::anyhow!("Config {} does not exist", name)),
-            Err(e) => return Err(anyhow::anyhow!("Error fetching config {}: {}", name, e)),
-        };
-
-        let (tx, rx) = watch::channel(config);
-        // Since we're starting a task, we need to own this data
-        let name = name.to_owned();
-        let mut watcher = self.watch(&name).await.context("Failed to watch config")?;
 
-        tokio::spawn(async move {
-            loop {
-                if tx.is_closed() {
-                    warn!(%name, "config watch channel closed, aborting watch");
-                    return;
-                }
-
-                match watcher.try_next().await {
-                    Ok(Some(entry))
-                        if matches!(entry.operation, Operation::Delete | Operation::Purge) =>
-                    {
-                        // NOTE(thomastaylor312): We should probably do something and notify something up
-                        // the chain if we get a delete or purge event of a config that is still being used.
-                        // For now we just zero it out
-                        tx.send_replace(HashMap::new());
-                    }
-                    Ok(Some(entry)) => {
-                        let config: HashMap<String, String> = match serde_json::from_slice(
-                            &entry.value,
-                        ) {
-                            Ok(config) => config,
-                            Err(e) => {
-                                error!(%name, error = %e, "Error decoding config from store during watch");
-                                continue;
-                            }
-                        };
-                        tx.send_if_modified(|current| {
-                            if current == &config {
-                                false
-                            } else {
-                                *current = config;
-                                true
-                            }
-                        });
-                    }
-                    Ok(None) => {
-                        error!(%name, "Watcher for config has closed");
-                        return;
-                    }
-                    Err(e) => {
-                        error!(%name, error = %e, "Error reading from watcher for config. Will wait for next entry");
-                        continue;
-                    }
-                }
-            }
-        });
+    #[instrument(level = "debug", skip(self))]
+    async fn keys(&self, prefix: &str) -> anyhow::Result<Vec<String>> {
+        self.keys()
+            .await
+            .map_err(|err| anyhow::anyhow!("Failed to list keys: {}", err))?
+            .try_filter(|key| futures::future::ready(key.starts_with(prefix)))
+            .try_collect()
+            .await
+            .map_err(|err| anyhow::anyhow!("Failed to list keys: {}", err))
+    }
 
-        Ok(rx)
+    #[instrument(level = "debug", skip(self))]
+    async fn watch(&self, prefix: &str) -> anyhow::Result<StoreWatch> {
+        // Keys are subjects, which the server can only filter by whole tokens, so keys are matched
+        // against the plain prefix here
+        let prefix = prefix.to_string();
+        Ok(self
+            .watch_all()
+            .await
+            .map_err(|err| anyhow::anyhow!("Failed to watch keys: {}", err))?
+            .try_filter(move |KvEntry { key, .. }| futures::future::ready(key.starts_with(&prefix)))
+            .map(|entry| match entry {
+                Ok(KvEntry {
+                    key,
+                    value,
+                    operation: Operation::Put,
+                    ..
+                }) => Ok(StoreEvent::Put { key, value }),
+                Ok(KvEntry { key, .. }) => Ok(StoreEvent::Delete { key }),
+                Err(err) => Err(anyhow!(err).context("Failed to read watched key")),
+            })
+            .boxed())
     }
 }
 
+/// Config watches are served by [StoreManager::watch]
+impl ConfigManager for Store {}
+
 /// This is an extra implementation for the host to process entries coming from a JetStream bucket.
 impl crate::wasmbus::Host {
     #[instrument(level = "trace", skip_all)]
@@ -126,6 +92,12 @@ impl crate::wasmbus::Host {
             ..
         }: KvEntry,
     ) {
+        self.process_data_event(&key, operation, value).await
+    }
+
+    /// Processes a change to the data of the lattice, regardless of the store it originates from
+    #[instrument(level = "trace", skip(self, value))]
+    pub(crate) async fn process_data_event(&self, key: &str, operation: Operation, value: Bytes) {
         let key_id = key.split_once('_');
         let res = match (operation, key_id) {
             (Operation::Put, Some(("COMPONENT", id))) => {
@@ -1,9 +1,11 @@
 // Adapted from
 // https://github.com/wasmCloud/wasmcloud-otp/blob/5f13500646d9e077afa1fca67a3fe9c8df5f3381/host_core/native/hostcore_wasmcloud_native/src/oci.rs
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
+use wasmcloud_core::oci_cache::OciCache;
 
 /// Configuration options for OCI operations.
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -20,4 +22,25 @@ pub struct Config {
     pub oci_user: Option<String>,
     /// Password for the OCI registry specified by `oci_registry`.
     pub oci_password: Option<String>,
+    /// Paths to PEM encoded public keys, keyed by OCI registry. Artifacts fetched from a registry
+    /// with keys must have a cosign-compatible signature valid for one of them
+    pub signature_public_keys: HashMap<String, Vec<PathBuf>>,
+    /// Directory in which OCI artifacts are cached, defaults to a directory in the system
+    /// temporary directory
+    pub cache_dir: Option<PathBuf>,
+    /// Maximum total size of cached OCI artifacts in bytes. Least recently used artifacts are
+    /// evicted once it is exceeded. Unbounded if not set
+    pub cache_max_size: Option<u64>,
+}
+
+impl Config {
+    /// Returns the OCI artifact cache configured by `cache_dir` and `cache_max_size`
+    #[must_use]
+    pub fn cache(&self) -> OciCache {
+        let dir = self
+            .cache_dir
+            .clone()
+            .unwrap_or_else(|| OciCache::default().dir().to_path_buf());
+        OciCache::new(dir, self.cache_max_size)
+    }
 }
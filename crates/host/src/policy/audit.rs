@@ -0,0 +1,307 @@
+//! Structured audit logging of policy decisions
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt as _;
+use tokio::sync::Mutex;
+use tracing::{error, info, instrument};
+use wascap::jwt;
+
+use crate::policy::{PerformInvocationRequest, PolicyManager, RequestBody, RequestKind, Response};
+
+/// A single audited policy decision
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditRecord {
+    /// The time the decision was made, in RFC 3339 format
+    pub timestamp: String,
+    /// The kind of policy request that was evaluated
+    pub kind: RequestKind,
+    /// The request that was evaluated
+    pub request: RequestBody,
+    /// The decision returned by the policy manager
+    pub response: Response,
+}
+
+/// A trait for recording policy decisions to an audit log
+#[async_trait::async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Record a single policy decision
+    async fn record(&self, record: &AuditRecord) -> anyhow::Result<()>;
+}
+
+/// An [AuditSink] that emits each decision as a structured `tracing` event with the
+/// `wasmcloud_host::policy::audit` target
+#[derive(Default)]
+pub struct TracingAuditSink;
+
+#[async_trait::async_trait]
+impl AuditSink for TracingAuditSink {
+    async fn record(&self, record: &AuditRecord) -> anyhow::Result<()> {
+        let request =
+            serde_json::to_string(&record.request).context("failed to serialize request")?;
+        info!(
+            target: "wasmcloud_host::policy::audit",
+            request_id = record.response.request_id,
+            kind = ?record.kind,
+            permitted = record.response.permitted,
+            message = record.response.message,
+            request,
+            "policy decision"
+        );
+        Ok(())
+    }
+}
+
+/// An [AuditSink] that appends each decision to a file as a line of JSON
+pub struct FileAuditSink {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl FileAuditSink {
+    /// Open (creating if necessary) the file at `path` for appending audit records
+    pub async fn new(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .with_context(|| format!("failed to open audit log `{}`", path.display()))?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Returns the path of the audit log
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditSink for FileAuditSink {
+    async fn record(&self, record: &AuditRecord) -> anyhow::Result<()> {
+        let mut line = serde_json::to_vec(record).context("failed to serialize audit record")?;
+        line.push(b'\n');
+        let mut file = self.file.lock().await;
+        file.write_all(&line)
+            .await
+            .context("failed to write audit record")?;
+        file.flush().await.context("failed to flush audit log")
+    }
+}
+
+/// Wraps a [PolicyManager], recording every decision it makes to an [AuditSink]. Failures to
+/// record a decision are logged and do not affect the decision itself.
+pub struct AuditingPolicyManager {
+    inner: Arc<dyn PolicyManager>,
+    sink: Arc<dyn AuditSink>,
+}
+
+impl AuditingPolicyManager {
+    /// Wrap `inner`, recording each of its decisions to `sink`
+    pub fn new(inner: Arc<dyn PolicyManager>, sink: Arc<dyn AuditSink>) -> Self {
+        Self { inner, sink }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn audit(
+        &self,
+        request: RequestBody,
+        response: anyhow::Result<Response>,
+    ) -> anyhow::Result<Response> {
+        let response = response?;
+        let timestamp = OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .context("failed to format current time")?;
+        let record = AuditRecord {
+            timestamp,
+            kind: request.kind(),
+            request,
+            response,
+        };
+        if let Err(err) = self.sink.record(&record).await {
+            error!(
+                ?err,
+                request_id = record.response.request_id,
+                "failed to record policy decision"
+            );
+        }
+        Ok(record.response)
+    }
+}
+
+#[async_trait::async_trait]
+impl PolicyManager for AuditingPolicyManager {
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_start_component(
+        &self,
+        component_id: &str,
+        image_ref: &str,
+        max_instances: u32,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::Component>>,
+    ) -> anyhow::Result<Response> {
+        let response = self
+            .inner
+            .evaluate_start_component(component_id, image_ref, max_instances, annotations, claims)
+            .await;
+        self.audit(
+            RequestBody::start_component(
+                component_id,
+                image_ref,
+                max_instances,
+                annotations,
+                claims,
+            ),
+            response,
+        )
+        .await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_start_provider(
+        &self,
+        provider_id: &str,
+        provider_ref: &str,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::CapabilityProvider>>,
+    ) -> anyhow::Result<Response> {
+        let response = self
+            .inner
+            .evaluate_start_provider(provider_id, provider_ref, annotations, claims)
+            .await;
+        self.audit(
+            RequestBody::start_provider(provider_id, provider_ref, annotations, claims),
+            response,
+        )
+        .await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_perform_invocation(
+        &self,
+        component_id: &str,
+        image_ref: &str,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::Component>>,
+        interface: String,
+        function: String,
+    ) -> anyhow::Result<Response> {
+        let request = PerformInvocationRequest::new(
+            component_id,
+            image_ref,
+            annotations,
+            claims,
+            interface,
+            function,
+        );
+        let response = self
+            .inner
+            .evaluate_perform_invocation(
+                component_id,
+                image_ref,
+                annotations,
+                claims,
+                request.interface.clone(),
+                request.function.clone(),
+            )
+            .await;
+        self.audit(RequestBody::PerformInvocation(request), response)
+            .await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_perform_invocation_request(
+        &self,
+        request: &PerformInvocationRequest,
+        claims: Option<&jwt::Claims<jwt::Component>>,
+    ) -> anyhow::Result<Response> {
+        let response = self
+            .inner
+            .evaluate_perform_invocation_request(request, claims)
+            .await;
+        self.audit(RequestBody::PerformInvocation(request.clone()), response)
+            .await
+    }
+
+    fn caches_decisions(&self) -> bool {
+        self.inner.caches_decisions()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::policy::ProviderInformation;
+
+    #[derive(Default)]
+    struct MemoryAuditSink(Mutex<Vec<AuditRecord>>);
+
+    #[async_trait::async_trait]
+    impl AuditSink for MemoryAuditSink {
+        async fn record(&self, record: &AuditRecord) -> anyhow::Result<()> {
+            self.0.lock().await.push(record.clone());
+            Ok(())
+        }
+    }
+
+    struct DenyingPolicyManager;
+
+    #[async_trait::async_trait]
+    impl PolicyManager for DenyingPolicyManager {
+        async fn evaluate_start_provider(
+            &self,
+            _provider_id: &str,
+            _provider_ref: &str,
+            _annotations: &BTreeMap<String, String>,
+            _claims: Option<&jwt::Claims<jwt::CapabilityProvider>>,
+        ) -> anyhow::Result<Response> {
+            Ok(Response {
+                request_id: "denied".into(),
+                permitted: false,
+                message: Some("providers are not allowed".into()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn records_decisions() {
+        let sink = Arc::new(MemoryAuditSink::default());
+        let manager = AuditingPolicyManager::new(Arc::new(DenyingPolicyManager), sink.clone());
+        let response = manager
+            .evaluate_start_provider(
+                "provider",
+                "example.com/provider:0.1.0",
+                &BTreeMap::default(),
+                None,
+            )
+            .await
+            .expect("failed to evaluate provider start");
+        assert!(!response.permitted);
+
+        let records = sink.0.lock().await;
+        let [record] = records.as_slice() else {
+            panic!("expected exactly one audit record, got {}", records.len());
+        };
+        assert_eq!(record.kind, RequestKind::StartProvider);
+        assert_eq!(record.response.request_id, "denied");
+        assert!(matches!(
+            &record.request,
+            RequestBody::StartProvider(ProviderInformation { provider_id, .. }) if provider_id == "provider"
+        ));
+
+        let record = serde_json::to_value(record).expect("failed to serialize audit record");
+        assert_eq!(record["kind"], "startProvider");
+        assert_eq!(record["response"]["permitted"], false);
+        assert_eq!(record["request"]["providerId"], "provider");
+    }
+}
@@ -0,0 +1,413 @@
+//! A caching decorator for any [PolicyManager], storing decisions for a configurable TTL
+
+use core::time::Duration;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use futures::stream::AbortHandle;
+
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+use tracing::{debug, instrument, trace, warn};
+use wascap::jwt;
+
+use crate::policy::{PerformInvocationRequest, PolicyManager, RequestBody, RequestKey, Response};
+
+/// The default amount of time a cached policy decision is considered valid
+pub const DEFAULT_DECISION_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug)]
+struct CachedDecision {
+    response: Response,
+    expires_at: Instant,
+}
+
+/// Wraps a [PolicyManager], caching its decisions keyed on the request kind and target (for
+/// invocations, the component, interface and function) until they expire.
+///
+/// Cached decisions can be invalidated or overridden by request ID, for example in response to
+/// messages on [crate::wasmbus::host_config::PolicyService::policy_changes_topic], see
+/// [crate::nats::policy::watch_policy_changes].
+pub struct CachingPolicyManager {
+    inner: Arc<dyn PolicyManager>,
+    ttl: Duration,
+    decision_cache: RwLock<HashMap<RequestKey, CachedDecision>>,
+    request_to_key: RwLock<HashMap<String, RequestKey>>,
+    /// Abort handle of the task watching for policy changes, if any
+    policy_changes: Mutex<Option<AbortHandle>>,
+}
+
+impl Drop for CachingPolicyManager {
+    fn drop(&mut self) {
+        if let Some(policy_changes) = self.policy_changes.get_mut().ok().and_then(Option::take) {
+            policy_changes.abort();
+        }
+    }
+}
+
+impl CachingPolicyManager {
+    /// Wrap `inner`, caching its decisions for `ttl`
+    pub fn new(inner: Arc<dyn PolicyManager>, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            decision_cache: RwLock::default(),
+            request_to_key: RwLock::default(),
+            policy_changes: Mutex::default(),
+        }
+    }
+
+    /// Wrap `inner`, caching its decisions for `ttl`. Returns [None] if `inner` already caches
+    /// its decisions, see [PolicyManager::caches_decisions].
+    pub(crate) fn wrap(inner: &Arc<dyn PolicyManager>, ttl: Duration) -> Option<Arc<Self>> {
+        if inner.caches_decisions() {
+            warn!(
+                ?ttl,
+                "policy manager already caches decisions, ignoring policy cache TTL"
+            );
+            return None;
+        }
+        Some(Arc::new(Self::new(Arc::clone(inner), ttl)))
+    }
+
+    /// Set the abort handle of the task watching for policy changes applied to this cache, see
+    /// [crate::nats::policy::watch_policy_changes]. The task is aborted when the cache is dropped
+    /// or another handle is set.
+    pub fn set_policy_changes(&self, policy_changes: AbortHandle) {
+        let previous = self
+            .policy_changes
+            .lock()
+            .map(|mut handle| handle.replace(policy_changes))
+            .unwrap_or_default();
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+    }
+
+    /// Returns the amount of time a decision is cached for
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns the number of decisions currently cached, including expired decisions that have
+    /// not been evicted yet
+    pub async fn len(&self) -> usize {
+        self.decision_cache.read().await.len()
+    }
+
+    /// Returns `true` if no decisions are cached
+    pub async fn is_empty(&self) -> bool {
+        self.decision_cache.read().await.is_empty()
+    }
+
+    /// Remove the cached decision produced by the request with `request_id`. Returns `true` if a
+    /// decision was removed.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn invalidate(&self, request_id: &str) -> bool {
+        let Some(key) = self.request_to_key.write().await.remove(request_id) else {
+            return false;
+        };
+        self.decision_cache.write().await.remove(&key).is_some()
+    }
+
+    /// Remove all cached decisions
+    #[instrument(level = "debug", skip(self))]
+    pub async fn invalidate_all(&self) {
+        self.decision_cache.write().await.clear();
+        self.request_to_key.write().await.clear();
+    }
+
+    /// Replace the cached decision for the request identified by `response.request_id`,
+    /// resetting its TTL. Returns `false` if the request ID is unknown.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn override_decision(&self, response: Response) -> bool {
+        let request_to_key = self.request_to_key.read().await;
+        let Some(key) = request_to_key.get(&response.request_id) else {
+            return false;
+        };
+        self.decision_cache.write().await.insert(
+            key.clone(),
+            CachedDecision {
+                response,
+                expires_at: Instant::now() + self.ttl,
+            },
+        );
+        true
+    }
+
+    /// Evaluate `request`, returning a cached decision if one exists and has not expired, and
+    /// calling `evaluate` to produce and cache a new decision otherwise
+    async fn evaluate_cached(
+        &self,
+        request: RequestBody,
+        evaluate: impl core::future::Future<Output = anyhow::Result<Response>>,
+    ) -> anyhow::Result<Response> {
+        let cache_key = RequestKey::from(&request);
+        if let Some(entry) = self.decision_cache.read().await.get(&cache_key) {
+            if entry.expires_at > Instant::now() {
+                trace!(?cache_key, "using cached policy decision");
+                return Ok(entry.response.clone());
+            }
+        }
+
+        let response = evaluate.await?;
+        let mut decision_cache = self.decision_cache.write().await;
+        let mut request_to_key = self.request_to_key.write().await;
+        if let Some(expired) = decision_cache.insert(
+            cache_key.clone(),
+            CachedDecision {
+                response: response.clone(),
+                expires_at: Instant::now() + self.ttl,
+            },
+        ) {
+            debug!(?cache_key, "replacing expired policy decision");
+            request_to_key.remove(&expired.response.request_id);
+        }
+        request_to_key.insert(response.request_id.clone(), cache_key);
+        Ok(response)
+    }
+}
+
+#[async_trait::async_trait]
+impl PolicyManager for CachingPolicyManager {
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_start_component(
+        &self,
+        component_id: &str,
+        image_ref: &str,
+        max_instances: u32,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::Component>>,
+    ) -> anyhow::Result<Response> {
+        self.evaluate_cached(
+            RequestBody::start_component(
+                component_id,
+                image_ref,
+                max_instances,
+                annotations,
+                claims,
+            ),
+            self.inner.evaluate_start_component(
+                component_id,
+                image_ref,
+                max_instances,
+                annotations,
+                claims,
+            ),
+        )
+        .await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_start_provider(
+        &self,
+        provider_id: &str,
+        provider_ref: &str,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::CapabilityProvider>>,
+    ) -> anyhow::Result<Response> {
+        self.evaluate_cached(
+            RequestBody::start_provider(provider_id, provider_ref, annotations, claims),
+            self.inner
+                .evaluate_start_provider(provider_id, provider_ref, annotations, claims),
+        )
+        .await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_perform_invocation(
+        &self,
+        component_id: &str,
+        image_ref: &str,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::Component>>,
+        interface: String,
+        function: String,
+    ) -> anyhow::Result<Response> {
+        self.evaluate_cached(
+            RequestBody::PerformInvocation(PerformInvocationRequest::new(
+                component_id,
+                image_ref,
+                annotations,
+                claims,
+                interface.clone(),
+                function.clone(),
+            )),
+            self.inner.evaluate_perform_invocation(
+                component_id,
+                image_ref,
+                annotations,
+                claims,
+                interface,
+                function,
+            ),
+        )
+        .await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_perform_invocation_request(
+        &self,
+        request: &PerformInvocationRequest,
+        claims: Option<&jwt::Claims<jwt::Component>>,
+    ) -> anyhow::Result<Response> {
+        self.evaluate_cached(
+            RequestBody::PerformInvocation(request.clone()),
+            self.inner
+                .evaluate_perform_invocation_request(request, claims),
+        )
+        .await
+    }
+
+    fn caches_decisions(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::policy::InvocationSource;
+
+    /// Permits every request, counting the number of evaluations
+    #[derive(Default)]
+    struct CountingPolicyManager(AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl PolicyManager for CountingPolicyManager {
+        async fn evaluate_perform_invocation_request(
+            &self,
+            _request: &PerformInvocationRequest,
+            _claims: Option<&jwt::Claims<jwt::Component>>,
+        ) -> anyhow::Result<Response> {
+            let n = self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(Response {
+                request_id: format!("request-{n}"),
+                permitted: true,
+                message: None,
+            })
+        }
+    }
+
+    async fn invoke(cache: &CachingPolicyManager, function: &str) -> Response {
+        invoke_from(cache, function, None).await
+    }
+
+    async fn invoke_from(
+        cache: &CachingPolicyManager,
+        function: &str,
+        source: Option<&InvocationSource>,
+    ) -> Response {
+        let request = PerformInvocationRequest::new(
+            "component",
+            "example.com/component:0.1.0",
+            &BTreeMap::default(),
+            None,
+            "wasi:http/incoming-handler".into(),
+            function.into(),
+        )
+        .with_source(source.cloned());
+        cache
+            .evaluate_perform_invocation_request(&request, None)
+            .await
+            .expect("failed to evaluate invocation")
+    }
+
+    #[tokio::test]
+    async fn caches_decisions_until_expired() {
+        let inner = Arc::new(CountingPolicyManager::default());
+        let cache = CachingPolicyManager::new(inner.clone(), DEFAULT_DECISION_TTL);
+        assert_eq!(invoke(&cache, "handle").await.request_id, "request-0");
+        assert_eq!(invoke(&cache, "handle").await.request_id, "request-0");
+        assert_eq!(invoke(&cache, "other").await.request_id, "request-1");
+        assert_eq!(inner.0.load(Ordering::Relaxed), 2);
+        assert_eq!(cache.len().await, 2);
+
+        // Decisions are cached per caller
+        let source = InvocationSource {
+            source_id: "caller".into(),
+            claims: None,
+            verified: true,
+        };
+        assert_eq!(
+            invoke_from(&cache, "handle", Some(&source))
+                .await
+                .request_id,
+            "request-2"
+        );
+        assert_eq!(cache.len().await, 3);
+
+        let inner = Arc::new(CountingPolicyManager::default());
+        let cache = CachingPolicyManager::new(inner.clone(), Duration::ZERO);
+        invoke(&cache, "handle").await;
+        assert_eq!(invoke(&cache, "handle").await.request_id, "request-1");
+        assert_eq!(inner.0.load(Ordering::Relaxed), 2);
+        assert_eq!(cache.len().await, 1);
+    }
+
+    #[tokio::test]
+    async fn overrides_and_invalidates_decisions() {
+        let inner = Arc::new(CountingPolicyManager::default());
+        let cache = CachingPolicyManager::new(inner.clone(), DEFAULT_DECISION_TTL);
+        invoke(&cache, "handle").await;
+
+        assert!(
+            cache
+                .override_decision(Response {
+                    request_id: "request-0".into(),
+                    permitted: false,
+                    message: Some("revoked".into()),
+                })
+                .await
+        );
+        assert!(!invoke(&cache, "handle").await.permitted);
+        assert!(
+            !cache
+                .override_decision(Response {
+                    request_id: "unknown".into(),
+                    permitted: true,
+                    message: None,
+                })
+                .await
+        );
+
+        assert!(cache.invalidate("request-0").await);
+        assert!(!cache.invalidate("request-0").await);
+        assert!(invoke(&cache, "handle").await.permitted);
+        assert_eq!(inner.0.load(Ordering::Relaxed), 2);
+
+        cache.invalidate_all().await;
+        assert!(cache.is_empty().await);
+    }
+
+    #[test]
+    fn aborts_policy_changes_on_drop() {
+        let cache = CachingPolicyManager::new(
+            Arc::new(CountingPolicyManager::default()),
+            DEFAULT_DECISION_TTL,
+        );
+        assert!(cache.caches_decisions());
+        assert!(!CountingPolicyManager::default().caches_decisions());
+
+        let (first, _) = AbortHandle::new_pair();
+        let (second, _) = AbortHandle::new_pair();
+        cache.set_policy_changes(first.clone());
+        cache.set_policy_changes(second.clone());
+        assert!(first.is_aborted());
+        assert!(!second.is_aborted());
+        drop(cache);
+        assert!(second.is_aborted());
+    }
+
+    #[test]
+    fn wraps_only_uncached_managers() {
+        let inner: Arc<dyn PolicyManager> = Arc::new(CountingPolicyManager::default());
+        let cache: Arc<dyn PolicyManager> =
+            CachingPolicyManager::wrap(&inner, DEFAULT_DECISION_TTL)
+                .expect("uncached manager should be wrapped");
+        assert!(CachingPolicyManager::wrap(&cache, DEFAULT_DECISION_TTL).is_none());
+    }
+}
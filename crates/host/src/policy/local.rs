@@ -0,0 +1,740 @@
+//! Policy manager implementation that evaluates requests in-process against a declarative rule
+//! file on the local filesystem.
+//!
+//! Rules are evaluated in order and the first rule that matches a request decides the outcome. If
+//! no rule matches, the file's `default` decision is used, which denies the request if omitted. A
+//! rule matches when *all* of its non-empty criteria match; an omitted criterion matches
+//! everything. String criteria support `*` (any sequence of characters) and `?` (any single
+//! character) wildcards.
+//!
+//! ```yaml
+//! default: deny
+//! allowedIssuers:
+//!   - ACOJJN6WUP4ODD75XEBKKTCCUJJCY5ZKQ56XVKYK4BEJWGVAOOQHZMCW
+//! rules:
+//!   - action: allow
+//!     kinds: [startComponent, startProvider]
+//!     imageRefs: ["ghcr.io/wasmcloud/*"]
+//!   - action: allow
+//!     kinds: [performInvocation]
+//!     annotations:
+//!       wasmcloud.dev/appspec: "billing-*"
+//!     interfaces: ["wasi:http/incoming-handler"]
+//!   - action: allow
+//!     kinds: [performInvocation]
+//!     imageRefs: ["ghcr.io/acme/ledger:*"]
+//!     sources: ["billing-api"]
+//!   - action: deny
+//!     kinds: [performInvocation]
+//!     imageRefs: ["ghcr.io/acme/ledger:*"]
+//! ```
+//!
+//! The rule file may be written in either JSON or YAML. The file is periodically checked for
+//! modifications and reloaded in place, so rule changes apply without restarting the host. If a
+//! modified file fails to parse, the previously loaded rules remain in effect.
+
+use core::time::Duration;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use anyhow::Context as _;
+use futures::stream::{AbortHandle, Abortable};
+use serde::Deserialize;
+use tokio::spawn;
+use tokio::sync::RwLock;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, error, info, instrument, trace};
+use ulid::Ulid;
+use uuid::Uuid;
+use wascap::jwt;
+
+use crate::policy::{PerformInvocationRequest, PolicyManager, RequestBody, RequestKind, Response};
+
+/// The default interval at which the rule file is checked for modifications
+pub const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The outcome of a matching policy rule
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Decision {
+    /// Permit the request
+    Allow,
+    /// Deny the request
+    #[default]
+    Deny,
+}
+
+/// A single rule in a [PolicyRules] file
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PolicyRule {
+    /// The decision applied when this rule matches
+    pub action: Decision,
+    /// The kinds of requests this rule applies to. Applies to all kinds if empty
+    #[serde(default)]
+    pub kinds: Vec<RequestKind>,
+    /// Issuer public keys (or patterns) that the entity's claims must be signed by
+    #[serde(default)]
+    pub issuers: Vec<String>,
+    /// Image reference patterns, e.g. `ghcr.io/wasmcloud/*`
+    #[serde(default)]
+    pub image_refs: Vec<String>,
+    /// Annotations that must be present on the entity, mapping keys to value patterns
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// Interface patterns, e.g. `wasi:http/incoming-handler`. Only matches invocation requests
+    #[serde(default)]
+    pub interfaces: Vec<String>,
+    /// Function patterns, e.g. `handle`. Only matches invocation requests
+    #[serde(default)]
+    pub functions: Vec<String>,
+    /// Patterns of the component or provider ID calling the target. Only matches invocation
+    /// requests with a verified caller, i.e. from the same host or signed by a trusted host
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Patterns of the public key of the verified claims of the caller. Only matches invocation
+    /// requests from a signed caller whose claims are known in the lattice
+    #[serde(default)]
+    pub source_subjects: Vec<String>,
+    /// An optional message returned with the decision, suitable for logging
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// A declarative set of policy rules, typically loaded from a file
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PolicyRules {
+    /// The decision used when no rule matches a request, [Decision::Deny] if omitted
+    #[serde(default)]
+    pub default: Decision,
+    /// If non-empty, any request for an entity whose claims are not signed by one of these issuers
+    /// is denied before rules are evaluated. Unsigned entities are denied as well.
+    #[serde(default)]
+    pub allowed_issuers: Vec<String>,
+    /// Ordered list of rules, the first matching rule decides the request
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+impl PolicyRules {
+    /// Parse rules from the contents of a rule file. `path` is used to determine the format,
+    /// YAML is used for `.yaml` and `.yml` extensions and JSON otherwise.
+    pub fn parse(path: impl AsRef<Path>, contents: &[u8]) -> anyhow::Result<Self> {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => {
+                serde_yaml::from_slice(contents).context("failed to parse YAML policy rules")
+            }
+            _ => serde_json::from_slice(contents).context("failed to parse JSON policy rules"),
+        }
+    }
+
+    /// Read and parse rules from a file
+    pub async fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let contents = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed to read policy rules from `{}`", path.display()))?;
+        Self::parse(path, &contents)
+    }
+
+    /// Evaluate a request against the rules, returning the decision and an optional message
+    pub fn evaluate(&self, request: &RequestBody) -> (Decision, Option<String>) {
+        let Some(target) = RequestTarget::from_request(request) else {
+            return (Decision::Deny, Some("unknown policy request kind".into()));
+        };
+        if !self.allowed_issuers.is_empty()
+            && !target.issuer.is_some_and(|issuer| {
+                self.allowed_issuers
+                    .iter()
+                    .any(|pattern| glob_match(pattern, issuer))
+            })
+        {
+            return (
+                Decision::Deny,
+                Some(format!(
+                    "issuer `{}` is not allowed",
+                    target.issuer.unwrap_or("<unsigned>")
+                )),
+            );
+        }
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(&target))
+            .map(|rule| (rule.action, rule.message.clone()))
+            .unwrap_or((self.default, None))
+    }
+}
+
+impl PolicyRule {
+    fn matches(&self, target: &RequestTarget<'_>) -> bool {
+        let any = |patterns: &[String], value: &str| {
+            patterns.iter().any(|pattern| glob_match(pattern, value))
+        };
+        (self.kinds.is_empty() || self.kinds.contains(&target.kind))
+            && (self.issuers.is_empty() || target.issuer.is_some_and(|i| any(&self.issuers, i)))
+            && (self.image_refs.is_empty() || any(&self.image_refs, target.image_ref))
+            && self.annotations.iter().all(|(key, pattern)| {
+                target
+                    .annotations
+                    .get(key)
+                    .is_some_and(|value| glob_match(pattern, value))
+            })
+            && (self.interfaces.is_empty()
+                || target.interface.is_some_and(|i| any(&self.interfaces, i)))
+            && (self.functions.is_empty()
+                || target.function.is_some_and(|f| any(&self.functions, f)))
+            && (self.sources.is_empty() || target.source_id.is_some_and(|s| any(&self.sources, s)))
+            && (self.source_subjects.is_empty()
+                || target
+                    .source_subject
+                    .is_some_and(|s| any(&self.source_subjects, s)))
+    }
+}
+
+/// The fields of a [RequestBody] that rules are matched against
+struct RequestTarget<'a> {
+    kind: RequestKind,
+    issuer: Option<&'a str>,
+    image_ref: &'a str,
+    annotations: &'a BTreeMap<String, String>,
+    interface: Option<&'a str>,
+    function: Option<&'a str>,
+    source_id: Option<&'a str>,
+    source_subject: Option<&'a str>,
+}
+
+impl<'a> RequestTarget<'a> {
+    fn from_request(request: &'a RequestBody) -> Option<Self> {
+        match request {
+            RequestBody::StartComponent(component) => Some(Self {
+                kind: RequestKind::StartComponent,
+                issuer: component.claims.as_ref().map(|c| c.issuer.as_str()),
+                image_ref: &component.image_ref,
+                annotations: &component.annotations,
+                interface: None,
+                function: None,
+                source_id: None,
+                source_subject: None,
+            }),
+            RequestBody::StartProvider(provider) => Some(Self {
+                kind: RequestKind::StartProvider,
+                issuer: provider.claims.as_ref().map(|c| c.issuer.as_str()),
+                image_ref: &provider.image_ref,
+                annotations: &provider.annotations,
+                interface: None,
+                function: None,
+                source_id: None,
+                source_subject: None,
+            }),
+            RequestBody::PerformInvocation(invocation) => Some(Self {
+                kind: RequestKind::PerformInvocation,
+                issuer: invocation.target.claims.as_ref().map(|c| c.issuer.as_str()),
+                image_ref: &invocation.target.image_ref,
+                annotations: &invocation.target.annotations,
+                interface: Some(&invocation.interface),
+                function: Some(&invocation.function),
+                // Rules never match callers whose identity was not verified
+                source_id: invocation
+                    .source
+                    .as_ref()
+                    .filter(|source| source.verified)
+                    .map(|source| source.source_id.as_str()),
+                source_subject: invocation
+                    .source
+                    .as_ref()
+                    .filter(|source| source.verified)
+                    .and_then(|source| source.claims.as_ref())
+                    .map(|claims| claims.public_key.as_str()),
+            }),
+            RequestBody::Unknown => None,
+        }
+    }
+}
+
+/// Match `value` against `pattern`, where `*` matches any sequence of characters and `?`
+/// matches exactly one character
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    let (mut p, mut v) = (0, 0);
+    // Position of the last `*` in the pattern and the value position it was tried against
+    let mut backtrack: Option<(usize, usize)> = None;
+    while v < value.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, v));
+                p += 1;
+            }
+            Some('?') => {
+                p += 1;
+                v += 1;
+            }
+            Some(c) if *c == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => {
+                let Some((star, matched)) = backtrack else {
+                    return false;
+                };
+                p = star + 1;
+                v = matched + 1;
+                backtrack = Some((star, v));
+            }
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Evaluates policy decisions in-process against rules loaded from a file, reloading the
+/// rules when the file changes
+pub struct LocalPolicyManager {
+    path: PathBuf,
+    rules: Arc<RwLock<PolicyRules>>,
+    /// An abort handle for the rule file reload task
+    pub reload: AbortHandle,
+}
+
+impl LocalPolicyManager {
+    /// Construct a new policy manager, loading rules from `path`. The file is checked for
+    /// modifications every `reload_interval`, defaulting to [DEFAULT_RELOAD_INTERVAL].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rule file cannot be read or parsed
+    #[instrument(level = "debug")]
+    pub async fn new(
+        path: impl Into<PathBuf> + core::fmt::Debug,
+        reload_interval: Option<Duration>,
+    ) -> anyhow::Result<Self> {
+        let path = path.into();
+        let rules = Arc::new(RwLock::new(PolicyRules::load(&path).await?));
+        let mut modified = modified_at(&path).await;
+        info!(path = %path.display(), "loaded local policy rules");
+
+        let (reload, reload_reg) = AbortHandle::new_pair();
+        let mut ticks = interval(reload_interval.unwrap_or(DEFAULT_RELOAD_INTERVAL));
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        spawn(Abortable::new(
+            {
+                let path = path.clone();
+                let rules = Arc::clone(&rules);
+                async move {
+                    loop {
+                        ticks.tick().await;
+                        let current = modified_at(&path).await;
+                        if current == modified {
+                            continue;
+                        }
+                        modified = current;
+                        match PolicyRules::load(&path).await {
+                            Ok(new_rules) => {
+                                info!(path = %path.display(), "reloaded local policy rules");
+                                *rules.write().await = new_rules;
+                            }
+                            Err(err) => error!(
+                                path = %path.display(),
+                                ?err,
+                                "failed to reload local policy rules, keeping previous rules"
+                            ),
+                        }
+                    }
+                }
+            },
+            reload_reg,
+        ));
+
+        Ok(Self {
+            path,
+            rules,
+            reload,
+        })
+    }
+
+    /// Construct a new policy manager as in [Self::new], for use by the host builders
+    pub(crate) async fn load(
+        path: PathBuf,
+        reload_interval: Option<Duration>,
+    ) -> anyhow::Result<Arc<dyn PolicyManager>> {
+        let policy_manager = Self::new(path, reload_interval)
+            .await
+            .context("failed to load local policy rules")?;
+        Ok(Arc::new(policy_manager))
+    }
+
+    /// Returns the path the rules are loaded from
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Evaluate a request against the currently loaded rules
+    #[instrument(level = "trace", skip_all)]
+    pub async fn evaluate_action(&self, request: RequestBody) -> anyhow::Result<Response> {
+        let (decision, message) = self.rules.read().await.evaluate(&request);
+        trace!(?request, ?decision, "evaluated local policy");
+        if decision == Decision::Deny {
+            debug!(?request, ?message, "local policy denied request");
+        }
+        Ok(Response {
+            request_id: Uuid::from_u128(Ulid::new().into()).to_string(),
+            permitted: decision == Decision::Allow,
+            message,
+        })
+    }
+}
+
+impl Drop for LocalPolicyManager {
+    fn drop(&mut self) {
+        self.reload.abort();
+    }
+}
+
+async fn modified_at(path: &Path) -> Option<SystemTime> {
+    tokio::fs::metadata(path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[async_trait::async_trait]
+impl PolicyManager for LocalPolicyManager {
+    /// Use the local rules to evaluate whether a component may be started
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_start_component(
+        &self,
+        component_id: &str,
+        image_ref: &str,
+        max_instances: u32,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::Component>>,
+    ) -> anyhow::Result<Response> {
+        self.evaluate_action(RequestBody::start_component(
+            component_id,
+            image_ref,
+            max_instances,
+            annotations,
+            claims,
+        ))
+        .await
+    }
+
+    /// Use the local rules to evaluate whether a provider may be started
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_start_provider(
+        &self,
+        provider_id: &str,
+        provider_ref: &str,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::CapabilityProvider>>,
+    ) -> anyhow::Result<Response> {
+        self.evaluate_action(RequestBody::start_provider(
+            provider_id,
+            provider_ref,
+            annotations,
+            claims,
+        ))
+        .await
+    }
+
+    /// Use the local rules to evaluate whether a component may be invoked
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_perform_invocation(
+        &self,
+        component_id: &str,
+        image_ref: &str,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::Component>>,
+        interface: String,
+        function: String,
+    ) -> anyhow::Result<Response> {
+        self.evaluate_perform_invocation_request(
+            &PerformInvocationRequest::new(
+                component_id,
+                image_ref,
+                annotations,
+                claims,
+                interface,
+                function,
+            ),
+            claims,
+        )
+        .await
+    }
+
+    /// Use the local rules to evaluate whether a component may be invoked, taking its caller into account
+    #[instrument(level = "trace", skip_all)]
+    async fn evaluate_perform_invocation_request(
+        &self,
+        request: &PerformInvocationRequest,
+        _claims: Option<&jwt::Claims<jwt::Component>>,
+    ) -> anyhow::Result<Response> {
+        self.evaluate_action(RequestBody::PerformInvocation(request.clone()))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{glob_match, Decision, PolicyRules};
+    use crate::policy::{
+        ComponentInformation, InvocationSource, PerformInvocationRequest, PolicyClaims,
+        ProviderInformation, RequestBody,
+    };
+
+    const RULES: &str = r#"
+default: deny
+rules:
+  - action: deny
+    kinds: [startComponent]
+    imageRefs: ["ghcr.io/evil/*"]
+    message: no evil components
+  - action: allow
+    kinds: [startComponent, startProvider]
+    imageRefs: ["ghcr.io/*"]
+  - action: allow
+    kinds: [performInvocation]
+    annotations:
+      team: "billing-?"
+    interfaces: ["wasi:http/*"]
+    functions: ["handle"]
+"#;
+
+    fn component(image_ref: &str, annotations: &[(&str, &str)]) -> ComponentInformation {
+        ComponentInformation {
+            component_id: "component".into(),
+            image_ref: image_ref.into(),
+            annotations: annotations
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn invocation(annotations: &[(&str, &str)], interface: &str) -> RequestBody {
+        RequestBody::PerformInvocation(PerformInvocationRequest {
+            interface: interface.into(),
+            function: "handle".into(),
+            target: component("ghcr.io/acme/billing:0.1.0", annotations),
+            source: None,
+        })
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("ghcr.io/*", "ghcr.io/acme/foo:0.1.0"));
+        assert!(glob_match("ghcr.io/*/foo:*", "ghcr.io/acme/foo:0.1.0"));
+        assert!(glob_match("a?c", "abc"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("a?c", "abbc"));
+        assert!(!glob_match("ghcr.io/*", "docker.io/ghcr.io/foo"));
+        assert!(!glob_match("exact", "exact-not"));
+    }
+
+    #[test]
+    fn evaluates_rules_in_order() {
+        let rules = PolicyRules::parse("rules.yaml", RULES.as_bytes()).expect("failed to parse");
+
+        assert_eq!(
+            rules.evaluate(&RequestBody::StartComponent(component(
+                "ghcr.io/evil/foo:0.1.0",
+                &[]
+            ))),
+            (Decision::Deny, Some("no evil components".into()))
+        );
+        assert_eq!(
+            rules
+                .evaluate(&RequestBody::StartComponent(component(
+                    "ghcr.io/acme/foo:0.1.0",
+                    &[]
+                )))
+                .0,
+            Decision::Allow
+        );
+        assert_eq!(
+            rules
+                .evaluate(&RequestBody::StartProvider(ProviderInformation {
+                    image_ref: "docker.io/acme/provider:0.1.0".into(),
+                    ..Default::default()
+                }))
+                .0,
+            Decision::Deny,
+            "unmatched requests should fall back to the default"
+        );
+        assert_eq!(
+            rules
+                .evaluate(&invocation(
+                    &[("team", "billing-1")],
+                    "wasi:http/incoming-handler"
+                ))
+                .0,
+            Decision::Allow
+        );
+        assert_eq!(
+            rules
+                .evaluate(&invocation(&[("team", "billing-1")], "wasi:keyvalue/store"))
+                .0,
+            Decision::Deny
+        );
+        assert_eq!(
+            rules
+                .evaluate(&invocation(&[], "wasi:http/incoming-handler"))
+                .0,
+            Decision::Deny,
+            "missing annotations should not match"
+        );
+        assert_eq!(rules.evaluate(&RequestBody::Unknown).0, Decision::Deny);
+    }
+
+    #[test]
+    fn enforces_allowed_issuers() {
+        let rules = PolicyRules::parse(
+            "rules.json",
+            br#"{"default": "allow", "allowedIssuers": ["ATRUSTED*"], "rules": []}"#,
+        )
+        .expect("failed to parse");
+        let mut signed = component("ghcr.io/acme/foo:0.1.0", &[]);
+        signed.claims = Some(PolicyClaims {
+            issuer: "ATRUSTEDISSUER".into(),
+            ..Default::default()
+        });
+        assert_eq!(
+            rules
+                .evaluate(&RequestBody::StartComponent(signed.clone()))
+                .0,
+            Decision::Allow
+        );
+
+        signed.claims = Some(PolicyClaims {
+            issuer: "AUNTRUSTED".into(),
+            ..Default::default()
+        });
+        assert_eq!(
+            rules.evaluate(&RequestBody::StartComponent(signed)).0,
+            Decision::Deny
+        );
+        assert_eq!(
+            rules
+                .evaluate(&RequestBody::StartComponent(component(
+                    "ghcr.io/acme/foo:0.1.0",
+                    &[]
+                )))
+                .0,
+            Decision::Deny,
+            "unsigned components should be denied when issuers are restricted"
+        );
+    }
+
+    #[test]
+    fn matches_invocation_sources() {
+        let rules = PolicyRules::parse(
+            "rules.yaml",
+            br#"
+default: deny
+rules:
+  - action: allow
+    sources: ["billing-*"]
+    sourceSubjects: ["MTRUSTED*"]
+"#,
+        )
+        .expect("failed to parse");
+        let from = |source_id: &str, subject: Option<&str>, verified: bool| {
+            RequestBody::PerformInvocation(PerformInvocationRequest {
+                interface: "wasi:http/incoming-handler".into(),
+                function: "handle".into(),
+                target: component("ghcr.io/acme/ledger:0.1.0", &[]),
+                source: Some(InvocationSource {
+                    source_id: source_id.into(),
+                    claims: subject.map(|subject| PolicyClaims {
+                        public_key: subject.into(),
+                        ..Default::default()
+                    }),
+                    verified,
+                }),
+            })
+        };
+
+        assert_eq!(
+            rules
+                .evaluate(&from("billing-api", Some("MTRUSTEDKEY"), true))
+                .0,
+            Decision::Allow
+        );
+        assert_eq!(
+            rules.evaluate(&from("billing-api", None, true)).0,
+            Decision::Deny,
+            "callers with unknown claims should not match subject criteria"
+        );
+        assert_eq!(
+            rules
+                .evaluate(&from("billing-api", Some("MTRUSTEDKEY"), false))
+                .0,
+            Decision::Deny,
+            "unverified callers should not match source criteria"
+        );
+        assert_eq!(
+            rules.evaluate(&from("other", Some("MTRUSTEDKEY"), true)).0,
+            Decision::Deny
+        );
+        assert_eq!(
+            rules
+                .evaluate(&invocation(&[], "wasi:http/incoming-handler"))
+                .0,
+            Decision::Deny,
+            "invocations without a known caller should not match source criteria"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        assert!(PolicyRules::parse("rules.json", br#"{"rulez": []}"#).is_err());
+        assert_eq!(
+            PolicyRules::parse("rules.json", b"{}").expect("failed to parse"),
+            PolicyRules {
+                default: Decision::Deny,
+                allowed_issuers: vec![],
+                rules: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn denies_without_default() {
+        let rules = PolicyRules::parse(
+            "rules.yaml",
+            br#"
+rules:
+  - action: allow
+    kinds: [startComponent]
+    imageRefs: ["ghcr.io/*"]
+"#,
+        )
+        .expect("failed to parse");
+        assert_eq!(
+            rules
+                .evaluate(&RequestBody::StartComponent(component(
+                    "ghcr.io/acme/foo:0.1.0",
+                    &[]
+                )))
+                .0,
+            Decision::Allow
+        );
+        assert_eq!(
+            rules
+                .evaluate(&RequestBody::StartComponent(component(
+                    "docker.io/acme/foo:0.1.0",
+                    &[]
+                )))
+                .0,
+            Decision::Deny,
+            "unmatched requests should be denied if no default is set"
+        );
+        assert_eq!(rules.evaluate(&RequestBody::Unknown).0, Decision::Deny);
+    }
+}
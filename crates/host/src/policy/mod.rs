@@ -6,6 +6,14 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use wascap::jwt;
 
+/// Structured audit logging of policy decisions made by any [PolicyManager]
+pub mod audit;
+/// A TTL-based decision cache that can wrap any [PolicyManager]
+pub mod cache;
+/// In-process implementation of the [PolicyManager] trait, evaluating requests against a
+/// declarative rule file
+pub mod local;
+
 // NOTE: All requests will be v1 until the schema changes, at which point we can change the version
 // per-request type
 pub(crate) const POLICY_TYPE_VERSION: &str = "v1";
@@ -60,6 +68,33 @@ pub trait PolicyManager: Send + Sync {
             message: None,
         })
     }
+
+    /// Evaluate whether a component may perform the invocation described by `request`, including
+    /// its caller. `claims` are the claims of the invoked component.
+    ///
+    /// The caller is only trustworthy if [InvocationSource::verified] is set. The default
+    /// implementation ignores the caller and calls [PolicyManager::evaluate_perform_invocation].
+    async fn evaluate_perform_invocation_request(
+        &self,
+        request: &PerformInvocationRequest,
+        claims: Option<&jwt::Claims<jwt::Component>>,
+    ) -> anyhow::Result<Response> {
+        self.evaluate_perform_invocation(
+            &request.target.component_id,
+            &request.target.image_ref,
+            &request.target.annotations,
+            claims,
+            request.interface.clone(),
+            request.function.clone(),
+        )
+        .await
+    }
+
+    /// Returns `true` if the manager caches its decisions itself, in which case it should not be
+    /// wrapped in a [cache::CachingPolicyManager]
+    fn caches_decisions(&self) -> bool {
+        false
+    }
 }
 
 /// A default policy manager that always returns true for all requests
@@ -119,6 +154,21 @@ pub struct ProviderInformation {
     pub claims: Option<PolicyClaims>,
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Hash)]
+/// Relevant policy information about the caller of an invocation
+pub struct InvocationSource {
+    /// The unique identifier of the calling component or provider, as reported by the caller
+    #[serde(rename = "sourceId")]
+    pub source_id: String,
+    /// Claims of the caller, if the caller is signed and its claims are known in the lattice.
+    /// These are looked up by the receiving host from the subject reported by the caller
+    pub claims: Option<PolicyClaims>,
+    /// Whether the identity of the caller was verified, i.e. the invocation originates from the
+    /// receiving host or carries a valid invocation token issued for the caller by a trusted host.
+    /// Decisions based on the caller must not be made for unverified callers
+    pub verified: bool,
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Hash)]
 /// A request to invoke a component function
 pub struct PerformInvocationRequest {
@@ -128,6 +178,40 @@ pub struct PerformInvocationRequest {
     pub function: String,
     /// Target of the invocation
     pub target: ComponentInformation,
+    /// Caller of the invocation, if known
+    pub source: Option<InvocationSource>,
+}
+
+impl PerformInvocationRequest {
+    /// Construct a request to invoke `function` of `interface` exported by a component, without a
+    /// known caller
+    pub fn new(
+        component_id: &str,
+        image_ref: &str,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::Component>>,
+        interface: String,
+        function: String,
+    ) -> Self {
+        Self {
+            interface,
+            function,
+            target: ComponentInformation {
+                component_id: component_id.to_string(),
+                image_ref: image_ref.to_string(),
+                max_instances: 0,
+                annotations: annotations.clone(),
+                claims: claims.map(PolicyClaims::from),
+            },
+            source: None,
+        }
+    }
+
+    /// Set the caller of the invocation
+    #[must_use]
+    pub fn with_source(self, source: Option<InvocationSource>) -> Self {
+        Self { source, ..self }
+    }
 }
 
 /// Relevant information about the host that is receiving the invocation, or starting the component or provider
@@ -144,7 +228,7 @@ pub struct HostInfo {
 }
 
 /// The action being requested
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub enum RequestKind {
     /// The host is checking whether it may invoke the target component
     #[serde(rename = "performInvocation")]
@@ -174,6 +258,50 @@ pub enum RequestBody {
     Unknown,
 }
 
+impl RequestBody {
+    /// Construct the body of a request to start a component
+    pub(crate) fn start_component(
+        component_id: &str,
+        image_ref: &str,
+        max_instances: u32,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::Component>>,
+    ) -> Self {
+        RequestBody::StartComponent(ComponentInformation {
+            component_id: component_id.to_string(),
+            image_ref: image_ref.to_string(),
+            max_instances,
+            annotations: annotations.clone(),
+            claims: claims.map(PolicyClaims::from),
+        })
+    }
+
+    /// Construct the body of a request to start a provider
+    pub(crate) fn start_provider(
+        provider_id: &str,
+        provider_ref: &str,
+        annotations: &BTreeMap<String, String>,
+        claims: Option<&jwt::Claims<jwt::CapabilityProvider>>,
+    ) -> Self {
+        RequestBody::StartProvider(ProviderInformation {
+            provider_id: provider_id.to_string(),
+            image_ref: provider_ref.to_string(),
+            annotations: annotations.clone(),
+            claims: claims.map(PolicyClaims::from),
+        })
+    }
+
+    /// Returns the kind of request this body represents
+    pub(crate) fn kind(&self) -> RequestKind {
+        match self {
+            RequestBody::StartComponent(_) => RequestKind::StartComponent,
+            RequestBody::StartProvider(_) => RequestKind::StartProvider,
+            RequestBody::PerformInvocation(_) => RequestKind::PerformInvocation,
+            RequestBody::Unknown => RequestKind::Unknown,
+        }
+    }
+}
+
 impl From<&RequestBody> for RequestKey {
     fn from(val: &RequestBody) -> RequestKey {
         match val {
@@ -188,8 +316,19 @@ impl From<&RequestBody> for RequestKey {
             RequestBody::PerformInvocation(ref req) => RequestKey {
                 kind: RequestKind::PerformInvocation,
                 cache_key: format!(
-                    "{}_{}_{}_{}",
-                    req.target.component_id, req.target.image_ref, req.interface, req.function
+                    "{}_{}_{}_{}_{}_{}_{}",
+                    req.target.component_id,
+                    req.target.image_ref,
+                    req.interface,
+                    req.function,
+                    req.source
+                        .as_ref()
+                        .map_or("", |source| source.source_id.as_str()),
+                    req.source
+                        .as_ref()
+                        .and_then(|source| source.claims.as_ref())
+                        .map_or("", |claims| claims.public_key.as_str()),
+                    req.source.as_ref().is_some_and(|source| source.verified),
                 ),
             },
             RequestBody::Unknown => RequestKey {
@@ -229,7 +368,7 @@ pub(crate) struct RequestKey {
 }
 
 /// A policy decision response
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Response {
     /// The request id copied from the request
     #[serde(rename = "requestId")]
@@ -99,6 +99,24 @@ pub async fn merge_registry_config(
         }
     });
 
+    // require signatures for all registries with signature keys
+    for (reg, keys) in oci_opts.signature_public_keys {
+        debug!(oci_registry_url = %reg, "set signature public keys");
+        match registry_config.entry(reg) {
+            Entry::Occupied(mut entry) => entry.get_mut().set_signature_public_keys(keys),
+            Entry::Vacant(entry) => {
+                entry.insert(
+                    RegistryConfig::builder()
+                        .reg_type(RegistryType::Oci)
+                        .auth(RegistryAuth::Anonymous)
+                        .signature_public_keys(keys)
+                        .build()
+                        .expect("failed to build registry config"),
+                );
+            }
+        }
+    }
+
     // update allow_latest for all registries
     registry_config.iter_mut().for_each(|(url, config)| {
         if !additional_ca_paths.is_empty() {
@@ -1,9 +1,67 @@
 //! Module with structs for use in managing and accessing data used by various wasmCloud entities
 use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use anyhow::{bail, Context as _};
+use async_nats::jetstream::kv::Operation;
 use bytes::Bytes;
-use tokio::sync::RwLock;
-use tracing::instrument;
+use futures::stream::{BoxStream, StreamExt as _};
+use sha2::{Digest as _, Sha256};
+use tokio::fs;
+use tokio::io::AsyncWriteExt as _;
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinSet;
+use tokio_stream::wrappers::BroadcastStream;
+use tracing::{error, instrument};
+use ulid::Ulid;
+
+/// The number of changes buffered for each watcher of an in-process store before it starts
+/// missing changes
+const WATCH_CHANNEL_CAPACITY: usize = 256;
+
+/// A change to a key in a [StoreManager], as yielded by [StoreManager::watch]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StoreEvent {
+    /// The key was created or updated
+    Put {
+        /// The changed key
+        key: String,
+        /// The new value of the key
+        value: Bytes,
+    },
+    /// The key was deleted
+    Delete {
+        /// The deleted key
+        key: String,
+    },
+}
+
+impl StoreEvent {
+    /// Returns the key this event applies to
+    pub fn key(&self) -> &str {
+        match self {
+            StoreEvent::Put { key, .. } | StoreEvent::Delete { key } => key,
+        }
+    }
+}
+
+/// A stream of changes to the keys of a [StoreManager], as returned by [StoreManager::watch]
+pub type StoreWatch = BoxStream<'static, anyhow::Result<StoreEvent>>;
+
+/// The error returned by the default implementation of [StoreManager::watch], for stores that do
+/// not support watching keys
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WatchUnsupported;
+
+impl core::fmt::Display for WatchUnsupported {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("watching keys is not supported by this store")
+    }
+}
+
+impl std::error::Error for WatchUnsupported {}
 
 #[async_trait::async_trait]
 /// A trait for managing a store of data, such as a config store or a data store.
@@ -16,12 +74,89 @@ pub trait StoreManager: Send + Sync {
 
     /// Deletes a key from the config store.
     async fn del(&self, key: &str) -> anyhow::Result<()>;
+
+    /// Lists all keys in the store starting with `prefix`. An empty `prefix` lists all keys.
+    ///
+    /// Like [StoreManager::watch], `prefix` is a plain string prefix, so e.g. `COMPONENT` also
+    /// matches `COMPONENTS.a`.
+    ///
+    /// The default implementation returns an error, as listing keys is not supported.
+    async fn keys(&self, _prefix: &str) -> anyhow::Result<Vec<String>> {
+        bail!("listing keys is not supported by this store")
+    }
+
+    /// Watches all keys starting with `prefix`, returning a stream of the changes made after this
+    /// call. An empty `prefix` watches all keys.
+    ///
+    /// Like [StoreManager::keys], `prefix` is a plain string prefix, so watchers interested in a
+    /// single key must filter the changes by key.
+    ///
+    /// The default implementation returns a [WatchUnsupported] error, as watching keys is not
+    /// supported.
+    async fn watch(&self, _prefix: &str) -> anyhow::Result<StoreWatch> {
+        Err(WatchUnsupported.into())
+    }
+}
+
+#[async_trait::async_trait]
+impl StoreManager for Arc<dyn StoreManager> {
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Bytes>> {
+        self.as_ref().get(key).await
+    }
+
+    async fn put(&self, key: &str, value: Bytes) -> anyhow::Result<()> {
+        self.as_ref().put(key, value).await
+    }
+
+    async fn del(&self, key: &str) -> anyhow::Result<()> {
+        self.as_ref().del(key).await
+    }
+
+    async fn keys(&self, prefix: &str) -> anyhow::Result<Vec<String>> {
+        self.as_ref().keys(prefix).await
+    }
+
+    async fn watch(&self, prefix: &str) -> anyhow::Result<StoreWatch> {
+        self.as_ref().watch(prefix).await
+    }
+}
+
+/// Broadcasts changes of an in-process store to its watchers
+#[derive(Clone, Debug)]
+struct Watchers(broadcast::Sender<StoreEvent>);
+
+impl Default for Watchers {
+    fn default() -> Self {
+        Self(broadcast::channel(WATCH_CHANNEL_CAPACITY).0)
+    }
+}
+
+impl Watchers {
+    fn notify(&self, event: StoreEvent) {
+        // Sending only fails if there are no watchers
+        let _ = self.0.send(event);
+    }
+
+    fn subscribe(&self, prefix: &str) -> StoreWatch {
+        let prefix = prefix.to_string();
+        BroadcastStream::new(self.0.subscribe())
+            .filter_map(move |event| {
+                let event = match event {
+                    Ok(event) if event.key().starts_with(&prefix) => Some(Ok(event)),
+                    Ok(_) => None,
+                    Err(err) => Some(Err(anyhow::Error::new(err).context("store watch lagged"))),
+                };
+                async move { event }
+            })
+            .boxed()
+    }
 }
 
 /// A struct that implements the StoreManager trait, storing data in an in-memory HashMap.
 #[derive(Default)]
 pub struct DefaultStore {
     store: RwLock<HashMap<String, Bytes>>,
+    watchers: Watchers,
 }
 
 #[async_trait::async_trait]
@@ -33,13 +168,376 @@ impl StoreManager for DefaultStore {
 
     #[instrument(skip(self, value))]
     async fn put(&self, key: &str, value: Bytes) -> anyhow::Result<()> {
-        self.store.write().await.insert(key.to_string(), value);
+        self.store
+            .write()
+            .await
+            .insert(key.to_string(), value.clone());
+        self.watchers.notify(StoreEvent::Put {
+            key: key.to_string(),
+            value,
+        });
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn del(&self, key: &str) -> anyhow::Result<()> {
+        if self.store.write().await.remove(key).is_some() {
+            self.watchers.notify(StoreEvent::Delete {
+                key: key.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn keys(&self, prefix: &str) -> anyhow::Result<Vec<String>> {
+        Ok(self
+            .store
+            .read()
+            .await
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn watch(&self, prefix: &str) -> anyhow::Result<StoreWatch> {
+        Ok(self.watchers.subscribe(prefix))
+    }
+}
+
+/// A struct that implements the StoreManager trait, persisting data in a directory on the local
+/// filesystem so that it survives restarts.
+///
+/// Each key is stored in its own file, named after the hex-encoded SHA-256 digest of the key, so
+/// that file names stay within file system limits for keys of any length. The file contains the
+/// length of the key as a big-endian `u32`, followed by the key and the value. Writes are atomic:
+/// values are written to a temporary file, which is synced to disk and then renamed over the
+/// previous value, so a crash never leaves a partially written value behind.
+///
+/// [StoreManager::watch] only observes changes made through this store (or its clones), not
+/// modifications of the directory by other processes.
+#[derive(Clone, Debug)]
+pub struct FileStore {
+    dir: PathBuf,
+    watchers: Watchers,
+}
+
+impl FileStore {
+    /// Open the store in `dir`, creating the directory if it does not exist
+    pub async fn new(dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .await
+            .with_context(|| format!("failed to create store directory `{}`", dir.display()))?;
+        Ok(Self {
+            dir,
+            watchers: Watchers::default(),
+        })
+    }
+
+    /// Returns the directory the store is persisted in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(hex::encode(Sha256::digest(key)))
+    }
+
+    /// Reads the key and value stored in the file at `path`, returning `None` if it does not exist
+    async fn read_entry(path: &Path) -> anyhow::Result<Option<(String, Bytes)>> {
+        let entry = match fs::read(path).await {
+            Ok(entry) => Bytes::from(entry),
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).context("failed to read store file"),
+        };
+        let Some(len) = entry
+            .get(..4)
+            .and_then(|len| len.try_into().ok())
+            .map(u32::from_be_bytes)
+            .and_then(|len| usize::try_from(len).ok())
+            .filter(|len| entry.len() >= 4 + len)
+        else {
+            bail!("store file `{}` is truncated", path.display());
+        };
+        let key = String::from_utf8(entry[4..4 + len].to_vec())
+            .with_context(|| format!("store file `{}` contains an invalid key", path.display()))?;
+        Ok(Some((key, entry.slice(4 + len..))))
+    }
+}
+
+#[async_trait::async_trait]
+impl StoreManager for FileStore {
+    #[instrument(skip(self))]
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Bytes>> {
+        match Self::read_entry(&self.path(key))
+            .await
+            .with_context(|| format!("failed to read key `{key}`"))?
+        {
+            Some((stored, value)) if stored == key => Ok(Some(value)),
+            Some((stored, _)) => bail!("key `{key}` collides with stored key `{stored}`"),
+            None => Ok(None),
+        }
+    }
+
+    #[instrument(skip(self, value))]
+    async fn put(&self, key: &str, value: Bytes) -> anyhow::Result<()> {
+        let path = self.path(key);
+        let tmp = self.dir.join(format!(".{}.tmp", Ulid::new()));
+        let key_len = u32::try_from(key.len()).context("key is too long")?;
+        let write = async {
+            let mut file = fs::File::create(&tmp).await?;
+            file.write_all(&key_len.to_be_bytes()).await?;
+            file.write_all(key.as_bytes()).await?;
+            file.write_all(&value).await?;
+            file.sync_all().await?;
+            fs::rename(&tmp, &path).await
+        };
+        if let Err(err) = write.await {
+            let _ = fs::remove_file(&tmp).await;
+            return Err(err).with_context(|| format!("failed to write key `{key}`"));
+        }
+        sync_dir(&self.dir).await?;
+        self.watchers.notify(StoreEvent::Put {
+            key: key.to_string(),
+            value,
+        });
         Ok(())
     }
 
     #[instrument(skip(self))]
     async fn del(&self, key: &str) -> anyhow::Result<()> {
-        self.store.write().await.remove(key);
+        match fs::remove_file(self.path(key)).await {
+            Ok(()) => {
+                sync_dir(&self.dir).await?;
+                self.watchers.notify(StoreEvent::Delete {
+                    key: key.to_string(),
+                });
+                Ok(())
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err).with_context(|| format!("failed to delete key `{key}`")),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn keys(&self, prefix: &str) -> anyhow::Result<Vec<String>> {
+        let mut entries = fs::read_dir(&self.dir)
+            .await
+            .with_context(|| format!("failed to read store directory `{}`", self.dir.display()))?;
+        let mut keys = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .context("failed to read store directory entry")?
+        {
+            // Temporary files start with a `.` and are not valid hex, so they are skipped as well
+            if !entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| hex::decode(name).is_ok())
+            {
+                continue;
+            }
+            // The file may have been removed concurrently
+            let Some((key, _)) = Self::read_entry(&entry.path()).await? else {
+                continue;
+            };
+            if key.starts_with(prefix) {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
+
+    #[instrument(skip(self))]
+    async fn watch(&self, prefix: &str) -> anyhow::Result<StoreWatch> {
+        Ok(self.watchers.subscribe(prefix))
+    }
+}
+
+/// Sync the directory entry of `dir` to disk, making renames and removals within it durable
+async fn sync_dir(dir: &Path) -> anyhow::Result<()> {
+    // Directories cannot be opened as files on all platforms, but on unix this is required to
+    // persist the directory entries
+    #[cfg(unix)]
+    fs::File::open(dir)
+        .await
+        .with_context(|| format!("failed to open store directory `{}`", dir.display()))?
+        .sync_all()
+        .await
+        .with_context(|| format!("failed to sync store directory `{}`", dir.display()))?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+/// Watch any [StoreManager] for changes to the ComponentSpec and claims data of the lattice.
+///
+/// This is the store-agnostic counterpart of [crate::nats::store::data_watch].
+pub async fn data_watch(
+    tasks: &mut JoinSet<anyhow::Result<()>>,
+    store: Arc<dyn StoreManager>,
+    host: Arc<crate::wasmbus::Host>,
+) -> anyhow::Result<()> {
+    tasks.spawn(async move {
+        // Setup data watch first
+        let mut data_watch = store
+            .watch("")
+            .await
+            .context("failed to watch lattice data store")?;
+
+        // Process existing data without emitting events
+        for key in store
+            .keys("")
+            .await
+            .context("failed to read keys of lattice data store")?
+        {
+            match store.get(&key).await {
+                Ok(Some(value)) => host.process_data_event(&key, Operation::Put, value).await,
+                Ok(None) => {}
+                Err(err) => error!(%err, key, "failed to read entry from lattice data store"),
+            }
+        }
+        while let Some(event) = data_watch.next().await {
+            match event {
+                Ok(StoreEvent::Put { key, value }) => {
+                    host.process_data_event(&key, Operation::Put, value).await
+                }
+                Ok(StoreEvent::Delete { key }) => {
+                    host.process_data_event(&key, Operation::Delete, Bytes::new())
+                        .await
+                }
+                Err(err) => error!("failed to watch lattice data store: {err}"),
+            }
+        }
+        let deadline = { *host.stop_rx.borrow() };
+        host.stop_tx.send_replace(deadline);
+        Ok(())
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::wasmbus::config::BundleGenerator;
+
+    #[tokio::test]
+    async fn file_store_persists_values() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = FileStore::new(dir.path()).await?;
+        assert_eq!(store.get("LINKDEF_component/wasi:http").await?, None);
+        store
+            .put("LINKDEF_component/wasi:http", Bytes::from_static(b"first"))
+            .await?;
+        store
+            .put("LINKDEF_component/wasi:http", Bytes::from_static(b"second"))
+            .await?;
+        store.put("config", Bytes::from_static(b"{}")).await?;
+        store.del("config").await?;
+        store.del("missing").await?;
+
+        let store = FileStore::new(dir.path()).await?;
+        assert_eq!(
+            store.get("LINKDEF_component/wasi:http").await?,
+            Some(Bytes::from_static(b"second"))
+        );
+        assert_eq!(store.get("config").await?, None);
+        assert_eq!(
+            store.keys("LINKDEF_").await?,
+            vec!["LINKDEF_component/wasi:http".to_string()]
+        );
+        assert!(store.keys("COMPONENT_").await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn file_store_supports_long_keys() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = FileStore::new(dir.path()).await?;
+        // Hex encoding these keys would exceed the file name limit of common file systems
+        let key = format!("LINKDEF_{}", "component/wasi:http".repeat(20));
+        let other = format!("{key}_other");
+        store.put(&key, Bytes::from_static(b"value")).await?;
+        store.put(&other, Bytes::new()).await?;
+
+        let store = FileStore::new(dir.path()).await?;
+        assert_eq!(store.get(&key).await?, Some(Bytes::from_static(b"value")));
+        assert_eq!(store.get(&other).await?, Some(Bytes::new()));
+        let mut keys = store.keys("LINKDEF_").await?;
+        keys.sort();
+        assert_eq!(keys, vec![key.clone(), other.clone()]);
+
+        store.del(&key).await?;
+        assert_eq!(store.get(&key).await?, None);
+        assert_eq!(store.keys("").await?, vec![other]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn default_store_watches_prefix() -> anyhow::Result<()> {
+        let store = DefaultStore::default();
+        store.put("COMPONENT.a", Bytes::from_static(b"a")).await?;
+        let mut watch = store.watch("COMPONENT").await?;
+        store.put("CLAIMS.b", Bytes::from_static(b"b")).await?;
+        store.put("COMPONENTS.b", Bytes::from_static(b"b")).await?;
+        store.put("COMPONENT.c", Bytes::from_static(b"c")).await?;
+        store.put("COMPONENT", Bytes::from_static(b"d")).await?;
+        store.del("COMPONENT.missing").await?;
+        store.del("COMPONENT.a").await?;
+
+        // Keys are matched by plain prefix, like `keys` does
+        assert_eq!(
+            watch.next().await.transpose()?,
+            Some(StoreEvent::Put {
+                key: "COMPONENTS.b".into(),
+                value: Bytes::from_static(b"b"),
+            })
+        );
+        assert_eq!(
+            watch.next().await.transpose()?,
+            Some(StoreEvent::Put {
+                key: "COMPONENT.c".into(),
+                value: Bytes::from_static(b"c"),
+            })
+        );
+        assert_eq!(
+            watch.next().await.transpose()?,
+            Some(StoreEvent::Put {
+                key: "COMPONENT".into(),
+                value: Bytes::from_static(b"d"),
+            })
+        );
+        assert_eq!(
+            watch.next().await.transpose()?,
+            Some(StoreEvent::Delete {
+                key: "COMPONENT.a".into(),
+            })
+        );
+        let mut keys = store.keys("COMPONENT").await?;
+        keys.sort();
+        assert_eq!(keys, vec!["COMPONENT", "COMPONENT.c", "COMPONENTS.b"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn generates_bundles_from_any_store() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store: Arc<dyn StoreManager> = Arc::new(FileStore::new(dir.path()).await?);
+        store
+            .put("greeting", Bytes::from_static(br#"{"message":"hello"}"#))
+            .await?;
+
+        let generator = BundleGenerator::new(Arc::new(Arc::clone(&store)));
+        let bundle = generator.generate(vec!["greeting".into()]).await?;
+        assert_eq!(
+            bundle.get_config().await.get("message").map(String::as_str),
+            Some("hello")
+        );
         Ok(())
     }
 }
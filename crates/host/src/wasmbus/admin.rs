@@ -0,0 +1,304 @@
+//! This module contains the HTTP administration API of the host.
+//!
+//! The API serves liveness and readiness probes, read-only views of the host state and a Prometheus
+//! scrape endpoint. If a token is configured, it additionally serves write endpoints to scale
+//! components and stop the host, which require the token as a bearer token. This allows operating
+//! hosts without access to the control interface over NATS.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use std::sync::{Arc, Weak};
+
+use anyhow::Context as _;
+use bytes::Bytes;
+use http_body_util::{BodyExt as _, Full, Limited};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use serde::Serialize;
+use subtle::ConstantTimeEq as _;
+use tokio::net::TcpListener;
+use tracing::{error, warn};
+use wasmcloud_control_interface::{CtlResponse, ScaleComponentCommand, StopHostCommand};
+use wasmcloud_secrets_types::SECRET_PREFIX;
+
+use super::ctl::ControlInterfaceServer;
+use super::Host;
+
+/// Maximum size of the body of a request to a write endpoint
+const MAX_REQUEST_BODY_SIZE: usize = 1024 * 1024;
+
+/// Content type of the Prometheus text exposition format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+type Response = http::Response<Full<Bytes>>;
+
+/// Instance counts of a component running on the host
+#[derive(Debug, Serialize)]
+struct ComponentInstances<'a> {
+    id: &'a str,
+    image_ref: &'a str,
+    max_instances: usize,
+    active_instances: usize,
+    in_flight_invocations: usize,
+}
+
+/// Serve the HTTP administration API of `host` on `socket`
+pub(crate) async fn serve(
+    socket: TcpListener,
+    host: Weak<Host>,
+    ready: Arc<AtomicBool>,
+    token: Option<Arc<str>>,
+) {
+    let svc = hyper::service::service_fn(move |req| {
+        let host = host.clone();
+        let ready = Arc::clone(&ready);
+        let token = token.clone();
+        async move { handle(req, &host, &ready, token.as_deref()).await }
+    });
+    let srv = hyper_util::server::conn::auto::Builder::new(TokioExecutor::new());
+    loop {
+        let stream = match socket.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                error!(?err, "failed to accept HTTP administration connection");
+                continue;
+            }
+        };
+        let svc = svc.clone();
+        let srv = srv.clone();
+        // Connections are served concurrently, since write endpoints may take a while to respond
+        tokio::spawn(async move {
+            if let Err(err) = srv.serve_connection(TokioIo::new(stream), svc).await {
+                error!(?err, "failed to serve HTTP administration connection");
+            }
+        });
+    }
+}
+
+/// Handle a request to the HTTP administration API
+async fn handle(
+    req: http::Request<hyper::body::Incoming>,
+    host: &Weak<Host>,
+    ready: &AtomicBool,
+    token: Option<&str>,
+) -> Result<Response, http::Error> {
+    const OK: &str = r#"{"status":"ok"}"#;
+    const FAIL: &str = r#"{"status":"failure"}"#;
+
+    let (
+        http::request::Parts {
+            method,
+            uri,
+            headers,
+            ..
+        },
+        body,
+    ) = req.into_parts();
+    match (method.as_str(), uri.path()) {
+        ("HEAD", "/livez") => Ok(http::Response::default()),
+        ("GET", "/livez") => Ok(http::Response::new(Full::new(Bytes::from(OK)))),
+        ("HEAD", "/readyz") => {
+            if ready.load(Ordering::Relaxed) {
+                Ok(http::Response::default())
+            } else {
+                http::Response::builder()
+                    .status(http::StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Full::default())
+            }
+        }
+        ("GET", "/readyz") => {
+            if ready.load(Ordering::Relaxed) {
+                Ok(http::Response::new(Full::new(Bytes::from(OK))))
+            } else {
+                http::Response::builder()
+                    .status(http::StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Full::new(Bytes::from(FAIL)))
+            }
+        }
+        ("GET", path @ ("/inventory" | "/links" | "/config" | "/components" | "/metrics")) => {
+            let Some(host) = host.upgrade() else {
+                return unavailable();
+            };
+            let res = match path {
+                "/inventory" => host.handle_inventory().await.and_then(|res| {
+                    serde_json::to_vec(&res).context("failed to serialize inventory")
+                }),
+                "/links" => host.handle_links().await,
+                "/config" => config_names(&host).await,
+                "/components" => component_instances(&host).await,
+                _ => {
+                    return match wasmcloud_tracing::encode_prometheus_metrics() {
+                        Ok(metrics) => http::Response::builder()
+                            .header(http::header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)
+                            .body(Full::new(Bytes::from(metrics))),
+                        Err(err) => http::Response::builder()
+                            .status(http::StatusCode::NOT_FOUND)
+                            .body(Full::new(Bytes::from(format!("{err:#}")))),
+                    };
+                }
+            };
+            json(res)
+        }
+        ("POST", path @ ("/components/scale" | "/host/stop")) => {
+            let Some(expected) = token else {
+                return http::Response::builder()
+                    .status(http::StatusCode::FORBIDDEN)
+                    .body(Full::new(Bytes::from(
+                        "write endpoints are disabled, no administration token is configured",
+                    )));
+            };
+            if !authorized(&headers, expected) {
+                warn!(path, "rejected unauthorized HTTP administration request");
+                return http::Response::builder()
+                    .status(http::StatusCode::UNAUTHORIZED)
+                    .header(http::header::WWW_AUTHENTICATE, "Bearer")
+                    .body(Full::new(Bytes::from("invalid administration token")));
+            }
+            let body = match Limited::new(body, MAX_REQUEST_BODY_SIZE).collect().await {
+                Ok(body) => body.to_bytes(),
+                Err(err) => {
+                    return http::Response::builder()
+                        .status(http::StatusCode::BAD_REQUEST)
+                        .body(Full::new(Bytes::from(format!(
+                            "failed to read request body: {err}"
+                        ))));
+                }
+            };
+            let Some(host) = host.upgrade() else {
+                return unavailable();
+            };
+            let res = if path == "/host/stop" {
+                stop_host(&host, &body).await
+            } else {
+                scale_component(host, &body).await
+            };
+            json(res)
+        }
+        (
+            method,
+            path @ ("/livez" | "/readyz" | "/inventory" | "/links" | "/config" | "/components"
+            | "/metrics" | "/components/scale" | "/host/stop"),
+        ) => http::Response::builder()
+            .status(http::StatusCode::METHOD_NOT_ALLOWED)
+            .body(Full::new(Bytes::from(format!(
+                "method `{method}` not supported for path `{path}`"
+            )))),
+        (.., path) => http::Response::builder()
+            .status(http::StatusCode::NOT_FOUND)
+            .body(Full::new(Bytes::from(format!("unknown endpoint `{path}`")))),
+    }
+}
+
+/// Returns whether `headers` carry `token` as a bearer token
+fn authorized(headers: &http::HeaderMap, token: &str) -> bool {
+    headers
+        .get(http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|value| value.as_bytes().ct_eq(token.as_bytes()).into())
+}
+
+/// Returns a response to a request received while the host is shutting down
+fn unavailable() -> Result<Response, http::Error> {
+    http::Response::builder()
+        .status(http::StatusCode::SERVICE_UNAVAILABLE)
+        .body(Full::new(Bytes::from("host is shutting down")))
+}
+
+/// Returns a JSON response with the serialized result of a request, or the error as a
+/// [`CtlResponse`]
+fn json(res: anyhow::Result<Vec<u8>>) -> Result<Response, http::Error> {
+    let (status, body) = match res {
+        Ok(body) => (http::StatusCode::OK, body),
+        Err(err) => {
+            error!(?err, "failed to handle HTTP administration request");
+            (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                serde_json::to_vec(&CtlResponse::error(&format!("{err:#}"))).unwrap_or_default(),
+            )
+        }
+    };
+    http::Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body)))
+}
+
+/// Returns the names of the named configurations stored on the host, excluding secret references
+async fn config_names(host: &Host) -> anyhow::Result<Vec<u8>> {
+    let mut names = host
+        .config_store
+        .keys("")
+        .await
+        .context("failed to list configuration names")?;
+    names.retain(|name| !name.starts_with(SECRET_PREFIX));
+    names.sort_unstable();
+    serde_json::to_vec(&CtlResponse::ok(names)).context("failed to serialize configuration names")
+}
+
+/// Returns the maximum and active number of instances of each component running on the host
+async fn component_instances(host: &Host) -> anyhow::Result<Vec<u8>> {
+    let components = host.components.read().await;
+    let mut instances = components
+        .iter()
+        .map(|(id, component)| ComponentInstances {
+            id,
+            image_ref: &component.image_reference,
+            max_instances: component.max_instances.get(),
+            active_instances: component
+                .max_instances
+                .get()
+                .saturating_sub(component.permits.available_permits()),
+            in_flight_invocations: component.in_flight.load(Ordering::Relaxed),
+        })
+        .collect::<Vec<_>>();
+    instances.sort_unstable_by_key(|instances| instances.id);
+    serde_json::to_vec(&CtlResponse::ok(instances))
+        .context("failed to serialize component instances")
+}
+
+/// Scale a component on the host as requested by a [`ScaleComponentCommand`] in `body`
+async fn scale_component(host: Arc<Host>, body: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut request: serde_json::Value =
+        serde_json::from_slice(body).context("failed to parse scale component request")?;
+    // The request is handled by this host, regardless of the host ID in the request
+    if let Some(request) = request.as_object_mut() {
+        request.insert("host_id".into(), host.host_key.public_key().into());
+    }
+    let request: ScaleComponentCommand =
+        serde_json::from_value(request).context("failed to parse scale component request")?;
+    let res = ControlInterfaceServer::handle_scale_component(host, request).await?;
+    serde_json::to_vec(&res).context("failed to serialize response")
+}
+
+/// Stop the host as requested by an optional [`StopHostCommand`] in `body`
+async fn stop_host(host: &Host, body: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let request = if body.is_empty() {
+        StopHostCommand::default()
+    } else {
+        serde_json::from_slice(body).context("failed to parse stop host request")?
+    };
+    let res = ControlInterfaceServer::handle_stop_host(host, request).await?;
+    serde_json::to_vec(&res).context("failed to serialize response")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn authorizes_bearer_token() {
+        let mut headers = http::HeaderMap::new();
+        assert!(!authorized(&headers, "secret"));
+        headers.insert(
+            http::header::AUTHORIZATION,
+            http::HeaderValue::from_static("Bearer secret"),
+        );
+        assert!(authorized(&headers, "secret"));
+        assert!(!authorized(&headers, "other"));
+        headers.insert(
+            http::header::AUTHORIZATION,
+            http::HeaderValue::from_static("Basic secret"),
+        );
+        assert!(!authorized(&headers, "secret"));
+    }
+}
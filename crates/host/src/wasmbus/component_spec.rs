@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use tracing::{error, instrument, warn};
 use wasmcloud_control_interface::Link;
 
-use crate::wasmbus::component_import_links;
+use crate::wasmbus::{component_import_links, component_link_policies, component_weighted_targets};
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 /// The specification of a component that is or did run in the lattice. This contains all of the information necessary to
@@ -145,12 +145,15 @@ impl super::Host {
             // send the link to the provider for handling based on the xkey public key.
             for link in new_links {
                 if let Some(provider) = providers.get(link.source_id()) {
-                    if let Err(e) = self.put_provider_link(provider, link).await {
+                    if let Err(e) = self
+                        .put_provider_link(link.source_id(), provider, link)
+                        .await
+                    {
                         error!(?e, "failed to put provider link");
                     }
                 }
                 if let Some(provider) = providers.get(link.target()) {
-                    if let Err(e) = self.put_provider_link(provider, link).await {
+                    if let Err(e) = self.put_provider_link(link.target(), provider, link).await {
                         error!(?e, "failed to put provider link");
                     }
                 }
@@ -160,6 +163,9 @@ impl super::Host {
         // If the component is already running, update the links
         if let Some(component) = self.components.write().await.get(id.as_ref()) {
             *component.handler.instance_links.write().await = component_import_links(&spec.links);
+            *component.handler.link_policies.write().await = component_link_policies(&spec.links);
+            *component.handler.weighted_targets.write().await =
+                component_weighted_targets(&spec.links);
             // NOTE(brooksmtownsend): We can consider updating the component if the image URL changes
         };
 
@@ -227,9 +227,7 @@ impl BundleGenerator {
             });
         }
 
-        let receiver = self
-            .store
-            .watch(&name)
+        let receiver = ConfigManager::watch(self.store.as_ref(), &name)
             .await
             .context(format!("error setting up watcher for {name}"))?;
         self.watch_cache
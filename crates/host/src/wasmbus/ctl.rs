@@ -1,9 +1,9 @@
 use core::sync::atomic::Ordering;
 
 use std::collections::btree_map::Entry as BTreeMapEntry;
-use std::collections::{hash_map, HashMap};
+use std::collections::{hash_map, HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use anyhow::{anyhow, bail, Context as _};
 use bytes::Bytes;
@@ -13,17 +13,21 @@ use tokio::spawn;
 use tokio::time::Instant;
 use tracing::{debug, error, info, instrument, trace, warn};
 use wasmcloud_control_interface::{
-    ComponentAuctionAck, ComponentAuctionRequest, CtlResponse,
-    DeleteInterfaceLinkDefinitionRequest, HostInventory, HostLabel, HostLabelIdentifier, Link,
-    ProviderAuctionAck, ProviderAuctionRequest, RegistryCredential, ScaleComponentCommand,
-    StartProviderCommand, StopHostCommand, StopProviderCommand, UpdateComponentCommand,
+    CachedArtifact, ComponentAuctionAck, ComponentAuctionRequest, CtlResponse,
+    DeleteInterfaceLinkDefinitionRequest, DrainHostCommand, HostInventory, HostLabel,
+    HostLabelIdentifier, Link, ProviderAuctionAck, ProviderAuctionRequest, PruneCacheCommand,
+    PullArtifactsCommand, RegistryCredential, ScaleComponentCommand, StartProviderCommand,
+    StopHostCommand, StopProviderCommand, UpdateComponentCommand,
 };
+use wasmcloud_core::oci_cache::CacheEntry;
 use wasmcloud_core::shutdown_subject;
+use wasmcloud_runtime::component::from_string_map;
 use wasmcloud_tracing::context::TraceContextInjector;
 
 use crate::registry::RegistryCredentialExt;
 use crate::wasmbus::{
-    human_friendly_uptime, injector_to_headers, Annotations, Claims, Host, Provider, StoredClaims,
+    human_friendly_uptime, injector_to_headers, routing, Annotations, Claims, Host, Provider,
+    StoredClaims,
 };
 use crate::ResourceRef;
 
@@ -51,6 +55,16 @@ pub trait ControlInterfaceServer: Send + Sync {
     /// or failure.
     async fn handle_stop_host(&self, request: StopHostCommand) -> anyhow::Result<CtlResponse<()>>;
 
+    /// Handle a request to drain the host. This method should return a response indicating
+    /// whether draining started, without waiting for it to complete. By default, draining is not
+    /// supported.
+    async fn handle_drain_host(
+        self: Arc<Self>,
+        _request: DrainHostCommand,
+    ) -> anyhow::Result<CtlResponse<()>> {
+        Ok(CtlResponse::error("draining is not supported by this host"))
+    }
+
     /// Handle a request to scale a component. This method should return a response indicating success
     /// or failure.
     async fn handle_scale_component(
@@ -146,6 +160,52 @@ pub trait ControlInterfaceServer: Send + Sync {
     async fn handle_ping_hosts(
         &self,
     ) -> anyhow::Result<CtlResponse<wasmcloud_control_interface::Host>>;
+
+    /// Handle a request to get the artifacts in the OCI artifact cache. This method should return a
+    /// response containing the cached artifacts. By default, the artifact cache is not supported and
+    /// an error is returned, which is sent as an unsuccessful response.
+    async fn handle_cached_artifacts(&self) -> anyhow::Result<CtlResponse<Vec<CachedArtifact>>> {
+        bail!("the artifact cache is not supported by this host")
+    }
+
+    /// Handle a request to prune the OCI artifact cache. This method should return a response
+    /// containing the removed artifacts. By default, the artifact cache is not supported and an error
+    /// is returned, which is sent as an unsuccessful response.
+    async fn handle_prune_cache(
+        &self,
+        _request: PruneCacheCommand,
+    ) -> anyhow::Result<CtlResponse<Vec<CachedArtifact>>> {
+        bail!("the artifact cache is not supported by this host")
+    }
+
+    /// Handle a request to pull artifacts into the OCI artifact cache. This method should return a
+    /// response containing the cached artifacts once they have been pulled. By default, the
+    /// artifact cache is not supported and an error is returned, which is sent as an unsuccessful
+    /// response.
+    async fn handle_pull_artifacts(
+        &self,
+        _request: PullArtifactsCommand,
+    ) -> anyhow::Result<CtlResponse<Vec<CachedArtifact>>> {
+        bail!("the artifact cache is not supported by this host")
+    }
+}
+
+/// Convert an entry of the OCI artifact cache to a [`CachedArtifact`]
+fn cached_artifact(entry: CacheEntry) -> CachedArtifact {
+    let last_used = entry
+        .last_used
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    CachedArtifact::new(entry.digest, entry.references, entry.size, last_used)
+}
+
+/// Returns the reference under which an artifact is cached if `image_ref` is an OCI reference
+fn cached_reference(image_ref: &str) -> Option<String> {
+    match ResourceRef::try_from(image_ref) {
+        Ok(ResourceRef::Oci(oci_ref)) => Some(oci_ref.to_lowercase()),
+        _ => None,
+    }
 }
 
 #[async_trait::async_trait]
@@ -171,9 +231,11 @@ impl ControlInterfaceServer for Host {
             .iter()
             .all(|(k, v)| host_labels.get(k).is_some_and(|hv| hv == v));
         let component_id_running = self.components.read().await.contains_key(component_id);
+        let draining = *self.drain_tx.borrow();
 
-        // This host can run the component if all constraints are satisfied and the component is not already running
-        if constraints_satisfied && !component_id_running {
+        // This host can run the component if all constraints are satisfied, the component is not already running
+        // and the host is not draining
+        if constraints_satisfied && !component_id_running && !draining {
             Ok(Some(CtlResponse::ok(
                 ComponentAuctionAck::from_component_host_and_constraints(
                     component_ref,
@@ -209,7 +271,8 @@ impl ControlInterfaceServer for Host {
             .all(|(k, v)| host_labels.get(k).is_some_and(|hv| hv == v));
         let providers = self.providers.read().await;
         let provider_running = providers.contains_key(provider_id);
-        if constraints_satisfied && !provider_running {
+        let draining = *self.drain_tx.borrow();
+        if constraints_satisfied && !provider_running && !draining {
             Ok(Some(CtlResponse::ok(
                 ProviderAuctionAck::builder()
                     .provider_ref(provider_ref.into())
@@ -240,6 +303,30 @@ impl ControlInterfaceServer for Host {
             "successfully handled stop host".into(),
         ))
     }
+
+    #[instrument(level = "debug", skip_all)]
+    async fn handle_drain_host(
+        self: Arc<Self>,
+        request: DrainHostCommand,
+    ) -> anyhow::Result<CtlResponse<()>> {
+        let timeout = request.timeout();
+
+        info!(?timeout, "handling drain host");
+
+        if self.drain_tx.send_replace(true) {
+            return Ok(CtlResponse::<()>::success(
+                "host is already draining".into(),
+            ));
+        }
+        self.ready.store(false, Ordering::Relaxed);
+        spawn(async move {
+            if let Err(err) = self.drain(timeout.map(Duration::from_millis)).await {
+                error!(?err, "failed to drain host");
+            }
+        });
+
+        Ok(CtlResponse::<()>::success("host is draining".into()))
+    }
     #[instrument(level = "debug", skip_all)]
     async fn handle_scale_component(
         self: Arc<Self>,
@@ -259,6 +346,12 @@ impl ControlInterfaceServer for Host {
             max_instances, component_id, "handling scale component"
         );
 
+        if max_instances > 0 && *self.drain_tx.borrow() {
+            return Ok(CtlResponse::error(
+                "host is draining, components can only be stopped",
+            ));
+        }
+
         let host_id = host_id.to_string();
         let annotations: Annotations = annotations
             .cloned()
@@ -266,6 +359,22 @@ impl ControlInterfaceServer for Host {
             .into_iter()
             .collect();
 
+        let reservation = if max_instances > 0 {
+            let limits = from_string_map(component_limits.as_ref());
+            match self
+                .reserve_component_quota(component_id, &annotations, max_instances, limits.as_ref())
+                .await
+            {
+                Ok(reservation) => reservation,
+                Err(err) => {
+                    warn!(component_id, err, "rejected scale component request");
+                    return Ok(CtlResponse::error(&err));
+                }
+            }
+        } else {
+            None
+        };
+
         // Basic validation to ensure that the component is running and that the image reference matches
         // If it doesn't match, we can still successfully scale, but we won't be updating the image reference
         let (original_ref, ref_changed) = {
@@ -322,18 +431,15 @@ impl ControlInterfaceServer for Host {
                 Ok((_, Err(e))) => {
                     if let Err(e) = self
                         .event_publisher
-                        .publish_event(
-                            "component_scale_failed",
-                            crate::event::component_scale_failed(
-                                None,
-                                &annotations,
-                                host_id,
-                                &component_ref,
-                                &component_id,
-                                max_instances,
-                                &e,
-                            ),
-                        )
+                        .publish_event(crate::event::component_scale_failed(
+                            None,
+                            &annotations,
+                            host_id,
+                            &component_ref,
+                            &component_id,
+                            max_instances,
+                            &e,
+                        ))
                         .await
                     {
                         error!(%component_ref, %component_id, err = ?e, "failed to publish component scale failed event");
@@ -343,7 +449,7 @@ impl ControlInterfaceServer for Host {
                 Err(e) => (None, None, Some(e)),
             };
             // Scale the component
-            if let Err(e) = self
+            let scaled = self
                 .handle_scale_component_task(
                     Arc::clone(&component_ref),
                     Arc::clone(&component_id),
@@ -357,23 +463,22 @@ impl ControlInterfaceServer for Host {
                     }),
                     claims_token.as_ref(),
                 )
-                .await
-            {
+                .await;
+            // The instances of a scaled component are accounted for by the running component
+            drop(reservation);
+            if let Err(e) = scaled {
                 error!(%component_ref, %component_id, err = ?e, "failed to scale component");
                 if let Err(e) = self
                     .event_publisher
-                    .publish_event(
-                        "component_scale_failed",
-                        crate::event::component_scale_failed(
-                            claims_token.map(|c| c.claims).as_ref(),
-                            &annotations,
-                            host_id,
-                            &component_ref,
-                            &component_id,
-                            max_instances,
-                            &e,
-                        ),
-                    )
+                    .publish_event(crate::event::component_scale_failed(
+                        claims_token.map(|c| c.claims).as_ref(),
+                        &annotations,
+                        host_id,
+                        &component_ref,
+                        &component_id,
+                        max_instances,
+                        &e,
+                    ))
                     .await
                 {
                     error!(%component_ref, %component_id, err = ?e, "failed to publish component scale failed event");
@@ -470,6 +575,11 @@ impl ControlInterfaceServer for Host {
         self: Arc<Self>,
         request: StartProviderCommand,
     ) -> anyhow::Result<Option<CtlResponse<()>>> {
+        if *self.drain_tx.borrow() {
+            return Ok(Some(CtlResponse::error(
+                "host is draining, providers cannot be started",
+            )));
+        }
         if self
             .providers
             .read()
@@ -481,6 +591,23 @@ impl ControlInterfaceServer for Host {
             )));
         }
 
+        let annotations: Annotations = request
+            .annotations()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let reservation = match self.reserve_provider_quota(&annotations).await {
+            Ok(reservation) => reservation,
+            Err(err) => {
+                warn!(
+                    provider_id = request.provider_id(),
+                    err, "rejected start provider request"
+                );
+                return Ok(Some(CtlResponse::error(&err)));
+            }
+        };
+
         // Avoid responding to start providers for builtin providers if they're not enabled
         if let Ok(ResourceRef::Builtin(name)) = ResourceRef::try_from(request.provider_ref()) {
             if !self.experimental_features.builtin_http_server && name == "http-server" {
@@ -515,7 +642,7 @@ impl ControlInterfaceServer for Host {
             let provider_ref = request.provider_ref();
             let annotations = request.annotations();
 
-            if let Err(err) = Arc::clone(&self)
+            let started = Arc::clone(&self)
                 .handle_start_provider_task(
                     config,
                     provider_id,
@@ -523,20 +650,19 @@ impl ControlInterfaceServer for Host {
                     annotations.cloned().unwrap_or_default(),
                     &host_id,
                 )
-                .await
-            {
+                .await;
+            // A started provider is accounted for by the running provider
+            drop(reservation);
+            if let Err(err) = started {
                 error!(provider_ref, provider_id, ?err, "failed to start provider");
                 if let Err(err) = self
                     .event_publisher
-                    .publish_event(
-                        "provider_start_failed",
-                        crate::event::provider_start_failed(
-                            provider_ref,
-                            provider_id,
-                            host_id,
-                            &err,
-                        ),
-                    )
+                    .publish_event(crate::event::provider_start_failed(
+                        provider_ref,
+                        provider_id,
+                        host_id,
+                        &err,
+                    ))
                     .await
                 {
                     error!(?err, "failed to publish provider_start_failed event");
@@ -579,22 +705,29 @@ impl ControlInterfaceServer for Host {
         shutdown.store(true, Ordering::Relaxed);
 
         // Send a request to the provider, requesting a graceful shutdown
-        let req = serde_json::to_vec(&json!({ "host_id": host_id }))
-            .context("failed to encode provider stop request")?;
-        let req = async_nats::Request::new()
-            .payload(req.into())
-            .timeout(self.host_config.provider_shutdown_delay)
-            .headers(injector_to_headers(
-                &TraceContextInjector::default_with_span(),
-            ));
-        if let Err(e) = self
-            .rpc_nats
-            .send_request(
-                shutdown_subject(&self.host_config.lattice, provider_id, "default"),
-                req,
-            )
-            .await
-        {
+        let stopped = if let Some(local_providers) = &self.local_providers {
+            local_providers
+                .shutdown(provider_id, self.host_config.provider_shutdown_delay)
+                .await
+        } else {
+            let req = serde_json::to_vec(&json!({ "host_id": host_id }))
+                .context("failed to encode provider stop request")?;
+            let req = async_nats::Request::new()
+                .payload(req.into())
+                .timeout(self.host_config.provider_shutdown_delay)
+                .headers(injector_to_headers(
+                    &TraceContextInjector::default_with_span(),
+                ));
+            self.rpc_nats()?
+                .send_request(
+                    shutdown_subject(&self.host_config.lattice, provider_id, "default"),
+                    req,
+                )
+                .await
+                .map(|_| ())
+                .map_err(anyhow::Error::from)
+        };
+        if let Err(e) = stopped {
             warn!(
                 ?e,
                 provider_id,
@@ -609,10 +742,12 @@ impl ControlInterfaceServer for Host {
 
         info!(provider_id, "provider stopped");
         self.event_publisher
-            .publish_event(
-                "provider_stopped",
-                crate::event::provider_stopped(annotations, host_id, provider_id, "stop"),
-            )
+            .publish_event(crate::event::provider_stopped(
+                annotations,
+                host_id,
+                provider_id,
+                "stop",
+            ))
             .await?;
         Ok(CtlResponse::<()>::success(
             "successfully stopped provider".into(),
@@ -681,7 +816,7 @@ impl ControlInterfaceServer for Host {
             .context("Unable to delete config data")?;
 
         self.event_publisher
-            .publish_event("config_deleted", crate::event::config_deleted(config_name))
+            .publish_event(crate::event::config_deleted(config_name))
             .await?;
 
         Ok(CtlResponse::<()>::success(
@@ -714,10 +849,10 @@ impl ControlInterfaceServer for Host {
         }
 
         self.event_publisher
-            .publish_event(
-                "labels_changed",
-                crate::event::labels_changed(host_id, HashMap::from_iter(labels.clone())),
-            )
+            .publish_event(crate::event::labels_changed(
+                host_id,
+                HashMap::from_iter(labels.clone()),
+            ))
             .await
             .context("failed to publish labels_changed event")?;
 
@@ -743,10 +878,10 @@ impl ControlInterfaceServer for Host {
 
         info!(key, "removed label");
         self.event_publisher
-            .publish_event(
-                "labels_changed",
-                crate::event::labels_changed(host_id, HashMap::from_iter(labels.clone())),
-            )
+            .publish_event(crate::event::labels_changed(
+                host_id,
+                HashMap::from_iter(labels.clone()),
+            ))
             .await
             .context("failed to publish labels_changed event")?;
 
@@ -784,6 +919,13 @@ impl ControlInterfaceServer for Host {
                     .iter()
                     .chain(request.target_config())
             ).await?;
+            routing::validate_link(&request)?;
+            self.validate_link_config_schema(source_id, request.source_config())
+                .await?;
+            for target in routing::link_targets(&request) {
+                self.validate_link_config_schema(target, request.target_config())
+                    .await?;
+            }
 
             let mut component_spec = self
                 .get_component_spec(source_id)
@@ -792,6 +934,8 @@ impl ControlInterfaceServer for Host {
 
             // If the link is defined from this source on the same interface and link name, but to a different target,
             // we need to reject this link and suggest deleting the existing link or using a different link name.
+            // Links sharing any of their weighted targets are considered the same link, which allows shifting
+            // traffic between targets.
             if let Some(existing_conflict_link) = component_spec.links.iter().find(|link| {
                 link.source_id() == source_id
                     && link.wit_namespace() == wit_namespace
@@ -799,7 +943,7 @@ impl ControlInterfaceServer for Host {
                     && link.name() == name
                     // Check if interfaces have no intersection
                     && link.interfaces().iter().any(|i| interfaces.contains(i))
-                    && link.target() != target
+                    && !routing::shares_target(link, &request)
             }) {
                 error!(
                     source_id,
@@ -816,7 +960,7 @@ impl ControlInterfaceServer for Host {
             // Otherwise, add the new link to the component specification.
             if let Some(existing_link_index) = component_spec.links.iter().position(|link| {
                 link.source_id() == source_id
-                    && link.target() == target
+                    && routing::shares_target(link, &request)
                     && link.wit_namespace() == wit_namespace
                     && link.wit_package() == wit_package
                     && link.name() == name
@@ -843,15 +987,12 @@ impl ControlInterfaceServer for Host {
 
         if let Err(e) = link_set_result {
             self.event_publisher
-                .publish_event(
-                    "linkdef_set_failed",
-                    crate::event::linkdef_set_failed(&request, &e),
-                )
+                .publish_event(crate::event::linkdef_set_failed(&request, &e))
                 .await?;
             Ok(CtlResponse::error(e.to_string().as_ref()))
         } else {
             self.event_publisher
-                .publish_event("linkdef_set", crate::event::linkdef_set(&request))
+                .publish_event(crate::event::linkdef_set(&request))
                 .await?;
             Ok(CtlResponse::<()>::success("successfully set link".into()))
         }
@@ -917,17 +1058,14 @@ impl ControlInterfaceServer for Host {
             .as_ref()
             .map(|link| String::from(link.target()));
         self.event_publisher
-            .publish_event(
-                "linkdef_deleted",
-                crate::event::linkdef_deleted(
-                    source_id,
-                    deleted_link_target.as_ref(),
-                    link_name,
-                    wit_namespace,
-                    wit_package,
-                    deleted_link.as_ref().map(|link| link.interfaces()),
-                ),
-            )
+            .publish_event(crate::event::linkdef_deleted(
+                source_id,
+                deleted_link_target.as_ref(),
+                link_name,
+                wit_namespace,
+                wit_package,
+                deleted_link.as_ref().map(|link| link.interfaces()),
+            ))
             .await?;
 
         Ok(CtlResponse::<()>::success(
@@ -954,6 +1092,15 @@ impl ControlInterfaceServer for Host {
                 }
                 hash_map::Entry::Vacant(entry) => {
                     new_config.set_allow_latest(self.host_config.oci_opts.allow_latest);
+                    // Registry credentials must not lift the signature requirement of a registry
+                    if let Some(keys) = self
+                        .host_config
+                        .oci_opts
+                        .signature_public_keys
+                        .get(entry.key())
+                    {
+                        new_config.set_signature_public_keys(keys.clone());
+                    }
                     entry.insert(new_config);
                 }
             }
@@ -981,7 +1128,7 @@ impl ControlInterfaceServer for Host {
         // We don't write it into the cached data and instead let the caching thread handle it as we
         // won't need it immediately.
         self.event_publisher
-            .publish_event("config_set", crate::event::config_set(config_name))
+            .publish_event(crate::event::config_set(config_name))
             .await?;
 
         Ok(CtlResponse::<()>::success("successfully put config".into()))
@@ -1015,4 +1162,99 @@ impl ControlInterfaceServer for Host {
 
         Ok(CtlResponse::ok(host))
     }
+
+    #[instrument(level = "debug", skip_all)]
+    async fn handle_cached_artifacts(&self) -> anyhow::Result<CtlResponse<Vec<CachedArtifact>>> {
+        trace!("handling cached artifacts");
+        let entries = self
+            .host_config
+            .oci_opts
+            .cache()
+            .list()
+            .await
+            .context("failed to list cached artifacts")?;
+        Ok(CtlResponse::ok(
+            entries.into_iter().map(cached_artifact).collect(),
+        ))
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    async fn handle_prune_cache(
+        &self,
+        request: PruneCacheCommand,
+    ) -> anyhow::Result<CtlResponse<Vec<CachedArtifact>>> {
+        let all = request.all();
+        info!(all, "handling prune cache");
+
+        // Keep the artifacts of running components and providers, unless pruning all artifacts
+        let mut keep = HashSet::new();
+        if !all {
+            let (components, providers) = join!(self.components.read(), self.providers.read());
+            keep.extend(
+                components
+                    .values()
+                    .filter_map(|component| cached_reference(&component.image_reference)),
+            );
+            keep.extend(
+                providers
+                    .values()
+                    .filter_map(|provider| cached_reference(&provider.image_ref)),
+            );
+        }
+        let removed = self
+            .host_config
+            .oci_opts
+            .cache()
+            .prune(|reference| keep.contains(reference))
+            .await
+            .context("failed to prune OCI artifact cache")?;
+        Ok(CtlResponse::ok(
+            removed.into_iter().map(cached_artifact).collect(),
+        ))
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    async fn handle_pull_artifacts(
+        &self,
+        request: PullArtifactsCommand,
+    ) -> anyhow::Result<CtlResponse<Vec<CachedArtifact>>> {
+        let references = request.references();
+        info!(?references, "handling pull artifacts");
+
+        // Pulls may take long, so do not block registry config updates while pulling
+        let registry_config = self.registry_config.read().await.clone();
+        let mut paths = HashSet::new();
+        let mut errors = Vec::new();
+        for reference in references {
+            match crate::pull_artifact(reference, &self.host_config.oci_opts, &registry_config)
+                .await
+            {
+                Ok(path) => {
+                    paths.insert(path);
+                }
+                Err(err) => {
+                    error!(reference, ?err, "failed to pull artifact");
+                    errors.push(format!("{reference}: {err:#}"));
+                }
+            }
+        }
+        if !errors.is_empty() {
+            bail!("failed to pull artifacts: {}", errors.join(", "));
+        }
+
+        let entries = self
+            .host_config
+            .oci_opts
+            .cache()
+            .list()
+            .await
+            .context("failed to list cached artifacts")?;
+        Ok(CtlResponse::ok(
+            entries
+                .into_iter()
+                .filter(|entry| paths.contains(&entry.path))
+                .map(cached_artifact)
+                .collect(),
+        ))
+    }
 }
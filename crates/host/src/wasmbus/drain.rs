@@ -0,0 +1,202 @@
+//! This module contains draining of the host.
+//!
+//! A draining host stops accepting invocations of its components, so that they are served by
+//! other hosts in the lattice, and waits for the invocations in flight to finish. It then stops its
+//! providers, stopping providers before the providers they link to. Progress is reported with
+//! [`host_drain_started`](crate::event::host_drain_started),
+//! [`host_drain_progress`](crate::event::host_drain_progress) and
+//! [`host_drained`](crate::event::host_drained) events.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+use wasmcloud_control_interface::{Link, StopProviderCommand};
+
+use super::ctl::ControlInterfaceServer;
+use super::{routing, Host};
+
+/// Interval at which the in-flight invocations of a draining host are checked
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Guard counting an invocation of a component as in flight until it is dropped
+pub(crate) struct InFlightGuard(Arc<AtomicUsize>);
+
+impl InFlightGuard {
+    pub(crate) fn new(in_flight: &Arc<AtomicUsize>) -> Self {
+        in_flight.fetch_add(1, Ordering::SeqCst);
+        Self(Arc::clone(in_flight))
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Returns `providers` in the order they should be stopped in, which is before any of the
+/// providers they link to. Providers linking to each other are stopped in order of their IDs.
+pub(crate) fn provider_stop_order<'a>(
+    providers: impl IntoIterator<Item = &'a str>,
+    links: &HashMap<String, Vec<Link>>,
+) -> Vec<&'a str> {
+    let mut remaining = providers.into_iter().collect::<BTreeSet<_>>();
+    // Providers linking to each provider
+    let mut sources = remaining
+        .iter()
+        .map(|provider| (*provider, BTreeSet::new()))
+        .collect::<BTreeMap<_, _>>();
+    for source in &remaining {
+        for link in links.get(*source).into_iter().flatten() {
+            for target in routing::link_targets(link) {
+                if target != *source {
+                    if let Some(sources) = sources.get_mut(target) {
+                        sources.insert(*source);
+                    }
+                }
+            }
+        }
+    }
+    let mut order = Vec::with_capacity(remaining.len());
+    while let Some(next) = remaining
+        .iter()
+        .find(|provider| sources[*provider].is_empty())
+        .or_else(|| remaining.first())
+        .copied()
+    {
+        remaining.remove(next);
+        for sources in sources.values_mut() {
+            sources.remove(next);
+        }
+        order.push(next);
+    }
+    order
+}
+
+impl Host {
+    /// Returns the number of in-flight invocations of each component, which has any in flight
+    async fn in_flight_invocations(&self) -> BTreeMap<String, u64> {
+        let components = self.components.read().await;
+        components
+            .iter()
+            .filter_map(|(id, component)| {
+                let in_flight = component.in_flight.load(Ordering::SeqCst);
+                (in_flight > 0).then(|| (id.clone(), in_flight as u64))
+            })
+            .collect()
+    }
+
+    /// Drain the host, waiting for in-flight invocations to finish for at most `timeout`, which
+    /// defaults to the maximum execution time of invocations
+    pub(crate) async fn drain(&self, timeout: Option<Duration>) -> anyhow::Result<()> {
+        let host_id = self.host_key.public_key();
+        let deadline = Instant::now() + timeout.unwrap_or(self.max_execution_time);
+
+        let mut in_flight = self.in_flight_invocations().await;
+        info!(?in_flight, "host draining");
+        self.event_publisher
+            .publish_event(crate::event::host_drain_started(
+                &host_id,
+                in_flight.clone(),
+            ))
+            .await?;
+        let timed_out = loop {
+            if in_flight.is_empty() {
+                break false;
+            }
+            if Instant::now() >= deadline {
+                warn!(
+                    ?in_flight,
+                    "timed out waiting for in-flight invocations to finish"
+                );
+                break true;
+            }
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+            let next = self.in_flight_invocations().await;
+            if next != in_flight {
+                in_flight = next;
+                self.event_publisher
+                    .publish_event(crate::event::host_drain_progress(
+                        &host_id,
+                        in_flight.clone(),
+                    ))
+                    .await?;
+            }
+        };
+
+        let provider_ids = self
+            .providers
+            .read()
+            .await
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        let order = {
+            let links = self.links.read().await;
+            provider_stop_order(provider_ids.iter().map(String::as_str), &links)
+                .into_iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        for provider_id in order {
+            let request = StopProviderCommand::builder()
+                .host_id(&host_id)
+                .provider_id(&provider_id)
+                .build()
+                .map_err(|e| anyhow!(e))?;
+            if let Err(err) = ControlInterfaceServer::handle_stop_provider(self, request).await {
+                error!(provider_id, ?err, "failed to stop provider while draining");
+            }
+        }
+
+        info!(timed_out, "host drained");
+        self.event_publisher
+            .publish_event(crate::event::host_drained(&host_id, in_flight, timed_out))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn link(source_id: &str, target: &str) -> Link {
+        Link::builder()
+            .source_id(source_id)
+            .target(target)
+            .name("default")
+            .wit_namespace("wasi")
+            .wit_package("keyvalue")
+            .build()
+            .expect("failed to build link")
+    }
+
+    #[test]
+    fn orders_providers_by_links() {
+        let links = HashMap::from([
+            ("a".to_string(), vec![link("a", "b"), link("a", "c")]),
+            (
+                "b".to_string(),
+                vec![link("b", "c"), link("b", "component")],
+            ),
+            ("d".to_string(), vec![link("d", "e")]),
+            ("e".to_string(), vec![link("e", "d")]),
+        ]);
+        assert_eq!(
+            provider_stop_order(["c", "b", "a"], &links),
+            ["a", "b", "c"]
+        );
+        // Providers linking to each other are stopped in order of their IDs
+        assert_eq!(
+            provider_stop_order(["e", "d", "c"], &links),
+            ["c", "d", "e"]
+        );
+        assert!(provider_stop_order([], &links).is_empty());
+    }
+}
@@ -9,6 +9,7 @@ use anyhow::{anyhow, bail, Context as _};
 use async_nats::header::{IntoHeaderName as _, IntoHeaderValue as _};
 use async_trait::async_trait;
 use bytes::Bytes;
+use nkeys::KeyPair;
 use secrecy::SecretBox;
 #[cfg(unix)]
 use spire_api::{
@@ -16,6 +17,7 @@ use spire_api::{
 };
 use tokio::sync::RwLock;
 use tracing::{error, instrument, warn};
+use wasmcloud_control_interface::InvocationPolicy;
 use wasmcloud_runtime::capability::logging::logging;
 use wasmcloud_runtime::capability::secrets::store::SecretValue;
 use wasmcloud_runtime::capability::{
@@ -27,9 +29,13 @@ use wasmcloud_runtime::component::{
     MessagingHostMessage0_3, ReplacedInstanceTarget, Secrets,
 };
 use wasmcloud_tracing::context::TraceContextInjector;
-use wrpc_transport::InvokeExt as _;
+use wrpc_transport::{Invoke as _, InvokeExt as _};
 
 use super::config::ConfigBundle;
+use super::invocation;
+use super::link_policy::{self, CircuitBreakers};
+use super::local::{self, LocalComponents};
+use super::routing::WeightedTargets;
 use super::{injector_to_headers, Features};
 
 // The key used to represent a wasmCloud-specific selector:
@@ -40,9 +46,17 @@ const WASMCLOUD_SELECTOR_TYPE: &str = "wasmcloud";
 // Similar to the existing Kubernetes types: https://github.com/maxlambrecht/rust-spiffe/blob/929a090f99d458dd67fa499b74afbeb2fc44b114/spire-api/src/selectors.rs#L38-L39
 const WASMCLOUD_SELECTOR_COMPONENT: &str = "component";
 
+/// Header containing the identifier of the component or provider an invocation originates from
+pub(crate) const SOURCE_ID_HEADER: &str = "source-id";
+
+/// Header containing the claims subject of the component an invocation originates from, used by
+/// the receiving host to resolve the caller's claims
+pub(crate) const SOURCE_CLAIMS_SUBJECT_HEADER: &str = "source-claims-subject";
+
 #[derive(Clone, Debug)]
 pub struct Handler {
-    pub nats: Arc<async_nats::Client>,
+    /// NATS client used to invoke targets not running on the host, unless the host is embedded
+    pub nats: Option<Arc<async_nats::Client>>,
     // ConfigBundle is perfectly safe to pass around, but in order to update it on the fly, we need
     // to have it behind a lock since it can be cloned and because the `Actor` struct this gets
     // placed into is also inside of an Arc
@@ -55,6 +69,13 @@ pub struct Handler {
     pub lattice: Arc<str>,
     /// The identifier of the component that this handler is associated with
     pub component_id: Arc<str>,
+    /// The claims subject of the component that this handler is associated with, if it is signed
+    pub claims_subject: Option<Arc<str>>,
+    /// The host key used to sign outgoing invocations, if invocation signing is enabled
+    pub invocation_key: Option<Arc<KeyPair>>,
+    /// Components on the host which invocations are dispatched to in-process, if local
+    /// invocations are enabled
+    pub local_components: Option<LocalComponents>,
     /// The current link targets. `instance` -> `link-name`
     /// Instance specification does not include a version
     pub targets: Arc<RwLock<HashMap<Box<str>, Arc<str>>>>,
@@ -70,6 +91,15 @@ pub struct Handler {
     /// - Some other opaque string
     #[allow(clippy::type_complexity)]
     pub instance_links: Arc<RwLock<HashMap<Box<str>, HashMap<Box<str>, Box<str>>>>>,
+    /// Map of link names -> instance -> invocation policy of the link, for links with a policy
+    #[allow(clippy::type_complexity)]
+    pub link_policies: Arc<RwLock<HashMap<Box<str>, HashMap<Box<str>, InvocationPolicy>>>>,
+    /// Map of link names -> instance -> weighted targets of the link, for links with weighted
+    /// targets
+    #[allow(clippy::type_complexity)]
+    pub weighted_targets: Arc<RwLock<HashMap<Box<str>, HashMap<Box<str>, Arc<WeightedTargets>>>>>,
+    /// Circuit breakers of the link targets, shared by all instances of the component
+    pub circuit_breakers: Arc<CircuitBreakers>,
     /// Link name -> messaging client
     pub messaging_links: Arc<RwLock<HashMap<Box<str>, async_nats::Client>>>,
 
@@ -90,8 +120,14 @@ impl Handler {
             secrets: self.secrets.clone(),
             lattice: self.lattice.clone(),
             component_id: self.component_id.clone(),
+            claims_subject: self.claims_subject.clone(),
+            invocation_key: self.invocation_key.clone(),
+            local_components: self.local_components.clone(),
             targets: Arc::default(),
             instance_links: self.instance_links.clone(),
+            link_policies: self.link_policies.clone(),
+            weighted_targets: self.weighted_targets.clone(),
+            circuit_breakers: self.circuit_breakers.clone(),
             messaging_links: self.messaging_links.clone(),
             invocation_timeout: self.invocation_timeout,
             experimental_features: self.experimental_features,
@@ -177,8 +213,10 @@ impl Bus for Handler {
 
 impl wrpc_transport::Invoke for Handler {
     type Context = Option<ReplacedInstanceTarget>;
-    type Outgoing = <wrpc_transport_nats::Client as wrpc_transport::Invoke>::Outgoing;
-    type Incoming = <wrpc_transport_nats::Client as wrpc_transport::Invoke>::Incoming;
+    type Outgoing =
+        local::Outgoing<<wrpc_transport_nats::Client as wrpc_transport::Invoke>::Outgoing>;
+    type Incoming =
+        local::Incoming<<wrpc_transport_nats::Client as wrpc_transport::Invoke>::Incoming>;
 
     #[instrument(level = "debug", skip_all)]
     async fn invoke<P>(
@@ -238,22 +276,141 @@ impl wrpc_transport::Invoke for Handler {
             format!("failed to call `{func}` in instance `{instance}` (failed to find a configured link with name `{link_name}` from component `{id}`, please check your configuration)", id = self.component_id)
         }).map_err(Error::LinkNotFound)?;
 
+        let policy = self
+            .link_policies
+            .read()
+            .await
+            .get(link_name)
+            .and_then(|instances| instances.get(target_instance))
+            .cloned()
+            .unwrap_or_default();
+        let weighted_targets = self
+            .weighted_targets
+            .read()
+            .await
+            .get(link_name)
+            .and_then(|instances| instances.get(target_instance))
+            .cloned();
+        let link_name: Box<str> = link_name.into();
+        let id: Box<str> = id.clone();
+        // Do not hold the links while waiting for retries
+        drop(links);
+        drop(targets);
+
         let mut headers = injector_to_headers(&TraceContextInjector::default_with_span());
-        headers.insert("source-id", &*self.component_id);
-        headers.insert("link-name", link_name);
+        headers.insert(SOURCE_ID_HEADER, &*self.component_id);
+        if let Some(subject) = &self.claims_subject {
+            headers.insert(SOURCE_CLAIMS_SUBJECT_HEADER, &**subject);
+        }
+        headers.insert("link-name", &*link_name);
+        let id = match weighted_targets {
+            Some(targets) => targets.select().into(),
+            None => id,
+        };
+        let timeout = policy
+            .timeout_ms()
+            .map_or(self.invocation_timeout, Duration::from_millis);
+        let max_retries = link_policy::max_retries(&policy, target_instance, func);
+        let mut backoff = None;
+        let mut attempt = 0;
+        loop {
+            self.circuit_breakers
+                .acquire(&link_name, &id, &policy)
+                .map_err(Error::Handler)?;
+            let res = self
+                .dispatch(
+                    headers.clone(),
+                    &id,
+                    instance,
+                    func,
+                    params.clone(),
+                    paths.as_ref(),
+                    timeout,
+                )
+                .await;
+            self.circuit_breakers
+                .record(&link_name, &id, &policy, res.is_ok());
+            match res {
+                Ok(res) => return Ok(res),
+                Err(err) if attempt < max_retries => {
+                    let delay = link_policy::retry_backoff(&policy, backoff);
+                    warn!(
+                        ?err,
+                        instance,
+                        func,
+                        target = &*id,
+                        attempt,
+                        ?delay,
+                        "failed to invoke target, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    backoff = Some(delay);
+                    attempt += 1;
+                }
+                Err(err) => return Err(Error::Handler(err).into()),
+            }
+        }
+    }
+}
+
+impl Handler {
+    /// Dispatch a single invocation of `func` of `instance` exported by `target`, either locally
+    /// or over NATS, failing if it is not accepted within `timeout`
+    #[allow(clippy::too_many_arguments)]
+    async fn dispatch<P>(
+        &self,
+        mut headers: async_nats::HeaderMap,
+        target: &str,
+        instance: &str,
+        func: &str,
+        params: Bytes,
+        paths: &[P],
+        timeout: Duration,
+    ) -> anyhow::Result<(
+        local::Outgoing<<wrpc_transport_nats::Client as wrpc_transport::Invoke>::Outgoing>,
+        local::Incoming<<wrpc_transport_nats::Client as wrpc_transport::Invoke>::Incoming>,
+    )>
+    where
+        P: AsRef<[Option<usize>]> + Send + Sync,
+    {
+        if let Some(local) = match &self.local_components {
+            Some(components) => components.get(target).await,
+            None => None,
+        } {
+            let (tx, rx) = tokio::time::timeout(
+                timeout,
+                local.invoke(headers, instance, func, params, paths),
+            )
+            .await
+            .with_context(|| format!("invocation of `{instance}.{func}` timed out"))??;
+            return Ok((local::Outgoing::Local(tx), local::Incoming::Local(rx)));
+        }
+        if let Some(key) = &self.invocation_key {
+            let token = invocation::sign(
+                key,
+                &self.lattice,
+                &self.component_id,
+                target,
+                instance,
+                func,
+                &params,
+            )?;
+            headers.insert(invocation::INVOCATION_TOKEN_HEADER, token);
+        }
+        let Some(nats) = &self.nats else {
+            bail!("`{target}` is not running on the host and the host is not connected to NATS");
+        };
         let nats = wrpc_transport_nats::Client::new(
-            Arc::clone(&self.nats),
-            format!("{}.{id}", &self.lattice),
+            Arc::clone(nats),
+            format!("{}.{target}", self.lattice),
             None,
         )
-        .await
-        .map_err(Error::Handler)?;
+        .await?;
         let (tx, rx) = nats
-            .timeout(self.invocation_timeout)
+            .timeout(timeout)
             .invoke(Some(headers), instance, func, params, paths)
-            .await
-            .map_err(Error::Handler)?;
-        Ok((tx, rx))
+            .await?;
+        Ok((local::Outgoing::Remote(tx), local::Incoming::Remote(rx)))
     }
 }
 
@@ -594,7 +751,7 @@ impl MessagingHostMessage0_3 for Message {
                     .iter()
                     // NOTE(brooksmtownsend): The funky construction here is to provide a concrete type
                     // to the `as_ref()` call, which is necessary to satisfy the type inference on Windows.
-                    .filter(|(k, ..)| (<&async_nats::HeaderName as AsRef<str>>::as_ref(k) != key))
+                    .filter(|(k, ..)| <&async_nats::HeaderName as AsRef<str>>::as_ref(k) != key)
                     .flat_map(|(k, vs)| zip(repeat(k.clone()), vs.iter().cloned()))
                     .collect();
                 Ok(())
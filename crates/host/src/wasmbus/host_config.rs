@@ -3,17 +3,20 @@ use crate::OciConfig;
 use core::net::SocketAddr;
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 use nkeys::KeyPair;
 use url::Url;
 use wasmcloud_core::{logging::Level as LogLevel, OtelConfig};
+pub use wasmcloud_runtime::cache::DEFAULT_COMPILATION_CACHE_SIZE;
 use wasmcloud_runtime::{
     DEFAULT_MAX_CORE_INSTANCES_PER_COMPONENT, MAX_COMPONENTS, MAX_COMPONENT_SIZE, MAX_LINEAR_MEMORY,
 };
 
 use crate::wasmbus::experimental::Features;
+use crate::wasmbus::quota::TenantQuota;
 
 /// wasmCloud Host configuration
 #[allow(clippy::struct_excessive_bools)]
@@ -64,16 +67,58 @@ pub struct Host {
     pub max_components: u32,
     /// The maximum number of core instances that are allowed in a given component
     pub max_core_instances_per_component: u32,
+    /// Whether to meter the fuel consumed by component invocations
+    pub fuel_metering: bool,
+    /// The maximum amount of fuel a single component invocation may consume. Enables fuel metering
+    pub max_fuel: Option<u64>,
+    /// The directory to cache compiled components in, if any
+    pub compilation_cache_dir: Option<PathBuf>,
+    /// The maximum size of the compiled component cache in bytes
+    pub compilation_cache_max_size: u64,
     /// The interval at which the Host will send heartbeats
     pub heartbeat_interval: Option<Duration>,
     /// Experimental features that can be enabled in the host
     pub experimental_features: Features,
     /// HTTP administration endpoint address
     pub http_admin: Option<SocketAddr>,
+    /// Bearer token authorizing the write endpoints of the HTTP administration API, which are
+    /// disabled if not set
+    pub http_admin_token: Option<String>,
     /// Whether component auctions are enabled
     pub enable_component_auction: bool,
     /// Whether capability provider auctions are enabled
     pub enable_provider_auction: bool,
+    /// Whether to sign invocations sent by components with the host key
+    pub sign_invocations: bool,
+    /// Whether to reject invocations of components which are not signed by a trusted host.
+    /// Invocations by capability providers are not signed, so they are rejected as well
+    pub require_signed_invocations: bool,
+    /// Public keys of the hosts trusted to sign invocations, in addition to this host
+    pub trusted_invocation_issuers: Vec<String>,
+    /// Whether invocations of components running on this host are dispatched in-process instead
+    /// of being sent over NATS
+    pub local_invocations: bool,
+    /// Resource quotas of tenants on the host, keyed by the tenant named by the
+    /// [`TENANT_ANNOTATION`](crate::wasmbus::TENANT_ANNOTATION) of components and providers
+    pub tenant_quotas: HashMap<String, TenantQuota>,
+    /// Whether the host runs embedded, without connecting to NATS. Embedded hosts dispatch all
+    /// invocations in-process and can only run builtin providers
+    pub embedded: bool,
+    /// JWTs of the operators trusted to issue accounts. If any are set, only components and
+    /// providers whose issuers chain up to one of these operators are started
+    pub trusted_operators: Vec<String>,
+    /// JWTs of the accounts issuing components and providers, which must be issued by one of the
+    /// [`trusted_operators`](Self::trusted_operators)
+    pub trusted_accounts: Vec<String>,
+    /// Path to a file containing revocation list JWTs, separated by whitespace. The file is
+    /// checked for modifications every
+    /// [`revocation_list_reload_interval`](Self::revocation_list_reload_interval)
+    pub revocation_list_file: Option<PathBuf>,
+    /// The interval at which the revocation list file is checked for modifications
+    pub revocation_list_reload_interval: Option<Duration>,
+    /// Name of a config in the config store whose values are revocation list JWTs. The config is
+    /// watched for updates
+    pub revocation_list_config: Option<String>,
 }
 
 /// Configuration for wasmCloud policy service
@@ -115,11 +160,27 @@ impl Default for Host {
             max_component_size: MAX_COMPONENT_SIZE,
             max_core_instances_per_component: DEFAULT_MAX_CORE_INSTANCES_PER_COMPONENT,
             max_components: MAX_COMPONENTS,
+            fuel_metering: false,
+            max_fuel: None,
+            compilation_cache_dir: None,
+            compilation_cache_max_size: DEFAULT_COMPILATION_CACHE_SIZE,
             heartbeat_interval: None,
             experimental_features: Features::default(),
             http_admin: None,
+            http_admin_token: None,
             enable_component_auction: true,
             enable_provider_auction: true,
+            sign_invocations: false,
+            require_signed_invocations: false,
+            trusted_invocation_issuers: Vec::default(),
+            local_invocations: true,
+            tenant_quotas: HashMap::default(),
+            embedded: false,
+            trusted_operators: Vec::default(),
+            trusted_accounts: Vec::default(),
+            revocation_list_file: None,
+            revocation_list_reload_interval: None,
+            revocation_list_config: None,
         }
     }
 }
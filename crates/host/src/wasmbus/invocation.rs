@@ -0,0 +1,335 @@
+//! This module contains signing and verification of invocation tokens, which hosts attach to
+//! component invocations sent over wRPC.
+//!
+//! An invocation token is a JWT with [`jwt::Invocation`] claims, issued by the key of the invoking
+//! host, with the invoking component as subject. The token binds the origin and target of the
+//! invocation and a hash of its encoded parameters, formatted as `<length>:<sha256>`. Parameters
+//! are streamed, so the hash is verified as they are read and before the invocation is dispatched.
+
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+use core::time::Duration;
+
+use std::collections::HashSet;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{ensure, Context as _};
+use nkeys::{KeyPair, KeyPairType};
+use sha2::{Digest as _, Sha256};
+use tokio::io::{AsyncRead, ReadBuf};
+use wascap::jwt;
+
+use super::handler::SOURCE_ID_HEADER;
+
+/// Header containing the invocation token of an invocation
+pub(crate) const INVOCATION_TOKEN_HEADER: &str = "invocation-token";
+
+/// Duration after which invocation tokens expire
+const INVOCATION_TOKEN_TTL: Duration = Duration::from_secs(60);
+
+/// Returns the URL of the invocation origin `source_id` in `lattice`
+fn origin_url(lattice: &str, source_id: &str) -> String {
+    format!("wrpc://{lattice}/{source_id}")
+}
+
+/// Returns the URL of function `func` of `instance` exported by `target` in `lattice`
+fn target_url(lattice: &str, target: &str, instance: &str, func: &str) -> String {
+    format!("wrpc://{lattice}/{target}/{instance}.{func}")
+}
+
+/// Returns a hasher over the origin and target of an invocation, to be updated with its parameters
+fn hasher(origin_url: &str, target_url: &str) -> Sha256 {
+    let mut hasher = Sha256::new();
+    hasher.update(origin_url);
+    hasher.update(target_url);
+    hasher
+}
+
+/// Sign an invocation of `func` of `instance` exported by `target` from `source_id` with
+/// `params`, returning the encoded invocation token
+pub(crate) fn sign(
+    key: &KeyPair,
+    lattice: &str,
+    source_id: &str,
+    target: &str,
+    instance: &str,
+    func: &str,
+    params: &[u8],
+) -> anyhow::Result<String> {
+    let origin_url = origin_url(lattice, source_id);
+    let target_url = target_url(lattice, target, instance, func);
+    let mut hasher = hasher(&origin_url, &target_url);
+    hasher.update(params);
+    let hash = format!("{}:{}", params.len(), hex::encode_upper(hasher.finalize()));
+    let expires = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("system time is before the UNIX epoch")?
+        .saturating_add(INVOCATION_TOKEN_TTL)
+        .as_secs();
+    jwt::Claims::<jwt::Invocation>::with_dates(
+        key.public_key(),
+        source_id.to_string(),
+        None,
+        Some(expires),
+        &target_url,
+        &origin_url,
+        &hash,
+    )
+    .encode(key)
+    .context("failed to encode invocation token")
+}
+
+/// Verifies the invocation tokens of incoming invocations
+#[derive(Debug)]
+pub(crate) struct InvocationVerifier {
+    lattice: String,
+    trusted_issuers: HashSet<String>,
+}
+
+impl InvocationVerifier {
+    /// Construct a verifier for invocations in `lattice`, trusting tokens issued by any of the
+    /// host keys in `trusted_issuers`
+    pub(crate) fn new(
+        lattice: impl Into<String>,
+        trusted_issuers: impl IntoIterator<Item = String>,
+    ) -> anyhow::Result<Self> {
+        let trusted_issuers = trusted_issuers
+            .into_iter()
+            .map(|issuer| {
+                let key = KeyPair::from_public_key(&issuer)
+                    .with_context(|| format!("invalid invocation issuer `{issuer}`"))?;
+                ensure!(
+                    key.key_pair_type() == KeyPairType::Server,
+                    "invocation issuer `{issuer}` is not a host key"
+                );
+                Ok(issuer)
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            lattice: lattice.into(),
+            trusted_issuers,
+        })
+    }
+
+    /// Verify the invocation token in `headers` for an invocation of `func` of `instance`
+    /// exported by `target`, returning a verifier for the parameters of the invocation
+    pub(crate) fn verify(
+        &self,
+        headers: Option<&async_nats::HeaderMap>,
+        target: &str,
+        instance: &str,
+        func: &str,
+    ) -> anyhow::Result<ParamsVerifier> {
+        let token = headers
+            .and_then(|headers| headers.get(INVOCATION_TOKEN_HEADER))
+            .context("invocation is not signed")?
+            .as_str();
+        let validation = jwt::validate_token::<jwt::Invocation>(token)
+            .context("failed to validate invocation token")?;
+        ensure!(
+            validation.signature_valid,
+            "invocation token signature is invalid"
+        );
+        ensure!(!validation.expired, "invocation token has expired");
+        ensure!(
+            !validation.cannot_use_yet,
+            "invocation token is not valid yet"
+        );
+        let claims = jwt::Claims::<jwt::Invocation>::decode(token)
+            .context("failed to decode invocation token")?;
+        ensure!(claims.expires.is_some(), "invocation token does not expire");
+        ensure!(
+            self.trusted_issuers.contains(&claims.issuer),
+            "invocation token issuer `{}` is not trusted",
+            claims.issuer
+        );
+        let source_id = headers
+            .and_then(|headers| headers.get(SOURCE_ID_HEADER))
+            .map(async_nats::HeaderValue::as_str);
+        ensure!(
+            source_id == Some(claims.subject.as_str()),
+            "invocation token was issued for a different source"
+        );
+        let jwt::Invocation {
+            target_url,
+            origin_url,
+            invocation_hash,
+        } = claims
+            .metadata
+            .context("invocation token is missing invocation claims")?;
+        ensure!(
+            origin_url == self::origin_url(&self.lattice, &claims.subject),
+            "invocation token was issued for a different origin"
+        );
+        ensure!(
+            target_url == self::target_url(&self.lattice, target, instance, func),
+            "invocation token was issued for a different target"
+        );
+        let (len, hash) = invocation_hash
+            .split_once(':')
+            .context("invalid invocation hash")?;
+        let params = ParamsVerifier {
+            hasher: hasher(&origin_url, &target_url),
+            remaining: len.parse().context("invalid invocation parameter length")?,
+            hash: hex::decode(hash).context("invalid invocation hash")?,
+        };
+        if params.remaining == 0 {
+            params.finish()?;
+        }
+        Ok(params)
+    }
+}
+
+/// Verifies the encoded parameters of an invocation against the hash in its token as they are
+/// read
+#[derive(Debug)]
+pub(crate) struct ParamsVerifier {
+    hasher: Sha256,
+    remaining: usize,
+    hash: Vec<u8>,
+}
+
+impl ParamsVerifier {
+    /// Update the hash with `data` read from the parameters, failing if the parameters exceed
+    /// their signed length or do not match the signed hash once they are complete
+    fn update(&mut self, data: &[u8]) -> io::Result<()> {
+        if data.len() > self.remaining {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invocation parameters exceed the signed length",
+            ));
+        }
+        self.hasher.update(data);
+        self.remaining -= data.len();
+        if self.remaining == 0 && !data.is_empty() {
+            self.finish()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        }
+        Ok(())
+    }
+
+    /// Compare the hash of the complete parameters with the signed hash
+    fn finish(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.hasher.clone().finalize().as_slice() == self.hash,
+            "invocation parameters do not match the signed hash"
+        );
+        Ok(())
+    }
+}
+
+/// Incoming stream of an invocation, verifying the parameters of signed invocations as they are
+/// read
+pub(crate) struct Incoming {
+    inner: wrpc_transport_nats::Reader,
+    params: Option<ParamsVerifier>,
+}
+
+impl Incoming {
+    /// Wrap the incoming stream of an invocation, verifying its parameters with `params`, if set
+    pub(crate) fn new(inner: wrpc_transport_nats::Reader, params: Option<ParamsVerifier>) -> Self {
+        Self { inner, params }
+    }
+}
+
+impl wrpc_transport::Index<Self> for Incoming {
+    fn index(&self, path: &[usize]) -> anyhow::Result<Self> {
+        // Only the synchronous parameters are covered by the token, nested streams are not
+        self.inner.index(path).map(|inner| Self::new(inner, None))
+    }
+}
+
+impl AsyncRead for Incoming {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled = buf.filled().len();
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        if let Some(params) = &mut this.params {
+            let data = &buf.filled()[filled..];
+            if data.is_empty() && params.remaining > 0 {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "invocation parameters are shorter than the signed length",
+                )));
+            }
+            params.update(data)?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use async_nats::HeaderMap;
+    use nkeys::KeyPair;
+
+    use super::*;
+
+    fn headers(source_id: &str, token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(SOURCE_ID_HEADER, source_id);
+        headers.insert(INVOCATION_TOKEN_HEADER, token);
+        headers
+    }
+
+    #[test]
+    fn verifies_invocation_tokens() -> anyhow::Result<()> {
+        let host = KeyPair::new_server();
+        let verifier = InvocationVerifier::new("default", [host.public_key()])?;
+        let params = b"hello world";
+        let token = sign(&host, "default", "a", "b", "wasi:cli/run", "run", params)?;
+        let headers = headers("a", &token);
+
+        let mut verifier_params = verifier.verify(Some(&headers), "b", "wasi:cli/run", "run")?;
+        verifier_params.update(&params[..5])?;
+        verifier_params.update(&params[5..])?;
+        assert!(verifier_params.update(b"!").is_err());
+
+        let mut tampered = verifier.verify(Some(&headers), "b", "wasi:cli/run", "run")?;
+        assert!(tampered.update(b"hello World").is_err());
+
+        assert!(verifier
+            .verify(Some(&headers), "c", "wasi:cli/run", "run")
+            .is_err());
+        assert!(verifier
+            .verify(
+                Some(&self::headers("c", &token)),
+                "b",
+                "wasi:cli/run",
+                "run"
+            )
+            .is_err());
+        assert!(verifier.verify(None, "b", "wasi:cli/run", "run").is_err());
+
+        let untrusted = KeyPair::new_server();
+        let token = sign(
+            &untrusted,
+            "default",
+            "a",
+            "b",
+            "wasi:cli/run",
+            "run",
+            params,
+        )?;
+        assert!(verifier
+            .verify(
+                Some(&self::headers("a", &token)),
+                "b",
+                "wasi:cli/run",
+                "run"
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_issuers() {
+        assert!(InvocationVerifier::new("default", ["invalid".to_string()]).is_err());
+        assert!(InvocationVerifier::new("default", [KeyPair::new_account().public_key()]).is_err());
+    }
+}
@@ -0,0 +1,312 @@
+//! This module contains the per-link policies applied to outgoing invocations of components.
+//!
+//! Policies are configured through the [`InvocationPolicy`] of a link and control the timeout of
+//! invocations, retries of failed invocations of idempotent functions and a circuit breaker, which
+//! fails invocations fast after a number of consecutive failures to reach the target.
+
+use core::time::Duration;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::bail;
+use tokio::time::Instant;
+use tracing::{info, warn};
+use wasmcloud_control_interface::InvocationPolicy;
+use wasmcloud_tracing::KeyValue;
+
+use crate::metrics::HostMetrics;
+
+/// Delay before the first retry of a failed invocation, if not configured by the link
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Maximum delay between retries of a failed invocation
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Time after which an open circuit breaker lets a probe through, if not configured by the link
+const DEFAULT_CIRCUIT_BREAKER_RESET: Duration = Duration::from_secs(30);
+
+/// Functions of well-known interfaces, which are retried without the link marking them idempotent
+const IDEMPOTENT_FUNCTIONS: &[(&str, &str)] = &[
+    ("wasi:keyvalue/store", "get"),
+    ("wasi:keyvalue/store", "exists"),
+    ("wasi:keyvalue/store", "list-keys"),
+    ("wasi:keyvalue/batch", "get-many"),
+    ("wasi:blobstore/blobstore", "get-container"),
+    ("wasi:blobstore/blobstore", "container-exists"),
+];
+
+/// Returns the number of times a failed invocation of `func` of `instance` may be retried under
+/// `policy`, which is zero for functions that are not idempotent
+pub(crate) fn max_retries(policy: &InvocationPolicy, instance: &str, func: &str) -> u32 {
+    let idempotent = policy
+        .idempotent()
+        .unwrap_or_else(|| IDEMPOTENT_FUNCTIONS.contains(&(instance, func)));
+    if idempotent {
+        policy.max_retries().unwrap_or_default()
+    } else {
+        0
+    }
+}
+
+/// Returns the delay before the retry following a retry delayed by `backoff`, or before the first
+/// retry, if `backoff` is `None`
+pub(crate) fn retry_backoff(policy: &InvocationPolicy, backoff: Option<Duration>) -> Duration {
+    match backoff {
+        Some(backoff) => backoff.saturating_mul(2).min(MAX_RETRY_BACKOFF),
+        None => policy
+            .retry_backoff_ms()
+            .map_or(DEFAULT_RETRY_BACKOFF, Duration::from_millis)
+            .min(MAX_RETRY_BACKOFF),
+    }
+}
+
+/// State of the circuit breaker of a link target
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BreakerState {
+    /// Invocations are let through, counting consecutive failures
+    Closed { failures: u32 },
+    /// Invocations fail fast until the deadline, after which a single probe is let through
+    Open { until: Instant },
+    /// A probe is in flight since the instant, other invocations fail fast
+    HalfOpen { since: Instant },
+}
+
+impl BreakerState {
+    /// Value of the state reported in [`HostMetrics`]
+    fn metric(self) -> u64 {
+        match self {
+            Self::Closed { .. } => 0,
+            Self::Open { .. } => 1,
+            Self::HalfOpen { .. } => 2,
+        }
+    }
+}
+
+/// Circuit breakers of the link targets of a component, keyed by link name and target
+#[derive(Debug)]
+pub struct CircuitBreakers {
+    breakers: Mutex<HashMap<(Box<str>, Box<str>), BreakerState>>,
+    metrics: Arc<HostMetrics>,
+    attributes: Vec<KeyValue>,
+}
+
+impl CircuitBreakers {
+    /// Construct circuit breakers reporting their state to `metrics` with `attributes`
+    pub fn new(metrics: Arc<HostMetrics>, attributes: Vec<KeyValue>) -> Self {
+        Self {
+            breakers: Mutex::default(),
+            metrics,
+            attributes,
+        }
+    }
+
+    /// Check whether an invocation of `target` over link `link_name` may proceed under `policy`,
+    /// failing fast if the circuit breaker of the target is open
+    pub(crate) fn acquire(
+        &self,
+        link_name: &str,
+        target: &str,
+        policy: &InvocationPolicy,
+    ) -> anyhow::Result<()> {
+        if policy.circuit_breaker_threshold().is_none() {
+            return Ok(());
+        }
+        let reset = reset_duration(policy);
+        let now = Instant::now();
+        let mut breakers = self
+            .breakers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let Some(state) = breakers.get_mut(&(link_name.into(), target.into())) else {
+            return Ok(());
+        };
+        match *state {
+            BreakerState::Closed { .. } => Ok(()),
+            BreakerState::Open { until } if now >= until => {
+                self.set_state(
+                    link_name,
+                    target,
+                    state,
+                    BreakerState::HalfOpen { since: now },
+                );
+                Ok(())
+            }
+            // A probe which never completed, for example because the invocation was cancelled,
+            // must not keep the breaker half-open forever
+            BreakerState::HalfOpen { since } if now.duration_since(since) >= reset => {
+                *state = BreakerState::HalfOpen { since: now };
+                Ok(())
+            }
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => {
+                bail!("circuit breaker of link `{link_name}` to `{target}` is open")
+            }
+        }
+    }
+
+    /// Record the outcome of an invocation of `target` over link `link_name` under `policy`,
+    /// opening the circuit breaker of the target once the failure threshold is reached
+    pub(crate) fn record(
+        &self,
+        link_name: &str,
+        target: &str,
+        policy: &InvocationPolicy,
+        success: bool,
+    ) {
+        let Some(threshold) = policy.circuit_breaker_threshold() else {
+            return;
+        };
+        let mut breakers = self
+            .breakers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if success {
+            if let Some(state) = breakers.remove(&(link_name.into(), target.into())) {
+                if !matches!(state, BreakerState::Closed { .. }) {
+                    info!(link_name, target, "circuit breaker closed");
+                    self.report(
+                        link_name,
+                        target,
+                        BreakerState::Closed { failures: 0 },
+                        false,
+                    );
+                }
+            }
+            return;
+        }
+        let state = breakers
+            .entry((link_name.into(), target.into()))
+            .or_insert(BreakerState::Closed { failures: 0 });
+        let next = match *state {
+            BreakerState::Closed { failures } if failures.saturating_add(1) < threshold => {
+                BreakerState::Closed {
+                    failures: failures + 1,
+                }
+            }
+            BreakerState::Closed { .. } | BreakerState::HalfOpen { .. } => BreakerState::Open {
+                until: Instant::now() + reset_duration(policy),
+            },
+            // Invocations which started before the breaker opened do not extend it
+            BreakerState::Open { .. } => return,
+        };
+        self.set_state(link_name, target, state, next);
+    }
+
+    /// Transition the circuit breaker of `target` over link `link_name` from `state` to `next`
+    fn set_state(
+        &self,
+        link_name: &str,
+        target: &str,
+        state: &mut BreakerState,
+        next: BreakerState,
+    ) {
+        let opened = matches!(next, BreakerState::Open { .. });
+        if opened {
+            warn!(link_name, target, "circuit breaker opened");
+        }
+        if state.metric() != next.metric() {
+            self.report(link_name, target, next, opened);
+        }
+        *state = next;
+    }
+
+    /// Report `state` of the circuit breaker of `target` over link `link_name` to the metrics
+    fn report(&self, link_name: &str, target: &str, state: BreakerState, opened: bool) {
+        let mut attributes = self.attributes.clone();
+        attributes.push(KeyValue::new("link.name", link_name.to_string()));
+        attributes.push(KeyValue::new("link.target", target.to_string()));
+        self.metrics
+            .set_circuit_breaker_state(state.metric(), opened, &attributes);
+    }
+}
+
+/// Returns the time after which an open circuit breaker lets a probe through under `policy`
+fn reset_duration(policy: &InvocationPolicy) -> Duration {
+    policy
+        .circuit_breaker_reset_ms()
+        .map_or(DEFAULT_CIRCUIT_BREAKER_RESET, Duration::from_millis)
+}
+
+#[cfg(test)]
+mod test {
+    use wasmcloud_tracing::global;
+
+    use super::*;
+
+    fn breakers() -> anyhow::Result<CircuitBreakers> {
+        let metrics = HostMetrics::new(
+            &global::meter("test"),
+            "host".into(),
+            "default".into(),
+            None,
+        )?;
+        Ok(CircuitBreakers::new(Arc::new(metrics), Vec::new()))
+    }
+
+    #[tokio::test]
+    async fn opens_and_resets_circuit_breaker() -> anyhow::Result<()> {
+        let breakers = breakers()?;
+        let policy = InvocationPolicy::builder()
+            .circuit_breaker_threshold(2)
+            .circuit_breaker_reset_ms(10)
+            .build()
+            .map_err(|err| anyhow::anyhow!(err))?;
+
+        breakers.acquire("default", "a", &policy)?;
+        breakers.record("default", "a", &policy, false);
+        breakers.record("default", "a", &policy, true);
+        breakers.record("default", "a", &policy, false);
+        breakers.acquire("default", "a", &policy)?;
+        breakers.record("default", "a", &policy, false);
+        assert!(breakers.acquire("default", "a", &policy).is_err());
+        // Breakers are tracked per link target
+        breakers.acquire("default", "b", &policy)?;
+        breakers.acquire("other", "a", &policy)?;
+
+        // A single probe is let through once the breaker resets
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        breakers.acquire("default", "a", &policy)?;
+        assert!(breakers.acquire("default", "a", &policy).is_err());
+        breakers.record("default", "a", &policy, false);
+        assert!(breakers.acquire("default", "a", &policy).is_err());
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        breakers.acquire("default", "a", &policy)?;
+        breakers.record("default", "a", &policy, true);
+        breakers.acquire("default", "a", &policy)?;
+        breakers.acquire("default", "a", &policy)?;
+
+        // Links without a threshold are never broken
+        let policy = InvocationPolicy::default();
+        for _ in 0..10 {
+            breakers.record("default", "c", &policy, false);
+        }
+        breakers.acquire("default", "c", &policy)?;
+        Ok(())
+    }
+
+    #[test]
+    fn retries_idempotent_functions() -> anyhow::Result<()> {
+        let policy = InvocationPolicy::builder()
+            .max_retries(3)
+            .build()
+            .map_err(|err| anyhow::anyhow!(err))?;
+        assert_eq!(max_retries(&policy, "wasi:keyvalue/store", "get"), 3);
+        assert_eq!(max_retries(&policy, "wasi:keyvalue/store", "set"), 0);
+        let policy = InvocationPolicy::builder()
+            .max_retries(3)
+            .idempotent(true)
+            .build()
+            .map_err(|err| anyhow::anyhow!(err))?;
+        assert_eq!(max_retries(&policy, "wasi:keyvalue/store", "set"), 3);
+
+        let backoff = retry_backoff(&policy, None);
+        assert_eq!(backoff, DEFAULT_RETRY_BACKOFF);
+        assert_eq!(retry_backoff(&policy, Some(backoff)), backoff * 2);
+        assert_eq!(
+            retry_backoff(&policy, Some(MAX_RETRY_BACKOFF)),
+            MAX_RETRY_BACKOFF
+        );
+        Ok(())
+    }
+}
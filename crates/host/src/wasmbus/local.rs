@@ -0,0 +1,287 @@
+//! This module contains the in-process transport used to dispatch invocations to components
+//! running on the same host, without a round-trip through NATS.
+//!
+//! Local invocations are served by the same [`wrpc_transport::Serve`] implementation as remote
+//! ones, so tracing, policy checks and metrics are applied to them identically.
+
+use core::fmt;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, Weak};
+
+use anyhow::Context as _;
+use bytes::Bytes;
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf, ReadHalf, WriteHalf};
+use tokio::sync::{watch, RwLock};
+use wasmcloud_core::ComponentId;
+use wrpc_transport::frame;
+
+use super::providers::local::LocalProviderManager;
+use super::Component;
+
+/// Size of the in-memory buffer of a local invocation in each direction
+const LOCAL_INVOCATION_BUFFER_SIZE: usize = 64 * 1024;
+
+type LocalConnection = (
+    Option<async_nats::HeaderMap>,
+    WriteHalf<DuplexStream>,
+    ReadHalf<DuplexStream>,
+);
+
+/// Server accepting invocations of the exports of a component from within the host
+#[derive(Default)]
+pub struct LocalServer(
+    frame::Server<Option<async_nats::HeaderMap>, ReadHalf<DuplexStream>, WriteHalf<DuplexStream>>,
+);
+
+impl fmt::Debug for LocalServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalServer").finish_non_exhaustive()
+    }
+}
+
+impl wrpc_transport::Serve for LocalServer {
+    type Context = Option<async_nats::HeaderMap>;
+    type Outgoing = frame::Outgoing;
+    type Incoming = frame::Incoming;
+
+    async fn serve(
+        &self,
+        instance: &str,
+        func: &str,
+        paths: impl Into<Arc<[Box<[Option<usize>]>]>> + Send,
+    ) -> anyhow::Result<
+        impl futures::Stream<Item = anyhow::Result<(Self::Context, Self::Outgoing, Self::Incoming)>>
+            + Send
+            + 'static,
+    > {
+        self.0.serve(instance, func, paths).await
+    }
+}
+
+impl LocalServer {
+    /// Invoke `func` of `instance` with `params`, passing `headers` as the invocation context
+    pub async fn invoke<P>(
+        &self,
+        headers: async_nats::HeaderMap,
+        instance: &str,
+        func: &str,
+        params: Bytes,
+        paths: impl AsRef<[P]> + Send,
+    ) -> anyhow::Result<(frame::Outgoing, frame::Incoming)>
+    where
+        P: AsRef<[Option<usize>]> + Send + Sync,
+    {
+        let (client, server) = tokio::io::duplex(LOCAL_INVOCATION_BUFFER_SIZE);
+        let (client_rx, client_tx) = tokio::io::split(client);
+        let (server_rx, server_tx) = tokio::io::split(server);
+        // Parameters may exceed the buffer size, so the invocation has to be accepted while the
+        // parameters are written
+        let (accepted, invoked) = tokio::join!(
+            self.0.accept(Accept(std::sync::Mutex::new(Some((
+                Some(headers),
+                server_tx,
+                server_rx
+            ))))),
+            frame::invoke(client_tx, client_rx, instance, func, params, paths),
+        );
+        accepted.with_context(|| format!("failed to invoke `{instance}.{func}` locally"))?;
+        invoked
+    }
+}
+
+/// [`frame::Accept`] implementation accepting a single local connection
+struct Accept(std::sync::Mutex<Option<LocalConnection>>);
+
+impl frame::Accept for Accept {
+    type Context = Option<async_nats::HeaderMap>;
+    type Outgoing = WriteHalf<DuplexStream>;
+    type Incoming = ReadHalf<DuplexStream>;
+
+    async fn accept(&self) -> io::Result<LocalConnection> {
+        self.0
+            .lock()
+            .map_err(|_| io::Error::other("failed to lock local connection"))?
+            .take()
+            .ok_or_else(|| io::ErrorKind::NotConnected.into())
+    }
+}
+
+/// Components and builtin providers running on the host, which invocations are dispatched to
+/// locally
+#[derive(Clone, Debug)]
+pub struct LocalComponents {
+    components: Weak<RwLock<HashMap<ComponentId, Arc<Component>>>>,
+    providers: Option<Weak<LocalProviderManager>>,
+    draining: watch::Receiver<bool>,
+}
+
+impl LocalComponents {
+    /// Dispatch invocations to `components` and the builtin `providers` of embedded hosts, which
+    /// are only referenced weakly to avoid a cycle through the handlers of the components, until
+    /// `draining` is set
+    pub fn new(
+        components: &Arc<RwLock<HashMap<ComponentId, Arc<Component>>>>,
+        providers: Option<&Arc<LocalProviderManager>>,
+        draining: watch::Receiver<bool>,
+    ) -> Self {
+        Self {
+            components: Arc::downgrade(components),
+            providers: providers.map(Arc::downgrade),
+            draining,
+        }
+    }
+
+    /// Returns the local server of the component or builtin provider `id`, if it is running on
+    /// the host and the host is not draining, in which case invocations are left to other hosts
+    pub async fn get(&self, id: &str) -> Option<Arc<LocalServer>> {
+        if *self.draining.borrow() {
+            return None;
+        }
+        let components = self.components.upgrade()?;
+        if let Some(component) = components.read().await.get(id) {
+            return component.local.clone();
+        }
+        self.providers.as_ref()?.upgrade()?.server(id).await
+    }
+}
+
+/// Outgoing stream of an invocation, which is either sent to a remote target or dispatched
+/// locally
+pub enum Outgoing<T> {
+    Remote(T),
+    Local(frame::Outgoing),
+}
+
+impl<T: wrpc_transport::Index<T>> wrpc_transport::Index<Self> for Outgoing<T> {
+    fn index(&self, path: &[usize]) -> anyhow::Result<Self> {
+        match self {
+            Self::Remote(tx) => tx.index(path).map(Self::Remote),
+            Self::Local(tx) => tx.index(path).map(Self::Local),
+        }
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Outgoing<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Remote(tx) => Pin::new(tx).poll_write(cx, buf),
+            Self::Local(tx) => Pin::new(tx).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Remote(tx) => Pin::new(tx).poll_flush(cx),
+            Self::Local(tx) => Pin::new(tx).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Remote(tx) => Pin::new(tx).poll_shutdown(cx),
+            Self::Local(tx) => Pin::new(tx).poll_shutdown(cx),
+        }
+    }
+}
+
+/// Incoming stream of an invocation, which is either received from a remote source or
+/// dispatched locally
+pub enum Incoming<T> {
+    Remote(T),
+    Local(frame::Incoming),
+}
+
+impl<T: wrpc_transport::Index<T>> wrpc_transport::Index<Self> for Incoming<T> {
+    fn index(&self, path: &[usize]) -> anyhow::Result<Self> {
+        match self {
+            Self::Remote(rx) => rx.index(path).map(Self::Remote),
+            Self::Local(rx) => rx.index(path).map(Self::Local),
+        }
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Incoming<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Remote(rx) => Pin::new(rx).poll_read(cx, buf),
+            Self::Local(rx) => Pin::new(rx).poll_read(cx, buf),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::StreamExt as _;
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+    use wrpc_transport::Serve as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn invokes_locally() -> anyhow::Result<()> {
+        let srv = LocalServer::default();
+        let invocations = srv
+            .serve("wasi:cli/run", "run", Vec::<Box<[Option<usize>]>>::new())
+            .await?;
+        let mut invocations = Box::pin(invocations);
+
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("source-id", "caller");
+        let (tx, mut rx) = srv
+            .invoke(
+                headers,
+                "wasi:cli/run",
+                "run",
+                Bytes::from("hello"),
+                &[[]; 0],
+            )
+            .await?;
+        // Streams are closed once all of their handles are dropped
+        drop(tx);
+
+        let (cx, mut srv_tx, mut srv_rx) = invocations
+            .next()
+            .await
+            .context("no invocation received")??;
+        assert_eq!(
+            cx.as_ref()
+                .and_then(|headers| headers.get("source-id"))
+                .map(async_nats::HeaderValue::as_str),
+            Some("caller")
+        );
+        let mut params = Vec::new();
+        srv_rx.read_to_end(&mut params).await?;
+        assert_eq!(params, b"hello");
+        srv_tx.write_all(b"world").await?;
+        drop(srv_tx);
+
+        let mut results = Vec::new();
+        rx.read_to_end(&mut results).await?;
+        assert_eq!(results, b"world");
+
+        // Functions which are not served are rejected
+        assert!(srv
+            .invoke(
+                async_nats::HeaderMap::new(),
+                "wasi:cli/run",
+                "other",
+                Bytes::new(),
+                &[[]; 0]
+            )
+            .await
+            .is_err());
+        Ok(())
+    }
+}
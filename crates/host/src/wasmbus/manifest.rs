@@ -0,0 +1,347 @@
+//! This module contains host manifests, describing the configuration, components, providers and
+//! links to apply to a single host.
+//!
+//! Manifests allow running a host, in particular an embedded one without NATS, from a single file
+//! instead of through the control interface. They are applied using the [`ControlInterfaceServer`]
+//! implementation of the host, so the commands in a manifest are handled exactly like the ones
+//! received over the control interface.
+//!
+//! ```yaml
+//! config:
+//!   greeting:
+//!     message: hello
+//!   http-address:
+//!     address: 0.0.0.0:8080
+//! components:
+//!   - id: hello
+//!     image: file:///path/to/hello.wasm
+//!     max_instances: 10
+//!     config: [greeting]
+//! providers:
+//!   - id: http-server
+//!     image: wasmcloud+builtin://http-server
+//! links:
+//!   - source: http-server
+//!     target: hello
+//!     namespace: wasi
+//!     package: http
+//!     interfaces: [incoming-handler]
+//!     source_config: [http-address]
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{bail, Context as _};
+use bytes::Bytes;
+use serde::Deserialize;
+use tracing::{info, instrument};
+use wasmcloud_control_interface::{CtlResponse, Link, ScaleComponentCommand, StartProviderCommand};
+
+use super::ctl::ControlInterfaceServer;
+use super::Host;
+
+/// Configuration, components, providers and links to apply to a host
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HostManifest {
+    /// Named configuration, keyed by name
+    #[serde(default)]
+    pub config: BTreeMap<String, HashMap<String, String>>,
+    /// Components to run on the host
+    #[serde(default)]
+    pub components: Vec<ManifestComponent>,
+    /// Providers to run on the host
+    #[serde(default)]
+    pub providers: Vec<ManifestProvider>,
+    /// Links between components and providers
+    #[serde(default)]
+    pub links: Vec<ManifestLink>,
+}
+
+/// Component to run on a host
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestComponent {
+    /// Unique identifier of the component
+    pub id: String,
+    /// Image reference of the component
+    pub image: String,
+    /// Maximum number of concurrent instances of the component
+    #[serde(default = "default_max_instances")]
+    pub max_instances: u32,
+    /// Names of the configuration of the component
+    #[serde(default)]
+    pub config: Vec<String>,
+    /// Annotations of the component
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// Provider to run on a host
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestProvider {
+    /// Unique identifier of the provider
+    pub id: String,
+    /// Image reference of the provider
+    pub image: String,
+    /// Names of the configuration of the provider
+    #[serde(default)]
+    pub config: Vec<String>,
+    /// Annotations of the provider
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// Link from a source component or provider to a target
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestLink {
+    /// Identifier of the source of the link
+    pub source: String,
+    /// Identifier of the target of the link
+    pub target: String,
+    /// WIT namespace of the linked interfaces, e.g. `wasi`
+    pub namespace: String,
+    /// WIT package of the linked interfaces, e.g. `http`
+    pub package: String,
+    /// Linked interfaces, e.g. `incoming-handler`
+    pub interfaces: Vec<String>,
+    /// Name of the link
+    #[serde(default = "default_link_name")]
+    pub name: String,
+    /// Names of the configuration of the source of the link
+    #[serde(default)]
+    pub source_config: Vec<String>,
+    /// Names of the configuration of the target of the link
+    #[serde(default)]
+    pub target_config: Vec<String>,
+}
+
+fn default_max_instances() -> u32 {
+    1
+}
+
+fn default_link_name() -> String {
+    "default".into()
+}
+
+/// Returns an error if `res` indicates a failure to `action`
+fn ensure_succeeded<T>(res: CtlResponse<T>, action: impl FnOnce() -> String) -> anyhow::Result<()> {
+    if !res.succeeded() {
+        bail!("failed to {}: {}", action(), res.message());
+    }
+    Ok(())
+}
+
+impl HostManifest {
+    /// Load a manifest from the YAML or JSON file at `path`
+    pub async fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let manifest = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("failed to read manifest `{}`", path.display()))?;
+        serde_yaml::from_str(&manifest)
+            .with_context(|| format!("failed to parse manifest `{}`", path.display()))
+    }
+
+    /// Apply the manifest to `host`, putting configuration and links, scaling components and
+    /// starting providers
+    #[instrument(level = "debug", skip_all)]
+    pub async fn apply(&self, host: &Arc<Host>) -> anyhow::Result<()> {
+        let host_id = host.host_key.public_key();
+        for (name, config) in &self.config {
+            let data = serde_json::to_vec(config).context("failed to encode config")?;
+            let res =
+                ControlInterfaceServer::handle_config_put(&**host, name, Bytes::from(data)).await?;
+            ensure_succeeded(res, || format!("put config `{name}`"))?;
+        }
+        for ManifestComponent {
+            id,
+            image,
+            max_instances,
+            config,
+            annotations,
+        } in &self.components
+        {
+            let cmd = ScaleComponentCommand::builder()
+                .host_id(&host_id)
+                .component_id(id)
+                .component_ref(image)
+                .max_instances(*max_instances)
+                .config(config.clone())
+                .annotations(annotations.clone())
+                .build()
+                .map_err(anyhow::Error::msg)?;
+            let res = ControlInterfaceServer::handle_scale_component(Arc::clone(host), cmd).await?;
+            ensure_succeeded(res, || format!("scale component `{id}`"))?;
+        }
+        for ManifestProvider {
+            id,
+            image,
+            config,
+            annotations,
+        } in &self.providers
+        {
+            let cmd = StartProviderCommand::builder()
+                .host_id(&host_id)
+                .provider_id(id)
+                .provider_ref(image)
+                .config(config.clone())
+                .annotations(annotations.clone())
+                .build()
+                .map_err(anyhow::Error::msg)?;
+            if let Some(res) =
+                ControlInterfaceServer::handle_start_provider(Arc::clone(host), cmd).await?
+            {
+                ensure_succeeded(res, || format!("start provider `{id}`"))?;
+            }
+        }
+        for ManifestLink {
+            source,
+            target,
+            namespace,
+            package,
+            interfaces,
+            name,
+            source_config,
+            target_config,
+        } in &self.links
+        {
+            let link = Link::builder()
+                .source_id(source)
+                .target(target)
+                .name(name)
+                .wit_namespace(namespace)
+                .wit_package(package)
+                .interfaces(interfaces.clone())
+                .source_config(source_config.clone())
+                .target_config(target_config.clone())
+                .build()
+                .map_err(anyhow::Error::msg)?;
+            let res = ControlInterfaceServer::handle_link_put(&**host, link).await?;
+            ensure_succeeded(res, || format!("put link from `{source}` to `{target}`"))?;
+        }
+        info!(
+            components = self.components.len(),
+            providers = self.providers.len(),
+            links = self.links.len(),
+            "applied host manifest"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::time::Duration;
+
+    use super::*;
+    use crate::wasmbus::{HostBuilder, HostConfig};
+
+    #[test]
+    fn parses_manifest() {
+        let manifest: HostManifest = serde_yaml::from_str(
+            r#"
+config:
+  greeting:
+    message: hello
+components:
+  - id: hello
+    image: file:///hello.wasm
+    config: [greeting]
+providers:
+  - id: http-server
+    image: wasmcloud+builtin://http-server
+links:
+  - source: http-server
+    target: hello
+    namespace: wasi
+    package: http
+    interfaces: [incoming-handler]
+"#,
+        )
+        .expect("failed to parse manifest");
+        assert_eq!(
+            manifest,
+            HostManifest {
+                config: BTreeMap::from([(
+                    "greeting".into(),
+                    HashMap::from([("message".into(), "hello".into())])
+                )]),
+                components: vec![ManifestComponent {
+                    id: "hello".into(),
+                    image: "file:///hello.wasm".into(),
+                    max_instances: 1,
+                    config: vec!["greeting".into()],
+                    ..Default::default()
+                }],
+                providers: vec![ManifestProvider {
+                    id: "http-server".into(),
+                    image: "wasmcloud+builtin://http-server".into(),
+                    ..Default::default()
+                }],
+                links: vec![ManifestLink {
+                    source: "http-server".into(),
+                    target: "hello".into(),
+                    namespace: "wasi".into(),
+                    package: "http".into(),
+                    interfaces: vec!["incoming-handler".into()],
+                    name: "default".into(),
+                    ..Default::default()
+                }],
+            }
+        );
+        assert!(serde_yaml::from_str::<HostManifest>("actors: []").is_err());
+    }
+
+    #[tokio::test]
+    async fn applies_manifest_to_embedded_host() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let wasm = dir.path().join("hello.wasm");
+        tokio::fs::write(&wasm, wat::parse_str("(component)")?).await?;
+
+        // An embedded host must not require a NATS connection
+        let (host, _shutdown) = HostBuilder::from(HostConfig {
+            rpc_nats_url: "nats://127.0.0.1:1".parse()?,
+            allow_file_load: true,
+            embedded: true,
+            ..Default::default()
+        })
+        .build()
+        .await?;
+        assert!(host.is_embedded());
+
+        HostManifest {
+            config: BTreeMap::from([(
+                "greeting".into(),
+                HashMap::from([("message".into(), "hello".into())]),
+            )]),
+            components: vec![ManifestComponent {
+                id: "hello".into(),
+                image: format!("file://{}", wasm.display()),
+                max_instances: 2,
+                config: vec!["greeting".into()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+        .apply(&host)
+        .await?;
+
+        let max_instances = tokio::time::timeout(Duration::from_secs(30), async {
+            loop {
+                if let Some(component) = host.components.read().await.get("hello") {
+                    return component.max_instances;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .context("component was not scaled")?;
+        assert_eq!(max_instances.get(), 2);
+        Ok(())
+    }
+}
@@ -7,10 +7,11 @@ use std::env::consts::{ARCH, FAMILY, OS};
 use std::future::Future;
 use std::num::NonZeroUsize;
 use std::ops::Deref;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::str::FromStr;
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::{Arc, Weak};
 use std::task::{Context, Poll};
 use std::time::Duration;
 
@@ -18,31 +19,33 @@ use anyhow::{anyhow, bail, ensure, Context as _};
 use bytes::{BufMut, Bytes, BytesMut};
 use claims::{Claims, StoredClaims};
 use futures::stream::{AbortHandle, Abortable};
-use futures::{join, stream, Stream, StreamExt, TryStreamExt};
-use hyper_util::rt::{TokioExecutor, TokioIo};
+use futures::{join, stream, FutureExt, Stream, StreamExt, TryStreamExt};
 use nkeys::{KeyPair, KeyPairType, XKey};
+use providers::local::LocalProviderManager;
 use providers::Provider;
 use secrecy::SecretBox;
-use serde_json::json;
 use sysinfo::System;
 use tokio::io::AsyncWrite;
 use tokio::net::TcpListener;
-use tokio::spawn;
 use tokio::sync::{mpsc, watch, RwLock, Semaphore};
 use tokio::task::{JoinHandle, JoinSet};
 use tokio::time::{interval_at, timeout, Instant};
+use tokio::{select, spawn};
 use tokio_stream::wrappers::IntervalStream;
 use tracing::{debug, debug_span, error, info, instrument, trace, warn, Instrument as _};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 use wascap::jwt;
 use wasmcloud_control_interface::{
-    ComponentAuctionAck, ComponentAuctionRequest, ComponentDescription, CtlResponse,
-    DeleteInterfaceLinkDefinitionRequest, HostInventory, HostLabel, HostLabelIdentifier, Link,
-    ProviderAuctionAck, ProviderAuctionRequest, ProviderDescription, RegistryCredential,
+    CachedArtifact, ComponentAuctionAck, ComponentAuctionRequest, ComponentDescription,
+    CtlResponse, DeleteInterfaceLinkDefinitionRequest, DrainHostCommand, HostInventory, HostLabel,
+    HostLabelIdentifier, InvocationPolicy, Link, ProviderAuctionAck, ProviderAuctionRequest,
+    ProviderDescription, PruneCacheCommand, PullArtifactsCommand, RegistryCredential,
     ScaleComponentCommand, StartProviderCommand, StopHostCommand, StopProviderCommand,
     UpdateComponentCommand,
 };
+use wasmcloud_core::config_schema::{validate_config, validate_partial_config, ConfigViolation};
 use wasmcloud_core::ComponentId;
+use wasmcloud_runtime::cache::CompilationCache;
 use wasmcloud_runtime::capability::secrets::store::SecretValue;
 use wasmcloud_runtime::component::{from_string_map, Limits, WrpcServeEvent};
 use wasmcloud_runtime::Runtime;
@@ -54,16 +57,31 @@ use crate::event::{DefaultEventPublisher, EventPublisher};
 use crate::metrics::HostMetrics;
 use crate::nats::connect_nats;
 use crate::nats::provider::NatsProviderManager;
-use crate::policy::DefaultPolicyManager;
+use crate::policy::audit::{AuditSink, AuditingPolicyManager};
+use crate::policy::cache::CachingPolicyManager;
+use crate::policy::local::LocalPolicyManager;
+use crate::policy::{
+    DefaultPolicyManager, InvocationSource, PerformInvocationRequest, PolicyClaims,
+};
 use crate::secrets::{DefaultSecretsManager, SecretsManager};
 use crate::store::{DefaultStore, StoreManager};
 use crate::wasmbus::ctl::ControlInterfaceServer;
 use crate::workload_identity::WorkloadIdentityConfig;
 use crate::{fetch_component, PolicyManager, PolicyResponse, RegistryConfig, ResourceRef};
 
+mod admin;
 mod component_spec;
+mod drain;
 mod experimental;
 mod handler;
+mod invocation;
+mod link_policy;
+mod local;
+mod manifest;
+mod quota;
+mod revocation;
+mod routing;
+mod trust;
 
 pub(crate) mod claims;
 pub(crate) mod providers;
@@ -79,15 +97,27 @@ pub mod host_config;
 
 pub use self::experimental::Features;
 pub use self::host_config::Host as HostConfig;
+pub use self::manifest::{HostManifest, ManifestComponent, ManifestLink, ManifestProvider};
+pub use self::quota::{TenantQuota, TENANT_ANNOTATION};
 pub use component_spec::ComponentSpecification;
 pub use providers::ProviderManager;
 
 use self::config::{BundleGenerator, ConfigBundle};
 use self::handler::Handler;
+use self::invocation::InvocationVerifier;
+use self::link_policy::CircuitBreakers;
+use self::local::{LocalComponents, LocalServer};
+use self::revocation::{RevocationLists, RevocationSource};
+use self::routing::WeightedTargets;
+use self::trust::IssuerTrustStore;
 
 const MAX_INVOCATION_CHANNEL_SIZE: usize = 5000;
 const MIN_INVOCATION_CHANNEL_SIZE: usize = 256;
 
+/// Annotation setting the number of pre-instantiated instances of a component, unless it is set
+/// in the limits of the component
+const PREWARM_INSTANCES_ANNOTATION: &str = "wasmcloud.dev/prewarm-instances";
+
 #[derive(Clone, Default)]
 struct AsyncBytesMut(Arc<std::sync::Mutex<BytesMut>>);
 
@@ -145,6 +175,10 @@ struct Component {
     image_reference: Arc<str>,
     events: mpsc::Sender<WrpcServeEvent<<WrpcServer as wrpc_transport::Serve>::Context>>,
     permits: Arc<Semaphore>,
+    /// Server for invocations from components on the same host, if local invocations are enabled
+    local: Option<Arc<LocalServer>>,
+    /// Number of invocations of this component which were accepted and have not finished yet
+    in_flight: Arc<AtomicUsize>,
 }
 
 impl Deref for Component {
@@ -157,13 +191,53 @@ impl Deref for Component {
 
 #[derive(Clone)]
 struct WrpcServer {
-    nats: wrpc_transport_nats::Client,
+    nats: Option<wrpc_transport_nats::Client>,
     claims: Option<Arc<jwt::Claims<jwt::Component>>>,
     id: Arc<str>,
     image_reference: Arc<str>,
     annotations: Arc<Annotations>,
     policy_manager: Arc<dyn PolicyManager>,
     metrics: Arc<HostMetrics>,
+    component_claims: Arc<RwLock<HashMap<ComponentId, jwt::Claims<jwt::Component>>>>,
+    provider_claims: Arc<RwLock<HashMap<String, jwt::Claims<jwt::CapabilityProvider>>>>,
+    invocation_verifier: Option<Arc<InvocationVerifier>>,
+    local: Option<Arc<LocalServer>>,
+}
+
+impl WrpcServer {
+    /// Resolve the caller of an invocation from the headers in its context. The headers are only
+    /// trustworthy if the identity of the caller was `verified`, i.e. the invocation was sent by
+    /// this host or carries a valid invocation token issued for the caller by a trusted host.
+    ///
+    /// Claims are only attached if the claims subject sent by the caller is known in the lattice,
+    /// otherwise the caller is identified by its ID alone.
+    async fn invocation_source(
+        &self,
+        cx: Option<&async_nats::HeaderMap>,
+        verified: bool,
+    ) -> Option<InvocationSource> {
+        let cx = cx?;
+        let source_id = cx.get(handler::SOURCE_ID_HEADER)?.to_string();
+        let claims = if let Some(subject) = cx.get(handler::SOURCE_CLAIMS_SUBJECT_HEADER) {
+            let subject = subject.as_str();
+            if let Some(claims) = self.component_claims.read().await.get(subject) {
+                Some(PolicyClaims::from(claims))
+            } else if let Some(claims) = self.provider_claims.read().await.get(subject) {
+                Some(PolicyClaims::from(claims))
+            } else {
+                // Claims may not have propagated through the lattice yet
+                debug!(source_id, subject, "claims of invocation source not known");
+                None
+            }
+        } else {
+            None
+        };
+        Some(InvocationSource {
+            source_id,
+            claims,
+            verified,
+        })
+    }
 }
 
 struct InvocationContext {
@@ -182,8 +256,9 @@ impl Deref for InvocationContext {
 
 impl wrpc_transport::Serve for WrpcServer {
     type Context = InvocationContext;
-    type Outgoing = <wrpc_transport_nats::Client as wrpc_transport::Serve>::Outgoing;
-    type Incoming = <wrpc_transport_nats::Client as wrpc_transport::Serve>::Incoming;
+    type Outgoing =
+        local::Outgoing<<wrpc_transport_nats::Client as wrpc_transport::Serve>::Outgoing>;
+    type Incoming = local::Incoming<invocation::Incoming>;
 
     #[instrument(
         level = "info",
@@ -203,7 +278,27 @@ impl wrpc_transport::Serve for WrpcServer {
             + 'static,
     > {
         debug!("serving invocations");
-        let invocations = self.nats.serve(instance, func, paths).await?;
+        let paths = paths.into();
+        let remote = if let Some(nats) = &self.nats {
+            nats.serve(instance, func, Arc::clone(&paths))
+                .await?
+                .map_ok(|(cx, tx, rx)| {
+                    (cx, local::Outgoing::Remote(tx), local::Incoming::Remote(rx))
+                })
+                .left_stream()
+        } else {
+            stream::empty().right_stream()
+        };
+        let local = if let Some(local) = &self.local {
+            local
+                .serve(instance, func, paths)
+                .await?
+                .map_ok(|(cx, tx, rx)| (cx, local::Outgoing::Local(tx), local::Incoming::Local(rx)))
+                .left_stream()
+        } else {
+            stream::empty().right_stream()
+        };
+        let invocations = stream::select(remote, local);
 
         let func: Arc<str> = Arc::from(func);
         let instance: Arc<str> = Arc::from(instance);
@@ -213,6 +308,7 @@ impl wrpc_transport::Serve for WrpcServer {
         let metrics = Arc::clone(&self.metrics);
         let policy_manager = Arc::clone(&self.policy_manager);
         let claims = self.claims.clone();
+        let server = self.clone();
         Ok(invocations.and_then(move |(cx, tx, rx)| {
             let annotations = Arc::clone(&annotations);
             let claims = claims.clone();
@@ -222,8 +318,16 @@ impl wrpc_transport::Serve for WrpcServer {
             let instance = Arc::clone(&instance);
             let metrics = Arc::clone(&metrics);
             let policy_manager = Arc::clone(&policy_manager);
-            let span = tracing::info_span!("component_invocation", func = %func, id = %id, instance = %instance);
+            let server = server.clone();
+            let span = tracing::info_span!("component_invocation", func = %func, id = %id, instance = %instance, source_id = tracing::field::Empty);
             async move {
+                let reported_source_id = cx
+                    .as_ref()
+                    .and_then(|cx| cx.get(handler::SOURCE_ID_HEADER))
+                    .map(|source_id| source_id.to_string());
+                if let Some(source_id) = &reported_source_id {
+                    span.record("source_id", source_id.as_str());
+                }
                 if let Some(ref cx) = cx {
                     // Coerce the HashMap<String, Vec<String>> into a Vec<(String, String)> by
                     // flattening the values
@@ -239,12 +343,31 @@ impl wrpc_transport::Serve for WrpcServer {
                     span.set_parent(wasmcloud_tracing::context::get_span_context(&trace_context));
                 }
 
-                    let PolicyResponse {
-                        request_id,
-                        permitted,
-                        message,
-                    } = policy_manager
-                        .evaluate_perform_invocation(
+                // Local invocations originate from this host and are not signed
+                let (rx, verified) = match (rx, &server.invocation_verifier) {
+                    (local::Incoming::Remote(rx), Some(verifier)) => {
+                        let params = verifier
+                            .verify(cx.as_ref(), &id, &instance, &func)
+                            .map_err(|err| {
+                                warn!(?err, "rejecting invocation without a valid invocation token");
+                                err
+                            })?;
+                        (local::Incoming::Remote(invocation::Incoming::new(rx, Some(params))), true)
+                    }
+                    (local::Incoming::Remote(rx), None) => {
+                        (local::Incoming::Remote(invocation::Incoming::new(rx, None)), false)
+                    }
+                    (local::Incoming::Local(rx), _) => (local::Incoming::Local(rx), true),
+                };
+                let source = server.invocation_source(cx.as_ref(), verified).await;
+
+                let PolicyResponse {
+                    request_id,
+                    permitted,
+                    message,
+                } = policy_manager
+                    .evaluate_perform_invocation_request(
+                        &PerformInvocationRequest::new(
                             &id,
                             &image_reference,
                             &annotations,
@@ -252,19 +375,22 @@ impl wrpc_transport::Serve for WrpcServer {
                             instance.to_string(),
                             func.to_string(),
                         )
-                        .instrument(debug_span!(parent: &span, "policy_check"))
-                        .await?;
-                    ensure!(
-                        permitted,
-                        "policy denied request to invoke component `{request_id}`: `{message:?}`",
-                    );
+                        .with_source(source),
+                        claims.as_deref(),
+                    )
+                    .instrument(debug_span!(parent: &span, "policy_check"))
+                    .await?;
+                ensure!(
+                    permitted,
+                    "policy denied request to invoke component `{request_id}`: `{message:?}`",
+                );
 
                 Ok((
                     InvocationContext{
                         start_at: Instant::now(),
-                        // TODO(metrics): insert information about the source once we have concrete context data
                         attributes: vec![
                             KeyValue::new("component.ref", image_reference),
+                            KeyValue::new("source.id", reported_source_id.unwrap_or_default()),
                             KeyValue::new("lattice", metrics.lattice_id.clone()),
                             KeyValue::new("host", metrics.host_id.clone()),
                             KeyValue::new("operation", format!("{instance}/{func}")),
@@ -319,6 +445,12 @@ pub struct Host {
     /// Indicates whether the host is ready to process requests.
     ready: Arc<AtomicBool>,
 
+    /// A channel signaling components to stop accepting invocations once the host is draining.
+    drain_tx: watch::Sender<bool>,
+
+    /// Resources reserved for components being scaled and providers being started.
+    quota_reservations: quota::Reservations,
+
     /// The encryption key used to secure secrets when transmitting over NATS.
     secrets_xkey: Arc<XKey>,
 
@@ -347,12 +479,15 @@ pub struct Host {
     /// Optional overrides for registry configuration settings.
     registry_config: RwLock<HashMap<String, RegistryConfig>>,
 
-    /// The NATS client used for making RPC calls.
-    rpc_nats: Arc<async_nats::Client>,
+    /// The NATS client used for making RPC calls, unless the host is embedded.
+    rpc_nats: Option<Arc<async_nats::Client>>,
 
     /// The manager for communicating with capability providers.
     provider_manager: Arc<dyn ProviderManager>,
 
+    /// The builtin providers running in-process, if the host is embedded.
+    local_providers: Option<Arc<LocalProviderManager>>,
+
     /// Configured OpenTelemetry metrics for monitoring the host.
     metrics: Arc<HostMetrics>,
 
@@ -362,6 +497,16 @@ pub struct Host {
     /// The policy manager used for evaluating policy decisions.
     policy_manager: Arc<dyn PolicyManager>,
 
+    /// The verifier of invocation tokens, if signed invocations are required.
+    invocation_verifier: Option<Arc<InvocationVerifier>>,
+
+    /// The store of trusted operators and accounts, if issuers of components and providers are
+    /// required to chain up to a trusted operator.
+    trust_store: Option<IssuerTrustStore>,
+
+    /// The revocation lists of components and providers.
+    revocations: RevocationLists,
+
     /// The secrets manager used for managing and retrieving secrets.
     secrets_manager: Arc<dyn SecretsManager>,
 
@@ -457,6 +602,48 @@ impl HostBuilder {
         }
     }
 
+    /// Initialize the host with the local, file-based policy manager. The rule file is watched for
+    /// changes and reloaded every `reload_interval`, see [crate::policy::local] for the file format.
+    pub async fn with_local_policy_manager(
+        self,
+        policy_file: PathBuf,
+        reload_interval: Option<Duration>,
+    ) -> anyhow::Result<Self> {
+        let policy_manager = LocalPolicyManager::load(policy_file, reload_interval).await?;
+        Ok(Self {
+            policy_manager: Some(policy_manager),
+            ..self
+        })
+    }
+
+    /// Cache the decisions of the configured policy manager for `ttl`. Has no effect if no policy
+    /// manager is configured or the policy manager already caches its decisions.
+    pub fn with_policy_cache(self, ttl: Duration) -> Self {
+        let Some(policy_manager) = self
+            .policy_manager
+            .as_ref()
+            .and_then(|policy_manager| CachingPolicyManager::wrap(policy_manager, ttl))
+        else {
+            return self;
+        };
+        Self {
+            policy_manager: Some(policy_manager),
+            ..self
+        }
+    }
+
+    /// Record every decision of the configured policy manager to `sink`. If no policy manager is
+    /// configured, the decisions of the [DefaultPolicyManager] permitting all requests are recorded.
+    pub fn with_policy_audit(self, sink: Arc<dyn AuditSink>) -> Self {
+        let policy_manager = self
+            .policy_manager
+            .unwrap_or_else(|| Arc::new(DefaultPolicyManager));
+        Self {
+            policy_manager: Some(Arc::new(AuditingPolicyManager::new(policy_manager, sink))),
+            ..self
+        }
+    }
+
     /// Initialize the host with the given registry configuration
     pub fn with_registry_config(self, registry_config: HashMap<String, RegistryConfig>) -> Self {
         Self {
@@ -525,18 +712,55 @@ impl HostBuilder {
             .context("failed to encode host claims")?;
         let host_token = Arc::new(jwt::Token { jwt, claims });
 
+        let invocation_verifier = if self.config.require_signed_invocations {
+            let verifier = InvocationVerifier::new(
+                self.config.lattice.as_ref(),
+                self.config
+                    .trusted_invocation_issuers
+                    .iter()
+                    .cloned()
+                    .chain([self.config.host_key.public_key()]),
+            )
+            .context("failed to construct invocation verifier")?;
+            Some(Arc::new(verifier))
+        } else {
+            None
+        };
+
+        let trust_store = if self.config.trusted_operators.is_empty() {
+            None
+        } else {
+            let trust_store = IssuerTrustStore::new(
+                &self.config.trusted_operators,
+                &self.config.trusted_accounts,
+            )
+            .context("failed to construct issuer trust store")?;
+            Some(trust_store)
+        };
+
+        let revocations = RevocationLists::default();
+        if let Some(path) = &self.config.revocation_list_file {
+            let lists = revocation::load_file(path)
+                .await
+                .context("failed to load revocation list file")?;
+            revocations.set(RevocationSource::File, lists).await;
+        }
+
         let workload_identity_config = if self.config.experimental_features.workload_identity_auth {
             Some(WorkloadIdentityConfig::from_env()?)
         } else {
             None
         };
 
-        debug!(
-            rpc_nats_url = self.config.rpc_nats_url.as_str(),
-            "connecting to NATS RPC server"
-        );
-        let rpc_nats = Arc::new(
-            connect_nats(
+        let rpc_nats = if self.config.embedded {
+            debug!("running embedded, not connecting to NATS RPC server");
+            None
+        } else {
+            debug!(
+                rpc_nats_url = self.config.rpc_nats_url.as_str(),
+                "connecting to NATS RPC server"
+            );
+            let rpc_nats = connect_nats(
                 self.config.rpc_nats_url.as_str(),
                 self.config.rpc_jwt.as_ref(),
                 self.config.rpc_key.clone(),
@@ -545,20 +769,46 @@ impl HostBuilder {
                 workload_identity_config.clone(),
             )
             .await
-            .context("failed to establish NATS RPC server connection")?,
-        );
+            .context("failed to establish NATS RPC server connection")?;
+            Some(Arc::new(rpc_nats))
+        };
+        // TODO(#4407): This trait abstraction isn't actually abstracted since all capability
+        // providers other than the builtin ones of embedded hosts are NATS based. As we revise
+        // communication with providers, we can update this to be a trait object from the builder
+        // instead.
+        let (provider_manager, local_providers): (Arc<dyn ProviderManager>, _) =
+            if let Some(rpc_nats) = &rpc_nats {
+                let manager =
+                    NatsProviderManager::new(Arc::clone(rpc_nats), self.config.lattice.to_string());
+                (Arc::new(manager), None)
+            } else {
+                let manager = Arc::new(LocalProviderManager::default());
+                (Arc::clone(&manager) as _, Some(manager))
+            };
 
         let (stop_tx, stop_rx) = watch::channel(None);
 
-        let (runtime, _epoch) = Runtime::builder()
+        let runtime = Runtime::builder()
             .max_execution_time(self.config.max_execution_time)
             .max_linear_memory(self.config.max_linear_memory)
             .max_components(self.config.max_components)
             .max_core_instances_per_component(self.config.max_core_instances_per_component)
             .max_component_size(self.config.max_component_size)
-            .experimental_features(self.config.experimental_features.into())
-            .build()
-            .context("failed to build runtime")?;
+            .experimental_features(self.config.experimental_features.into());
+        let runtime = match (self.config.fuel_metering, self.config.max_fuel) {
+            (_, Some(max_fuel)) => runtime.max_fuel(max_fuel),
+            (true, None) => runtime.fuel_metering(),
+            (false, None) => runtime,
+        };
+        let runtime = if let Some(dir) = &self.config.compilation_cache_dir {
+            runtime.compilation_cache(
+                CompilationCache::new(dir, self.config.compilation_cache_max_size)
+                    .context("failed to initialize compilation cache")?,
+            )
+        } else {
+            runtime
+        };
+        let (runtime, _epoch) = runtime.build().context("failed to build runtime")?;
 
         let scope = InstrumentationScope::builder("wasmcloud-host")
             .with_version(self.config.version.clone())
@@ -589,135 +839,108 @@ impl HostBuilder {
         debug!("Feature flags: {:?}", self.config.experimental_features);
         let mut tasks = JoinSet::new();
         let ready = Arc::new(AtomicBool::new(true));
-        if let Some(addr) = self.config.http_admin {
-            let socket = TcpListener::bind(addr)
-                .await
-                .context("failed to bind on HTTP administration endpoint")?;
-            let ready = Arc::clone(&ready);
-            let svc = hyper::service::service_fn(move |req| {
-                const OK: &str = r#"{"status":"ok"}"#;
-                const FAIL: &str = r#"{"status":"failure"}"#;
-                let ready = Arc::clone(&ready);
-                async move {
-                    let (http::request::Parts { method, uri, .. }, _) = req.into_parts();
-                    match (method.as_str(), uri.path()) {
-                        ("HEAD", "/livez") => Ok(http::Response::default()),
-                        ("GET", "/livez") => Ok(http::Response::new(http_body_util::Full::new(
-                            Bytes::from(OK),
-                        ))),
-                        (method, "/livez") => http::Response::builder()
-                            .status(http::StatusCode::METHOD_NOT_ALLOWED)
-                            .body(http_body_util::Full::new(Bytes::from(format!(
-                                "method `{method}` not supported for path `/livez`"
-                            )))),
-                        ("HEAD", "/readyz") => {
-                            if ready.load(Ordering::Relaxed) {
-                                Ok(http::Response::default())
-                            } else {
-                                http::Response::builder()
-                                    .status(http::StatusCode::INTERNAL_SERVER_ERROR)
-                                    .body(http_body_util::Full::default())
-                            }
-                        }
-                        ("GET", "/readyz") => {
-                            if ready.load(Ordering::Relaxed) {
-                                Ok(http::Response::new(http_body_util::Full::new(Bytes::from(
-                                    OK,
-                                ))))
-                            } else {
-                                http::Response::builder()
-                                    .status(http::StatusCode::INTERNAL_SERVER_ERROR)
-                                    .body(http_body_util::Full::new(Bytes::from(FAIL)))
-                            }
-                        }
-                        (method, "/readyz") => http::Response::builder()
-                            .status(http::StatusCode::METHOD_NOT_ALLOWED)
-                            .body(http_body_util::Full::new(Bytes::from(format!(
-                                "method `{method}` not supported for path `/readyz`"
-                            )))),
-                        (.., path) => http::Response::builder()
-                            .status(http::StatusCode::NOT_FOUND)
-                            .body(http_body_util::Full::new(Bytes::from(format!(
-                                "unknown endpoint `{path}`"
-                            )))),
-                    }
-                }
-            });
-            let srv = hyper_util::server::conn::auto::Builder::new(TokioExecutor::new());
-            tasks.spawn(async move {
-                loop {
-                    let stream = match socket.accept().await {
-                        Ok((stream, _)) => stream,
-                        Err(err) => {
-                            error!(?err, "failed to accept HTTP administration connection");
-                            continue;
-                        }
-                    };
-                    let svc = svc.clone();
-                    if let Err(err) = srv.serve_connection(TokioIo::new(stream), svc).await {
-                        error!(?err, "failed to serve HTTP administration connection");
-                    }
-                }
-            });
-        }
+        let http_admin = if let Some(addr) = self.config.http_admin {
+            Some(
+                TcpListener::bind(addr)
+                    .await
+                    .context("failed to bind on HTTP administration endpoint")?,
+            )
+        } else {
+            None
+        };
 
         let (heartbeat_abort, heartbeat_abort_reg) = AbortHandle::new_pair();
         let start_at = Instant::now();
 
-        let host = Host {
-            components: Arc::new(RwLock::new(HashMap::new())),
-            providers: RwLock::new(HashMap::new()),
-            friendly_name,
-            heartbeat: heartbeat_abort.clone(),
-            host_key: self.config.host_key.clone(),
-            host_token,
-            secrets_xkey: Arc::new(XKey::new()),
-            labels: Arc::new(RwLock::new(labels)),
-            experimental_features: self.config.experimental_features,
-            runtime,
-            start_at,
-            stop_rx,
-            stop_tx,
-            links: RwLock::new(HashMap::new()),
-            component_claims: Arc::new(RwLock::new(HashMap::new())),
-            provider_claims: Arc::new(RwLock::new(HashMap::new())),
-            metrics: Arc::new(metrics),
-            max_execution_time: self.config.max_execution_time,
-            messaging_links: Arc::default(),
-            ready: Arc::clone(&ready),
-            tasks,
-            rpc_nats: Arc::clone(&rpc_nats),
-            registry_config: RwLock::new(self.registry_config),
-            // Extension traits that we fallback to defaults for
-            event_publisher: self
-                .event_publisher
-                .unwrap_or_else(|| Arc::new(DefaultEventPublisher::default())),
-            policy_manager: self
-                .policy_manager
-                .unwrap_or_else(|| Arc::new(DefaultPolicyManager)),
-            secrets_manager: self
-                .secrets_manager
-                .unwrap_or_else(|| Arc::new(DefaultSecretsManager::default())),
-            data_store: self
-                .data_store
-                .unwrap_or_else(|| Arc::new(DefaultStore::default())),
-            config_store: self
-                .config_store
-                .unwrap_or_else(|| Arc::new(DefaultStore::default())),
-            config_generator: self
-                .bundle_generator
-                .unwrap_or_else(|| BundleGenerator::new(Arc::new(DefaultStore::default()))),
-            // TODO(#4407): This trait abstraction isn't actually abstracted since all capability
-            // providers are NATS based. As we revise communication with providers, we can update
-            // this to be a trait object from the builder instead.
-            provider_manager: Arc::new(NatsProviderManager::new(
+        let http_admin_token = self.config.http_admin_token.as_deref().map(Arc::from);
+        // Unless configured otherwise, bundles are generated from the configuration in the store
+        let config_store = self
+            .config_store
+            .unwrap_or_else(|| Arc::new(DefaultStore::default()));
+        if let Some(name) = &self.config.revocation_list_config {
+            let lists = revocation::load_config(config_store.as_ref(), name)
+                .await
+                .context("failed to load revocation list config")?;
+            revocations.set(RevocationSource::Config, lists).await;
+        }
+        // The administration API only references the host weakly, since it is served by one of
+        // the host's own tasks
+        let host = Arc::new_cyclic(|host| {
+            if let Some(socket) = http_admin {
+                tasks.spawn(admin::serve(
+                    socket,
+                    Weak::clone(host),
+                    Arc::clone(&ready),
+                    http_admin_token,
+                ));
+            }
+            if let Some(path) = &self.config.revocation_list_file {
+                tasks.spawn(revocation::watch_file(
+                    Weak::clone(host),
+                    path.clone(),
+                    self.config
+                        .revocation_list_reload_interval
+                        .unwrap_or(revocation::DEFAULT_RELOAD_INTERVAL),
+                ));
+            }
+            if let Some(name) = &self.config.revocation_list_config {
+                tasks.spawn(revocation::watch_config(
+                    Weak::clone(host),
+                    Arc::clone(&config_store),
+                    name.clone(),
+                ));
+            }
+            Host {
+                components: Arc::new(RwLock::new(HashMap::new())),
+                providers: RwLock::new(HashMap::new()),
+                friendly_name,
+                heartbeat: heartbeat_abort.clone(),
+                host_key: self.config.host_key.clone(),
+                host_token,
+                secrets_xkey: Arc::new(XKey::new()),
+                labels: Arc::new(RwLock::new(labels)),
+                experimental_features: self.config.experimental_features,
+                runtime,
+                start_at,
+                stop_rx,
+                stop_tx,
+                links: RwLock::new(HashMap::new()),
+                component_claims: Arc::new(RwLock::new(HashMap::new())),
+                provider_claims: Arc::new(RwLock::new(HashMap::new())),
+                metrics: Arc::new(metrics),
+                max_execution_time: self.config.max_execution_time,
+                messaging_links: Arc::default(),
+                ready: Arc::clone(&ready),
+                drain_tx: watch::channel(false).0,
+                quota_reservations: quota::Reservations::default(),
+                tasks,
                 rpc_nats,
-                self.config.lattice.to_string(),
-            )),
-            host_config: self.config,
-        };
-
-        let host = Arc::new(host);
+                registry_config: RwLock::new(self.registry_config),
+                // Extension traits that we fallback to defaults for
+                event_publisher: self
+                    .event_publisher
+                    .unwrap_or_else(|| Arc::new(DefaultEventPublisher::default())),
+                policy_manager: self
+                    .policy_manager
+                    .unwrap_or_else(|| Arc::new(DefaultPolicyManager)),
+                invocation_verifier,
+                trust_store,
+                revocations,
+                secrets_manager: self
+                    .secrets_manager
+                    .unwrap_or_else(|| Arc::new(DefaultSecretsManager::default())),
+                data_store: self
+                    .data_store
+                    .unwrap_or_else(|| Arc::new(DefaultStore::default())),
+                config_generator: self
+                    .bundle_generator
+                    .unwrap_or_else(|| BundleGenerator::new(Arc::new(Arc::clone(&config_store)))),
+                config_store,
+                provider_manager,
+                local_providers,
+                host_config: self.config,
+            }
+        });
 
         let heartbeat_interval = host
             .host_config
@@ -746,10 +969,7 @@ impl HostBuilder {
                                     }
                                 };
 
-                                if let Err(e) = host
-                                    .event_publisher
-                                    .publish_event("host_heartbeat", heartbeat)
-                                    .await
+                                if let Err(e) = host.event_publisher.publish_event(heartbeat).await
                                 {
                                     error!("failed to publish heartbeat: {e}");
                                 }
@@ -767,15 +987,14 @@ impl HostBuilder {
             }
         });
 
-        let start_evt = json!({
-            "id": host.host_key.public_key(),
-            "friendly_name": host.friendly_name,
-            "labels": *host.labels.read().await,
-            "uptime_seconds": 0,
-            "version": host.host_config.version,
-        });
+        let start_evt = crate::event::host_started(
+            host.host_key.public_key(),
+            &host.friendly_name,
+            &host.host_config.version,
+            host.labels.read().await.clone(),
+        );
         host.event_publisher
-            .publish_event("host_started", start_evt)
+            .publish_event(start_evt)
             .await
             .context("failed to publish start event")?;
         info!(
@@ -788,20 +1007,21 @@ impl HostBuilder {
             heartbeat_abort.abort();
             heartbeat.await.context("failed to await heartbeat")?;
             host.event_publisher
-                .publish_event(
-                    "host_stopped",
-                    json!({
-                        "labels": *host.labels.read().await,
-                    }),
-                )
+                .publish_event(crate::event::host_stopped(host.labels.read().await.clone()))
                 .await
                 .context("failed to publish stop event")?;
-            // Before we exit, make sure to flush all messages or we may lose some that we've
-            // thought were sent (like the host_stopped event)
-            host.rpc_nats
+            host.event_publisher
                 .flush()
                 .await
-                .context("failed to flush NATS clients")?;
+                .context("failed to flush events")?;
+            // Before we exit, make sure to flush all messages or we may lose some that we've
+            // thought were sent (like the host_stopped event)
+            if let Some(rpc_nats) = &host.rpc_nats {
+                rpc_nats
+                    .flush()
+                    .await
+                    .context("failed to flush NATS clients")?;
+            }
             Ok(())
         }))
     }
@@ -851,6 +1071,24 @@ impl Host {
         &self.host_config.lattice
     }
 
+    /// Returns whether the host is embedded, running without NATS
+    pub fn is_embedded(&self) -> bool {
+        self.rpc_nats.is_none()
+    }
+
+    /// Returns the NATS client used for RPC, failing if the host is embedded
+    fn rpc_nats(&self) -> anyhow::Result<&Arc<async_nats::Client>> {
+        self.rpc_nats
+            .as_ref()
+            .context("host is embedded and not connected to NATS")
+    }
+
+    /// Returns whether invocations of components on the host are dispatched in-process, which is
+    /// always the case for embedded hosts
+    fn local_invocations(&self) -> bool {
+        self.host_config.local_invocations || self.rpc_nats.is_none()
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn inventory(&self) -> HostInventory {
         trace!("generating host inventory");
@@ -946,14 +1184,15 @@ impl Host {
             .uptime_seconds(uptime.as_secs())
             .version(self.host_config.version.clone())
             .host_id(self.host_key.public_key())
+            .tenant_quotas(self.tenant_quota_usage().await)
             .build()
             .expect("failed to build host inventory")
     }
 
     #[instrument(level = "debug", skip_all)]
-    async fn heartbeat(&self) -> anyhow::Result<serde_json::Value> {
+    async fn heartbeat(&self) -> anyhow::Result<crate::event::WasmbusEvent> {
         trace!("generating heartbeat");
-        Ok(serde_json::to_value(self.inventory().await)?)
+        Ok(crate::event::host_heartbeat(self.inventory().await))
     }
 
     /// Instantiate a component
@@ -983,13 +1222,31 @@ impl Host {
                 .get()
                 .clamp(MIN_INVOCATION_CHANNEL_SIZE, MAX_INVOCATION_CHANNEL_SIZE),
         );
-        let prefix = Arc::from(format!("{}.{id}", &self.host_config.lattice));
-        let nats = wrpc_transport_nats::Client::new(
-            Arc::clone(&self.rpc_nats),
-            Arc::clone(&prefix),
-            Some(prefix),
-        )
-        .await?;
+        let nats = if let Some(rpc_nats) = &self.rpc_nats {
+            let prefix = Arc::from(format!("{}.{id}", self.host_config.lattice));
+            let nats = wrpc_transport_nats::Client::new(
+                Arc::clone(rpc_nats),
+                Arc::clone(&prefix),
+                Some(prefix),
+            )
+            .await?;
+            Some(nats)
+        } else {
+            None
+        };
+        let handler = Handler {
+            claims_subject: component
+                .claims()
+                .map(|claims| Arc::from(claims.subject.as_str())),
+            ..handler
+        };
+        // Instances used by both wRPC and the builtin HTTP server share the pre-warmed pool
+        component
+            .prewarm(handler.clone())
+            .context("failed to pre-warm component instances")?;
+        let local = self
+            .local_invocations()
+            .then(|| Arc::new(LocalServer::default()));
         let exports = component
             .serve_wrpc(
                 &WrpcServer {
@@ -1000,6 +1257,10 @@ impl Host {
                     annotations: Arc::new(annotations.clone()),
                     policy_manager: Arc::clone(&self.policy_manager),
                     metrics: Arc::clone(&self.metrics),
+                    component_claims: Arc::clone(&self.component_claims),
+                    provider_claims: Arc::clone(&self.provider_claims),
+                    invocation_verifier: self.invocation_verifier.clone(),
+                    local: local.clone(),
                 },
                 handler.clone(),
                 events_tx.clone(),
@@ -1018,12 +1279,17 @@ impl Host {
             .set_max_instances(max_instances.get() as u64, &component_attributes);
 
         let metrics = Arc::clone(&self.metrics);
+        let metered = component.clone();
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let mut drain_rx = self.drain_tx.subscribe();
         Ok(Arc::new(Component {
             component,
             id: Arc::clone(&id),
             handler,
             events: events_tx,
             permits: Arc::clone(&permits),
+            local,
+            in_flight: Arc::clone(&in_flight),
             exports: spawn(async move {
                 // Since we are joining two `move` closures, we need two separate `Arc`s
                 let metrics_left = Arc::clone(&metrics);
@@ -1035,7 +1301,22 @@ impl Host {
                             let metrics_left = Arc::clone(&metrics_left);
                             let component_attributes = Arc::clone(&component_attributes);
                             let permits = Arc::clone(&permits);
-                            if let Some(fut) = exports.next().await {
+                            let (guard, fut) = select! {
+                                biased;
+
+                                // Dropping the exports stops accepting new invocations, while
+                                // in-flight invocations run to completion in their own tasks
+                                _ = drain_rx.wait_for(|draining| *draining) => {
+                                    info!("host is draining, no longer accepting invocations");
+                                    break;
+                                }
+                                // Invocations are counted as in flight as soon as they are
+                                // accepted, so that draining cannot miss them
+                                fut = exports
+                                    .next()
+                                    .map(|fut| (drain::InFlightGuard::new(&in_flight), fut)) => fut,
+                            };
+                            if let Some(fut) = fut {
                                 match fut {
                                     Ok(fut) => {
                                         debug!("accepted invocation, acquiring permit");
@@ -1045,6 +1326,7 @@ impl Host {
                                         metrics_left
                                             .increment_active_instance(&component_attributes);
                                         spawn(async move {
+                                            let _guard = guard;
                                             let _permit = permit;
                                             debug!("handling invocation");
                                             // Awaiting this future drives the execution of the component
@@ -1074,6 +1356,10 @@ impl Host {
                                         warn!(?err, "failed to accept invocation")
                                     }
                                 }
+                            } else {
+                                // All exports are done, which is immediately the case for
+                                // components without any exports
+                                break;
                             }
                         }
                     },
@@ -1088,6 +1374,7 @@ impl Host {
                                             ..
                                         },
                                     success,
+                                    fuel_consumed,
                                 }
                                 | WrpcServeEvent::MessagingHandlerHandleMessageReturned {
                                     context:
@@ -1097,6 +1384,7 @@ impl Host {
                                             ..
                                         },
                                     success,
+                                    fuel_consumed,
                                 }
                                 | WrpcServeEvent::DynamicExportReturned {
                                     context:
@@ -1106,12 +1394,23 @@ impl Host {
                                             ..
                                         },
                                     success,
-                                } => metrics_right.record_component_invocation(
-                                    u64::try_from(start_at.elapsed().as_nanos())
-                                        .unwrap_or_default(),
-                                    attributes,
-                                    !success,
-                                ),
+                                    fuel_consumed,
+                                } => {
+                                    metrics_right.record_component_invocation(
+                                        u64::try_from(start_at.elapsed().as_nanos())
+                                            .unwrap_or_default(),
+                                        attributes,
+                                        !success,
+                                    );
+                                    if let Some(fuel) = fuel_consumed {
+                                        metrics_right.record_fuel_consumed(fuel, attributes);
+                                    }
+                                    if let Some((hits, misses)) = metered.take_instance_pool_stats()
+                                    {
+                                        metrics_right
+                                            .record_instance_pool_stats(hits, misses, attributes);
+                                    }
+                                }
                             }
                         }
                         debug!("serving event stream is done");
@@ -1158,13 +1457,37 @@ impl Host {
 
         // Map the imports to pull out the result types of the functions for lookup when invoking them
         let handler = Handler {
-            nats: Arc::clone(&self.rpc_nats),
+            nats: self.rpc_nats.clone(),
             config_data: Arc::new(RwLock::new(config)),
             lattice: Arc::clone(&self.host_config.lattice),
             component_id: Arc::clone(&component_id),
+            claims_subject: None,
+            invocation_key: self
+                .host_config
+                .sign_invocations
+                .then(|| Arc::clone(&self.host_key)),
+            local_components: self.local_invocations().then(|| {
+                LocalComponents::new(
+                    &self.components,
+                    self.local_providers.as_ref(),
+                    self.drain_tx.subscribe(),
+                )
+            }),
             secrets: Arc::new(RwLock::new(secrets)),
             targets: Arc::default(),
             instance_links: Arc::new(RwLock::new(component_import_links(&component_spec.links))),
+            link_policies: Arc::new(RwLock::new(component_link_policies(&component_spec.links))),
+            weighted_targets: Arc::new(RwLock::new(component_weighted_targets(
+                &component_spec.links,
+            ))),
+            circuit_breakers: Arc::new(CircuitBreakers::new(
+                Arc::clone(&self.metrics),
+                vec![
+                    KeyValue::new("component.id", component_id.to_string()),
+                    KeyValue::new("lattice", self.host_config.lattice.to_string()),
+                    KeyValue::new("host", self.host_key.public_key()),
+                ],
+            )),
             messaging_links: {
                 let mut links = self.messaging_links.write().await;
                 Arc::clone(links.entry(Arc::clone(&component_id)).or_default())
@@ -1189,17 +1512,14 @@ impl Host {
 
         info!(?component_ref, "component started");
         self.event_publisher
-            .publish_event(
-                "component_scaled",
-                crate::event::component_scaled(
-                    claims.as_ref(),
-                    annotations,
-                    self.host_key.public_key(),
-                    max_instances,
-                    &component_ref,
-                    &component_id,
-                ),
-            )
+            .publish_event(crate::event::component_scaled(
+                claims.as_ref(),
+                annotations,
+                self.host_key.public_key(),
+                max_instances,
+                &component_ref,
+                &component_id,
+            ))
             .await?;
 
         Ok(entry.insert(component))
@@ -1227,6 +1547,16 @@ impl Host {
         .context("failed to fetch component")
     }
 
+    /// Ensures that `issuer`, the issuer of a component or provider, chains up to a trusted
+    /// operator if the host has a trust store. Unsigned components and providers are not trusted.
+    fn ensure_trusted_issuer(&self, issuer: Option<&str>) -> anyhow::Result<()> {
+        let Some(trust_store) = &self.trust_store else {
+            return Ok(());
+        };
+        let issuer = issuer.context("unsigned components and providers are not trusted")?;
+        trust_store.verify(issuer)
+    }
+
     #[instrument(level = "debug", skip_all)]
     pub(crate) async fn handle_auction_component(
         &self,
@@ -1293,6 +1623,33 @@ impl Host {
         .await
     }
 
+    #[instrument(level = "debug", skip_all)]
+    pub(crate) async fn handle_drain_host(
+        self: Arc<Self>,
+        payload: impl AsRef<[u8]>,
+        transport_host_id: &str,
+    ) -> anyhow::Result<CtlResponse<()>> {
+        // Allow an empty payload to be used for draining hosts
+        let cmd = if payload.as_ref().is_empty() {
+            DrainHostCommand::default()
+        } else {
+            serde_json::from_slice::<DrainHostCommand>(payload.as_ref())
+                .context("failed to deserialize drain command")?
+        };
+        let host_id = cmd.host_id();
+        if !host_id.is_empty() {
+            anyhow::ensure!(
+                host_id == transport_host_id && host_id == self.host_key.public_key(),
+                "invalid host_id [{host_id}]"
+            );
+        }
+        anyhow::ensure!(
+            transport_host_id == self.host_key.public_key(),
+            "invalid host_id [{transport_host_id}]"
+        );
+        <Self as ControlInterfaceServer>::handle_drain_host(self, cmd).await
+    }
+
     #[instrument(level = "debug", skip_all)]
     pub(crate) async fn handle_scale_component(
         self: Arc<Self>,
@@ -1322,6 +1679,13 @@ impl Host {
         trace!(?component_ref, max_instances, "scale component task");
 
         let claims = claims_token.map(|c| c.claims.clone());
+        if max_instances > 0 {
+            self.ensure_trusted_issuer(claims.as_ref().map(|c| c.issuer.as_str()))
+                .with_context(|| format!("refusing to scale component `{component_id}`"))?;
+            self.ensure_not_revoked(claims.as_ref())
+                .await
+                .with_context(|| format!("refusing to scale component `{component_id}`"))?;
+        }
         match self
             .policy_manager
             .evaluate_start_component(
@@ -1346,7 +1710,8 @@ impl Host {
             } => (),
         };
 
-        let limits: Option<Limits> = from_string_map(component_limits.as_ref());
+        let limits =
+            resolve_component_limits(component_limits.as_ref(), annotations, max_instances)?;
 
         let scaled_event = match (
             self.components
@@ -1403,18 +1768,15 @@ impl Host {
                         error!(%component_ref, %component_id, err = ?e, "failed to scale component");
                         if let Err(e) = self
                             .event_publisher
-                            .publish_event(
-                                "component_scale_failed",
-                                crate::event::component_scale_failed(
-                                    claims_token.map(|c| c.claims.clone()).as_ref(),
-                                    annotations,
-                                    host_id,
-                                    &component_ref,
-                                    &component_id,
-                                    max_instances,
-                                    e,
-                                ),
-                            )
+                            .publish_event(crate::event::component_scale_failed(
+                                claims_token.map(|c| c.claims.clone()).as_ref(),
+                                annotations,
+                                host_id,
+                                &component_ref,
+                                &component_id,
+                                max_instances,
+                                e,
+                            ))
                             .await
                         {
                             error!(%component_ref, %component_id, err = ?e, "failed to publish component scale failed event");
@@ -1497,9 +1859,7 @@ impl Host {
             }
         };
 
-        self.event_publisher
-            .publish_event("component_scaled", scaled_event)
-            .await?;
+        self.event_publisher.publish_event(scaled_event).await?;
 
         Ok(())
     }
@@ -1547,6 +1907,11 @@ impl Host {
             )
             .context("failed to initialize component")?;
             let new_claims = new_component.claims().cloned();
+            self.ensure_trusted_issuer(new_claims.as_ref().map(|c| c.issuer.as_str()))
+                .with_context(|| format!("refusing to update component `{component_id}`"))?;
+            self.ensure_not_revoked(new_claims.as_ref())
+                .await
+                .with_context(|| format!("refusing to update component `{component_id}`"))?;
             if let Some(ref claims) = new_claims {
                 self.store_claims(Claims::Component(claims.clone()))
                     .await
@@ -1571,17 +1936,14 @@ impl Host {
 
             info!(%new_component_ref, "component updated");
             self.event_publisher
-                .publish_event(
-                    "component_scaled",
-                    crate::event::component_scaled(
-                        new_claims.as_ref(),
-                        &component.annotations,
-                        host_id,
-                        max,
-                        new_component_ref,
-                        &component_id,
-                    ),
-                )
+                .publish_event(crate::event::component_scaled(
+                    new_claims.as_ref(),
+                    &component.annotations,
+                    host_id,
+                    max,
+                    new_component_ref,
+                    &component_id,
+                ))
                 .await?;
 
             // TODO(#1548): If this errors, we need to rollback
@@ -1589,17 +1951,14 @@ impl Host {
                 .await
                 .context("failed to stop old component")?;
             self.event_publisher
-                .publish_event(
-                    "component_scaled",
-                    crate::event::component_scaled(
-                        component.claims(),
-                        &component.annotations,
-                        host_id,
-                        0_usize,
-                        &component.image_reference,
-                        &component.id,
-                    ),
-                )
+                .publish_event(crate::event::component_scaled(
+                    component.claims(),
+                    &component.annotations,
+                    host_id,
+                    0_usize,
+                    &component.image_reference,
+                    &component.id,
+                ))
                 .await?;
 
             component
@@ -1652,6 +2011,14 @@ impl Host {
             }
         };
         let claims = claims_token.as_ref().map(|t| t.claims.clone());
+        // Builtin providers are part of the host and have no issuer
+        if !matches!(provider_ref, ResourceRef::Builtin(..)) {
+            self.ensure_trusted_issuer(claims.as_ref().map(|c| c.issuer.as_str()))
+                .with_context(|| format!("refusing to start provider `{provider_id}`"))?;
+            self.ensure_not_revoked(claims.as_ref())
+                .await
+                .with_context(|| format!("refusing to start provider `{provider_id}`"))?;
+        }
 
         if let Some(claims) = claims.clone() {
             self.store_claims(Claims::Provider(claims))
@@ -1705,6 +2072,16 @@ impl Host {
                     &annotations,
                 )
                 .await?;
+            if let Some(schema) = claims
+                .as_ref()
+                .and_then(|claims| claims.metadata.as_ref()?.config_schema.as_ref())
+            {
+                // Keys required by the schema may be provided by the config of links instead
+                ensure_config_matches_schema(
+                    provider_id,
+                    validate_partial_config(schema, &host_data.config),
+                )?;
+            }
             let config_bundle = Arc::new(RwLock::new(config_bundle));
             // Used by provider child tasks (health check, config watch, process restarter) to
             // know when to shutdown.
@@ -1758,22 +2135,20 @@ impl Host {
                 provider_id, "provider started"
             );
             self.event_publisher
-                .publish_event(
-                    "provider_started",
-                    crate::event::provider_started(
-                        claims.as_ref(),
-                        &annotations,
-                        host_id,
-                        &provider_ref,
-                        provider_id,
-                    ),
-                )
+                .publish_event(crate::event::provider_started(
+                    claims.as_ref(),
+                    &annotations,
+                    host_id,
+                    &provider_ref,
+                    provider_id,
+                ))
                 .await?;
 
             // Add the provider
             entry.insert(Provider {
                 tasks,
                 annotations,
+                config: config_bundle,
                 claims_token,
                 image_ref: provider_ref.as_ref().to_string(),
                 xkey,
@@ -1801,6 +2176,58 @@ impl Host {
         <Self as ControlInterfaceServer>::handle_inventory(self).await
     }
 
+    #[instrument(level = "debug", skip_all)]
+    pub(crate) async fn handle_cached_artifacts(
+        &self,
+    ) -> anyhow::Result<CtlResponse<Vec<CachedArtifact>>> {
+        <Self as ControlInterfaceServer>::handle_cached_artifacts(self).await
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    pub(crate) async fn handle_prune_cache(
+        &self,
+        payload: impl AsRef<[u8]>,
+        transport_host_id: &str,
+    ) -> anyhow::Result<CtlResponse<Vec<CachedArtifact>>> {
+        // Allow an empty payload to be used for pruning the cache
+        let cmd = if payload.as_ref().is_empty() {
+            PruneCacheCommand::default()
+        } else {
+            serde_json::from_slice::<PruneCacheCommand>(payload.as_ref())
+                .context("failed to deserialize prune cache command")?
+        };
+        let host_id = cmd.host_id();
+        anyhow::ensure!(
+            host_id.is_empty() || host_id == transport_host_id,
+            "invalid host_id [{host_id}]"
+        );
+        anyhow::ensure!(
+            transport_host_id == self.host_key.public_key(),
+            "invalid host_id [{transport_host_id}]"
+        );
+        <Self as ControlInterfaceServer>::handle_prune_cache(self, cmd).await
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    pub(crate) async fn handle_pull_artifacts(
+        &self,
+        payload: impl AsRef<[u8]>,
+        transport_host_id: &str,
+    ) -> anyhow::Result<CtlResponse<Vec<CachedArtifact>>> {
+        let cmd = serde_json::from_slice::<PullArtifactsCommand>(payload.as_ref())
+            .context("failed to deserialize pull artifacts command")?;
+        let host_id = cmd.host_id();
+        anyhow::ensure!(
+            host_id.is_empty() || host_id == transport_host_id,
+            "invalid host_id [{host_id}]"
+        );
+        anyhow::ensure!(
+            transport_host_id == self.host_key.public_key(),
+            "invalid host_id [{transport_host_id}]"
+        );
+        <Self as ControlInterfaceServer>::handle_pull_artifacts(self, cmd).await
+    }
+
     #[instrument(level = "trace", skip_all)]
     pub(crate) async fn handle_claims(
         &self,
@@ -1910,6 +2337,10 @@ impl Host {
     // Thankfully, in a lattice where there are no "older" providers running, these publishes
     // will return immediately as there will be no subscribers on those topics.
     async fn put_backwards_compat_provider_link(&self, link: &Link) -> anyhow::Result<()> {
+        // Builtin providers of embedded hosts always receive links put to their xkey
+        if self.is_embedded() {
+            return Ok(());
+        }
         // Only attempt to publish the backwards-compatible provider link definition if the link
         // does not contain any secret values.
         let source_config_contains_secret = link
@@ -1954,9 +2385,15 @@ impl Host {
         Ok(())
     }
 
-    /// Publishes a link to a provider running on this host to handle.
+    /// Publishes a link to a provider running on this host to handle, unless the config of the
+    /// link does not match the config schema of the provider.
     #[instrument(level = "debug", skip_all)]
-    async fn put_provider_link(&self, provider: &Provider, link: &Link) -> anyhow::Result<()> {
+    async fn put_provider_link(
+        &self,
+        provider_id: &str,
+        provider: &Provider,
+        link: &Link,
+    ) -> anyhow::Result<()> {
         let provider_link = self
             .resolve_link_config(
                 link.clone(),
@@ -1966,6 +2403,22 @@ impl Host {
             )
             .await
             .context("failed to resolve link config and secrets")?;
+        // Links are validated where they are consumed, since only the hosts running a provider
+        // know its config schema
+        if let Some(schema) = provider_config_schema(provider) {
+            let link_config = if link.source_id() == provider_id {
+                &provider_link.source_config
+            } else {
+                &provider_link.target_config
+            };
+            let provider_config = provider.config.read().await;
+            ensure_link_config_matches_schema(
+                provider_id,
+                schema,
+                &*provider_config.get_config().await,
+                link_config,
+            )?;
+        }
 
         self.provider_manager
             .put_link(&provider_link, &provider.xkey.public_key())
@@ -2073,6 +2526,39 @@ impl Host {
         Ok(())
     }
 
+    /// Validates the named configuration `config_names` of a link of the provider `provider_id`,
+    /// if it is running on this host, against the config schema of the provider, excluding
+    /// secrets.
+    ///
+    /// Providers running on other hosts validate the link once they receive it.
+    async fn validate_link_config_schema(
+        &self,
+        provider_id: &str,
+        config_names: &[String],
+    ) -> anyhow::Result<()> {
+        let providers = self.providers.read().await;
+        let Some((provider, schema)) = providers
+            .get(provider_id)
+            .and_then(|provider| Some((provider, provider_config_schema(provider)?)))
+        else {
+            return Ok(());
+        };
+        let config_names = config_names
+            .iter()
+            .filter(|name| !name.starts_with(SECRET_PREFIX))
+            .cloned()
+            .collect();
+        let bundle = self
+            .config_generator
+            .generate(config_names)
+            .await
+            .context("Unable to fetch requested config")?;
+        let provider_config = provider.config.read().await;
+        let provider_config = provider_config.get_config().await;
+        let link_config = bundle.get_config().await;
+        ensure_link_config_matches_schema(provider_id, schema, &provider_config, &link_config)
+    }
+
     /// Transform a [`wasmcloud_control_interface::Link`] into a [`wasmcloud_core::InterfaceLinkDefinition`]
     /// by fetching the source and target configurations and secrets, and encrypting the secrets.
     async fn resolve_link_config(
@@ -2159,6 +2645,79 @@ impl Host {
     }
 }
 
+/// Returns the config schema embedded in the claims of `provider`, if it has one
+fn provider_config_schema(provider: &Provider) -> Option<&serde_json::Value> {
+    let token = provider.claims_token.as_ref()?;
+    token.claims.metadata.as_ref()?.config_schema.as_ref()
+}
+
+/// Validates the config of a link of the provider `provider_id`, merged over the config of the
+/// provider, against the config `schema` of the provider, since keys required by the schema may
+/// be provided by either
+pub(crate) fn ensure_link_config_matches_schema(
+    provider_id: &str,
+    schema: &serde_json::Value,
+    provider_config: &HashMap<String, String>,
+    link_config: &HashMap<String, String>,
+) -> anyhow::Result<()> {
+    let mut config = provider_config.clone();
+    config.extend(link_config.iter().map(|(k, v)| (k.clone(), v.clone())));
+    ensure_config_matches_schema(provider_id, validate_config(schema, &config))
+}
+
+/// Returns an error listing the paths of all `violations` of the config schema of the provider
+/// `provider_id`
+fn ensure_config_matches_schema(
+    provider_id: &str,
+    violations: anyhow::Result<Vec<ConfigViolation>>,
+) -> anyhow::Result<()> {
+    let violations = violations
+        .with_context(|| format!("failed to validate config of provider `{provider_id}`"))?;
+    if !violations.is_empty() {
+        let violations = violations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        bail!("config does not match the config schema of provider `{provider_id}`: {violations}");
+    }
+    Ok(())
+}
+
+/// Resolve the [`Limits`] of a component from `component_limits` and the
+/// [`PREWARM_INSTANCES_ANNOTATION`] in `annotations`. The number of pre-instantiated instances is
+/// bounded by `max_instances`.
+fn resolve_component_limits(
+    component_limits: Option<&HashMap<String, String>>,
+    annotations: &Annotations,
+    max_instances: u32,
+) -> anyhow::Result<Option<Limits>> {
+    let mut limits = from_string_map(component_limits);
+    let prewarm_instances = match (
+        limits.and_then(|limits| limits.prewarm_instances),
+        annotations.get(PREWARM_INSTANCES_ANNOTATION),
+    ) {
+        (Some(prewarm_instances), _) => prewarm_instances,
+        (None, Some(prewarm_instances)) => prewarm_instances.parse().with_context(|| {
+            format!("invalid `{PREWARM_INSTANCES_ANNOTATION}` annotation `{prewarm_instances}`")
+        })?,
+        (None, None) => return Ok(limits),
+    };
+    let prewarm_instances = prewarm_instances.min(max_instances as usize);
+    match &mut limits {
+        Some(limits) => limits.prewarm_instances = Some(prewarm_instances),
+        None => {
+            limits = Some(Limits {
+                max_memory_limit: None,
+                max_execution_time: None,
+                max_fuel: None,
+                prewarm_instances: Some(prewarm_instances),
+            })
+        }
+    }
+    Ok(limits)
+}
+
 /// Helper function to transform a Vec of [`Link`]s into the structure components expect to be able
 /// to quickly look up the desired target for a given interface
 ///
@@ -2188,6 +2747,77 @@ fn component_import_links(links: &[Link]) -> HashMap<Box<str>, HashMap<Box<str>,
     m
 }
 
+/// Helper function to transform a Vec of [`Link`]s into the structure components use to look up
+/// the [`InvocationPolicy`] of the link used for a given interface
+///
+/// # Arguments
+/// - links: A Vec of [`Link`]s
+///
+/// # Returns
+/// - A `HashMap` in the form of `link_name` -> `instance` -> policy, containing only links with a
+///   policy
+fn component_link_policies(
+    links: &[Link],
+) -> HashMap<Box<str>, HashMap<Box<str>, InvocationPolicy>> {
+    let mut m = HashMap::new();
+    for link in links {
+        let Some(policy) = link.invocation_policy() else {
+            continue;
+        };
+        let instances: &mut HashMap<Box<str>, InvocationPolicy> = m
+            .entry(link.name().to_string().into_boxed_str())
+            .or_default();
+        for interface in link.interfaces() {
+            instances.insert(
+                format!(
+                    "{}:{}/{interface}",
+                    link.wit_namespace(),
+                    link.wit_package(),
+                )
+                .into_boxed_str(),
+                policy.clone(),
+            );
+        }
+    }
+    m
+}
+
+/// Helper function to transform a Vec of [`Link`]s into the structure components use to look up
+/// the [`WeightedTargets`] of the link used for a given interface
+///
+/// # Arguments
+/// - links: A Vec of [`Link`]s
+///
+/// # Returns
+/// - A `HashMap` in the form of `link_name` -> `instance` -> weighted targets, containing only
+///   links with weighted targets
+fn component_weighted_targets(
+    links: &[Link],
+) -> HashMap<Box<str>, HashMap<Box<str>, Arc<WeightedTargets>>> {
+    let mut m = HashMap::new();
+    for link in links {
+        let Some(targets) = WeightedTargets::new(link) else {
+            continue;
+        };
+        let targets = Arc::new(targets);
+        let instances: &mut HashMap<Box<str>, Arc<WeightedTargets>> = m
+            .entry(link.name().to_string().into_boxed_str())
+            .or_default();
+        for interface in link.interfaces() {
+            instances.insert(
+                format!(
+                    "{}:{}/{interface}",
+                    link.wit_namespace(),
+                    link.wit_package(),
+                )
+                .into_boxed_str(),
+                Arc::clone(&targets),
+            );
+        }
+    }
+    m
+}
+
 fn human_friendly_uptime(uptime: Duration) -> String {
     // strip sub-seconds, then convert to human-friendly format
     humantime::format_duration(
@@ -2354,4 +2984,116 @@ mod test {
 
         assert_eq!(links_map, expected_result);
     }
+
+    #[test]
+    fn resolves_prewarm_instances() -> anyhow::Result<()> {
+        use std::collections::{BTreeMap, HashMap};
+
+        let annotations = BTreeMap::from([(
+            super::PREWARM_INSTANCES_ANNOTATION.to_string(),
+            "4".to_string(),
+        )]);
+        assert_eq!(
+            super::resolve_component_limits(None, &BTreeMap::new(), 10)?,
+            None
+        );
+        let limits = super::resolve_component_limits(None, &annotations, 10)?;
+        assert_eq!(limits.and_then(|l| l.prewarm_instances), Some(4));
+        // Pre-warmed instances are bounded by the maximum number of instances
+        let limits = super::resolve_component_limits(None, &annotations, 2)?;
+        assert_eq!(limits.and_then(|l| l.prewarm_instances), Some(2));
+        // Limits take precedence over annotations
+        let component_limits = HashMap::from([
+            ("prewarm_instances".to_string(), "1".to_string()),
+            ("max_fuel".to_string(), "1000".to_string()),
+        ]);
+        let limits = super::resolve_component_limits(Some(&component_limits), &annotations, 10)?;
+        assert_eq!(limits.and_then(|l| l.prewarm_instances), Some(1));
+        assert_eq!(limits.and_then(|l| l.max_fuel), Some(1000));
+
+        let annotations = BTreeMap::from([(
+            super::PREWARM_INSTANCES_ANNOTATION.to_string(),
+            "many".to_string(),
+        )]);
+        assert!(super::resolve_component_limits(None, &annotations, 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reports_config_schema_violations() {
+        use std::collections::HashMap;
+
+        use wasmcloud_core::config_schema::{validate_config, validate_partial_config};
+
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "port": { "pattern": "^[0-9]+$" } },
+            "required": ["address"],
+        });
+        let config = HashMap::from([("address".to_string(), "localhost".to_string())]);
+        assert!(
+            super::ensure_config_matches_schema("provider", validate_config(&schema, &config))
+                .is_ok()
+        );
+
+        let config = HashMap::from([("port".to_string(), "http".to_string())]);
+        assert!(super::ensure_config_matches_schema(
+            "provider",
+            validate_config(&schema, &HashMap::default())
+        )
+        .is_err());
+        assert!(super::ensure_config_matches_schema(
+            "provider",
+            validate_partial_config(&schema, &HashMap::default())
+        )
+        .is_ok());
+        let err =
+            super::ensure_config_matches_schema("provider", validate_config(&schema, &config))
+                .expect_err("config should not match schema")
+                .to_string();
+        assert!(err.starts_with("config does not match the config schema of provider `provider`"));
+        assert!(err.contains("`/`: "), "{err}");
+        assert!(err.contains("`/port`: "), "{err}");
+    }
+
+    #[test]
+    fn validates_link_config_merged_over_provider_config() {
+        use std::collections::HashMap;
+
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "port": { "pattern": "^[0-9]+$" } },
+            "required": ["address", "port"],
+        });
+        let provider_config = HashMap::from([("address".to_string(), "localhost".to_string())]);
+        let link_config = HashMap::from([("port".to_string(), "8080".to_string())]);
+        // Required keys may be provided by either the provider or the link
+        assert!(super::ensure_link_config_matches_schema(
+            "provider",
+            &schema,
+            &provider_config,
+            &link_config
+        )
+        .is_ok());
+        assert!(super::ensure_link_config_matches_schema(
+            "provider",
+            &schema,
+            &HashMap::default(),
+            &link_config
+        )
+        .is_err());
+        // Link config takes precedence over the config of the provider
+        let provider_config = HashMap::from([
+            ("address".to_string(), "localhost".to_string()),
+            ("port".to_string(), "8080".to_string()),
+        ]);
+        let link_config = HashMap::from([("port".to_string(), "http".to_string())]);
+        assert!(super::ensure_link_config_matches_schema(
+            "provider",
+            &schema,
+            &provider_config,
+            &link_config
+        )
+        .is_err());
+    }
 }
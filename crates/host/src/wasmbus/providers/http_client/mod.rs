@@ -1,10 +1,11 @@
+use core::future::Future;
+
 use anyhow::Context as _;
 use futures::StreamExt as _;
 use nkeys::XKey;
 use rustls_pemfile;
 use std::io::BufReader;
 use std::sync::Arc;
-use tokio::select;
 use tokio::sync::broadcast;
 use tokio::task::JoinSet;
 use tracing::{debug, error, info, warn};
@@ -13,10 +14,13 @@ use webpki_roots;
 use wasmcloud_core::HostData;
 use wasmcloud_provider_sdk::{
     provider::{handle_provider_commands, receive_link_for_provider, ProviderCommandReceivers},
-    ProviderConnection,
+    Context, ProviderConnection,
 };
 use wrpc_interface_http::ServeHttp;
 
+use crate::wasmbus::local::LocalServer;
+use crate::wasmbus::providers::local::ProviderServer;
+
 // Re-export the main HTTP client provider implementation
 pub(crate) mod provider;
 
@@ -108,12 +112,33 @@ impl crate::wasmbus::Host {
         debug!("Creating HTTP client provider instance");
         let provider = provider::HttpClientProvider::new(tls, DEFAULT_IDLE_TIMEOUT).await?;
 
+        if let Some(local_providers) = &self.local_providers {
+            debug!("Serving provider exports in-process");
+            let server = Arc::new(LocalServer::default());
+            let invocations =
+                serve_outgoing_handler(&ProviderServer(Arc::clone(&server)), provider.clone())
+                    .await?;
+            let mut tasks = self
+                .start_local_provider(
+                    local_providers,
+                    provider,
+                    provider_id,
+                    provider_xkey,
+                    host_data.link_definitions,
+                    Some(server),
+                )
+                .await;
+            tasks.spawn(invocations);
+            info!("HTTP client provider started successfully");
+            return Ok(tasks);
+        }
+
         let mut tasks = JoinSet::new();
 
         debug!("Setting up provider command receivers");
         let (quit_tx, quit_rx) = broadcast::channel(1);
         let commands = ProviderCommandReceivers::new(
-            Arc::clone(&self.rpc_nats),
+            Arc::clone(self.rpc_nats()?),
             &quit_tx,
             &self.host_config.lattice,
             provider_id,
@@ -124,7 +149,7 @@ impl crate::wasmbus::Host {
 
         debug!("Creating provider connection");
         let conn = ProviderConnection::new(
-            Arc::clone(&self.rpc_nats),
+            Arc::clone(self.rpc_nats()?),
             Arc::from(provider_id),
             Arc::clone(&self.host_config.lattice),
             host_id.to_string(),
@@ -186,38 +211,9 @@ impl crate::wasmbus::Host {
                     return;
                 }
             };
-
-            let [(_, _, mut invocations)] = match wrpc_interface_http::bindings::exports::wrpc::http::outgoing_handler::serve_interface(
-                &wrpc,
-                ServeHttp(provider_clone.clone()),
-            ).await {
-                Ok(interfaces) => interfaces,
-                Err(err) => {
-                    error!("Failed to serve exports: {}", err);
-                    return;
-                }
-            };
-
-            info!("HTTP client provider ready to handle requests");
-            let mut tasks = JoinSet::new();
-
-            loop {
-                select! {
-                    Some(res) = invocations.next() => {
-                        match res {
-                            Ok(fut) => {
-                                tasks.spawn(async move {
-                                    if let Err(err) = fut.await {
-                                        warn!(?err, "failed to serve invocation");
-                                    }
-                                });
-                            },
-                            Err(err) => {
-                                warn!(?err, "failed to accept invocation");
-                            }
-                        }
-                    }
-                }
+            match serve_outgoing_handler(&wrpc, provider_clone).await {
+                Ok(invocations) => invocations.await,
+                Err(err) => error!("Failed to serve exports: {:#}", err),
             }
         });
 
@@ -225,3 +221,39 @@ impl crate::wasmbus::Host {
         Ok(tasks)
     }
 }
+
+/// Serve the `wrpc:http/outgoing-handler` exports of `provider` on `srv`, returning the future
+/// handling the invocations
+async fn serve_outgoing_handler<S>(
+    srv: &S,
+    provider: provider::HttpClientProvider,
+) -> anyhow::Result<impl Future<Output = ()>>
+where
+    S: wrpc_transport::Serve<Context = Option<Context>>,
+{
+    let [(_, _, mut invocations)] =
+        wrpc_interface_http::bindings::exports::wrpc::http::outgoing_handler::serve_interface(
+            srv,
+            ServeHttp(provider),
+        )
+        .await
+        .context("failed to serve exports")?;
+    Ok(async move {
+        info!("HTTP client provider ready to handle requests");
+        let mut tasks = JoinSet::new();
+        while let Some(res) = invocations.next().await {
+            match res {
+                Ok(fut) => {
+                    tasks.spawn(async move {
+                        if let Err(err) = fut.await {
+                            warn!(?err, "failed to serve invocation");
+                        }
+                    });
+                }
+                Err(err) => {
+                    warn!(?err, "failed to accept invocation");
+                }
+            }
+        }
+    })
+}
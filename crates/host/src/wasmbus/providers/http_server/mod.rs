@@ -76,9 +76,48 @@ impl crate::wasmbus::Host {
             Some(other) => bail!("unknown routing_mode: {other}"),
         };
 
+        if let Some(local_providers) = &self.local_providers {
+            let link_definitions = host_data.link_definitions;
+            return Ok(match provider {
+                HttpServerProvider::Address(provider) => {
+                    self.start_local_provider(
+                        local_providers,
+                        provider,
+                        provider_id,
+                        provider_xkey,
+                        link_definitions,
+                        None,
+                    )
+                    .await
+                }
+                HttpServerProvider::Path(provider) => {
+                    self.start_local_provider(
+                        local_providers,
+                        provider,
+                        provider_id,
+                        provider_xkey,
+                        link_definitions,
+                        None,
+                    )
+                    .await
+                }
+                HttpServerProvider::Host(provider) => {
+                    self.start_local_provider(
+                        local_providers,
+                        provider,
+                        provider_id,
+                        provider_xkey,
+                        link_definitions,
+                        None,
+                    )
+                    .await
+                }
+            });
+        }
+
         let (quit_tx, quit_rx) = broadcast::channel(1);
         let commands = ProviderCommandReceivers::new(
-            Arc::clone(&self.rpc_nats),
+            Arc::clone(self.rpc_nats()?),
             &quit_tx,
             &self.host_config.lattice,
             provider_id,
@@ -87,7 +126,7 @@ impl crate::wasmbus::Host {
         )
         .await?;
         let conn = ProviderConnection::new(
-            Arc::clone(&self.rpc_nats),
+            Arc::clone(self.rpc_nats()?),
             Arc::from(provider_id),
             Arc::clone(&self.host_config.lattice),
             host_id.to_string(),
@@ -0,0 +1,296 @@
+//! This module contains the in-process communication with builtin providers, used by hosts
+//! running embedded, without NATS.
+//!
+//! Links are passed to builtin providers directly instead of being published on the lattice.
+//! Builtin providers exporting interfaces, like the HTTP client, serve them on a [`LocalServer`],
+//! which components on the host dispatch invocations to in-process.
+
+use core::time::Duration;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context as _};
+use futures::{Stream, TryStreamExt as _};
+use nkeys::XKey;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio::task::JoinSet;
+use tracing::{error, instrument};
+use wasmcloud_core::InterfaceLinkDefinition;
+use wasmcloud_provider_sdk::provider::{invocation_context, receive_link};
+use wasmcloud_provider_sdk::{Context, Provider};
+use wrpc_transport::frame;
+
+use crate::wasmbus::local::LocalServer;
+use crate::wasmbus::Host;
+
+use super::ProviderManager;
+
+/// Capacity of the channel of commands of a builtin provider
+const PROVIDER_COMMAND_CHANNEL_SIZE: usize = 16;
+
+/// Command passed to a builtin provider
+#[derive(Debug)]
+pub(crate) enum ProviderCommand {
+    PutLink(InterfaceLinkDefinition),
+    DeleteLink(InterfaceLinkDefinition),
+    /// Shut down the provider, acknowledging once done
+    Shutdown(oneshot::Sender<()>),
+}
+
+/// Builtin provider running on the host
+#[derive(Debug)]
+struct LocalProvider {
+    commands: mpsc::Sender<ProviderCommand>,
+    server: Option<Arc<LocalServer>>,
+}
+
+/// [`ProviderManager`] passing links to the builtin providers running on the host in-process
+#[derive(Debug, Default)]
+pub struct LocalProviderManager {
+    /// Builtin providers keyed by both their ID and the public key of their xkey, since links are
+    /// put to the latter and deleted from the former
+    providers: RwLock<HashMap<String, Arc<LocalProvider>>>,
+}
+
+impl LocalProviderManager {
+    /// Register the builtin provider `provider_id`, returning the receiver of its commands. If the
+    /// provider exports any interfaces, they are served on `server`.
+    pub(crate) async fn register(
+        &self,
+        provider_id: &str,
+        provider_xkey: &XKey,
+        server: Option<Arc<LocalServer>>,
+    ) -> mpsc::Receiver<ProviderCommand> {
+        let (tx, rx) = mpsc::channel(PROVIDER_COMMAND_CHANNEL_SIZE);
+        let provider = Arc::new(LocalProvider {
+            commands: tx,
+            server,
+        });
+        let mut providers = self.providers.write().await;
+        providers.insert(provider_id.into(), Arc::clone(&provider));
+        providers.insert(provider_xkey.public_key(), provider);
+        rx
+    }
+
+    /// Deregister the builtin provider `provider_id` and request it to shut down, waiting for at
+    /// most `timeout` for it to do so
+    pub(crate) async fn shutdown(
+        &self,
+        provider_id: &str,
+        timeout: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        let provider = {
+            let mut providers = self.providers.write().await;
+            let Some(provider) = providers.remove(provider_id) else {
+                return Ok(());
+            };
+            providers.retain(|_, p| !Arc::ptr_eq(p, &provider));
+            provider
+        };
+        let (tx, rx) = oneshot::channel();
+        provider
+            .commands
+            .send(ProviderCommand::Shutdown(tx))
+            .await
+            .map_err(|_| anyhow!("builtin provider `{provider_id}` is stopped"))?;
+        if let Some(timeout) = timeout {
+            tokio::time::timeout(timeout, rx)
+                .await
+                .context("timed out waiting for provider to shut down")?
+        } else {
+            rx.await
+        }
+        .context("provider stopped without acknowledging shutdown")
+    }
+
+    /// Returns the server of the exports of the builtin provider `provider_id`, if it is running
+    /// on the host and exports any interfaces
+    pub(crate) async fn server(&self, provider_id: &str) -> Option<Arc<LocalServer>> {
+        self.providers.read().await.get(provider_id)?.server.clone()
+    }
+
+    async fn send(&self, target: &str, cmd: ProviderCommand) -> anyhow::Result<()> {
+        let Some(provider) = self.providers.read().await.get(target).cloned() else {
+            // Providers which are not running receive their links when started
+            return Ok(());
+        };
+        provider
+            .commands
+            .send(cmd)
+            .await
+            .map_err(|_| anyhow!("builtin provider `{target}` is stopped"))
+    }
+}
+
+#[async_trait::async_trait]
+impl ProviderManager for LocalProviderManager {
+    #[instrument(level = "debug", skip(self))]
+    async fn put_link(&self, link: &InterfaceLinkDefinition, target: &str) -> anyhow::Result<()> {
+        self.send(target, ProviderCommand::PutLink(link.clone()))
+            .await
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn delete_link(
+        &self,
+        link: &InterfaceLinkDefinition,
+        target: &str,
+    ) -> anyhow::Result<()> {
+        self.send(target, ProviderCommand::DeleteLink(link.clone()))
+            .await
+    }
+}
+
+impl Host {
+    /// Run the builtin `provider` in-process on an embedded host, passing it `link_definitions`
+    /// and its links put later on. If the provider exports any interfaces, they are served on
+    /// `server`.
+    pub(crate) async fn start_local_provider<P>(
+        &self,
+        local_providers: &LocalProviderManager,
+        provider: P,
+        provider_id: &str,
+        provider_xkey: XKey,
+        link_definitions: Vec<InterfaceLinkDefinition>,
+        server: Option<Arc<LocalServer>>,
+    ) -> JoinSet<()>
+    where
+        P: Provider + Send + 'static,
+    {
+        let commands = local_providers
+            .register(provider_id, &provider_xkey, server)
+            .await;
+        let mut tasks = JoinSet::new();
+        tasks.spawn(handle_provider_commands(
+            provider,
+            Arc::from(provider_id),
+            provider_xkey,
+            Arc::clone(&self.secrets_xkey),
+            link_definitions,
+            commands,
+        ));
+        tasks
+    }
+}
+
+/// Pass `link_definitions` and the links received on `commands` to the builtin `provider`, until
+/// the provider is requested to shut down
+pub(crate) async fn handle_provider_commands<P>(
+    provider: P,
+    provider_id: Arc<str>,
+    provider_xkey: XKey,
+    host_xkey: Arc<XKey>,
+    link_definitions: Vec<InterfaceLinkDefinition>,
+    mut commands: mpsc::Receiver<ProviderCommand>,
+) where
+    P: Provider + Send,
+{
+    for ld in link_definitions {
+        if let Err(err) =
+            receive_link(&provider, &provider_id, &provider_xkey, &host_xkey, &ld).await
+        {
+            error!(?err, "failed to initialize link during provider startup");
+        }
+    }
+    let mut ack = None;
+    while let Some(cmd) = commands.recv().await {
+        match cmd {
+            ProviderCommand::PutLink(ld) => {
+                if let Err(err) =
+                    receive_link(&provider, &provider_id, &provider_xkey, &host_xkey, &ld).await
+                {
+                    error!(?err, "failed to receive link for provider");
+                }
+            }
+            ProviderCommand::DeleteLink(ld) => {
+                let res = if ld.source_id == *provider_id {
+                    provider.delete_link_as_source(&ld).await
+                } else if ld.target == *provider_id {
+                    provider.delete_link_as_target(&ld).await
+                } else {
+                    Ok(())
+                };
+                if let Err(err) = res {
+                    error!(?err, "failed to delete link for provider");
+                }
+            }
+            ProviderCommand::Shutdown(tx) => {
+                ack = Some(tx);
+                break;
+            }
+        }
+    }
+    if let Err(err) = provider.shutdown().await {
+        error!(?err, "failed to shutdown provider");
+    }
+    if let Some(ack) = ack {
+        _ = ack.send(());
+    }
+}
+
+/// [`wrpc_transport::Serve`] implementation serving the exports of a builtin provider on a
+/// [`LocalServer`], passing invocation contexts in the form expected by providers
+pub(crate) struct ProviderServer(pub(crate) Arc<LocalServer>);
+
+impl wrpc_transport::Serve for ProviderServer {
+    type Context = Option<Context>;
+    type Outgoing = frame::Outgoing;
+    type Incoming = frame::Incoming;
+
+    async fn serve(
+        &self,
+        instance: &str,
+        func: &str,
+        paths: impl Into<Arc<[Box<[Option<usize>]>]>> + Send,
+    ) -> anyhow::Result<
+        impl Stream<Item = anyhow::Result<(Self::Context, Self::Outgoing, Self::Incoming)>>
+            + Send
+            + 'static,
+    > {
+        let invocations = self.0.serve(instance, func, paths).await?;
+        Ok(invocations.map_ok(|(cx, tx, rx)| (cx.as_ref().map(invocation_context), tx, rx)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn delivers_links_to_registered_providers() -> anyhow::Result<()> {
+        let manager = LocalProviderManager::default();
+        let xkey = XKey::new();
+        let link = InterfaceLinkDefinition {
+            source_id: "component".into(),
+            target: "provider".into(),
+            ..Default::default()
+        };
+
+        // Links of providers which are not running are ignored
+        manager.put_link(&link, &xkey.public_key()).await?;
+
+        let mut commands = manager.register("provider", &xkey, None).await;
+        manager.put_link(&link, &xkey.public_key()).await?;
+        manager.delete_link(&link, "provider").await?;
+        assert!(matches!(
+            commands.recv().await,
+            Some(ProviderCommand::PutLink(ld)) if ld.source_id == "component"
+        ));
+        assert!(matches!(
+            commands.recv().await,
+            Some(ProviderCommand::DeleteLink(ld)) if ld.target == "provider"
+        ));
+
+        let (shutdown, ()) = tokio::join!(manager.shutdown("provider", None), async {
+            let Some(ProviderCommand::Shutdown(ack)) = commands.recv().await else {
+                panic!("provider was not requested to shut down");
+            };
+            ack.send(()).expect("failed to acknowledge shutdown");
+        });
+        shutdown?;
+        assert!(commands.recv().await.is_none());
+        manager.put_link(&link, &xkey.public_key()).await?;
+        Ok(())
+    }
+}
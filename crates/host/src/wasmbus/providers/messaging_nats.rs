@@ -298,9 +298,31 @@ impl crate::wasmbus::Host {
         let config =
             ConnectionConfig::from_map(&host_data.config).context("failed to parse config")?;
 
+        let provider = Provider {
+            config,
+            components: Arc::clone(&self.components),
+            messaging_links: Arc::clone(&self.messaging_links),
+            subscriptions: Mutex::default(),
+            host_id: Arc::from(host_id.as_str()),
+            lattice_id: Arc::clone(&self.host_config.lattice),
+        };
+        if let Some(local_providers) = &self.local_providers {
+            return Ok(self
+                .start_local_provider(
+                    local_providers,
+                    provider,
+                    provider_id,
+                    provider_xkey,
+                    host_data.link_definitions,
+                    None,
+                )
+                .await);
+        }
+
+        let rpc_nats = self.rpc_nats()?;
         let (quit_tx, quit_rx) = broadcast::channel(1);
         let commands = ProviderCommandReceivers::new(
-            Arc::clone(&self.rpc_nats),
+            Arc::clone(rpc_nats),
             &quit_tx,
             &self.host_config.lattice,
             provider_id,
@@ -309,7 +331,7 @@ impl crate::wasmbus::Host {
         )
         .await?;
         let conn = ProviderConnection::new(
-            Arc::clone(&self.rpc_nats),
+            Arc::clone(rpc_nats),
             Arc::from(provider_id),
             Arc::clone(&self.host_config.lattice),
             host_id.to_string(),
@@ -318,14 +340,6 @@ impl crate::wasmbus::Host {
             Arc::clone(&self.secrets_xkey),
         )
         .context("failed to establish provider connection")?;
-        let provider = Provider {
-            config,
-            components: Arc::clone(&self.components),
-            messaging_links: Arc::clone(&self.messaging_links),
-            subscriptions: Mutex::default(),
-            host_id: Arc::from(host_id),
-            lattice_id: Arc::clone(&self.host_config.lattice),
-        };
         for ld in host_data.link_definitions {
             if let Err(e) = receive_link_for_provider(&provider, &conn, ld).await {
                 error!(
@@ -33,13 +33,14 @@ use wasmcloud_tracing::context::TraceContextInjector;
 use crate::event::EventPublisher;
 use crate::jwt;
 use crate::wasmbus::injector_to_headers;
-use crate::wasmbus::{config::ConfigBundle, Annotations};
+use crate::wasmbus::{config::ConfigBundle, ensure_link_config_matches_schema, Annotations};
 
 use super::Host;
 
 // Add internal provider modules to the host
 mod http_client;
 mod http_server;
+pub(crate) mod local;
 mod messaging_nats;
 
 /// A trait for sending and receiving messages to/from a provider
@@ -67,6 +68,9 @@ pub(crate) struct Provider {
     pub(crate) claims_token: Option<jwt::Token<jwt::CapabilityProvider>>,
     pub(crate) xkey: XKey,
     pub(crate) annotations: Annotations,
+    /// Config of the provider, which links of the provider are validated against together with
+    /// their own config
+    pub(crate) config: Arc<RwLock<ConfigBundle>>,
     /// Shutdown signal for the provider, set to `false` initially. When set to `true`, the
     /// tasks running the provider, health check, and config watcher will stop.
     pub(crate) shutdown: Arc<AtomicBool>,
@@ -117,7 +121,34 @@ impl Host {
                         )
                         .await
                     {
-                        Ok(provider_link) => Some(provider_link),
+                        Ok(provider_link) => {
+                            let Some(schema) = claims_token.as_ref().and_then(|token| {
+                                token.claims.metadata.as_ref()?.config_schema.as_ref()
+                            }) else {
+                                return Some(provider_link);
+                            };
+                            let link_config = if link.source_id() == provider_id {
+                                &provider_link.source_config
+                            } else {
+                                &provider_link.target_config
+                            };
+                            if let Err(e) = ensure_link_config_matches_schema(
+                                provider_id,
+                                schema,
+                                &*config.get_config().await,
+                                link_config,
+                            ) {
+                                error!(
+                                    error = ?e,
+                                    provider_id,
+                                    source_id = link.source_id(),
+                                    target = link.target(),
+                                    "link config does not match provider config schema, skipping link"
+                                );
+                                return None;
+                            }
+                            Some(provider_link)
+                        }
                         Err(e) => {
                             error!(
                                 error = ?e,
@@ -232,6 +263,10 @@ impl Host {
         annotations: BTreeMap<String, String>,
         shutdown: Arc<AtomicBool>,
     ) -> anyhow::Result<JoinSet<()>> {
+        let rpc_nats = Arc::clone(
+            self.rpc_nats()
+                .context("only builtin providers can be started on embedded hosts")?,
+        );
         trace!("spawn provider process");
 
         let mut tasks = JoinSet::new();
@@ -256,7 +291,7 @@ impl Host {
 
         // Spawn a task to check the health of the provider every 30 seconds
         tasks.spawn(check_health(
-            Arc::clone(&self.rpc_nats),
+            rpc_nats,
             self.event_publisher.clone(),
             Arc::clone(&self.host_config.lattice),
             self.host_key.public_key(),
@@ -289,6 +324,7 @@ impl Host {
                 .await
                 .context("failed to configure binary provider command")?,
         ));
+        let rpc_nats = Arc::clone(self.rpc_nats()?);
         let lattice = Arc::clone(&self.host_config.lattice);
         Ok(async move {
             // Use a JoinSet to manage the config watcher task so that
@@ -296,7 +332,7 @@ impl Host {
             // when a provider restarts
             let mut config_task = JoinSet::new();
             config_task.spawn(watch_config(
-                Arc::clone(&self.rpc_nats),
+                Arc::clone(&rpc_nats),
                 Arc::clone(&config_bundle),
                 Arc::clone(&lattice),
                 provider_id.clone(),
@@ -357,7 +393,7 @@ impl Host {
                         // Stop the config watcher and start a new one with the new config bundle
                         config_task.abort_all();
                         config_task.spawn(watch_config(
-                            Arc::clone(&self.rpc_nats),
+                            Arc::clone(&rpc_nats),
                             new_config_bundle,
                             Arc::clone(&lattice),
                             provider_id.clone(),
@@ -479,10 +515,9 @@ fn check_health(
                         trace!(?provider_id, "provider health check succeeded");
                         previous_healthy = true;
                         if let Err(e) = event_publisher
-                            .publish_event(
-                                "health_check_passed",
+                            .publish_event(crate::event::WasmbusEvent::HealthCheckPassed(
                                 crate::event::provider_health_check(&host_id, &provider_id),
-                            )
+                            ))
                             .await
                         {
                             warn!(
@@ -496,10 +531,9 @@ fn check_health(
                         trace!(?provider_id, "provider health check failed");
                         previous_healthy = false;
                         if let Err(e) = event_publisher
-                            .publish_event(
-                                "health_check_failed",
+                            .publish_event(crate::event::WasmbusEvent::HealthCheckFailed(
                                 crate::event::provider_health_check(&host_id, &provider_id),
-                            )
+                            ))
                             .await
                         {
                             warn!(
@@ -512,10 +546,9 @@ fn check_health(
                     // If the provider health status didn't change, we simply publish a health check status event
                     (Ok(_), _) => {
                         if let Err(e) = event_publisher
-                            .publish_event(
-                                "health_check_status",
+                            .publish_event(crate::event::WasmbusEvent::HealthCheckStatus(
                                 crate::event::provider_health_check(&host_id, &provider_id),
-                            )
+                            ))
                             .await
                         {
                             warn!(
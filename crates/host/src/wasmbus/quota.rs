@@ -0,0 +1,449 @@
+//! This module contains resource quotas of lattice tenants.
+//!
+//! Components and providers belong to the tenant named by their [`TENANT_ANNOTATION`]. Hosts
+//! shared between tenants may be configured with a [`TenantQuota`] per tenant, capping the total
+//! number of component instances, the linear memory those instances may allocate and the number
+//! of providers of the tenant on the host. Quotas are enforced when scaling components and
+//! starting providers.
+//!
+//! Resources of components being scaled and providers being started are reserved by the quota
+//! check, so that concurrent requests cannot exceed the quota together. The reservations are
+//! released once the component or provider is running, or failed to start.
+
+use core::future::Future;
+use core::str::FromStr;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::{bail, Context as _};
+use wasmcloud_control_interface::TenantQuotaUsage;
+
+use super::{Annotations, Host, Limits};
+
+/// Annotation naming the tenant a component or provider belongs to
+pub const TENANT_ANNOTATION: &str = "wasmcloud.dev/tenant";
+
+/// Resource quota of a tenant on a host. Resources without a maximum are not limited.
+///
+/// Parsed from a comma-separated list of `resource=maximum` pairs, e.g.
+/// `instances=10,memory=104857600,providers=2`, where `memory` is specified in bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TenantQuota {
+    /// Maximum total number of instances of the components of the tenant
+    pub max_instances: Option<u64>,
+    /// Maximum total linear memory in bytes the component instances of the tenant may allocate
+    pub max_memory_bytes: Option<u64>,
+    /// Maximum number of providers of the tenant
+    pub max_providers: Option<u64>,
+}
+
+impl FromStr for TenantQuota {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut quota = Self::default();
+        for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let Some((resource, max)) = pair.split_once('=') else {
+                bail!("invalid quota `{pair}`, expected `resource=maximum`");
+            };
+            let max = max
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid maximum in quota `{pair}`"))?;
+            match resource.trim() {
+                "instances" => quota.max_instances = Some(max),
+                "memory" => quota.max_memory_bytes = Some(max),
+                "providers" => quota.max_providers = Some(max),
+                resource => bail!(
+                    "unknown quota resource `{resource}`, expected `instances`, `memory` or `providers`"
+                ),
+            }
+        }
+        Ok(quota)
+    }
+}
+
+/// Resources used by a tenant on the host
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Usage {
+    pub(crate) instances: u64,
+    pub(crate) memory_bytes: u64,
+    pub(crate) providers: u64,
+}
+
+impl Usage {
+    fn add(&mut self, other: &Self) {
+        self.instances = self.instances.saturating_add(other.instances);
+        self.memory_bytes = self.memory_bytes.saturating_add(other.memory_bytes);
+        self.providers = self.providers.saturating_add(other.providers);
+    }
+}
+
+/// Resources reserved for a component being scaled or a provider being started
+#[derive(Debug)]
+struct Reservation {
+    tenant: String,
+    /// ID of the component being scaled, `None` for providers
+    component_id: Option<String>,
+    usage: Usage,
+}
+
+/// Resources reserved by quota checks, which are not yet accounted for by the components and
+/// providers running on the host
+#[derive(Debug, Default)]
+pub(crate) struct Reservations {
+    /// Serializes quota checks, so that concurrent requests cannot both pass a check
+    check: tokio::sync::Mutex<()>,
+    next_id: AtomicU64,
+    pending: Arc<Mutex<HashMap<u64, Reservation>>>,
+}
+
+/// Guard releasing reserved resources when dropped
+#[derive(Debug)]
+pub(crate) struct QuotaReservation {
+    id: u64,
+    pending: Arc<Mutex<HashMap<u64, Reservation>>>,
+}
+
+impl Drop for QuotaReservation {
+    fn drop(&mut self) {
+        self.pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.id);
+    }
+}
+
+impl Reservations {
+    /// Reserves `requested` resources for `tenant`, if the resources used by the tenant as
+    /// returned by `usage`, the resources already reserved and `requested` do not exceed `quota`.
+    /// Resources reserved for the component with `component_id` are replaced by the new
+    /// reservation, since they are replaced once the component is scaled.
+    ///
+    /// Otherwise, returns an error message.
+    async fn reserve(
+        &self,
+        tenant: &str,
+        quota: &TenantQuota,
+        component_id: Option<&str>,
+        requested: Usage,
+        usage: impl Future<Output = BTreeMap<String, Usage>>,
+    ) -> Result<QuotaReservation, String> {
+        let _check = self.check.lock().await;
+        // Reservations are read before the usage, so that a component or provider, which starts
+        // running and releases its reservation in between, is not missed
+        let mut total = Usage::default();
+        for reservation in self
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+        {
+            if reservation.tenant == tenant
+                && (component_id.is_none() || reservation.component_id.as_deref() != component_id)
+            {
+                total.add(&reservation.usage);
+            }
+        }
+        total.add(&usage.await.remove(tenant).unwrap_or_default());
+        total.add(&requested);
+        if let Some(exceeded) = quota.exceeded_by(&total) {
+            return Err(format!("quota of tenant `{tenant}` exceeded: {exceeded}"));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                id,
+                Reservation {
+                    tenant: tenant.into(),
+                    component_id: component_id.map(Into::into),
+                    usage: requested,
+                },
+            );
+        Ok(QuotaReservation {
+            id,
+            pending: Arc::clone(&self.pending),
+        })
+    }
+}
+
+/// Returns the tenant a component or provider with `annotations` belongs to, if any
+pub(crate) fn tenant(annotations: &Annotations) -> Option<&str> {
+    annotations.get(TENANT_ANNOTATION).map(String::as_str)
+}
+
+impl TenantQuota {
+    /// Returns a description of the first resource of `usage` exceeding the quota, if any
+    pub(crate) fn exceeded_by(&self, usage: &Usage) -> Option<String> {
+        [
+            ("instances", usage.instances, self.max_instances),
+            ("memory bytes", usage.memory_bytes, self.max_memory_bytes),
+            ("providers", usage.providers, self.max_providers),
+        ]
+        .into_iter()
+        .find_map(|(resource, used, max)| {
+            max.filter(|max| used > *max)
+                .map(|max| format!("{used} {resource} requested, at most {max} allowed"))
+        })
+    }
+}
+
+impl Host {
+    /// Returns the maximum linear memory in bytes a single instance of a component with `limits`
+    /// may allocate
+    fn instance_memory(&self, limits: Option<&Limits>) -> u64 {
+        limits
+            .and_then(|limits| limits.max_memory_limit)
+            .map_or(u64::from(self.host_config.max_linear_memory), |limit| {
+                limit as u64
+            })
+    }
+
+    /// Returns the resources used by each tenant on the host, excluding the component with
+    /// `exclude_component_id`
+    async fn tenant_usage(&self, exclude_component_id: Option<&str>) -> BTreeMap<String, Usage> {
+        let mut usage = BTreeMap::<String, Usage>::new();
+        for (id, component) in self.components.read().await.iter() {
+            if Some(id.as_str()) == exclude_component_id {
+                continue;
+            }
+            if let Some(tenant) = tenant(&component.annotations) {
+                let instances = component.max_instances.get() as u64;
+                let usage = usage.entry(tenant.into()).or_default();
+                usage.instances = usage.instances.saturating_add(instances);
+                usage.memory_bytes = usage.memory_bytes.saturating_add(
+                    instances.saturating_mul(self.instance_memory(component.limits.as_ref())),
+                );
+            }
+        }
+        for provider in self.providers.read().await.values() {
+            if let Some(tenant) = tenant(&provider.annotations) {
+                usage.entry(tenant.into()).or_default().providers += 1;
+            }
+        }
+        usage
+    }
+
+    /// Reserves the resources required to scale the component with `component_id` to
+    /// `max_instances` instances with `limits`, returning an error message if this would exceed
+    /// the quota of its tenant. The reservation must be held until the component is scaled.
+    pub(crate) async fn reserve_component_quota(
+        &self,
+        component_id: &str,
+        annotations: &Annotations,
+        max_instances: u32,
+        limits: Option<&Limits>,
+    ) -> Result<Option<QuotaReservation>, String> {
+        let Some(tenant) = tenant(annotations) else {
+            return Ok(None);
+        };
+        let Some(quota) = self.host_config.tenant_quotas.get(tenant) else {
+            return Ok(None);
+        };
+        let instances = u64::from(max_instances);
+        let requested = Usage {
+            instances,
+            memory_bytes: instances.saturating_mul(self.instance_memory(limits)),
+            providers: 0,
+        };
+        self.quota_reservations
+            .reserve(
+                tenant,
+                quota,
+                Some(component_id),
+                requested,
+                self.tenant_usage(Some(component_id)),
+            )
+            .await
+            .map(Some)
+    }
+
+    /// Reserves the resources required to start a provider with `annotations`, returning an
+    /// error message if this would exceed the quota of its tenant. The reservation must be held
+    /// until the provider is started.
+    pub(crate) async fn reserve_provider_quota(
+        &self,
+        annotations: &Annotations,
+    ) -> Result<Option<QuotaReservation>, String> {
+        let Some(tenant) = tenant(annotations) else {
+            return Ok(None);
+        };
+        let Some(quota) = self.host_config.tenant_quotas.get(tenant) else {
+            return Ok(None);
+        };
+        let requested = Usage {
+            providers: 1,
+            ..Usage::default()
+        };
+        self.quota_reservations
+            .reserve(tenant, quota, None, requested, self.tenant_usage(None))
+            .await
+            .map(Some)
+    }
+
+    /// Returns the resource usage and quota of each tenant with a quota or resources on the host
+    pub(crate) async fn tenant_quota_usage(&self) -> BTreeMap<String, TenantQuotaUsage> {
+        let mut usage = self.tenant_usage(None).await;
+        for tenant in self.host_config.tenant_quotas.keys() {
+            usage.entry(tenant.clone()).or_default();
+        }
+        usage
+            .into_iter()
+            .map(|(tenant, usage)| {
+                let mut quota_usage =
+                    TenantQuotaUsage::new(usage.instances, usage.memory_bytes, usage.providers);
+                if let Some(quota) = self.host_config.tenant_quotas.get(&tenant) {
+                    quota_usage = quota_usage.with_quota(
+                        quota.max_instances,
+                        quota.max_memory_bytes,
+                        quota.max_providers,
+                    );
+                }
+                (tenant, quota_usage)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_tenant_quota() {
+        assert_eq!(
+            "instances=10, memory=1048576,providers=2"
+                .parse::<TenantQuota>()
+                .expect("failed to parse quota"),
+            TenantQuota {
+                max_instances: Some(10),
+                max_memory_bytes: Some(1_048_576),
+                max_providers: Some(2),
+            }
+        );
+        assert_eq!(
+            "providers=0"
+                .parse::<TenantQuota>()
+                .expect("failed to parse quota"),
+            TenantQuota {
+                max_providers: Some(0),
+                ..Default::default()
+            }
+        );
+        assert!("cpu=1".parse::<TenantQuota>().is_err());
+        assert!("instances".parse::<TenantQuota>().is_err());
+        assert!("instances=-1".parse::<TenantQuota>().is_err());
+    }
+
+    #[test]
+    fn reports_exceeded_quota() {
+        let quota = TenantQuota {
+            max_instances: Some(4),
+            max_memory_bytes: Some(1024),
+            max_providers: None,
+        };
+        let usage = Usage {
+            instances: 4,
+            memory_bytes: 1024,
+            providers: 100,
+        };
+        assert_eq!(quota.exceeded_by(&usage), None);
+        assert_eq!(
+            quota.exceeded_by(&Usage {
+                memory_bytes: 2048,
+                ..usage
+            }),
+            Some("2048 memory bytes requested, at most 1024 allowed".into())
+        );
+        assert_eq!(
+            quota.exceeded_by(&Usage {
+                instances: 5,
+                ..usage
+            }),
+            Some("5 instances requested, at most 4 allowed".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn reserves_concurrently_scaled_components() {
+        let quota = TenantQuota {
+            max_instances: Some(10),
+            ..Default::default()
+        };
+        // Reading the usage yields, so that concurrent checks interleave
+        let running = || async {
+            tokio::task::yield_now().await;
+            BTreeMap::from([(
+                "tenant".to_string(),
+                Usage {
+                    instances: 2,
+                    ..Default::default()
+                },
+            )])
+        };
+        let instances = |instances| Usage {
+            instances,
+            ..Default::default()
+        };
+        let reservations = Reservations::default();
+
+        // Two components are scaled before either is running
+        let (first, second) = tokio::join!(
+            reservations.reserve("tenant", &quota, Some("a"), instances(5), running()),
+            reservations.reserve("tenant", &quota, Some("b"), instances(5), running()),
+        );
+        let (reservation, err) = match (first, second) {
+            (Ok(reservation), Err(err)) | (Err(err), Ok(reservation)) => (reservation, err),
+            res => panic!("exactly one scale should be rejected, got {res:?}"),
+        };
+        assert_eq!(
+            err,
+            "quota of tenant `tenant` exceeded: 12 instances requested, at most 10 allowed"
+        );
+
+        // Reservations of the same component are replaced, other tenants are not affected
+        let component_id = reservations
+            .pending
+            .lock()
+            .expect("failed to lock reservations")
+            .values()
+            .next()
+            .and_then(|reservation| reservation.component_id.clone())
+            .expect("missing reservation");
+        let replaced = reservations
+            .reserve(
+                "tenant",
+                &quota,
+                Some(&component_id),
+                instances(8),
+                running(),
+            )
+            .await
+            .expect("failed to replace reservation");
+        drop(replaced);
+        reservations
+            .reserve("other", &quota, Some("c"), instances(10), running())
+            .await
+            .expect("failed to reserve for another tenant");
+
+        // Released reservations no longer count towards the quota
+        assert!(reservations
+            .reserve("tenant", &quota, Some("c"), instances(4), running())
+            .await
+            .is_err());
+        drop(reservation);
+        assert!(reservations
+            .reserve("tenant", &quota, Some("c"), instances(8), running())
+            .await
+            .is_ok());
+        assert!(reservations
+            .pending
+            .lock()
+            .expect("failed to lock reservations")
+            .is_empty());
+    }
+}
@@ -0,0 +1,399 @@
+//! This module contains the claims revocation lists of a host.
+//!
+//! A revocation list is a JWT with [`jwt::Revocations`] metadata, issued and signed by an account.
+//! It maps the subjects of components and providers signed by the account to an issued-at cut-off,
+//! revoking all of their claims issued at or before it. Hosts load revocation lists from a file
+//! containing JWTs separated by whitespace and from a named config in the config store, whose
+//! values are JWTs. Both sources are watched for updates, which replace the lists previously
+//! loaded from that source. Revoked components and providers are refused to start, and running
+//! instances are stopped when an update revokes them.
+
+use core::time::Duration;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::SystemTime;
+
+use anyhow::{bail, ensure, Context as _};
+use futures::StreamExt as _;
+use nkeys::{KeyPair, KeyPairType};
+use tokio::sync::RwLock;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{error, info, warn};
+use wascap::jwt;
+use wasmcloud_control_interface::StopProviderCommand;
+
+use super::ctl::ControlInterfaceServer;
+use super::Host;
+use crate::store::StoreManager;
+
+/// The default interval at which the revocation list file is checked for modifications
+pub(crate) const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A source of revocation lists
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum RevocationSource {
+    /// The revocation list file
+    File,
+    /// The revocation list config in the config store
+    Config,
+}
+
+/// Validate and decode the revocation list JWT `token`, which must be signed by the account it
+/// is issued for
+fn parse_revocation_list(token: &str) -> anyhow::Result<jwt::Claims<jwt::Revocations>> {
+    let validation =
+        jwt::validate_token::<jwt::Revocations>(token).context("failed to validate JWT")?;
+    ensure!(validation.signature_valid, "JWT signature is invalid");
+    ensure!(!validation.expired, "JWT has expired");
+    ensure!(!validation.cannot_use_yet, "JWT is not valid yet");
+    let claims = jwt::Claims::<jwt::Revocations>::decode(token).context("failed to decode JWT")?;
+    let issuer = KeyPair::from_public_key(&claims.issuer)
+        .with_context(|| format!("invalid issuer `{}`", claims.issuer))?;
+    ensure!(
+        issuer.key_pair_type() == KeyPairType::Account,
+        "revocation list is not issued by an account key"
+    );
+    ensure!(
+        claims.subject == claims.issuer,
+        "revocation list of `{}` is not issued by the account itself",
+        claims.subject
+    );
+    Ok(claims)
+}
+
+/// Validate and decode the revocation list JWTs `tokens`
+pub(crate) fn parse_revocation_lists(
+    tokens: impl IntoIterator<Item = impl AsRef<str>>,
+) -> anyhow::Result<Vec<jwt::Claims<jwt::Revocations>>> {
+    tokens
+        .into_iter()
+        .enumerate()
+        .map(|(i, token)| {
+            parse_revocation_list(token.as_ref())
+                .with_context(|| format!("invalid revocation list JWT at position {i}"))
+        })
+        .collect()
+}
+
+/// Read and parse the revocation list JWTs in the file at `path`, separated by whitespace
+pub(crate) async fn load_file(
+    path: impl AsRef<Path>,
+) -> anyhow::Result<Vec<jwt::Claims<jwt::Revocations>>> {
+    let path = path.as_ref();
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("failed to read revocation lists from `{}`", path.display()))?;
+    parse_revocation_lists(contents.split_whitespace())
+}
+
+/// Read and parse the revocation list JWTs in the values of the config `name`. A missing config
+/// contains no revocation lists.
+pub(crate) async fn load_config(
+    store: &dyn StoreManager,
+    name: &str,
+) -> anyhow::Result<Vec<jwt::Claims<jwt::Revocations>>> {
+    let Some(data) = store
+        .get(name)
+        .await
+        .with_context(|| format!("failed to get revocation list config `{name}`"))?
+    else {
+        return Ok(Vec::default());
+    };
+    let config = serde_json::from_slice::<HashMap<String, String>>(&data)
+        .with_context(|| format!("failed to parse revocation list config `{name}`"))?;
+    let mut tokens: Vec<_> = config.into_iter().collect();
+    tokens.sort();
+    parse_revocation_lists(tokens.into_iter().map(|(_, token)| token))
+}
+
+async fn modified_at(path: &Path) -> Option<SystemTime> {
+    tokio::fs::metadata(path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Revocation lists of a host, by the source they are loaded from
+#[derive(Debug, Default)]
+pub(crate) struct RevocationLists {
+    lists: RwLock<HashMap<RevocationSource, Vec<jwt::Claims<jwt::Revocations>>>>,
+}
+
+impl RevocationLists {
+    /// Replace the revocation lists loaded from `source` with `lists`
+    pub(crate) async fn set(
+        &self,
+        source: RevocationSource,
+        lists: Vec<jwt::Claims<jwt::Revocations>>,
+    ) {
+        self.lists.write().await.insert(source, lists);
+    }
+
+    /// Ensure that `claims` are not revoked by any of the revocation lists
+    pub(crate) async fn check<T>(&self, claims: &jwt::Claims<T>) -> anyhow::Result<()> {
+        let lists = self.lists.read().await;
+        if let Some(list) = lists.values().flatten().find(|list| list.revokes(claims)) {
+            bail!(
+                "`{}` issued at {} is revoked by account `{}`",
+                claims.subject,
+                claims.issued_at,
+                list.issuer
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Host {
+    /// Ensures that the claims of a component or provider are not revoked
+    pub(crate) async fn ensure_not_revoked<T>(
+        &self,
+        claims: Option<&jwt::Claims<T>>,
+    ) -> anyhow::Result<()> {
+        match claims {
+            Some(claims) => self.revocations.check(claims).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Replace the revocation lists loaded from `source` with `lists` and stop the components and
+    /// providers running on this host which are revoked by them
+    pub(crate) async fn update_revocations(
+        &self,
+        source: RevocationSource,
+        lists: Vec<jwt::Claims<jwt::Revocations>>,
+    ) -> anyhow::Result<()> {
+        self.revocations.set(source, lists).await;
+        let host_id = self.host_key.public_key();
+
+        let revoked_components = {
+            let mut components = self.components.write().await;
+            let mut revoked = Vec::new();
+            for (id, component) in components.iter() {
+                if let Some(claims) = component.claims() {
+                    if let Err(err) = self.revocations.check(claims).await {
+                        warn!(component_id = id, ?err, "stopping revoked component");
+                        revoked.push(id.clone());
+                    }
+                }
+            }
+            revoked
+                .iter()
+                .filter_map(|id| components.remove(id))
+                .collect::<Vec<_>>()
+        };
+        for component in revoked_components {
+            if let Err(err) = self.stop_component(&component, &host_id).await {
+                error!(component_id = %component.id, ?err, "failed to stop revoked component");
+                continue;
+            }
+            if let Err(err) = self
+                .event_publisher
+                .publish_event(crate::event::component_scaled(
+                    component.claims(),
+                    &component.annotations,
+                    &host_id,
+                    0_usize,
+                    &component.image_reference,
+                    &component.id,
+                ))
+                .await
+            {
+                error!(component_id = %component.id, ?err, "failed to publish component scaled event");
+            }
+        }
+
+        let mut revoked_providers = Vec::new();
+        for (id, provider) in self.providers.read().await.iter() {
+            if let Some(token) = &provider.claims_token {
+                if let Err(err) = self.revocations.check(&token.claims).await {
+                    warn!(provider_id = id, ?err, "stopping revoked provider");
+                    revoked_providers.push(id.clone());
+                }
+            }
+        }
+        for provider_id in revoked_providers {
+            let request = match StopProviderCommand::builder()
+                .host_id(&host_id)
+                .provider_id(&provider_id)
+                .build()
+            {
+                Ok(request) => request,
+                Err(err) => {
+                    error!(
+                        provider_id,
+                        ?err,
+                        "failed to build stop request of revoked provider"
+                    );
+                    continue;
+                }
+            };
+            if let Err(err) = ControlInterfaceServer::handle_stop_provider(self, request).await {
+                error!(provider_id, ?err, "failed to stop revoked provider");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reload the revocation list file at `path` of the host whenever it is modified, checking for
+/// modifications every `reload_interval`. If a modified file fails to parse, the previously loaded
+/// revocation lists remain in effect.
+pub(crate) async fn watch_file(host: Weak<Host>, path: PathBuf, reload_interval: Duration) {
+    let mut modified = modified_at(&path).await;
+    let mut ticks = interval(reload_interval);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticks.tick().await;
+        let current = modified_at(&path).await;
+        if current == modified {
+            continue;
+        }
+        modified = current;
+        let lists = match load_file(&path).await {
+            Ok(lists) => lists,
+            Err(err) => {
+                error!(
+                    path = %path.display(),
+                    ?err,
+                    "failed to reload revocation lists, keeping previous lists"
+                );
+                continue;
+            }
+        };
+        let Some(host) = host.upgrade() else {
+            return;
+        };
+        info!(path = %path.display(), "reloaded revocation lists");
+        if let Err(err) = host.update_revocations(RevocationSource::File, lists).await {
+            error!(?err, "failed to apply revocation lists");
+        }
+    }
+}
+
+/// Reload the revocation list config `name` of the host from `store` whenever it changes. If an
+/// updated config fails to parse, the previously loaded revocation lists remain in effect.
+pub(crate) async fn watch_config(host: Weak<Host>, store: Arc<dyn StoreManager>, name: String) {
+    let mut watch = match store.watch(&name).await {
+        Ok(watch) => watch,
+        Err(err) => {
+            error!(
+                config = name,
+                ?err,
+                "failed to watch revocation list config"
+            );
+            return;
+        }
+    };
+    while let Some(event) = watch.next().await {
+        match event {
+            Ok(event) if event.key() == name => {}
+            Ok(_) => continue,
+            Err(err) => {
+                error!(
+                    config = name,
+                    ?err,
+                    "failed to receive revocation list config update"
+                );
+                continue;
+            }
+        }
+        let lists = match load_config(store.as_ref(), &name).await {
+            Ok(lists) => lists,
+            Err(err) => {
+                error!(
+                    config = name,
+                    ?err,
+                    "failed to reload revocation lists, keeping previous lists"
+                );
+                continue;
+            }
+        };
+        let Some(host) = host.upgrade() else {
+            return;
+        };
+        info!(config = name, "reloaded revocation lists");
+        if let Err(err) = host
+            .update_revocations(RevocationSource::Config, lists)
+            .await
+        {
+            error!(?err, "failed to apply revocation lists");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use bytes::Bytes;
+
+    use crate::store::DefaultStore;
+
+    fn component_claims(issuer: &KeyPair, subject: &KeyPair) -> jwt::Claims<jwt::Component> {
+        jwt::Claims::<jwt::Component>::new(
+            "component".into(),
+            issuer.public_key(),
+            subject.public_key(),
+            None,
+            false,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn checks_revocation_lists() -> anyhow::Result<()> {
+        let account = KeyPair::new_account();
+        let component = KeyPair::new_module();
+        let claims = component_claims(&account, &component);
+        let list = jwt::Claims::<jwt::Revocations>::new(
+            account.public_key(),
+            HashMap::from([(component.public_key(), claims.issued_at)]),
+        )
+        .encode(&account)?;
+
+        let store = DefaultStore::default();
+        assert!(load_config(&store, "revocations").await?.is_empty());
+        store
+            .put(
+                "revocations",
+                Bytes::from(serde_json::to_vec(&HashMap::from([("acme", &list)]))?),
+            )
+            .await?;
+        let lists = load_config(&store, "revocations").await?;
+
+        let revocations = RevocationLists::default();
+        revocations.check(&claims).await?;
+        revocations.set(RevocationSource::Config, lists).await;
+        let err = revocations
+            .check(&claims)
+            .await
+            .expect_err("revoked claims should be rejected");
+        assert!(err
+            .to_string()
+            .ends_with(&format!("is revoked by account `{}`", account.public_key())));
+        // Claims of other subjects and claims issued by other accounts are not revoked
+        revocations
+            .check(&component_claims(&account, &KeyPair::new_module()))
+            .await?;
+        revocations
+            .check(&component_claims(&KeyPair::new_account(), &component))
+            .await?;
+        revocations.set(RevocationSource::Config, Vec::new()).await;
+        revocations.check(&claims).await?;
+
+        // Revocation lists must be issued by an account for itself
+        let operator = KeyPair::new_operator();
+        let list = jwt::Claims::<jwt::Revocations>::new(operator.public_key(), HashMap::new())
+            .encode(&operator)?;
+        assert!(parse_revocation_lists([list]).is_err());
+        let mut list = jwt::Claims::<jwt::Revocations>::new(account.public_key(), HashMap::new());
+        list.subject = KeyPair::new_account().public_key();
+        assert!(parse_revocation_lists([list.encode(&account)?]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+//! This module contains the routing of invocations over links with weighted targets.
+//!
+//! Invocations over a link with [`WeightedTarget`]s are split between the targets in proportion to
+//! their weights, which allows shifting traffic to a new version of a target gradually. The target
+//! is chosen at random for each invocation.
+//!
+//! Link configuration is only delivered to the primary target of a link, so weighted targets are
+//! meant to be different versions of the same component.
+
+use std::collections::HashSet;
+
+use anyhow::ensure;
+use wasmcloud_control_interface::Link;
+
+/// Returns the targets of `link`, which are its weighted targets, if any, or its primary target
+pub(crate) fn link_targets(link: &Link) -> Vec<&str> {
+    if link.weighted_targets().is_empty() {
+        vec![link.target()]
+    } else {
+        link.weighted_targets()
+            .iter()
+            .map(|target| target.target())
+            .collect()
+    }
+}
+
+/// Returns whether links `a` and `b` have any target in common, in which case they are considered
+/// to be versions of the same link
+pub(crate) fn shares_target(a: &Link, b: &Link) -> bool {
+    let b = link_targets(b);
+    link_targets(a).iter().any(|target| b.contains(target))
+}
+
+/// Validate the weighted targets of `link`
+pub(crate) fn validate_link(link: &Link) -> anyhow::Result<()> {
+    let weighted_targets = link.weighted_targets();
+    if weighted_targets.is_empty() {
+        return Ok(());
+    }
+    let mut targets = HashSet::with_capacity(weighted_targets.len());
+    let mut total = 0u64;
+    for target in weighted_targets {
+        ensure!(
+            !target.target().is_empty(),
+            "weighted target must not be empty"
+        );
+        ensure!(
+            targets.insert(target.target()),
+            "weighted target `{}` is specified more than once",
+            target.target()
+        );
+        total += u64::from(target.weight());
+    }
+    ensure!(
+        total > 0,
+        "weights of weighted targets must not all be zero"
+    );
+    ensure!(
+        targets.contains(link.target()),
+        "target `{}` must be one of the weighted targets",
+        link.target()
+    );
+    Ok(())
+}
+
+/// Weighted targets of a link, which select the target of each invocation over the link
+#[derive(Debug)]
+pub struct WeightedTargets {
+    /// Targets with non-zero weight and their cumulative weights
+    targets: Box<[(Box<str>, u64)]>,
+    /// Sum of the weights of all targets
+    total: u64,
+}
+
+impl WeightedTargets {
+    /// Construct the [`WeightedTargets`] of `link`, returning `None` if it has none
+    pub(crate) fn new(link: &Link) -> Option<Self> {
+        let mut total = 0;
+        let targets = link
+            .weighted_targets()
+            .iter()
+            .filter(|target| target.weight() > 0)
+            .map(|target| {
+                total += u64::from(target.weight());
+                (target.target().into(), total)
+            })
+            .collect::<Box<[_]>>();
+        (total > 0).then_some(Self { targets, total })
+    }
+
+    /// Select the target of an invocation
+    pub(crate) fn select(&self) -> &str {
+        let pos = rand::random_range(0..self.total);
+        self.targets
+            .iter()
+            .find(|(_, cumulative)| pos < *cumulative)
+            .or(self.targets.last())
+            .map_or("", |(target, _)| target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use wasmcloud_control_interface::WeightedTarget;
+
+    use super::*;
+
+    fn link(weighted_targets: Vec<WeightedTarget>) -> Link {
+        Link::builder()
+            .source_id("source")
+            .target("v1")
+            .name("default")
+            .wit_namespace("wasi")
+            .wit_package("keyvalue")
+            .weighted_targets(weighted_targets)
+            .build()
+            .expect("failed to build link")
+    }
+
+    #[test]
+    fn validates_weighted_targets() {
+        assert!(validate_link(&link(vec![])).is_ok());
+        assert!(validate_link(&link(vec![
+            WeightedTarget::new("v1", 90),
+            WeightedTarget::new("v2", 10)
+        ]))
+        .is_ok());
+        // The primary target must be a weighted target
+        assert!(validate_link(&link(vec![WeightedTarget::new("v2", 10)])).is_err());
+        assert!(validate_link(&link(vec![
+            WeightedTarget::new("v1", 1),
+            WeightedTarget::new("v1", 1)
+        ]))
+        .is_err());
+        assert!(validate_link(&link(vec![
+            WeightedTarget::new("v1", 0),
+            WeightedTarget::new("v2", 0)
+        ]))
+        .is_err());
+
+        assert!(shares_target(
+            &link(vec![]),
+            &link(vec![
+                WeightedTarget::new("v1", 90),
+                WeightedTarget::new("v2", 10)
+            ])
+        ));
+    }
+
+    #[test]
+    fn selects_weighted_targets() {
+        let targets = WeightedTargets::new(&link(vec![
+            WeightedTarget::new("v1", 3),
+            WeightedTarget::new("v2", 1),
+            WeightedTarget::new("v3", 0),
+        ]))
+        .expect("link has weighted targets");
+        let mut selected = HashMap::<_, usize>::new();
+        for _ in 0..1000 {
+            *selected.entry(targets.select()).or_default() += 1;
+        }
+        assert!(!selected.contains_key("v3"));
+        assert!(selected["v1"] > selected["v2"]);
+
+        assert!(WeightedTargets::new(&link(vec![])).is_none());
+    }
+}
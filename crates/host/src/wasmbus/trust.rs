@@ -0,0 +1,206 @@
+//! This module contains the issuer trust store of a host, restricting the components and providers
+//! it runs to those whose issuers chain up to a trusted operator.
+//!
+//! Components and providers are signed by account keys. An account is trusted if its JWT is issued
+//! by a trusted operator, using either the operator key or one of the [`jwt::Operator`]
+//! `valid_signers`. The issuer of a component or provider is trusted if it is a trusted account or
+//! one of the [`jwt::Account`] `valid_signers` of a trusted account. Operator JWTs are self-signed.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, ensure, Context as _};
+use nkeys::{KeyPair, KeyPairType};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wascap::jwt;
+
+/// Validate the signature and dates of `token`, decoding its claims
+fn decode_token<T>(token: &str) -> anyhow::Result<jwt::Claims<T>>
+where
+    T: Serialize + DeserializeOwned + jwt::WascapEntity,
+{
+    let validation = jwt::validate_token::<T>(token).context("failed to validate JWT")?;
+    ensure!(validation.signature_valid, "JWT signature is invalid");
+    ensure!(!validation.expired, "JWT has expired");
+    ensure!(!validation.cannot_use_yet, "JWT is not valid yet");
+    jwt::Claims::<T>::decode(token).context("failed to decode JWT")
+}
+
+/// Ensure that `key` is a public key of type `ty`
+fn ensure_key_type(key: &str, ty: KeyPairType) -> anyhow::Result<()> {
+    let key = KeyPair::from_public_key(key).with_context(|| format!("invalid key `{key}`"))?;
+    ensure!(
+        key.key_pair_type() == ty,
+        "key `{}` is not an {ty:?} key",
+        key.public_key()
+    );
+    Ok(())
+}
+
+/// Returns the keys in `signers`, the `valid_signers` of an operator or account
+fn valid_signers(signers: Option<&Vec<String>>) -> &[String] {
+    signers.map(Vec::as_slice).unwrap_or_default()
+}
+
+/// Trusted operators and the accounts issued by them
+#[derive(Debug)]
+pub(crate) struct IssuerTrustStore {
+    /// JWTs of trusted operators, keyed by operator public key
+    operators: HashMap<String, String>,
+    /// JWTs and claims of trusted accounts and the public key of the operator which issued them,
+    /// keyed by account public key
+    accounts: HashMap<String, (String, jwt::Claims<jwt::Account>, String)>,
+}
+
+impl IssuerTrustStore {
+    /// Construct a trust store of the operators with JWTs `operator_jwts` and the accounts with
+    /// JWTs `account_jwts`, which must be issued by one of the operators
+    pub(crate) fn new(
+        operator_jwts: impl IntoIterator<Item = impl AsRef<str>>,
+        account_jwts: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> anyhow::Result<Self> {
+        let mut operators = HashMap::new();
+        let mut operator_signers = HashMap::new();
+        for token in operator_jwts {
+            let token = token.as_ref();
+            let claims =
+                decode_token::<jwt::Operator>(token).context("invalid trusted operator JWT")?;
+            ensure_key_type(&claims.subject, KeyPairType::Operator)
+                .context("invalid trusted operator JWT")?;
+            ensure!(
+                claims.issuer == claims.subject,
+                "trusted operator JWT of `{}` is not self-signed",
+                claims.subject
+            );
+            let signers = claims
+                .metadata
+                .as_ref()
+                .and_then(|operator| operator.valid_signers.as_ref());
+            for signer in valid_signers(signers) {
+                operator_signers.insert(signer.clone(), claims.subject.clone());
+            }
+            operator_signers.insert(claims.subject.clone(), claims.subject.clone());
+            operators.insert(claims.subject, token.to_string());
+        }
+        let mut accounts = HashMap::new();
+        for token in account_jwts {
+            let token = token.as_ref();
+            let claims = decode_token::<jwt::Account>(token).context("invalid account JWT")?;
+            ensure_key_type(&claims.subject, KeyPairType::Account)
+                .context("invalid account JWT")?;
+            let Some(operator) = operator_signers.get(&claims.issuer) else {
+                bail!(
+                    "account `{}` is issued by `{}`, which is not a trusted operator",
+                    claims.subject,
+                    claims.issuer
+                );
+            };
+            accounts.insert(
+                claims.subject.clone(),
+                (token.to_string(), claims, operator.clone()),
+            );
+        }
+        Ok(Self {
+            operators,
+            accounts,
+        })
+    }
+
+    /// Verify that `issuer`, the issuer of a component or provider, chains up to a trusted
+    /// operator through a trusted account whose JWT is still valid
+    pub(crate) fn verify(&self, issuer: &str) -> anyhow::Result<()> {
+        let Some((account_token, account, operator)) = self.accounts.get(issuer).or_else(|| {
+            self.accounts.values().find(|(_, account, _)| {
+                let signers = account
+                    .metadata
+                    .as_ref()
+                    .and_then(|account| account.valid_signers.as_ref());
+                valid_signers(signers).iter().any(|signer| signer == issuer)
+            })
+        }) else {
+            bail!("issuer `{issuer}` does not chain up to a trusted operator");
+        };
+        decode_token::<jwt::Account>(account_token)
+            .with_context(|| format!("JWT of account `{}` is no longer valid", account.subject))?;
+        let operator_token = self
+            .operators
+            .get(operator)
+            .context("operator of account is not trusted")?;
+        decode_token::<jwt::Operator>(operator_token)
+            .with_context(|| format!("JWT of operator `{operator}` is no longer valid"))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn operator_jwt(operator: &KeyPair, signers: Vec<String>) -> anyhow::Result<String> {
+        let claims = jwt::Claims::<jwt::Operator>::new(
+            "operator".into(),
+            operator.public_key(),
+            operator.public_key(),
+            signers,
+        );
+        Ok(claims.encode(operator)?)
+    }
+
+    fn account_jwt(
+        issuer: &KeyPair,
+        account: &KeyPair,
+        signers: Vec<String>,
+    ) -> anyhow::Result<String> {
+        let claims = jwt::Claims::<jwt::Account>::new(
+            "account".into(),
+            issuer.public_key(),
+            account.public_key(),
+            signers,
+        );
+        Ok(claims.encode(issuer)?)
+    }
+
+    #[test]
+    fn verifies_issuer_chains() -> anyhow::Result<()> {
+        let operator = KeyPair::new_operator();
+        let operator_signer = KeyPair::new_operator();
+        let account = KeyPair::new_account();
+        let account_signer = KeyPair::new_account();
+        let other_account = KeyPair::new_account();
+
+        let store = IssuerTrustStore::new(
+            [operator_jwt(&operator, vec![operator_signer.public_key()])?],
+            [
+                account_jwt(&operator, &account, vec![account_signer.public_key()])?,
+                account_jwt(&operator_signer, &other_account, vec![])?,
+            ],
+        )?;
+        store.verify(&account.public_key())?;
+        store.verify(&account_signer.public_key())?;
+        store.verify(&other_account.public_key())?;
+        let err = store
+            .verify(&KeyPair::new_account().public_key())
+            .expect_err("untrusted issuer should be rejected");
+        assert!(err
+            .to_string()
+            .ends_with("does not chain up to a trusted operator"));
+
+        // Accounts must be issued by a trusted operator
+        let untrusted = KeyPair::new_operator();
+        assert!(IssuerTrustStore::new(
+            [operator_jwt(&operator, vec![])?],
+            [account_jwt(&untrusted, &account, vec![])?],
+        )
+        .is_err());
+
+        // Operators must be self-signed
+        let claims = jwt::Claims::<jwt::Operator>::new(
+            "operator".into(),
+            untrusted.public_key(),
+            operator.public_key(),
+            vec![],
+        );
+        assert!(IssuerTrustStore::new([claims.encode(&untrusted)?], [""; 0]).is_err());
+        Ok(())
+    }
+}
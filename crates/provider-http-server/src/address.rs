@@ -12,7 +12,6 @@ use std::sync::Arc;
 use anyhow::{bail, Context as _};
 use axum::extract;
 use axum::handler::Handler;
-use axum_server::tls_rustls::RustlsConfig;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument};
 use wasmcloud_core::http::{default_listen_address, load_settings, ServiceSettings};
@@ -20,6 +19,8 @@ use wasmcloud_provider_sdk::core::LinkName;
 use wasmcloud_provider_sdk::provider::WrpcClient;
 use wasmcloud_provider_sdk::{get_connection, HostData, LinkConfig, LinkDeleteInfo, Provider};
 
+use crate::limits::RequestLimits;
+use crate::tls::TlsConfig;
 use crate::{build_request, get_cors_layer, get_tcp_listener, invoke_component};
 
 /// Lookup for handlers by socket
@@ -200,6 +201,8 @@ struct RequestContext {
     server_address: SocketAddr,
     /// Settings that can be
     settings: Arc<ServiceSettings>,
+    /// Limits of the listener
+    limits: Arc<RequestLimits>,
     /// HTTP scheme
     scheme: http::uri::Scheme,
     /// Handlers for components
@@ -207,17 +210,20 @@ struct RequestContext {
 }
 
 /// Handle an HTTP request by invoking the target component as configured in the listener
-#[instrument(level = "debug", skip(settings, handlers_by_socket))]
+#[instrument(level = "debug", skip(settings, limits, handlers_by_socket))]
 async fn handle_request(
     extract::State(RequestContext {
         server_address,
         settings,
+        limits,
         scheme,
         handlers_by_socket,
     }): extract::State<RequestContext>,
+    extract::ConnectInfo(client): extract::ConnectInfo<SocketAddr>,
     axum_extra::extract::Host(authority): axum_extra::extract::Host,
     request: extract::Request,
 ) -> impl axum::response::IntoResponse {
+    let (request, admission) = limits.apply(client.ip(), request).map_err(|err| *err)?;
     let (component_id, wrpc) = {
         let Some((component_id, wrpc)) = handlers_by_socket
             .read()
@@ -236,16 +242,15 @@ async fn handle_request(
 
     let timeout = settings.timeout_ms.map(Duration::from_millis);
     let req = build_request(request, scheme, authority, &settings).map_err(|err| *err)?;
-    axum::response::Result::<_, axum::response::ErrorResponse>::Ok(
-        invoke_component(
-            &wrpc,
-            &component_id,
-            req,
-            timeout,
-            settings.cache_control.as_ref(),
-        )
-        .await,
+    let res = invoke_component(
+        &wrpc,
+        &component_id,
+        req,
+        timeout,
+        settings.cache_control.as_ref(),
     )
+    .await;
+    axum::response::Result::<_, axum::response::ErrorResponse>::Ok(admission.respond(res))
 }
 
 /// An asynchronous `wrpc:http/incoming-handler` with support for CORS and TLS
@@ -271,6 +276,8 @@ impl HttpServerCore {
             "httpserver starting listener for target",
         );
         let cors = get_cors_layer(&settings)?;
+        let limits = RequestLimits::new(&settings).context("failed to construct request limits")?;
+        let limits = Arc::new(limits);
         let service = handle_request.layer(cors);
         let handle = axum_server::Handle::new();
         let listener = get_tcp_listener(&settings)
@@ -278,16 +285,15 @@ impl HttpServerCore {
 
         let target = target.to_owned();
         let task_handle = handle.clone();
-        let task = if let (Some(crt), Some(key)) =
-            (&settings.tls_cert_file, &settings.tls_priv_key_file)
+        let task = if let Some(tls) =
+            TlsConfig::load(&settings).context("failed to construct TLS config")?
         {
             debug!(?addr, "bind HTTPS listener");
-            let tls = RustlsConfig::from_pem_file(crt, key)
-                .await
-                .context("failed to construct TLS config")?;
 
-            let srv = axum_server::from_tcp_rustls(listener, tls);
+            let srv = axum_server::from_tcp(listener).acceptor(tls.acceptor());
             tokio::spawn(async move {
+                // Keep reloading the TLS config for as long as the server is running
+                let _tls = tls;
                 if let Err(e) = srv
                     .handle(task_handle)
                     .serve(
@@ -295,10 +301,11 @@ impl HttpServerCore {
                             .with_state(RequestContext {
                                 server_address: addr,
                                 settings,
+                                limits,
                                 scheme: http::uri::Scheme::HTTPS,
                                 handlers_by_socket,
                             })
-                            .into_make_service(),
+                            .into_make_service_with_connect_info::<SocketAddr>(),
                     )
                     .await
                 {
@@ -318,10 +325,11 @@ impl HttpServerCore {
                             .with_state(RequestContext {
                                 server_address: addr,
                                 settings,
+                                limits,
                                 scheme: http::uri::Scheme::HTTP,
                                 handlers_by_socket,
                             })
-                            .into_make_service(),
+                            .into_make_service_with_connect_info::<SocketAddr>(),
                     )
                     .await
                 {
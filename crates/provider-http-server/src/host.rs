@@ -13,7 +13,6 @@ use std::sync::Arc;
 use anyhow::{bail, Context as _};
 use axum::extract;
 use axum::handler::Handler;
-use axum_server::tls_rustls::RustlsConfig;
 use axum_server::Handle;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
@@ -21,6 +20,8 @@ use tracing::{debug, error, info, instrument};
 use wasmcloud_provider_sdk::provider::WrpcClient;
 use wasmcloud_provider_sdk::{get_connection, HostData, LinkConfig, LinkDeleteInfo, Provider};
 
+use crate::limits::RequestLimits;
+use crate::tls::TlsConfig;
 use crate::{
     build_request, get_cors_layer, get_tcp_listener, invoke_component, load_settings,
     ServiceSettings,
@@ -31,7 +32,7 @@ use crate::{
 #[derive(Default)]
 struct Router {
     /// Lookup from a host to the component ID that is handling that host
-    hosts: HashMap<Arc<str>, (Arc<str>, WrpcClient)>,
+    hosts: HashMap<Arc<str>, (Arc<str>, WrpcClient, Arc<RequestLimits>)>,
     /// Reverse lookup to find the host for a (component,link_name) pair
     components: HashMap<(Arc<str>, Arc<str>), Arc<str>>,
     /// Header to match for host-based routing
@@ -87,22 +88,25 @@ impl HttpServerProvider {
             "httpserver starting listener in host-based mode",
         );
         let cors = get_cors_layer(&settings)?;
+        let limits = RequestLimits::new(&settings).context("failed to construct request limits")?;
+        let limits = Arc::new(limits);
         let listener = get_tcp_listener(&settings)?;
         let service = handle_request.layer(cors);
 
         let handle = axum_server::Handle::new();
         let task_handle = handle.clone();
         let task_router = Arc::clone(&router);
-        let task = if let (Some(crt), Some(key)) =
-            (&settings.tls_cert_file, &settings.tls_priv_key_file)
+        let task = if let Some(tls) =
+            TlsConfig::load(&settings).context("failed to construct TLS config")?
         {
             debug!(?addr, "bind HTTPS listener");
-            let tls = RustlsConfig::from_pem_file(crt, key)
-                .await
-                .context("failed to construct TLS config")?;
 
             tokio::spawn(async move {
-                if let Err(e) = axum_server::from_tcp_rustls(listener, tls)
+                let acceptor = tls.acceptor();
+                // Keep reloading the TLS config for as long as the server is running
+                let _tls = tls;
+                if let Err(e) = axum_server::from_tcp(listener)
+                    .acceptor(acceptor)
                     .handle(task_handle)
                     .serve(
                         service
@@ -110,8 +114,9 @@ impl HttpServerProvider {
                                 router: task_router,
                                 scheme: http::uri::Scheme::HTTPS,
                                 settings: Arc::clone(&settings),
+                                limits,
                             })
-                            .into_make_service(),
+                            .into_make_service_with_connect_info::<SocketAddr>(),
                     )
                     .await
                 {
@@ -130,8 +135,9 @@ impl HttpServerProvider {
                                 router: task_router,
                                 scheme: http::uri::Scheme::HTTP,
                                 settings: Arc::clone(&settings),
+                                limits,
                             })
-                            .into_make_service(),
+                            .into_make_service_with_connect_info::<SocketAddr>(),
                     )
                     .await
                 {
@@ -184,11 +190,15 @@ impl Provider for HttpServerProvider {
             .get_wrpc_client(link_config.target_id)
             .await
             .context("failed to construct wRPC client")?;
+        let limits = load_settings(None, link_config.config)
+            .context("failed to load host settings")
+            .and_then(|settings| RequestLimits::new(&settings))
+            .context("failed to construct request limits for host")?;
 
         let host = Arc::from(host.clone());
         // Insert the host into the hosts map for future lookups
         router.components.insert(key, Arc::clone(&host));
-        router.hosts.insert(host, (target, wrpc));
+        router.hosts.insert(host, (target, wrpc, Arc::new(limits)));
 
         Ok(())
     }
@@ -230,19 +240,24 @@ struct RequestContext {
     router: Arc<RwLock<Router>>,
     scheme: http::uri::Scheme,
     settings: Arc<ServiceSettings>,
+    /// Limits of the listener
+    limits: Arc<RequestLimits>,
 }
 
 /// Handle an HTTP request by looking up the component ID for the host and invoking the component
-#[instrument(level = "debug", skip(router, settings))]
+#[instrument(level = "debug", skip(router, settings, limits))]
 async fn handle_request(
     extract::State(RequestContext {
         router,
         scheme,
         settings,
+        limits,
     }): extract::State<RequestContext>,
+    extract::ConnectInfo(client): extract::ConnectInfo<SocketAddr>,
     axum_extra::extract::Host(authority): axum_extra::extract::Host,
     request: extract::Request,
 ) -> impl axum::response::IntoResponse {
+    let (request, listener_admission) = limits.apply(client.ip(), request).map_err(|err| *err)?;
     let timeout = settings.timeout_ms.map(Duration::from_millis);
     let req = build_request(request, scheme, authority, &settings).map_err(|err| *err)?;
 
@@ -254,18 +269,22 @@ async fn handle_request(
         .to_str()
         .map_err(|_| (http::StatusCode::BAD_REQUEST, "invalid host header"))?;
 
-    let Some((target_component, wrpc)) = router.read().await.hosts.get(lookup_host).cloned() else {
+    let Some((target_component, wrpc, route_limits)) =
+        router.read().await.hosts.get(lookup_host).cloned()
+    else {
         Err((http::StatusCode::NOT_FOUND, "host not found"))?
     };
-
+    let (req, route_admission) = route_limits.apply(client.ip(), req).map_err(|err| *err)?;
+
+    let res = invoke_component(
+        &wrpc,
+        &target_component,
+        req,
+        timeout,
+        settings.cache_control.as_ref(),
+    )
+    .await;
     axum::response::Result::<_, axum::response::ErrorResponse>::Ok(
-        invoke_component(
-            &wrpc,
-            &target_component,
-            req,
-            timeout,
-            settings.cache_control.as_ref(),
-        )
-        .await,
+        listener_admission.respond(route_admission.respond(res)),
     )
 }
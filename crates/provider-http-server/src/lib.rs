@@ -14,8 +14,9 @@
 //!   for production if a more secure configuration is required.
 //! - All settings can be specified at runtime, using per-component link settings:
 //!   - bind path/address
-//!   - TLS
+//!   - TLS, optionally with client certificates (mTLS), reloaded when files change
 //!   - Cors
+//!   - Rate, request body size and concurrency limits
 //! - Flexible configuration loading: from host, or from local toml or json file.
 //! - Fully asynchronous, using tokio lightweight "green" threads
 //! - Thread pool (for managing a pool of OS threads). The default
@@ -44,9 +45,13 @@ use wasmcloud_provider_sdk::provider::WrpcClient;
 use wasmcloud_provider_sdk::{initialize_observability, load_host_data, run_provider};
 use wrpc_interface_http::InvokeIncomingHandler as _;
 
+use crate::tls::{ClientCertificate, CLIENT_CERT_SAN_HEADER, CLIENT_CERT_SUBJECT_HEADER};
+
 mod address;
 mod host;
+mod limits;
 mod path;
+mod tls;
 
 pub async fn run() -> anyhow::Result<()> {
     initialize_observability!(
@@ -116,11 +121,31 @@ pub(crate) fn build_request(
         http::request::Parts {
             method,
             uri,
-            headers,
+            mut headers,
+            extensions,
             ..
         },
         body,
     ) = request.into_parts();
+    // Client certificate headers are only ever set from a verified client certificate
+    headers.remove(CLIENT_CERT_SUBJECT_HEADER);
+    headers.remove(CLIENT_CERT_SAN_HEADER);
+    if let Some(Some(ClientCertificate { subject, sans })) =
+        extensions.get::<Option<ClientCertificate>>()
+    {
+        for (name, value) in [
+            (CLIENT_CERT_SUBJECT_HEADER, subject.clone()),
+            (CLIENT_CERT_SAN_HEADER, sans.join(",")),
+        ] {
+            match http::HeaderValue::try_from(value) {
+                Ok(value) if !value.is_empty() => {
+                    headers.insert(name, value);
+                }
+                Ok(..) => {}
+                Err(err) => debug!(?err, name, "failed to forward client certificate header"),
+            }
+        }
+    }
     let http::uri::Parts { path_and_query, .. } = uri.into_parts();
 
     let mut uri = http::Uri::builder().scheme(scheme);
@@ -399,7 +424,42 @@ mod test {
     };
     use wasmcloud_test_util::testcontainers::{AsyncRunner, NatsServer};
 
-    use crate::{address, path};
+    use crate::tls::{ClientCertificate, CLIENT_CERT_SAN_HEADER, CLIENT_CERT_SUBJECT_HEADER};
+    use crate::{address, build_request, path};
+
+    #[test]
+    fn forwards_verified_client_certificate() -> Result<()> {
+        let settings = Default::default();
+        let request = || {
+            http::Request::builder()
+                .uri("/")
+                .header(CLIENT_CERT_SUBJECT_HEADER, "CN=spoofed")
+                .body(axum::body::Body::empty())
+        };
+
+        let req = build_request(
+            request()?,
+            http::uri::Scheme::HTTPS,
+            "localhost".into(),
+            &settings,
+        )
+        .map_err(|_| anyhow::anyhow!("failed to build request"))?;
+        assert!(req.headers().get(CLIENT_CERT_SUBJECT_HEADER).is_none());
+
+        let mut req = request()?;
+        req.extensions_mut().insert(Some(ClientCertificate {
+            subject: "CN=client".into(),
+            sans: vec!["DNS:client.example.com".into(), "IP:10.0.0.1".into()],
+        }));
+        let req = build_request(req, http::uri::Scheme::HTTPS, "localhost".into(), &settings)
+            .map_err(|_| anyhow::anyhow!("failed to build request"))?;
+        assert_eq!(req.headers()[CLIENT_CERT_SUBJECT_HEADER], "CN=client");
+        assert_eq!(
+            req.headers()[CLIENT_CERT_SAN_HEADER],
+            "DNS:client.example.com,IP:10.0.0.1"
+        );
+        Ok(())
+    }
 
     // This test is ignored by default as it requires a container runtime to be installed
     // to run the testcontainer. In GitHub Actions CI, this is only works on `linux`
@@ -0,0 +1,512 @@
+//! This module contains the request limits applied by the HTTP server in all routing modes.
+//!
+//! Limits are configured through [`ServiceSettings`] and enforced per listener, using the settings
+//! of the listener, and in path-based and host-based mode additionally per route, using the
+//! settings in the link config of the route.
+
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{ready, Context, Poll};
+use core::time::Duration;
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::str::FromStr as _;
+use std::sync::{Arc, Mutex};
+
+use axum::extract;
+use axum::response::{ErrorResponse, IntoResponse};
+use bytes::Bytes;
+use http_body_util::{LengthLimitError, Limited};
+use pin_project_lite::pin_project;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+use tracing::debug;
+use wasmcloud_core::http::ServiceSettings;
+
+/// Maximum number of client buckets tracked by a [`RateLimiter`]
+const MAX_BUCKETS: usize = 10_000;
+
+/// Token bucket of a single client
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token bucket rate limiter keyed by client
+#[derive(Debug)]
+struct RateLimiter {
+    /// Tokens added to a bucket per second
+    rate: f64,
+    /// Maximum number of tokens in a bucket
+    burst: f64,
+    /// Header identifying the client, falling back to the client IP address if not set
+    key_header: Option<http::HeaderName>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Takes a token from the bucket of `key`, returning the time until a token is available if
+    /// the bucket is empty
+    fn acquire(&self, key: String) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if buckets.len() >= MAX_BUCKETS && !buckets.contains_key(&key) {
+            self.prune(&mut buckets, now);
+        }
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        bucket.tokens = (bucket.tokens
+            + now.duration_since(bucket.updated).as_secs_f64() * self.rate)
+            .min(self.burst);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+
+    /// Drops full buckets and, if more than half of [`MAX_BUCKETS`] remain, the least recently
+    /// updated buckets, leaving at most half of [`MAX_BUCKETS`]. Clients rotating keys therefore
+    /// cannot grow the buckets without bound, and pruning runs at most once per
+    /// `MAX_BUCKETS / 2` new clients.
+    fn prune(&self, buckets: &mut HashMap<String, Bucket>, now: Instant) {
+        // Full buckets are indistinguishable from new ones, so they can safely be dropped
+        buckets.retain(|_, Bucket { tokens, updated }| {
+            *tokens + now.duration_since(*updated).as_secs_f64() * self.rate < self.burst
+        });
+        let keep = MAX_BUCKETS / 2;
+        if buckets.len() <= keep {
+            return;
+        }
+        let mut updated: Vec<_> = buckets.values().map(|bucket| bucket.updated).collect();
+        // The most recently updated of the buckets to evict
+        let evict = updated.len() - keep - 1;
+        let (_, cutoff, _) = updated.select_nth_unstable(evict);
+        let cutoff = *cutoff;
+        debug!(
+            buckets = buckets.len(),
+            "evicting least recently used rate limit buckets"
+        );
+        buckets.retain(|_, bucket| bucket.updated > cutoff);
+    }
+}
+
+/// Rate, request body size and concurrency limits of a listener or route
+#[derive(Debug, Default)]
+pub(crate) struct RequestLimits {
+    rate_limiter: Option<RateLimiter>,
+    max_body_bytes: Option<u64>,
+    concurrency: Option<Arc<Semaphore>>,
+}
+
+impl RequestLimits {
+    /// Construct the [`RequestLimits`] configured in `settings`
+    pub(crate) fn new(settings: &ServiceSettings) -> anyhow::Result<Self> {
+        let rate_limiter = settings
+            .rate_limit_per_second
+            .filter(|rate| *rate > 0)
+            .map(|rate| {
+                let key_header = settings
+                    .rate_limit_key_header
+                    .as_deref()
+                    .map(http::HeaderName::from_str)
+                    .transpose()?;
+                anyhow::Ok(RateLimiter {
+                    rate: rate.into(),
+                    burst: settings.rate_limit_burst.unwrap_or(rate).max(1).into(),
+                    key_header,
+                    buckets: Mutex::default(),
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            rate_limiter,
+            max_body_bytes: settings.max_request_body_bytes,
+            concurrency: settings
+                .max_concurrent_requests
+                .map(|max| Arc::new(Semaphore::new(max))),
+        })
+    }
+
+    /// Apply the limits to a request from `client`, returning the request with its body limited
+    /// to the maximum size and an [`Admission`], which must be used to respond to the request.
+    ///
+    /// Requests exceeding the concurrency limit are rejected with status 503, requests exceeding
+    /// the rate limit with status 429 and requests with a body larger than the maximum size with
+    /// status 413. The concurrency limit is checked first, so that rejected requests do not
+    /// consume rate limit tokens.
+    pub(crate) fn apply(
+        &self,
+        client: IpAddr,
+        request: extract::Request,
+    ) -> Result<(extract::Request, Admission), Box<ErrorResponse>> {
+        let permit = self
+            .concurrency
+            .as_ref()
+            .map(|concurrency| {
+                Arc::clone(concurrency).try_acquire_owned().map_err(|_| {
+                    debug!(%client, "too many concurrent requests");
+                    Box::new(ErrorResponse::from((
+                        http::StatusCode::SERVICE_UNAVAILABLE,
+                        "too many concurrent requests",
+                    )))
+                })
+            })
+            .transpose()?;
+        if let Some(limiter) = &self.rate_limiter {
+            let key = limiter
+                .key_header
+                .as_ref()
+                .and_then(|header| request.headers().get(header))
+                .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+                .unwrap_or_else(|| client.to_string());
+            if let Err(retry_after) = limiter.acquire(key) {
+                debug!(%client, "request rate limited");
+                let retry_after = retry_after.as_secs().saturating_add(1).to_string();
+                return Err(Box::new(
+                    (
+                        http::StatusCode::TOO_MANY_REQUESTS,
+                        [(http::header::RETRY_AFTER, retry_after)],
+                        "too many requests",
+                    )
+                        .into_response()
+                        .into(),
+                ));
+            }
+        }
+        let Some(max) = self.max_body_bytes else {
+            return Ok((
+                request,
+                Admission {
+                    permit,
+                    body_too_large: None,
+                },
+            ));
+        };
+        let content_length = request
+            .headers()
+            .get(http::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        if content_length.is_some_and(|len| len > max) {
+            debug!(%client, "request body too large");
+            return Err(Box::new(
+                (
+                    http::StatusCode::PAYLOAD_TOO_LARGE,
+                    "request body too large",
+                )
+                    .into(),
+            ));
+        }
+        // Bodies without a known length are cut off once they exceed the maximum size
+        let max = usize::try_from(max).unwrap_or(usize::MAX);
+        let body_too_large = Arc::<AtomicBool>::default();
+        let request = request.map(|body| {
+            axum::body::Body::new(LimitedBody {
+                body: Limited::new(body, max),
+                exceeded: Arc::clone(&body_too_large),
+            })
+        });
+        Ok((
+            request,
+            Admission {
+                permit,
+                body_too_large: Some(body_too_large),
+            },
+        ))
+    }
+}
+
+/// Admission of a request by [`RequestLimits::apply`]
+#[derive(Debug)]
+pub(crate) struct Admission {
+    permit: Option<OwnedSemaphorePermit>,
+    body_too_large: Option<Arc<AtomicBool>>,
+}
+
+impl Admission {
+    /// Respond to the admitted request with `res`.
+    ///
+    /// If the request body was cut off at the maximum size, the response is replaced by one with
+    /// status 413. Otherwise, the concurrency permit of the request is held until the response
+    /// body is dropped.
+    pub(crate) fn respond(self, res: impl IntoResponse) -> axum::response::Response {
+        if self
+            .body_too_large
+            .is_some_and(|exceeded| exceeded.load(Ordering::Relaxed))
+        {
+            return (
+                http::StatusCode::PAYLOAD_TOO_LARGE,
+                "request body too large",
+            )
+                .into_response();
+        }
+        let res = res.into_response();
+        let Some(permit) = self.permit else {
+            return res;
+        };
+        res.map(|body| {
+            axum::body::Body::new(PermitBody {
+                body,
+                _permit: permit,
+            })
+        })
+    }
+}
+
+pin_project! {
+    /// Request body cut off at a maximum size, recording whether the size was exceeded
+    struct LimitedBody {
+        #[pin]
+        body: Limited<axum::body::Body>,
+        exceeded: Arc<AtomicBool>,
+    }
+}
+
+impl http_body::Body for LimitedBody {
+    type Data = Bytes;
+    type Error = <Limited<axum::body::Body> as http_body::Body>::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        let frame = ready!(this.body.poll_frame(cx));
+        if let Some(Err(err)) = &frame {
+            if err.is::<LengthLimitError>() {
+                this.exceeded.store(true, Ordering::Relaxed);
+            }
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.body.size_hint()
+    }
+}
+
+pin_project! {
+    /// Response body holding the concurrency permit of its request until it is dropped
+    struct PermitBody {
+        #[pin]
+        body: axum::body::Body,
+        _permit: OwnedSemaphorePermit,
+    }
+}
+
+impl http_body::Body for PermitBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        self.project().body.poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.body.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::Ipv4Addr;
+
+    use axum::response::IntoResponse as _;
+    use http_body_util::BodyExt as _;
+    use wasmcloud_core::http::ServiceSettings;
+
+    use super::{Admission, RateLimiter, RequestLimits, MAX_BUCKETS};
+
+    fn request(headers: &[(&str, &str)], body: &'static str) -> axum::extract::Request {
+        let mut req = http::Request::builder();
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        req.body(axum::body::Body::from(body))
+            .expect("failed to build request")
+    }
+
+    fn status(
+        res: Result<(axum::extract::Request, Admission), Box<axum::response::ErrorResponse>>,
+    ) -> http::StatusCode {
+        match res {
+            Ok(..) => http::StatusCode::OK,
+            Err(err) => Err::<(), _>(*err).into_response().status(),
+        }
+    }
+
+    #[tokio::test]
+    async fn rate_limits_per_client() {
+        let limits = RequestLimits::new(&ServiceSettings {
+            rate_limit_per_second: Some(1),
+            rate_limit_burst: Some(2),
+            rate_limit_key_header: Some("x-api-key".into()),
+            ..Default::default()
+        })
+        .expect("failed to construct limits");
+        let a = Ipv4Addr::new(10, 0, 0, 1).into();
+        let b = Ipv4Addr::new(10, 0, 0, 2).into();
+        for _ in 0..2 {
+            assert_eq!(
+                status(limits.apply(a, request(&[], ""))),
+                http::StatusCode::OK
+            );
+        }
+        assert_eq!(
+            status(limits.apply(a, request(&[], ""))),
+            http::StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(
+            status(limits.apply(b, request(&[], ""))),
+            http::StatusCode::OK
+        );
+        // Clients identified by header are limited independently of their address
+        assert_eq!(
+            status(limits.apply(a, request(&[("x-api-key", "key")], ""))),
+            http::StatusCode::OK
+        );
+    }
+
+    #[test]
+    fn bounds_rate_limit_buckets() {
+        let limiter = RateLimiter {
+            rate: 1.0,
+            burst: 1.0,
+            key_header: None,
+            buckets: std::sync::Mutex::default(),
+        };
+        for i in 0..=MAX_BUCKETS + 1 {
+            assert!(limiter.acquire(i.to_string()).is_ok());
+        }
+        let buckets = limiter.buckets.lock().expect("failed to lock buckets");
+        assert!(buckets.len() <= MAX_BUCKETS / 2 + 2);
+        drop(buckets);
+        // Clients that were not evicted remain limited
+        assert!(limiter.acquire((MAX_BUCKETS + 1).to_string()).is_err());
+    }
+
+    #[tokio::test]
+    async fn limits_body_size_and_concurrency() {
+        let limits = RequestLimits::new(&ServiceSettings {
+            max_request_body_bytes: Some(4),
+            max_concurrent_requests: Some(1),
+            ..Default::default()
+        })
+        .expect("failed to construct limits");
+        let client = Ipv4Addr::LOCALHOST.into();
+        assert_eq!(
+            status(limits.apply(client, request(&[("content-length", "5")], "hello"))),
+            http::StatusCode::PAYLOAD_TOO_LARGE
+        );
+        let (_, permit) = limits
+            .apply(client, request(&[("content-length", "4")], "hell"))
+            .expect("request should be admitted");
+        assert_eq!(
+            status(limits.apply(client, request(&[], ""))),
+            http::StatusCode::SERVICE_UNAVAILABLE
+        );
+        drop(permit);
+        assert_eq!(
+            status(limits.apply(client, request(&[], ""))),
+            http::StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn limits_concurrency_before_rate() {
+        let limits = RequestLimits::new(&ServiceSettings {
+            rate_limit_per_second: Some(1),
+            rate_limit_burst: Some(2),
+            max_concurrent_requests: Some(1),
+            ..Default::default()
+        })
+        .expect("failed to construct limits");
+        let client = Ipv4Addr::LOCALHOST.into();
+        let (_, admission) = limits
+            .apply(client, request(&[], ""))
+            .expect("request should be admitted");
+        assert_eq!(
+            status(limits.apply(client, request(&[], ""))),
+            http::StatusCode::SERVICE_UNAVAILABLE
+        );
+        drop(admission);
+        // The rejected request did not take the second token
+        assert_eq!(
+            status(limits.apply(client, request(&[], ""))),
+            http::StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn holds_permit_until_response_body_is_dropped() {
+        let limits = RequestLimits::new(&ServiceSettings {
+            max_concurrent_requests: Some(1),
+            ..Default::default()
+        })
+        .expect("failed to construct limits");
+        let client = Ipv4Addr::LOCALHOST.into();
+        let (_, admission) = limits
+            .apply(client, request(&[], ""))
+            .expect("request should be admitted");
+        let res = admission.respond("hello");
+        assert_eq!(
+            status(limits.apply(client, request(&[], ""))),
+            http::StatusCode::SERVICE_UNAVAILABLE
+        );
+        let body = res
+            .into_body()
+            .collect()
+            .await
+            .expect("failed to read response body");
+        assert_eq!(body.to_bytes(), "hello");
+        assert_eq!(
+            status(limits.apply(client, request(&[], ""))),
+            http::StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_bodies_exceeding_limit_without_length() {
+        let limits = RequestLimits::new(&ServiceSettings {
+            max_request_body_bytes: Some(4),
+            ..Default::default()
+        })
+        .expect("failed to construct limits");
+        let client = Ipv4Addr::LOCALHOST.into();
+        let (req, admission) = limits
+            .apply(client, request(&[], "hello"))
+            .expect("request without length should be admitted");
+        assert!(req.into_body().collect().await.is_err());
+        assert_eq!(
+            admission.respond("hello").status(),
+            http::StatusCode::PAYLOAD_TOO_LARGE
+        );
+
+        let (req, admission) = limits
+            .apply(client, request(&[], "hell"))
+            .expect("request without length should be admitted");
+        assert!(req.into_body().collect().await.is_ok());
+        assert_eq!(admission.respond("hello").status(), http::StatusCode::OK);
+    }
+}
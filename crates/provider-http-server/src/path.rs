@@ -13,7 +13,6 @@ use std::sync::Arc;
 use anyhow::{bail, Context as _};
 use axum::extract::{self};
 use axum::handler::Handler;
-use axum_server::tls_rustls::RustlsConfig;
 use axum_server::Handle;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
@@ -21,6 +20,8 @@ use tracing::{debug, error, info, instrument};
 use wasmcloud_provider_sdk::provider::WrpcClient;
 use wasmcloud_provider_sdk::{get_connection, HostData, LinkConfig, LinkDeleteInfo, Provider};
 
+use crate::limits::RequestLimits;
+use crate::tls::TlsConfig;
 use crate::{
     build_request, get_cors_layer, get_tcp_listener, invoke_component, load_settings,
     ServiceSettings,
@@ -31,7 +32,7 @@ use crate::{
 #[derive(Default)]
 struct Router {
     /// Lookup from a path to the component ID that is handling that path
-    paths: HashMap<Arc<str>, (Arc<str>, WrpcClient)>,
+    paths: HashMap<Arc<str>, (Arc<str>, WrpcClient, Arc<RequestLimits>)>,
     /// Reverse lookup to find the path for a (component,link_name) pair
     components: HashMap<(Arc<str>, Arc<str>), Arc<str>>,
 }
@@ -74,22 +75,25 @@ impl HttpServerProvider {
             "httpserver starting listener in path-based mode",
         );
         let cors = get_cors_layer(&settings)?;
+        let limits = RequestLimits::new(&settings).context("failed to construct request limits")?;
+        let limits = Arc::new(limits);
         let listener = get_tcp_listener(&settings)?;
         let service = handle_request.layer(cors);
 
         let handle = axum_server::Handle::new();
         let task_handle = handle.clone();
         let task_router = Arc::clone(&path_router);
-        let task = if let (Some(crt), Some(key)) =
-            (&settings.tls_cert_file, &settings.tls_priv_key_file)
+        let task = if let Some(tls) =
+            TlsConfig::load(&settings).context("failed to construct TLS config")?
         {
             debug!(?addr, "bind HTTPS listener");
-            let tls = RustlsConfig::from_pem_file(crt, key)
-                .await
-                .context("failed to construct TLS config")?;
 
             tokio::spawn(async move {
-                if let Err(e) = axum_server::from_tcp_rustls(listener, tls)
+                let acceptor = tls.acceptor();
+                // Keep reloading the TLS config for as long as the server is running
+                let _tls = tls;
+                if let Err(e) = axum_server::from_tcp(listener)
+                    .acceptor(acceptor)
                     .handle(task_handle)
                     .serve(
                         service
@@ -97,8 +101,9 @@ impl HttpServerProvider {
                                 router: task_router,
                                 scheme: http::uri::Scheme::HTTPS,
                                 settings: Arc::clone(&settings),
+                                limits,
                             })
-                            .into_make_service(),
+                            .into_make_service_with_connect_info::<SocketAddr>(),
                     )
                     .await
                 {
@@ -117,8 +122,9 @@ impl HttpServerProvider {
                                 router: task_router,
                                 scheme: http::uri::Scheme::HTTP,
                                 settings: Arc::clone(&settings),
+                                limits,
                             })
-                            .into_make_service(),
+                            .into_make_service_with_connect_info::<SocketAddr>(),
                     )
                     .await
                 {
@@ -171,11 +177,17 @@ impl Provider for HttpServerProvider {
             .get_wrpc_client(link_config.target_id)
             .await
             .context("failed to construct wRPC client")?;
+        let limits = load_settings(None, link_config.config)
+            .context("failed to load path settings")
+            .and_then(|settings| RequestLimits::new(&settings))
+            .context("failed to construct request limits for path")?;
 
         let path = Arc::from(path.clone());
         // Insert the path into the paths map for future lookups
         path_router.components.insert(key, Arc::clone(&path));
-        path_router.paths.insert(path, (target, wrpc));
+        path_router
+            .paths
+            .insert(path, (target, wrpc, Arc::new(limits)));
 
         Ok(())
     }
@@ -217,33 +229,41 @@ struct RequestContext {
     router: Arc<RwLock<Router>>,
     scheme: http::uri::Scheme,
     settings: Arc<ServiceSettings>,
+    /// Limits of the listener
+    limits: Arc<RequestLimits>,
 }
 
 /// Handle an HTTP request by looking up the component ID for the path and invoking the component
-#[instrument(level = "debug", skip(router, settings))]
+#[instrument(level = "debug", skip(router, settings, limits))]
 async fn handle_request(
     extract::State(RequestContext {
         router,
         scheme,
         settings,
+        limits,
     }): extract::State<RequestContext>,
+    extract::ConnectInfo(client): extract::ConnectInfo<SocketAddr>,
     axum_extra::extract::Host(authority): axum_extra::extract::Host,
     request: extract::Request,
 ) -> impl axum::response::IntoResponse {
+    let (request, listener_admission) = limits.apply(client.ip(), request).map_err(|err| *err)?;
     let timeout = settings.timeout_ms.map(Duration::from_millis);
     let req = build_request(request, scheme, authority, &settings).map_err(|err| *err)?;
     let path = req.uri().path();
-    let Some((target_component, wrpc)) = router.read().await.paths.get(path).cloned() else {
+    let Some((target_component, wrpc, route_limits)) = router.read().await.paths.get(path).cloned()
+    else {
         Err((http::StatusCode::NOT_FOUND, "path not found"))?
     };
+    let (req, route_admission) = route_limits.apply(client.ip(), req).map_err(|err| *err)?;
+    let res = invoke_component(
+        &wrpc,
+        &target_component,
+        req,
+        timeout,
+        settings.cache_control.as_ref(),
+    )
+    .await;
     axum::response::Result::<_, axum::response::ErrorResponse>::Ok(
-        invoke_component(
-            &wrpc,
-            &target_component,
-            req,
-            timeout,
-            settings.cache_control.as_ref(),
-        )
-        .await,
+        listener_admission.respond(route_admission.respond(res)),
     )
 }
@@ -0,0 +1,240 @@
+//! This module contains the TLS configuration of the HTTP server in all routing modes.
+//!
+//! TLS configuration is reloaded whenever any of the certificate, key or client CA files change,
+//! without restarting the listener. If a client CA is configured, clients authenticate with mutual
+//! TLS and the identity of their verified certificate is forwarded to components in request
+//! headers (see [`CLIENT_CERT_SUBJECT_HEADER`] and [`CLIENT_CERT_SAN_HEADER`]).
+
+use core::time::Duration;
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use anyhow::Context as _;
+use axum_server::accept::Accept;
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use futures::future::BoxFuture;
+use rustls::server::WebPkiClientVerifier;
+use rustls::ServerConfig;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::task::JoinHandle;
+use tokio_rustls::server::TlsStream;
+use tower_http::add_extension::AddExtension;
+use tracing::{debug, info, warn};
+use wasmcloud_core::http::{ServiceSettings, TlsClientAuth};
+use x509_parser::extensions::GeneralName;
+
+/// Header containing the subject of the verified client certificate
+pub(crate) const CLIENT_CERT_SUBJECT_HEADER: &str = "x-client-cert-subject";
+
+/// Header containing the comma-separated subject alternative names of the verified client
+/// certificate, formatted as `DNS:<name>`, `IP:<address>`, `email:<address>` or `URI:<uri>`
+pub(crate) const CLIENT_CERT_SAN_HEADER: &str = "x-client-cert-san";
+
+/// Interval at which the TLS files of a listener are checked for changes
+const TLS_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Identity of the verified certificate a client presented, added as an extension to each
+/// request on the connection
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ClientCertificate {
+    pub(crate) subject: String,
+    pub(crate) sans: Vec<String>,
+}
+
+impl ClientCertificate {
+    /// Parse the identity of a DER-encoded X.509 certificate
+    fn parse(der: &[u8]) -> anyhow::Result<Self> {
+        let (_, cert) =
+            x509_parser::parse_x509_certificate(der).context("failed to parse certificate")?;
+        let sans = cert
+            .subject_alternative_name()
+            .context("failed to parse subject alternative names")?
+            .map(|san| {
+                san.value
+                    .general_names
+                    .iter()
+                    .filter_map(|name| match name {
+                        GeneralName::DNSName(name) => Some(format!("DNS:{name}")),
+                        GeneralName::RFC822Name(name) => Some(format!("email:{name}")),
+                        GeneralName::URI(uri) => Some(format!("URI:{uri}")),
+                        GeneralName::IPAddress(ip) => <[u8; 4]>::try_from(*ip)
+                            .map(IpAddr::from)
+                            .or_else(|_| <[u8; 16]>::try_from(*ip).map(IpAddr::from))
+                            .ok()
+                            .map(|ip| format!("IP:{ip}")),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Self {
+            subject: cert.subject().to_string(),
+            sans,
+        })
+    }
+}
+
+/// TLS configuration of a listener, which is reloaded in the background as long as it is alive
+pub(crate) struct TlsConfig {
+    config: RustlsConfig,
+    reload: JoinHandle<()>,
+}
+
+impl Drop for TlsConfig {
+    fn drop(&mut self) {
+        self.reload.abort();
+    }
+}
+
+impl TlsConfig {
+    /// Load the TLS configuration of a listener from [`ServiceSettings`], returning `None` if TLS
+    /// is not configured
+    pub(crate) fn load(settings: &ServiceSettings) -> anyhow::Result<Option<Self>> {
+        let (Some(_), Some(_)) = (&settings.tls_cert_file, &settings.tls_priv_key_file) else {
+            return Ok(None);
+        };
+        let mut modified = files_modified(settings);
+        let config = RustlsConfig::from_config(Arc::new(server_config(settings)?));
+        let reload = tokio::spawn({
+            let config = config.clone();
+            let settings = settings.clone();
+            async move {
+                let mut interval = tokio::time::interval(TLS_RELOAD_INTERVAL);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                loop {
+                    interval.tick().await;
+                    let latest = files_modified(&settings);
+                    if latest == modified {
+                        continue;
+                    }
+                    match server_config(&settings) {
+                        Ok(server_config) => {
+                            info!(address = %settings.address, "reloaded TLS configuration");
+                            config.reload_from_config(Arc::new(server_config));
+                            modified = latest;
+                        }
+                        // Files may be updated one by one, so keep retrying until they are consistent
+                        Err(err) => warn!(
+                            ?err,
+                            address = %settings.address,
+                            "failed to reload TLS configuration, keeping the current one"
+                        ),
+                    }
+                }
+            }
+        });
+        Ok(Some(Self { config, reload }))
+    }
+
+    /// Returns an acceptor for TLS connections to the listener
+    pub(crate) fn acceptor(&self) -> ClientCertAcceptor {
+        ClientCertAcceptor(RustlsAcceptor::new(self.config.clone()))
+    }
+}
+
+/// [`Accept`] implementation terminating TLS and adding the [`ClientCertificate`] verified
+/// during the handshake, if any, to each request on the connection
+#[derive(Clone)]
+pub(crate) struct ClientCertAcceptor(RustlsAcceptor);
+
+impl<I, S> Accept<I, S> for ClientCertAcceptor
+where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = TlsStream<I>;
+    type Service = AddExtension<S, Option<ClientCertificate>>;
+    type Future = BoxFuture<'static, io::Result<(Self::Stream, Self::Service)>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let acceptor = self.0.clone();
+        Box::pin(async move {
+            let (stream, service) = Accept::<I, S>::accept(&acceptor, stream, service).await?;
+            // Certificates are only present if they were verified against the client CA
+            let client_cert = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(<[_]>::first)
+                .and_then(|cert| match ClientCertificate::parse(cert) {
+                    Ok(cert) => Some(cert),
+                    Err(err) => {
+                        debug!(?err, "failed to parse verified client certificate");
+                        None
+                    }
+                });
+            Ok((stream, AddExtension::new(service, client_cert)))
+        })
+    }
+}
+
+/// Returns the modification times of the TLS files configured in `settings`
+fn files_modified(settings: &ServiceSettings) -> Vec<Option<SystemTime>> {
+    [
+        &settings.tls_cert_file,
+        &settings.tls_priv_key_file,
+        &settings.tls_client_ca_file,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|path| {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    })
+    .collect()
+}
+
+/// Construct a rustls [`ServerConfig`] from the TLS files configured in `settings`
+fn server_config(settings: &ServiceSettings) -> anyhow::Result<ServerConfig> {
+    let (Some(cert_file), Some(key_file)) = (&settings.tls_cert_file, &settings.tls_priv_key_file)
+    else {
+        anyhow::bail!("both 'tls_cert_file' and 'tls_priv_key_file' must be set");
+    };
+    let certs = rustls_pemfile::certs(&mut open(cert_file)?)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("failed to read certificates from `{cert_file}`"))?;
+    let key = rustls_pemfile::private_key(&mut open(key_file)?)
+        .with_context(|| format!("failed to read private key from `{key_file}`"))?
+        .with_context(|| format!("no private key found in `{key_file}`"))?;
+
+    let builder = ServerConfig::builder();
+    let builder = if let Some(ca_file) = &settings.tls_client_ca_file {
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut open(ca_file)?) {
+            let cert =
+                cert.with_context(|| format!("failed to read certificates from `{ca_file}`"))?;
+            roots
+                .add(cert)
+                .with_context(|| format!("invalid client CA certificate in `{ca_file}`"))?;
+        }
+        let verifier = WebPkiClientVerifier::builder(Arc::new(roots));
+        let verifier = match settings.tls_client_auth.unwrap_or_default() {
+            TlsClientAuth::Required => verifier,
+            TlsClientAuth::Optional => verifier.allow_unauthenticated(),
+        }
+        .build()
+        .context("failed to construct client certificate verifier")?;
+        builder.with_client_cert_verifier(verifier)
+    } else {
+        builder.with_no_client_auth()
+    };
+    let mut config = builder
+        .with_single_cert(certs, key)
+        .context("failed to construct TLS config")?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// Open a PEM file for reading
+fn open(path: impl AsRef<Path>) -> anyhow::Result<BufReader<File>> {
+    let path = path.as_ref();
+    File::open(path)
+        .map(BufReader::new)
+        .with_context(|| format!("failed to open `{}`", path.display()))
+}
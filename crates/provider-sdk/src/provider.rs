@@ -574,8 +574,53 @@ pub async fn receive_link_for_provider<P>(
 where
     P: Provider,
 {
-    match if ld.source_id == *connection.provider_id {
-        provider
+    match pass_link_config(
+        provider,
+        &connection.provider_id,
+        &connection.provider_xkey,
+        &connection.host_xkey,
+        &ld,
+    )
+    .await?
+    {
+        Ok(()) => connection.put_link(ld).await,
+        Err(e) => {
+            warn!(error = %e, "receiving link failed");
+        }
+    };
+    Ok(())
+}
+
+/// Appropriately receive a link (depending on if it's source/target) for a provider identified by
+/// `provider_id`, without a [`ProviderConnection`]. This is used by hosts running providers
+/// in-process.
+pub async fn receive_link<P>(
+    provider: &P,
+    provider_id: &str,
+    provider_xkey: &XKey,
+    host_xkey: &XKey,
+    ld: &InterfaceLinkDefinition,
+) -> Result<()>
+where
+    P: Provider,
+{
+    pass_link_config(provider, provider_id, provider_xkey, host_xkey, ld).await?
+}
+
+/// Pass the configuration and decrypted secrets of the link `ld` to `provider`. The outer error
+/// indicates an invalid link, the inner one a failure of the provider to handle it.
+async fn pass_link_config<P>(
+    provider: &P,
+    provider_id: &str,
+    provider_xkey: &XKey,
+    host_xkey: &XKey,
+    ld: &InterfaceLinkDefinition,
+) -> Result<Result<()>>
+where
+    P: Provider,
+{
+    if ld.source_id == provider_id {
+        Ok(provider
             .receive_link_config_as_source(LinkConfig {
                 source_id: &ld.source_id,
                 target_id: &ld.target,
@@ -583,14 +628,14 @@ where
                 config: &ld.source_config,
                 secrets: &decrypt_link_secret(
                     ld.source_secrets.as_deref(),
-                    &connection.provider_xkey,
-                    &connection.host_xkey,
+                    provider_xkey,
+                    host_xkey,
                 )?,
                 wit_metadata: (&ld.wit_namespace, &ld.wit_package, &ld.interfaces),
             })
-            .await
-    } else if ld.target == *connection.provider_id {
-        provider
+            .await)
+    } else if ld.target == provider_id {
+        Ok(provider
             .receive_link_config_as_target(LinkConfig {
                 source_id: &ld.source_id,
                 target_id: &ld.target,
@@ -598,21 +643,15 @@ where
                 config: &ld.target_config,
                 secrets: &decrypt_link_secret(
                     ld.target_secrets.as_deref(),
-                    &connection.provider_xkey,
-                    &connection.host_xkey,
+                    provider_xkey,
+                    host_xkey,
                 )?,
                 wit_metadata: (&ld.wit_namespace, &ld.wit_package, &ld.interfaces),
             })
-            .await
+            .await)
     } else {
         bail!("received link put where provider was neither source nor target");
-    } {
-        Ok(()) => connection.put_link(ld).await,
-        Err(e) => {
-            warn!(error = %e, "receiving link failed");
-        }
-    };
-    Ok(())
+    }
 }
 
 /// Given a serialized and encrypted [`HashMap<String, SecretValue>`], decrypts the secrets and deserializes
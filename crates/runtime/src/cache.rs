@@ -0,0 +1,299 @@
+use core::hash::{Hash, Hasher};
+
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::Context as _;
+use sha2::{Digest as _, Sha256};
+use tracing::{debug, instrument, trace, warn};
+
+/// Default maximum size of a [`CompilationCache`] directory (1 GiB)
+pub const DEFAULT_COMPILATION_CACHE_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// File extension of compiled component artifacts
+const ARTIFACT_EXTENSION: &str = "cwasm";
+
+/// Content-addressed, size-capped, on-disk cache of precompiled components.
+///
+/// Artifacts are keyed by the digest of the Wasm binary and the compatibility hash of the
+/// [`wasmtime::Engine`] compiling it, which covers both the engine configuration and the wasmtime
+/// version, so a change to either results in a cache miss rather than loading an incompatible
+/// artifact. Artifacts are written atomically and entries that fail to load are removed and
+/// recompiled. Once the total size of the cache exceeds the configured maximum, the least recently
+/// used artifacts are evicted.
+///
+/// Artifacts are loaded without verification of their contents, so the cache directory must only
+/// be writable by the host.
+#[derive(Clone, Debug)]
+pub struct CompilationCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+/// Feeds [`Hash`] input into a SHA-256 digest
+struct DigestHasher(Sha256);
+
+impl Hasher for DigestHasher {
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+        let mut buf = [0; 8];
+        buf.copy_from_slice(&digest[..8]);
+        u64::from_le_bytes(buf)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+}
+
+impl CompilationCache {
+    /// Returns a new [`CompilationCache`] storing at most `max_size` bytes of artifacts in `dir`,
+    /// which is created if it does not exist
+    ///
+    /// # Errors
+    ///
+    /// Fails if `dir` cannot be created
+    pub fn new(dir: impl Into<PathBuf>, max_size: u64) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).with_context(|| {
+            format!(
+                "failed to create compilation cache directory `{}`",
+                dir.display()
+            )
+        })?;
+        Ok(Self { dir, max_size })
+    }
+
+    /// Returns the directory artifacts are stored in
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the maximum size of all artifacts in the cache, in bytes
+    #[must_use]
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Removes all artifacts from the cache
+    ///
+    /// # Errors
+    ///
+    /// Fails if the cache directory cannot be read or an artifact cannot be removed
+    pub fn clear(&self) -> anyhow::Result<()> {
+        for (path, ..) in self.artifacts()? {
+            fs::remove_file(&path)
+                .with_context(|| format!("failed to remove `{}`", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the compiled component for `wasm`, loading it from the cache if present and
+    /// compiling and storing it otherwise. Failures to read or write the cache are logged and
+    /// fall back to compilation.
+    #[instrument(level = "debug", skip_all)]
+    pub(crate) fn get_or_compile(
+        &self,
+        engine: &wasmtime::Engine,
+        wasm: &[u8],
+    ) -> anyhow::Result<wasmtime::component::Component> {
+        let path = self.artifact_path(engine, wasm);
+        if path.exists() {
+            // SAFETY: artifacts are only ever written by `Engine::precompile_component` below and
+            // the cache directory is trusted not to be modified by anything else. Artifacts
+            // produced by an incompatible engine are rejected by `deserialize_file`.
+            match unsafe { wasmtime::component::Component::deserialize_file(engine, &path) } {
+                Ok(component) => {
+                    trace!(path = %path.display(), "loaded compiled component from cache");
+                    if let Err(err) = fs::File::options()
+                        .append(true)
+                        .open(&path)
+                        .and_then(|file| file.set_modified(SystemTime::now()))
+                    {
+                        debug!(?err, path = %path.display(), "failed to update artifact access time");
+                    }
+                    return Ok(component);
+                }
+                Err(err) => {
+                    warn!(?err, path = %path.display(), "failed to load cached component, recompiling");
+                    if let Err(err) = fs::remove_file(&path) {
+                        warn!(?err, path = %path.display(), "failed to remove invalid artifact");
+                    }
+                }
+            }
+        }
+
+        let artifact = engine
+            .precompile_component(wasm)
+            .context("failed to compile component")?;
+        if let Err(err) = self.store(&path, &artifact) {
+            warn!(?err, path = %path.display(), "failed to store compiled component in cache");
+        } else if let Err(err) = self.evict() {
+            warn!(?err, "failed to evict compiled components from cache");
+        }
+        // SAFETY: `artifact` was just produced by `Engine::precompile_component` using `engine`
+        unsafe { wasmtime::component::Component::deserialize(engine, &artifact) }
+            .context("failed to load compiled component")
+    }
+
+    fn artifact_path(&self, engine: &wasmtime::Engine, wasm: &[u8]) -> PathBuf {
+        let mut hasher = DigestHasher(Sha256::new());
+        engine.precompile_compatibility_hash().hash(&mut hasher);
+        hasher.0.update(wasm);
+        let key = hex::encode(hasher.0.finalize());
+        self.dir.join(format!("{key}.{ARTIFACT_EXTENSION}"))
+    }
+
+    /// Atomically writes `artifact` to `path`
+    fn store(&self, path: &Path, artifact: &[u8]) -> anyhow::Result<()> {
+        let mut file = tempfile::NamedTempFile::new_in(&self.dir)
+            .context("failed to create temporary artifact file")?;
+        file.write_all(artifact)
+            .context("failed to write artifact")?;
+        file.as_file()
+            .sync_all()
+            .context("failed to sync artifact")?;
+        file.persist(path).context("failed to persist artifact")?;
+        Ok(())
+    }
+
+    /// Returns the path, size and modification time of all artifacts in the cache
+    fn artifacts(&self) -> anyhow::Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut artifacts = Vec::new();
+        for entry in fs::read_dir(&self.dir).with_context(|| {
+            format!(
+                "failed to read compilation cache directory `{}`",
+                self.dir.display()
+            )
+        })? {
+            let entry = entry.context("failed to read compilation cache entry")?;
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(ARTIFACT_EXTENSION) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                // The artifact may have been removed concurrently
+                continue;
+            };
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            artifacts.push((path, metadata.len(), modified));
+        }
+        Ok(artifacts)
+    }
+
+    /// Removes the least recently used artifacts until the cache size is within the maximum
+    fn evict(&self) -> anyhow::Result<()> {
+        let mut artifacts = self.artifacts()?;
+        let mut size: u64 = artifacts.iter().map(|(_, len, _)| len).sum();
+        if size <= self.max_size {
+            return Ok(());
+        }
+        artifacts.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in artifacts {
+            if size <= self.max_size {
+                break;
+            }
+            debug!(path = %path.display(), "evicting compiled component from cache");
+            match fs::remove_file(&path) {
+                Ok(()) => size = size.saturating_sub(len),
+                Err(err) => warn!(?err, path = %path.display(), "failed to evict artifact"),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::time::Duration;
+
+    /// Returns an empty component with a custom section named `name`, so that distinct components
+    /// can be generated without a compiler
+    fn component(name: &str) -> Vec<u8> {
+        let mut wasm = b"\0asm\x0d\x00\x01\x00".to_vec();
+        let name_len = u8::try_from(name.len()).expect("name too long");
+        wasm.push(0);
+        wasm.push(name_len + 1);
+        wasm.push(name_len);
+        wasm.extend(name.as_bytes());
+        wasm
+    }
+
+    fn set_modified(path: &Path, modified: SystemTime) {
+        fs::File::options()
+            .append(true)
+            .open(path)
+            .and_then(|file| file.set_modified(modified))
+            .expect("failed to set artifact modification time");
+    }
+
+    #[test]
+    fn caches_compiled_components() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let engine = wasmtime::Engine::default();
+        let cache = CompilationCache::new(dir.path(), DEFAULT_COMPILATION_CACHE_SIZE)?;
+
+        cache.get_or_compile(&engine, &component("a"))?;
+        cache.get_or_compile(&engine, &component("a"))?;
+        assert_eq!(cache.artifacts()?.len(), 1);
+        cache.get_or_compile(&engine, &component("b"))?;
+        assert_eq!(cache.artifacts()?.len(), 2);
+
+        // Invalid artifacts are replaced by a recompiled component
+        let path = cache.artifact_path(&engine, &component("a"));
+        fs::write(&path, b"invalid")?;
+        cache.get_or_compile(&engine, &component("a"))?;
+        assert_ne!(fs::read(&path)?, b"invalid");
+        assert_eq!(cache.artifacts()?.len(), 2);
+
+        cache.clear()?;
+        assert!(cache.artifacts()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn evicts_least_recently_used_components() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let engine = wasmtime::Engine::default();
+        let cache = CompilationCache::new(dir.path(), DEFAULT_COMPILATION_CACHE_SIZE)?;
+
+        let now = SystemTime::now();
+        let mut sizes = Vec::new();
+        for (i, name) in ["a", "b", "c"].into_iter().enumerate() {
+            let wasm = component(name);
+            cache.get_or_compile(&engine, &wasm)?;
+            let path = cache.artifact_path(&engine, &wasm);
+            set_modified(&path, now - Duration::from_secs(60 * (3 - i as u64)));
+            sizes.push(fs::metadata(&path)?.len());
+        }
+        let path = |name| cache.artifact_path(&engine, &component(name));
+
+        // Loading an artifact marks it as most recently used
+        cache.get_or_compile(&engine, &component("a"))?;
+
+        // `b` is the least recently used artifact and is evicted first
+        let limited = CompilationCache::new(dir.path(), sizes[0] + sizes[2])?;
+        limited.evict()?;
+        assert!(path("a").exists());
+        assert!(!path("b").exists());
+        assert!(path("c").exists());
+
+        // Compiling a component evicts artifacts until the cache fits its maximum size
+        set_modified(&path("a"), now - Duration::from_secs(30));
+        let limited = CompilationCache::new(dir.path(), sizes[1])?;
+        limited.get_or_compile(&engine, &component("b"))?;
+        assert_eq!(limited.artifacts()?.len(), 1);
+        assert!(path("b").exists());
+
+        // Components are still compiled if the cache cannot hold any artifact
+        let limited = CompilationCache::new(dir.path(), 0)?;
+        limited.get_or_compile(&engine, &component("c"))?;
+        assert!(limited.artifacts()?.is_empty());
+        Ok(())
+    }
+}
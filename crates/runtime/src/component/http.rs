@@ -1,4 +1,9 @@
 use core::ops::Deref;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 
 use anyhow::{bail, Context as _};
 use futures::stream::StreamExt as _;
@@ -16,7 +21,10 @@ use wrpc_interface_http::ServeIncomingHandlerWasmtime;
 
 use crate::capability::http::types;
 
-use super::{new_store, Ctx, Handler, Instance, ReplacedInstanceTarget, WrpcServeEvent};
+use super::{
+    invocation_fuel, new_store, Component, Ctx, FuelMeter, Handler, Instance, MinimalHandler,
+    PoolStats, ReplacedInstanceTarget, WrpcServeEvent,
+};
 
 pub mod incoming_http_bindings {
     wasmtime::component::bindgen!({
@@ -29,6 +37,153 @@ pub mod incoming_http_bindings {
     });
 }
 
+/// Store and bindings of an instance serving `wasi:http/incoming-handler`
+type IncomingHttpInstance<H> = (
+    wasmtime::Store<Ctx<H>>,
+    incoming_http_bindings::IncomingHttp,
+);
+
+/// Instantiate a fresh instance serving `wasi:http/incoming-handler`
+async fn instantiate_incoming_http<H: Handler>(
+    engine: &wasmtime::Engine,
+    pre: &incoming_http_bindings::IncomingHttpPre<Ctx<H>>,
+    handler: H,
+    max_execution_time: Duration,
+    fuel: Option<&FuelMeter>,
+) -> anyhow::Result<IncomingHttpInstance<H>> {
+    let mut store = new_store(engine, handler, max_execution_time, fuel);
+    trace!("instantiating `wasi:http/incoming-handler`");
+    let bindings = pre
+        .instantiate_async(&mut store)
+        .instrument(debug_span!("instantiate_async"))
+        .await
+        .context("failed to instantiate `wasi:http/incoming-handler`")?;
+    Ok((store, bindings))
+}
+
+/// Bounded pool of pre-instantiated instances serving `wasi:http/incoming-handler`.
+///
+/// Instances are instantiated in the background ahead of invocations, so that initialization
+/// logic of the component does not add latency to them. Each instance serves a single invocation
+/// and is dropped afterwards, exactly like an instance instantiated on demand, so invocations
+/// remain isolated from each other.
+pub(super) struct IncomingHttpPool<H: MinimalHandler> {
+    engine: wasmtime::Engine,
+    pre: incoming_http_bindings::IncomingHttpPre<Ctx<H>>,
+    handler: H,
+    max_execution_time: Duration,
+    fuel: Option<FuelMeter>,
+    /// Maximum number of ready and pending instances
+    size: usize,
+    ready: Mutex<VecDeque<IncomingHttpInstance<H>>>,
+    /// Number of instances being instantiated in the background
+    pending: AtomicUsize,
+    stats: Arc<PoolStats>,
+}
+
+impl<H: Handler> IncomingHttpPool<H> {
+    /// Construct an empty pool of at most `prewarm_instances` instances of `component` using
+    /// `handler`, see [Limits::prewarm_instances](super::Limits::prewarm_instances)
+    pub(super) fn new(component: &Component<H>, handler: H) -> anyhow::Result<Arc<Self>> {
+        let pre = incoming_http_bindings::IncomingHttpPre::new(component.instance_pre.clone())
+            .context("failed to pre-instantiate `wasi:http/incoming-handler`")?;
+        Ok(Arc::new(Self {
+            engine: component.engine.clone(),
+            pre,
+            handler,
+            max_execution_time: component.max_execution_time,
+            fuel: component.fuel.clone(),
+            size: component.prewarm_instances,
+            ready: Mutex::default(),
+            pending: AtomicUsize::default(),
+            stats: Arc::clone(&component.pool_stats),
+        }))
+    }
+
+    /// Start instantiating instances in the background until the pool is full
+    pub(super) fn replenish(self: &Arc<Self>) {
+        loop {
+            let pending = self.pending.load(Ordering::Acquire);
+            let ready = self
+                .ready
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .len();
+            if ready.saturating_add(pending) >= self.size {
+                return;
+            }
+            if self
+                .pending
+                .compare_exchange(pending, pending + 1, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+            {
+                continue;
+            }
+            let pool = Arc::clone(self);
+            spawn(
+                async move {
+                    match instantiate_incoming_http(
+                        &pool.engine,
+                        &pool.pre,
+                        pool.handler.clone(),
+                        pool.max_execution_time,
+                        pool.fuel.as_ref(),
+                    )
+                    .await
+                    {
+                        Ok(instance) => pool
+                            .ready
+                            .lock()
+                            .unwrap_or_else(std::sync::PoisonError::into_inner)
+                            .push_back(instance),
+                        Err(err) => warn!(?err, "failed to pre-instantiate component"),
+                    }
+                    pool.pending.fetch_sub(1, Ordering::AcqRel);
+                }
+                .in_current_span(),
+            );
+        }
+    }
+
+    /// Take a pre-instantiated instance from the pool, instantiating one if the pool is empty
+    async fn get(self: &Arc<Self>) -> anyhow::Result<IncomingHttpInstance<H>> {
+        let ready = self
+            .ready
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .pop_front();
+        self.replenish();
+        if let Some((mut store, bindings)) = ready {
+            self.stats.hits.fetch_add(1, Ordering::Relaxed);
+            // The execution time of the invocation starts once the instance is taken
+            store.set_epoch_deadline(self.max_execution_time.as_secs());
+            if let Some(fuel) = &self.fuel {
+                // Account for the fuel consumed by instantiation and make the full limit
+                // available to the invocation
+                let remaining = store.get_fuel().unwrap_or(fuel.limit);
+                let consumed = fuel.limit.saturating_sub(remaining);
+                fuel.consumed.fetch_add(consumed, Ordering::Relaxed);
+                store
+                    .set_fuel(fuel.limit)
+                    .context("failed to refill store fuel")?;
+                let data = store.data_mut();
+                data.fuel_remaining = fuel.limit;
+                data.fuel_refilled = data.fuel_refilled.saturating_add(consumed);
+            }
+            return Ok((store, bindings));
+        }
+        self.stats.misses.fetch_add(1, Ordering::Relaxed);
+        instantiate_incoming_http(
+            &self.engine,
+            &self.pre,
+            self.handler.clone(),
+            self.max_execution_time,
+            self.fuel.as_ref(),
+        )
+        .await
+    }
+}
+
 #[instrument(level = "debug", skip_all)]
 async fn invoke_outgoing_handle<H>(
     handler: H,
@@ -168,7 +323,7 @@ where
 impl<H, C> ServeIncomingHandlerWasmtime<C> for Instance<H, C>
 where
     H: Handler,
-    C: Send + Deref<Target = tracing::Span>,
+    C: Send + Deref<Target = tracing::Span> + 'static,
 {
     #[instrument(level = "debug", skip_all)]
     async fn handle(
@@ -187,15 +342,20 @@ where
         let scheme = wrpc_interface_http::bindings::wrpc::http::types::Scheme::from(scheme).into();
 
         let (tx, rx) = oneshot::channel();
-        let mut store = new_store(&self.engine, self.handler.clone(), self.max_execution_time);
-        let pre = incoming_http_bindings::IncomingHttpPre::new(self.pre.clone())
-            .context("failed to pre-instantiate `wasi:http/incoming-handler`")?;
-        trace!("instantiating `wasi:http/incoming-handler`");
-        let bindings = pre
-            .instantiate_async(&mut store)
-            .instrument(debug_span!("instantiate_async"))
-            .await
-            .context("failed to instantiate `wasi:http/incoming-handler`")?;
+        let (mut store, bindings) = if let Some(pool) = &self.http_pool {
+            pool.get().await?
+        } else {
+            let pre = incoming_http_bindings::IncomingHttpPre::new(self.pre.clone())
+                .context("failed to pre-instantiate `wasi:http/incoming-handler`")?;
+            instantiate_incoming_http(
+                &self.engine,
+                &pre,
+                self.handler.clone(),
+                self.max_execution_time,
+                self.fuel.as_ref(),
+            )
+            .await?
+        };
         let data = store.data_mut();
 
         // The below is adapted from `WasiHttpView::new_incoming_request`, which is unusable for
@@ -218,15 +378,22 @@ where
         // Set the current invocation parent context for injection on outgoing wRPC requests
         let call_incoming_handle = info_span!("call_http_incoming_handle");
         store.data_mut().parent_context = Some(call_incoming_handle.context());
+        // The fuel consumed by the invocation is only known once the handler returns, which may
+        // be well after the response is sent
+        let (fuel_tx, fuel_rx) = oneshot::channel();
         let handle = spawn(
             async move {
                 debug!("invoking `wasi:http/incoming-handler.handle`");
-                if let Err(err) = bindings
+                let res = bindings
                     .wasi_http_incoming_handler()
                     .call_handle(&mut store, request, response)
                     .instrument(call_incoming_handle)
-                    .await
-                {
+                    .await;
+                let fuel_consumed = invocation_fuel(&store);
+                // Account for the fuel in the component before reporting it
+                drop(store);
+                _ = fuel_tx.send(fuel_consumed);
+                if let Err(err) = res {
                     warn!(?err, "failed to call `wasi:http/incoming-handler.handle`");
                     bail!(err.context("failed to call `wasi:http/incoming-handler.handle`"));
                 }
@@ -261,18 +428,242 @@ where
         .in_current_span()
         .await;
         let success = res.as_ref().is_ok_and(Result::is_ok);
-        if let Err(err) = self
-            .events
-            .try_send(WrpcServeEvent::HttpIncomingHandlerHandleReturned {
+        let events = self.events.clone();
+        let send_event = move |fuel_consumed| {
+            if let Err(err) = events.try_send(WrpcServeEvent::HttpIncomingHandlerHandleReturned {
                 context: cx,
                 success,
-            })
-        {
-            warn!(
-                ?err,
-                success, "failed to send `wasi:http/incoming-handler.handle` return event"
+                fuel_consumed,
+            }) {
+                warn!(
+                    ?err,
+                    success, "failed to send `wasi:http/incoming-handler.handle` return event"
+                );
+            }
+        };
+        if self.fuel.is_some() {
+            spawn(
+                async move {
+                    let fuel_consumed = fuel_rx.await.ok().flatten();
+                    send_event(fuel_consumed);
+                }
+                .in_current_span(),
             );
+        } else {
+            send_event(None);
         }
         res
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use tokio::sync::mpsc;
+
+    use crate::component::testing::NoopHandler;
+    use crate::component::{Component, Limits};
+    use crate::Runtime;
+
+    /// Returns a component exporting `wasi:http/incoming-handler`, which burns some fuel on
+    /// instantiation and traps if `handle` is called more than once on the same instance
+    fn component() -> Vec<u8> {
+        wat::parse_str(
+            r#"(component
+                (import "wasi:http/types@0.2.3" (instance $types
+                    (export "incoming-request" (type (sub resource)))
+                    (export "response-outparam" (type (sub resource)))
+                ))
+                (alias export $types "incoming-request" (type $request))
+                (alias export $types "response-outparam" (type $response-outparam))
+                (core module $m
+                    (global $i (mut i32) (i32.const 0))
+                    (global $handled (mut i32) (i32.const 0))
+                    (func $init
+                        (loop $loop
+                            (global.set $i (i32.add (global.get $i) (i32.const 1)))
+                            (br_if $loop (i32.lt_u (global.get $i) (i32.const 1000)))
+                        )
+                    )
+                    (start $init)
+                    (func (export "handle") (param i32 i32)
+                        (if (global.get $handled) (then unreachable))
+                        (global.set $handled (i32.const 1))
+                    )
+                )
+                (core instance $i (instantiate $m))
+                (func $handle
+                    (param "request" (own $request))
+                    (param "response-out" (own $response-outparam))
+                    (canon lift (core func $i "handle"))
+                )
+                (instance $handler (export "handle" (func $handle)))
+                (export "wasi:http/incoming-handler@0.2.3" (instance $handler))
+            )"#,
+        )
+        .expect("failed to parse component")
+    }
+
+    fn instantiate(
+        rt: &Runtime,
+        prewarm_instances: usize,
+    ) -> anyhow::Result<(
+        Component<NoopHandler>,
+        Instance<NoopHandler, Box<Span>>,
+        Arc<IncomingHttpPool<NoopHandler>>,
+    )> {
+        let component = Component::new(
+            rt,
+            &component(),
+            Some(Limits {
+                max_memory_limit: None,
+                max_execution_time: None,
+                max_fuel: None,
+                prewarm_instances: Some(prewarm_instances),
+            }),
+        )?;
+        let (events, _) = mpsc::channel(16);
+        let mut instance = component.instantiate(NoopHandler, events);
+        let pool = IncomingHttpPool::new(&component, NoopHandler)?;
+        instance.http_pool = Some(Arc::clone(&pool));
+        Ok((component, instance, pool))
+    }
+
+    /// Waits for the pool to hold `n` ready instances
+    async fn wait_ready(pool: &IncomingHttpPool<NoopHandler>, n: usize) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while pool
+                .ready
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .len()
+                < n
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("pool was not replenished");
+    }
+
+    #[tokio::test]
+    async fn pool_hits_and_misses() -> anyhow::Result<()> {
+        let (rt, _) = Runtime::new()?;
+        let (component, _instance, pool) = instantiate(&rt, 2)?;
+
+        // The pool starts empty, a miss instantiates on demand and starts refilling the pool
+        pool.get().await?;
+        assert_eq!(component.take_instance_pool_stats(), Some((0, 1)));
+        wait_ready(&pool, 2).await;
+
+        // Taken instances are replaced in the background
+        pool.get().await?;
+        pool.get().await?;
+        assert_eq!(component.take_instance_pool_stats(), Some((2, 0)));
+        wait_ready(&pool, 2).await;
+        assert_eq!(pool.pending.load(Ordering::Acquire), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pool_instances_serve_single_invocation() -> anyhow::Result<()> {
+        let (rt, _) = Runtime::new()?;
+        let (component, instance, pool) = instantiate(&rt, 1)?;
+        pool.replenish();
+
+        for _ in 0..3 {
+            wait_ready(&pool, 1).await;
+            let request = http::Request::get("http://localhost/").body(Default::default())?;
+            // The component does not set a response, but it traps if an instance is reused
+            let err = instance
+                .handle(Box::new(Span::current()), request)
+                .await
+                .expect_err("component should not set a response");
+            assert_eq!(
+                err.to_string(),
+                "component did not call `response-outparam::set`"
+            );
+        }
+        assert_eq!(component.take_instance_pool_stats(), Some((3, 0)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn instantiate_attaches_prewarmed_pool() -> anyhow::Result<()> {
+        let (rt, _) = Runtime::new()?;
+        let (mut component, _, _) = instantiate(&rt, 1)?;
+        let (events, _) = mpsc::channel::<WrpcServeEvent<Box<Span>>>(16);
+        assert!(component
+            .instantiate(NoopHandler, events.clone())
+            .http_pool
+            .is_none());
+
+        component.prewarm(NoopHandler)?;
+        let pool = component
+            .instantiate(NoopHandler, events)
+            .http_pool
+            .expect("pool should be attached to instances");
+        wait_ready(&pool, 1).await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pool_refills_fuel() -> anyhow::Result<()> {
+        let (rt, _) = Runtime::builder().max_fuel(1_000_000).build()?;
+        let (component, _instance, pool) = instantiate(&rt, 1)?;
+        pool.replenish();
+        wait_ready(&pool, 1).await;
+
+        let (store, _) = pool.get().await?;
+        assert_eq!(store.get_fuel()?, 1_000_000);
+        assert_eq!(store.data().fuel_remaining, 1_000_000);
+        // Fuel consumed by instantiation is still accounted for
+        let instantiation = component
+            .take_fuel_consumed()
+            .expect("fuel should be metered");
+        assert!(instantiation > 0);
+        assert_eq!(invocation_fuel(&store), Some(instantiation));
+        drop(store);
+        assert_eq!(component.take_fuel_consumed(), Some(0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn invocation_fuel_is_reported_with_event() -> anyhow::Result<()> {
+        let (rt, _) = Runtime::builder().max_fuel(1_000_000).build()?;
+        let (component, _, pool) = instantiate(&rt, 1)?;
+        let (events, mut events_rx) = mpsc::channel(16);
+        let mut instance = component.instantiate(NoopHandler, events);
+        instance.http_pool = Some(Arc::clone(&pool));
+        pool.replenish();
+
+        for _ in 0..2 {
+            wait_ready(&pool, 1).await;
+            let request = http::Request::get("http://localhost/").body(Default::default())?;
+            instance
+                .handle(Box::new(Span::current()), request)
+                .await
+                .expect_err("component should not set a response");
+            let evt = tokio::time::timeout(Duration::from_secs(10), events_rx.recv())
+                .await
+                .context("timed out waiting for return event")?
+                .context("event channel closed")?;
+            let WrpcServeEvent::HttpIncomingHandlerHandleReturned {
+                success,
+                fuel_consumed,
+                ..
+            } = evt
+            else {
+                bail!("unexpected event");
+            };
+            assert!(!success);
+            // Each event reports the fuel of its own invocation, including instantiation
+            assert!(fuel_consumed.is_some_and(|consumed| consumed > 0));
+            assert_eq!(component.take_fuel_consumed(), fuel_consumed);
+        }
+        Ok(())
+    }
+}
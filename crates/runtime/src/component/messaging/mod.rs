@@ -5,7 +5,7 @@ use tracing::{instrument, warn, Span};
 use tracing_opentelemetry::OpenTelemetrySpanExt as _;
 
 use crate::capability::wrpc;
-use crate::component::{new_store, Handler, Instance, WrpcServeEvent};
+use crate::component::{invocation_fuel, new_store, Handler, Instance, WrpcServeEvent};
 
 pub mod v0_2;
 pub mod v0_3;
@@ -23,7 +23,12 @@ where
     ) -> anyhow::Result<Result<(), String>> {
         // Set the parent of the current context to the span passed in
         Span::current().set_parent(cx.deref().context());
-        let mut store = new_store(&self.engine, self.handler.clone(), self.max_execution_time);
+        let mut store = new_store(
+            &self.engine,
+            self.handler.clone(),
+            self.max_execution_time,
+            self.fuel.as_ref(),
+        );
 
         // If wasmcloud:messaging@0.3.0 is enabled and we can instantiate the 0.3.0 bindings,
         // handle the message using 0.3.0. Otherwise, use the 0.2.0 bindings.
@@ -47,6 +52,7 @@ where
                 .try_send(WrpcServeEvent::MessagingHandlerHandleMessageReturned {
                     context: cx,
                     success,
+                    fuel_consumed: invocation_fuel(&store),
                 })
         {
             warn!(
@@ -2,6 +2,7 @@ use core::fmt::{self, Debug};
 use core::future::Future;
 use core::ops::{Bound, Deref};
 use core::pin::Pin;
+use core::sync::atomic::{AtomicU64, Ordering};
 use core::time::Duration;
 
 use anyhow::Result;
@@ -54,6 +55,8 @@ mod keyvalue;
 mod logging;
 pub(crate) mod messaging;
 mod secrets;
+#[cfg(test)]
+pub(crate) mod testing;
 
 /// Instance target, which is replaced in wRPC
 ///
@@ -165,6 +168,14 @@ pub struct Limits {
     pub max_memory_limit: Option<usize>,
     /// Maximum execution time in seconds. None defaults to host runtime limits.
     pub max_execution_time: Option<u64>,
+    /// Maximum amount of fuel a single invocation may consume. None defaults to host runtime
+    /// limits. Setting this enables fuel metering for the component.
+    #[serde(default)]
+    pub max_fuel: Option<u64>,
+    /// Number of pre-instantiated instances kept ready to serve `wasi:http/incoming-handler`
+    /// invocations. None or zero disables pre-warming.
+    #[serde(default)]
+    pub prewarm_instances: Option<usize>,
 }
 impl Limits {
     /// Converts limits to a string-based key-value map for serialization.
@@ -186,6 +197,17 @@ impl Limits {
             map.insert("max_execution_time".to_string(), execution_time.to_string());
         }
 
+        if let Some(max_fuel) = self.max_fuel {
+            map.insert("max_fuel".to_string(), max_fuel.to_string());
+        }
+
+        if let Some(prewarm_instances) = self.prewarm_instances {
+            map.insert(
+                "prewarm_instances".to_string(),
+                prewarm_instances.to_string(),
+            );
+        }
+
         map
     }
 }
@@ -208,6 +230,10 @@ pub fn from_string_map<S: std::hash::BuildHasher>(
         max_memory_limit: map.get("max_memory_limit").and_then(|s| s.parse().ok()),
 
         max_execution_time: map.get("max_execution_time").and_then(|s| s.parse().ok()),
+
+        max_fuel: map.get("max_fuel").and_then(|s| s.parse().ok()),
+
+        prewarm_instances: map.get("prewarm_instances").and_then(|s| s.parse().ok()),
     })
 }
 /// Extracts and validates claims contained within a WebAssembly binary, if present
@@ -238,6 +264,78 @@ pub fn claims_token(wasm: impl AsRef<[u8]>) -> anyhow::Result<Option<jwt::Token<
     Ok(Some(claims))
 }
 
+/// Fuel accounting shared by all invocations of a [Component]
+#[derive(Clone, Debug)]
+struct FuelMeter {
+    /// Fuel available to each invocation
+    limit: u64,
+    /// Fuel consumed by invocations completed since the last [`Component::take_fuel_consumed`]
+    consumed: Arc<AtomicU64>,
+}
+
+impl FuelMeter {
+    fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            consumed: Arc::default(),
+        }
+    }
+}
+
+/// Returns the fuel consumed by the invocation using `store` so far, including instantiation, or
+/// [`None`] if fuel metering is disabled
+fn invocation_fuel<H: MinimalHandler>(store: &wasmtime::Store<Ctx<H>>) -> Option<u64> {
+    let data = store.data();
+    let fuel = data.fuel.as_ref()?;
+    let remaining = store.get_fuel().unwrap_or(data.fuel_remaining);
+    Some(
+        data.fuel_refilled
+            .saturating_add(fuel.limit.saturating_sub(remaining)),
+    )
+}
+
+/// Hands the fuel consumed by invocations served using
+/// [`ServeExt::serve_function`](wrpc_runtime_wasmtime::ServeExt::serve_function), which owns
+/// their stores, over to their return events.
+///
+/// The store of an invocation is constructed right before the invocation is yielded by the
+/// served stream, so the fuel cell attached to the store is taken when handling the yielded
+/// invocation. The cell is set once the store is dropped, which happens as soon as the
+/// invocation completes, including on traps.
+#[derive(Clone, Default)]
+struct FuelHandoff(Arc<std::sync::Mutex<Option<Arc<AtomicU64>>>>);
+
+impl FuelHandoff {
+    /// Attach a new fuel cell to `store`, if fuel metering is enabled
+    fn attach<H: MinimalHandler>(&self, store: &mut wasmtime::Store<Ctx<H>>) {
+        if store.data().fuel.is_some() {
+            let cell = Arc::<AtomicU64>::default();
+            store.data_mut().invocation_fuel = Some(Arc::clone(&cell));
+            *self
+                .0
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(cell);
+        }
+    }
+
+    /// Take the fuel cell attached to the most recently constructed store
+    fn take(&self) -> Option<Arc<AtomicU64>> {
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take()
+    }
+}
+
+/// Hits and misses of the pools of pre-instantiated instances of a [Component]
+#[derive(Debug, Default)]
+struct PoolStats {
+    /// Invocations served by a pre-instantiated instance
+    hits: AtomicU64,
+    /// Invocations, which had to instantiate an instance because the pool was empty
+    misses: AtomicU64,
+}
+
 /// Pre-compiled component [Component], which is cheapily-[Cloneable](Clone)
 #[derive(Clone)]
 pub struct Component<H>
@@ -251,6 +349,12 @@ where
     max_execution_time: Duration,
     experimental_features: Features,
     max_memory_limit: usize,
+    fuel: Option<FuelMeter>,
+    prewarm_instances: usize,
+    pool_stats: Arc<PoolStats>,
+    /// Pool of pre-instantiated instances serving `wasi:http/incoming-handler`, which is attached
+    /// to instances returned by [Component::instantiate], see [Component::prewarm]
+    http_pool: Option<Arc<http::IncomingHttpPool<H>>>,
 }
 
 /// The [`CustomCtxComponent`] is similar to [`Component`], but it supports passing a custom context that
@@ -266,6 +370,7 @@ where
     #[allow(unused)]
     host_resources: Arc<HashMap<Box<str>, HashMap<Box<str>, (ResourceType, ResourceType)>>>,
     max_execution_time: Duration,
+    max_fuel: Option<u64>,
 }
 
 impl<C> CustomCtxComponent<C>
@@ -286,7 +391,8 @@ where
             anyhow::bail!("core modules are not supported in the minimal linker");
         }
         let engine = rt.engine.clone();
-        let component = wasmtime::component::Component::new(&engine, wasm)
+        let component = rt
+            .compile_component(&engine, wasm)
             .context("failed to compile component")?;
 
         let mut linker = Linker::new(&engine);
@@ -324,6 +430,7 @@ where
             instance_pre,
             host_resources,
             max_execution_time: rt.max_execution_time,
+            max_fuel: rt.max_fuel,
         })
     }
 
@@ -331,6 +438,11 @@ where
     pub fn new_store(&self, ctx: C) -> wasmtime::Store<C> {
         let mut store = wasmtime::Store::new(&self.engine, ctx);
         store.set_epoch_deadline(self.max_execution_time.as_secs());
+        if let Some(max_fuel) = self.max_fuel {
+            if let Err(err) = store.set_fuel(max_fuel) {
+                warn!(?err, "failed to set store fuel");
+            }
+        }
         store
     }
 
@@ -358,6 +470,7 @@ fn new_store<H: Handler>(
     engine: &wasmtime::Engine,
     handler: H,
     max_execution_time: Duration,
+    fuel: Option<&FuelMeter>,
 ) -> wasmtime::Store<Ctx<H>> {
     let table = ResourceTable::new();
     let wasi = WasiCtxBuilder::new()
@@ -375,21 +488,42 @@ fn new_store<H: Handler>(
             shared_resources: SharedResourceTable::default(),
             timeout: max_execution_time,
             parent_context: None,
+            fuel: fuel.cloned(),
+            fuel_remaining: fuel.map_or(0, |fuel| fuel.limit),
+            fuel_refilled: 0,
+            invocation_fuel: None,
         },
     );
     store.set_epoch_deadline(max_execution_time.as_secs());
+    if let Some(fuel) = fuel {
+        if let Err(err) = store.set_fuel(fuel.limit) {
+            warn!(?err, "failed to set store fuel");
+        }
+        // Track the remaining fuel whenever execution leaves Wasm, so that it can be accounted
+        // for once the store is dropped
+        store.call_hook(|mut store, hook| {
+            if hook.entering_host() {
+                store.data_mut().fuel_remaining = store.get_fuel()?;
+            }
+            Ok(())
+        });
+    }
     store
 }
 
 /// Events sent by [`Component::serve_wrpc`]
 #[derive(Clone, Debug)]
 pub enum WrpcServeEvent<C> {
-    /// `wasi:http/incoming-handler.handle` return event
+    /// `wasi:http/incoming-handler.handle` return event. If fuel metering is enabled, the event is
+    /// sent once the handler returned, which may be after the response was sent
     HttpIncomingHandlerHandleReturned {
         /// Invocation context
         context: C,
         /// Whether the invocation was successfully handled
         success: bool,
+        /// Fuel consumed by the invocation including instantiation, or [`None`] if fuel metering
+        /// is disabled
+        fuel_consumed: Option<u64>,
     },
     /// `wasmcloud:messaging/handler.handle-message` return event
     MessagingHandlerHandleMessageReturned {
@@ -397,6 +531,9 @@ pub enum WrpcServeEvent<C> {
         context: C,
         /// Whether the invocation was successfully handled
         success: bool,
+        /// Fuel consumed by the invocation including instantiation, or [`None`] if fuel metering
+        /// is disabled
+        fuel_consumed: Option<u64>,
     },
     /// dynamic export return event
     DynamicExportReturned {
@@ -404,6 +541,9 @@ pub enum WrpcServeEvent<C> {
         context: C,
         /// Whether the invocation was successfully handled
         success: bool,
+        /// Fuel consumed by the invocation including instantiation, or [`None`] if fuel metering
+        /// is disabled
+        fuel_consumed: Option<u64>,
     },
 }
 
@@ -441,7 +581,8 @@ where
         }
         let engine = rt.engine.clone();
         let claims = None;
-        let component = wasmtime::component::Component::new(&engine, wasm)
+        let component = rt
+            .compile_component(&engine, wasm)
             .context("failed to compile component")?;
 
         let mut linker = Linker::new(&engine);
@@ -481,6 +622,10 @@ where
             max_execution_time: rt.max_execution_time,
             experimental_features: rt.experimental_features,
             max_memory_limit: rt.max_linear_memory,
+            fuel: rt.max_fuel.map(FuelMeter::new),
+            prewarm_instances: 0,
+            pool_stats: Arc::default(),
+            http_pool: None,
         })
     }
 }
@@ -583,22 +728,27 @@ where
                 .context("failed to encode a component from module")?;
             return Self::new(rt, &wasm, limits);
         }
+        // use component specific fuel limit or runtime wide limit
+        let max_fuel = limits.and_then(|l| l.max_fuel).or(rt.max_fuel);
         let engine: wasmtime::Engine = if let Some(limits) = limits {
-            if limits.max_memory_limit.is_none() {
+            // the runtime engine can be shared unless the memory limit differs, or fuel metering
+            // is requested for this component only
+            if limits.max_memory_limit.is_none()
+                && (limits.max_fuel.is_none() || rt.max_fuel.is_some())
+            {
                 rt.engine.clone()
             } else {
                 //use engine_config and create separate engine per component and edit the PoolingAllocatorConfig
                 let mut component_pooling_config = rt.pooling_config.clone();
-                component_pooling_config.max_memory_size(
-                    limits
-                        .max_memory_limit
-                        .expect("max_memory_limit should be Some"),
-                );
+                if let Some(max_memory_limit) = limits.max_memory_limit {
+                    component_pooling_config.max_memory_size(max_memory_limit);
+                }
 
                 let mut component_engine_config = rt.engine_config.clone();
                 component_engine_config.allocation_strategy(InstanceAllocationStrategy::Pooling(
                     component_pooling_config,
                 ));
+                component_engine_config.consume_fuel(max_fuel.is_some());
 
                 match wasmtime::Engine::new(&component_engine_config)
                     .context("failed to construct engine")
@@ -618,7 +768,8 @@ where
         };
         let claims_token = claims_token(wasm)?;
         let claims = claims_token.map(|c| c.claims);
-        let component = wasmtime::component::Component::new(&engine, wasm)
+        let component = rt
+            .compile_component(&engine, wasm)
             .context("failed to compile component")?;
 
         let mut linker = Linker::new(&engine);
@@ -790,6 +941,10 @@ where
             max_execution_time: rt.max_execution_time,
             experimental_features: rt.experimental_features,
             max_memory_limit,
+            fuel: max_fuel.map(FuelMeter::new),
+            prewarm_instances: limits.and_then(|l| l.prewarm_instances).unwrap_or_default(),
+            pool_stats: Arc::default(),
+            http_pool: None,
         })
     }
 
@@ -801,6 +956,60 @@ where
         self
     }
 
+    /// Starts pre-instantiating instances serving `wasi:http/incoming-handler` using `handler`,
+    /// if pre-warming is enabled and the component exports the interface. Instances returned by
+    /// [Self::instantiate] afterwards take pre-instantiated instances from this pool when
+    /// handling HTTP requests. Any previously started pool is replaced.
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `wasi:http/incoming-handler` cannot be pre-instantiated
+    #[instrument(level = "trace", skip_all)]
+    pub fn prewarm(&mut self, handler: H) -> anyhow::Result<&mut Self> {
+        self.http_pool = None;
+        if self.prewarm_instances == 0
+            || !self
+                .instance_pre
+                .component()
+                .component_type()
+                .exports(&self.engine)
+                .any(|(name, _)| name.starts_with("wasi:http/incoming-handler@0.2"))
+        {
+            return Ok(self);
+        }
+        let pool = http::IncomingHttpPool::new(self, handler)
+            .context("failed to construct `wasi:http/incoming-handler` instance pool")?;
+        pool.replenish();
+        self.http_pool = Some(pool);
+        Ok(self)
+    }
+
+    /// Returns the amount of fuel consumed by invocations of this component, which completed since
+    /// the last call to this method, or [`None`] if fuel metering is disabled.
+    /// Clones of a [Component] share the same accounting.
+    #[must_use]
+    pub fn take_fuel_consumed(&self) -> Option<u64> {
+        self.fuel
+            .as_ref()
+            .map(|fuel| fuel.consumed.swap(0, Ordering::Relaxed))
+    }
+
+    /// Returns the number of invocations served by pre-instantiated instances (hits) and the
+    /// number of invocations, which found the pool empty (misses), since the last call to this
+    /// method as `(hits, misses)`, or [`None`] if pre-warming is disabled.
+    /// Clones of a [Component] share the same accounting.
+    #[must_use]
+    pub fn take_instance_pool_stats(&self) -> Option<(u64, u64)> {
+        (self.prewarm_instances > 0).then(|| {
+            (
+                self.pool_stats.hits.swap(0, Ordering::Relaxed),
+                self.pool_stats.misses.swap(0, Ordering::Relaxed),
+            )
+        })
+    }
+
     /// Reads the WebAssembly binary asynchronously and calls [Component::new].
     ///
     /// # Errors
@@ -847,6 +1056,8 @@ where
             events,
             experimental_features: self.experimental_features,
             max_memory_limit: self.max_memory_limit,
+            fuel: self.fuel.clone(),
+            http_pool: self.http_pool.clone(),
         }
     }
 
@@ -868,6 +1079,7 @@ where
         S::Context: Deref<Target = tracing::Span>,
     {
         let max_execution_time = self.max_execution_time;
+        let fuel = self.fuel.clone();
         let mut invocations = vec![];
         let instance = self.instantiate(handler.clone(), events.clone());
         for (name, ty) in self
@@ -880,7 +1092,14 @@ where
                 (_, types::ComponentItem::ComponentInstance(..))
                     if name.starts_with("wasi:http/incoming-handler@0.2") =>
                 {
-                    let instance = instance.clone();
+                    let mut instance = instance.clone();
+                    if instance.http_pool.is_none() && self.prewarm_instances > 0 {
+                        let pool = http::IncomingHttpPool::new(self, handler.clone()).context(
+                            "failed to construct `wasi:http/incoming-handler` instance pool",
+                        )?;
+                        pool.replenish();
+                        instance.http_pool = Some(pool);
+                    }
                     let [(_, _, handle)] = wrpc_interface_http::bindings::exports::wrpc::http::incoming_handler::serve_interface(
                         srv,
                         wrpc_interface_http::ServeWasmtime(instance),
@@ -919,15 +1138,25 @@ where
                     let engine = self.engine.clone();
                     let handler = handler.clone();
                     let pre = self.instance_pre.clone();
+                    let fuel = fuel.clone();
+                    let fuel_handoff = FuelHandoff::default();
                     debug!(?name, "serving root function");
                     let func = srv
                         .serve_function(
-                            move || {
-                                let span = info_span!("call_instance_function");
-                                let mut store =
-                                    new_store(&engine, handler.clone(), max_execution_time);
-                                store.data_mut().parent_context = Some(span.context());
-                                store
+                            {
+                                let fuel_handoff = fuel_handoff.clone();
+                                move || {
+                                    let span = info_span!("call_instance_function");
+                                    let mut store = new_store(
+                                        &engine,
+                                        handler.clone(),
+                                        max_execution_time,
+                                        fuel.as_ref(),
+                                    );
+                                    store.data_mut().parent_context = Some(span.context());
+                                    fuel_handoff.attach(&mut store);
+                                    store
+                                }
                             },
                             pre,
                             Arc::clone(&self.host_resources),
@@ -941,15 +1170,19 @@ where
                     invocations.push(Box::pin(func.map_ok(move |(cx, res)| {
                         let events = events.clone();
                         let span = cx.deref().clone();
+                        let invocation_fuel = fuel_handoff.take();
                         Box::pin(
                             async move {
                                 let res =
                                     res.instrument(info_span!("handle_instance_function")).await;
                                 let success = res.is_ok();
+                                let fuel_consumed = invocation_fuel
+                                    .map(|invocation_fuel| invocation_fuel.load(Ordering::Relaxed));
                                 if let Err(err) =
                                     events.try_send(WrpcServeEvent::DynamicExportReturned {
                                         context: cx,
                                         success,
+                                        fuel_consumed,
                                     })
                                 {
                                     warn!(
@@ -980,18 +1213,26 @@ where
                                 let engine = self.engine.clone();
                                 let handler = handler.clone();
                                 let pre = self.instance_pre.clone();
+                                let fuel = fuel.clone();
+                                let fuel_handoff = FuelHandoff::default();
                                 debug!(?instance_name, ?name, "serving instance function");
                                 let func = srv
                                     .serve_function(
-                                        move || {
-                                            let span = info_span!("call_instance_function");
-                                            let mut store = new_store(
-                                                &engine,
-                                                handler.clone(),
-                                                max_execution_time,
-                                            );
-                                            store.data_mut().parent_context = Some(span.context());
-                                            store
+                                        {
+                                            let fuel_handoff = fuel_handoff.clone();
+                                            move || {
+                                                let span = info_span!("call_instance_function");
+                                                let mut store = new_store(
+                                                    &engine,
+                                                    handler.clone(),
+                                                    max_execution_time,
+                                                    fuel.as_ref(),
+                                                );
+                                                store.data_mut().parent_context =
+                                                    Some(span.context());
+                                                fuel_handoff.attach(&mut store);
+                                                store
+                                            }
                                         },
                                         pre,
                                         Arc::clone(&self.host_resources),
@@ -1005,14 +1246,21 @@ where
                                 invocations.push(Box::pin(func.map_ok(move |(cx, res)| {
                                     let events = events.clone();
                                     let span = cx.deref().clone();
+                                    let invocation_fuel = fuel_handoff.take();
                                     Box::pin(
                                         async move {
                                             let res = res.await;
                                             let success = res.is_ok();
+                                            let fuel_consumed = invocation_fuel.map(
+                                                |invocation_fuel| {
+                                                    invocation_fuel.load(Ordering::Relaxed)
+                                                },
+                                            );
                                             if let Err(err) = events.try_send(
                                                 WrpcServeEvent::DynamicExportReturned {
                                                     context: cx,
                                                     success,
+                                                    fuel_consumed,
                                                 },
                                             ) {
                                                 warn!(
@@ -1085,6 +1333,10 @@ where
     events: mpsc::Sender<WrpcServeEvent<C>>,
     experimental_features: Features,
     max_memory_limit: usize,
+    fuel: Option<FuelMeter>,
+    /// Pool of pre-instantiated instances serving `wasi:http/incoming-handler`, if pre-warming
+    /// is enabled
+    http_pool: Option<Arc<http::IncomingHttpPool<H>>>,
 }
 
 impl<H, C> Clone for Instance<H, C>
@@ -1100,6 +1352,8 @@ where
             events: self.events.clone(),
             experimental_features: self.experimental_features,
             max_memory_limit: self.max_memory_limit,
+            fuel: self.fuel.clone(),
+            http_pool: self.http_pool.clone(),
         }
     }
 }
@@ -1133,6 +1387,14 @@ where
     shared_resources: SharedResourceTable,
     timeout: Duration,
     parent_context: Option<opentelemetry::Context>,
+    fuel: Option<FuelMeter>,
+    /// Fuel remaining the last time execution left Wasm
+    fuel_remaining: u64,
+    /// Fuel consumed before the fuel of the store was last refilled, e.g. by instantiation of a
+    /// pre-instantiated instance
+    fuel_refilled: u64,
+    /// Set to the fuel consumed by the invocation using the store once it is dropped
+    invocation_fuel: Option<Arc<AtomicU64>>,
 }
 
 impl<H: MinimalHandler> IoView for Ctx<H> {
@@ -1173,6 +1435,21 @@ impl<H: MinimalHandler> Debug for Ctx<H> {
     }
 }
 
+impl<H: MinimalHandler> Drop for Ctx<H> {
+    fn drop(&mut self) {
+        if let Some(fuel) = &self.fuel {
+            let consumed = fuel.limit.saturating_sub(self.fuel_remaining);
+            fuel.consumed.fetch_add(consumed, Ordering::Relaxed);
+            if let Some(invocation_fuel) = &self.invocation_fuel {
+                invocation_fuel.store(
+                    self.fuel_refilled.saturating_add(consumed),
+                    Ordering::Relaxed,
+                );
+            }
+        }
+    }
+}
+
 impl<H: MinimalHandler> Ctx<H> {
     fn attach_parent_context(&self) {
         if let Some(context) = self.parent_context.as_ref() {
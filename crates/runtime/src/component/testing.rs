@@ -0,0 +1,161 @@
+//! Helpers shared by tests of component instances
+
+use std::sync::Arc;
+
+use anyhow::bail;
+use bytes::Bytes;
+use wasmcloud_core::CallTargetInterface;
+
+use crate::capability::messaging0_2_0::types::BrokerMessage;
+use crate::capability::messaging0_3_0::types::{Error, Topic};
+use crate::capability::{config, identity, logging, secrets};
+use crate::component::messaging::v0_3::{Message, RequestOptions};
+use crate::component::{
+    Bus, Config, Identity, InvocationErrorIntrospect, InvocationErrorKind, Logging, Messaging0_2,
+    Messaging0_3, MessagingClient0_3, MessagingHostMessage0_3, ReplacedInstanceTarget, Secrets,
+};
+
+/// [Handler](super::Handler) for components, which do not call any imports
+#[derive(Clone)]
+pub(crate) struct NoopHandler;
+
+impl wrpc_transport::Invoke for NoopHandler {
+    type Context = Option<ReplacedInstanceTarget>;
+    type Outgoing = wrpc_transport::frame::Outgoing;
+    type Incoming = wrpc_transport::frame::Incoming;
+
+    async fn invoke<P>(
+        &self,
+        _cx: Self::Context,
+        _instance: &str,
+        _func: &str,
+        _params: Bytes,
+        _paths: impl AsRef<[P]> + Send,
+    ) -> anyhow::Result<(Self::Outgoing, Self::Incoming)>
+    where
+        P: AsRef<[Option<usize>]> + Send + Sync,
+    {
+        bail!("not supported")
+    }
+}
+
+#[async_trait::async_trait]
+impl Bus for NoopHandler {
+    async fn set_link_name(
+        &self,
+        _link_name: String,
+        _interfaces: Vec<Arc<CallTargetInterface>>,
+    ) -> anyhow::Result<Result<(), String>> {
+        bail!("not supported")
+    }
+}
+
+#[async_trait::async_trait]
+impl Config for NoopHandler {
+    async fn get(
+        &self,
+        _key: &str,
+    ) -> anyhow::Result<Result<Option<String>, config::store::Error>> {
+        bail!("not supported")
+    }
+
+    async fn get_all(&self) -> anyhow::Result<Result<Vec<(String, String)>, config::store::Error>> {
+        bail!("not supported")
+    }
+}
+
+#[async_trait::async_trait]
+impl Logging for NoopHandler {
+    async fn log(
+        &self,
+        _level: logging::logging::Level,
+        _context: String,
+        _message: String,
+    ) -> anyhow::Result<()> {
+        bail!("not supported")
+    }
+}
+
+#[async_trait::async_trait]
+impl Secrets for NoopHandler {
+    async fn get(
+        &self,
+        _key: &str,
+    ) -> anyhow::Result<Result<secrets::store::Secret, secrets::store::SecretsError>> {
+        bail!("not supported")
+    }
+
+    async fn reveal(
+        &self,
+        _secret: secrets::reveal::Secret,
+    ) -> anyhow::Result<secrets::reveal::SecretValue> {
+        bail!("not supported")
+    }
+}
+
+impl Messaging0_2 for NoopHandler {
+    async fn request(
+        &self,
+        _subject: String,
+        _body: Vec<u8>,
+        _timeout_ms: u32,
+    ) -> anyhow::Result<Result<BrokerMessage, String>> {
+        bail!("not supported")
+    }
+
+    async fn publish(&self, _msg: BrokerMessage) -> anyhow::Result<Result<(), String>> {
+        bail!("not supported")
+    }
+}
+
+impl Messaging0_3 for NoopHandler {
+    async fn connect(
+        &self,
+        _name: String,
+    ) -> wasmtime::Result<Result<Box<dyn MessagingClient0_3 + Send + Sync>, Error>> {
+        bail!("not supported")
+    }
+
+    async fn send(
+        &self,
+        _client: &(dyn MessagingClient0_3 + Send + Sync),
+        _topic: Topic,
+        _message: Message,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        bail!("not supported")
+    }
+
+    async fn request(
+        &self,
+        _client: &(dyn MessagingClient0_3 + Send + Sync),
+        _topic: Topic,
+        _message: &Message,
+        _options: Option<RequestOptions>,
+    ) -> wasmtime::Result<Result<Vec<Box<dyn MessagingHostMessage0_3 + Send + Sync>>, Error>> {
+        bail!("not supported")
+    }
+
+    async fn reply(
+        &self,
+        _reply_to: &Message,
+        _message: Message,
+    ) -> wasmtime::Result<Result<(), Error>> {
+        bail!("not supported")
+    }
+}
+
+#[async_trait::async_trait]
+impl Identity for NoopHandler {
+    async fn get(
+        &self,
+        _audience: &str,
+    ) -> anyhow::Result<Result<Option<String>, identity::store::Error>> {
+        bail!("not supported")
+    }
+}
+
+impl InvocationErrorIntrospect for NoopHandler {
+    fn invocation_error_kind(&self, _err: &anyhow::Error) -> InvocationErrorKind {
+        InvocationErrorKind::Trap
+    }
+}
@@ -12,6 +12,9 @@ pub mod component;
 /// Capability bindings
 pub mod capability;
 
+/// Persistent cache of compiled components
+pub mod cache;
+
 /// Feature flags to enable experimental functionality in the runtime
 pub mod experimental;
 
@@ -1,4 +1,4 @@
-use crate::{experimental::Features, ComponentConfig};
+use crate::{cache::CompilationCache, experimental::Features, ComponentConfig};
 
 use core::fmt;
 use core::fmt::Debug;
@@ -31,6 +31,8 @@ pub struct RuntimeBuilder {
     max_execution_time: Duration,
     component_config: ComponentConfig,
     force_pooling_allocator: bool,
+    max_fuel: Option<u64>,
+    compilation_cache: Option<CompilationCache>,
     experimental_features: Features,
 }
 
@@ -54,6 +56,8 @@ impl RuntimeBuilder {
             max_execution_time: Duration::from_secs(10 * 60),
             component_config: ComponentConfig::default(),
             force_pooling_allocator: false,
+            max_fuel: None,
+            compilation_cache: None,
             experimental_features: Features::default(),
         }
     }
@@ -123,6 +127,37 @@ impl RuntimeBuilder {
         }
     }
 
+    /// Enables fuel metering, which accounts for the CPU work performed by each invocation of a
+    /// component, see [`crate::Component::take_fuel_consumed`]. Invocations are not limited unless
+    /// [`Self::max_fuel`] or [`crate::component::Limits::max_fuel`] is set.
+    #[must_use]
+    pub fn fuel_metering(self) -> Self {
+        Self {
+            max_fuel: Some(self.max_fuel.unwrap_or(u64::MAX)),
+            ..self
+        }
+    }
+
+    /// Sets the maximum amount of fuel a single invocation of a component may consume before it
+    /// traps. This enables fuel metering, see [`Self::fuel_metering`].
+    #[must_use]
+    pub fn max_fuel(self, max_fuel: u64) -> Self {
+        Self {
+            max_fuel: Some(max_fuel),
+            ..self
+        }
+    }
+
+    /// Sets a persistent [`CompilationCache`] to load compiled components from and store them in,
+    /// which avoids recompiling components across restarts
+    #[must_use]
+    pub fn compilation_cache(self, compilation_cache: CompilationCache) -> Self {
+        Self {
+            compilation_cache: Some(compilation_cache),
+            ..self
+        }
+    }
+
     /// Set the experimental features to enable in the runtime
     #[must_use]
     pub fn experimental_features(self, experimental_features: Features) -> Self {
@@ -178,6 +213,7 @@ impl RuntimeBuilder {
             .table_keep_resident(10 * 1024);
         self.engine_config
             .allocation_strategy(InstanceAllocationStrategy::Pooling(pooling_config.clone()));
+        self.engine_config.consume_fuel(self.max_fuel.is_some());
         let engine = match wasmtime::Engine::new(&self.engine_config)
             .context("failed to construct engine")
         {
@@ -213,6 +249,8 @@ impl RuntimeBuilder {
                 max_execution_time: self.max_execution_time,
                 experimental_features: self.experimental_features,
                 max_linear_memory: max_memory_limits,
+                max_fuel: self.max_fuel,
+                compilation_cache: self.compilation_cache,
             },
             epoch,
         ))
@@ -237,6 +275,9 @@ pub struct Runtime {
     pub(crate) experimental_features: Features,
     pub(crate) pooling_config: wasmtime::PoolingAllocationConfig,
     pub(crate) max_linear_memory: usize,
+    /// Fuel available to each invocation, [`None`] if fuel metering is disabled
+    pub(crate) max_fuel: Option<u64>,
+    pub(crate) compilation_cache: Option<CompilationCache>,
 }
 
 impl Debug for Runtime {
@@ -245,6 +286,8 @@ impl Debug for Runtime {
             .field("component_config", &self.component_config)
             .field("runtime", &"wasmtime")
             .field("max_execution_time", &"max_execution_time")
+            .field("max_fuel", &self.max_fuel)
+            .field("compilation_cache", &self.compilation_cache)
             .field("pooling_config", &self.pooling_config)
             .field("engine_config", &self.engine_config)
             .finish_non_exhaustive()
@@ -280,6 +323,20 @@ impl Runtime {
         &self.engine
     }
 
+    /// Compiles the `wasm` component using `engine`, going through the compilation cache if one is
+    /// configured
+    pub(crate) fn compile_component(
+        &self,
+        engine: &wasmtime::Engine,
+        wasm: &[u8],
+    ) -> anyhow::Result<wasmtime::component::Component> {
+        if let Some(cache) = &self.compilation_cache {
+            cache.get_or_compile(engine, wasm)
+        } else {
+            wasmtime::component::Component::new(engine, wasm)
+        }
+    }
+
     /// Returns a boolean indicating whether the runtime should skip linking a feature-gated instance
     pub(crate) fn skip_feature_gated_instance(&self, instance: &str) -> bool {
         match instance {
@@ -25,6 +25,9 @@ pub use traces::FlushGuard;
 
 mod metrics;
 
+#[cfg(feature = "otel")]
+pub use metrics::{enable_prometheus_metrics, encode_prometheus_metrics};
+
 #[cfg(not(feature = "otel"))]
 pub fn configure_observability(
     _: &str,
@@ -56,7 +59,7 @@ pub fn configure_observability(
 ) -> anyhow::Result<(tracing::Dispatch, traces::FlushGuard)> {
     let normalized_service_name = service_name.to_kebab_case();
 
-    if otel_config.metrics_enabled() {
+    if otel_config.metrics_enabled() || metrics::prometheus_metrics_enabled() {
         metrics::configure_metrics(&normalized_service_name, otel_config)?;
     }
 
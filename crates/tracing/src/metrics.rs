@@ -1,6 +1,44 @@
 #[cfg(feature = "otel")]
 use anyhow::Context;
 
+/// Registry of the metrics exposed in the Prometheus text exposition format, if enabled
+#[cfg(feature = "otel")]
+static PROMETHEUS_REGISTRY: once_cell::sync::OnceCell<prometheus::Registry> =
+    once_cell::sync::OnceCell::new();
+
+/// Enables exposing the metrics recorded by the global meter provider in the Prometheus text
+/// exposition format, see [`encode_prometheus_metrics`].
+///
+/// This must be called before [`crate::configure_observability`] and enables metrics even if
+/// exporting them over OTLP is disabled.
+#[cfg(feature = "otel")]
+pub fn enable_prometheus_metrics() {
+    PROMETHEUS_REGISTRY.get_or_init(prometheus::Registry::new);
+}
+
+/// Returns whether [`enable_prometheus_metrics`] was called
+#[cfg(feature = "otel")]
+pub(crate) fn prometheus_metrics_enabled() -> bool {
+    PROMETHEUS_REGISTRY.get().is_some()
+}
+
+/// Encodes the metrics recorded by the global meter provider in the Prometheus text exposition
+/// format
+#[cfg(feature = "otel")]
+#[allow(clippy::missing_errors_doc)]
+pub fn encode_prometheus_metrics() -> anyhow::Result<String> {
+    use prometheus::Encoder as _;
+
+    let registry = PROMETHEUS_REGISTRY
+        .get()
+        .context("Prometheus metrics are not enabled")?;
+    let mut buf = Vec::new();
+    prometheus::TextEncoder::new()
+        .encode(&registry.gather(), &mut buf)
+        .context("failed to encode Prometheus metrics")?;
+    String::from_utf8(buf).context("Prometheus metrics are not valid UTF-8")
+}
+
 #[cfg(feature = "otel")]
 #[allow(clippy::missing_errors_doc)]
 #[allow(clippy::module_name_repetitions)]
@@ -14,46 +52,75 @@ pub fn configure_metrics(
     };
     use wasmcloud_core::OtelProtocol;
 
-    let exporter = match otel_config.protocol {
-        OtelProtocol::Http => {
-            let client = crate::get_http_client(otel_config)
-                .context("failed to get an http client for otel metrics exporter")?;
-            opentelemetry_otlp::MetricExporter::builder()
-                .with_http()
-                .with_http_client(client)
-                .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
-                .with_endpoint(otel_config.metrics_endpoint())
-                .build()
-                .context("failed to create OTEL http exporter")?
-        }
-        OtelProtocol::Grpc => {
-            // TODO(joonas): Configure tonic::transport::ClientTlsConfig via .with_tls_config(...), passing in additional certificates.
-            opentelemetry_otlp::MetricExporter::builder()
-                .with_tonic()
-                .with_endpoint(otel_config.metrics_endpoint())
-                .build()
-                .context("failed to create OTEL tonic exporter")?
-        }
-    };
+    let mut meter_provider = SdkMeterProvider::builder().with_resource(
+        opentelemetry_sdk::Resource::builder_empty()
+            .with_detector(Box::new(
+                opentelemetry_sdk::resource::EnvResourceDetector::new(),
+            ))
+            .with_attribute(opentelemetry::KeyValue::new(
+                "service.name",
+                service_name.to_string(),
+            ))
+            .build(),
+    );
+
+    if otel_config.metrics_enabled() {
+        let exporter = match otel_config.protocol {
+            OtelProtocol::Http => {
+                let client = crate::get_http_client(otel_config)
+                    .context("failed to get an http client for otel metrics exporter")?;
+                opentelemetry_otlp::MetricExporter::builder()
+                    .with_http()
+                    .with_http_client(client)
+                    .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
+                    .with_endpoint(otel_config.metrics_endpoint())
+                    .build()
+                    .context("failed to create OTEL http exporter")?
+            }
+            OtelProtocol::Grpc => {
+                // TODO(joonas): Configure tonic::transport::ClientTlsConfig via .with_tls_config(...), passing in additional certificates.
+                opentelemetry_otlp::MetricExporter::builder()
+                    .with_tonic()
+                    .with_endpoint(otel_config.metrics_endpoint())
+                    .build()
+                    .context("failed to create OTEL tonic exporter")?
+            }
+        };
+        let reader = PeriodicReader::builder(exporter, opentelemetry_sdk::runtime::Tokio).build();
+        meter_provider = meter_provider.with_reader(reader);
+    }
 
-    let reader = PeriodicReader::builder(exporter, opentelemetry_sdk::runtime::Tokio).build();
-
-    let meter_provider = SdkMeterProvider::builder()
-        .with_resource(
-            opentelemetry_sdk::Resource::builder_empty()
-                .with_detector(Box::new(
-                    opentelemetry_sdk::resource::EnvResourceDetector::new(),
-                ))
-                .with_attribute(opentelemetry::KeyValue::new(
-                    "service.name",
-                    service_name.to_string(),
-                ))
-                .build(),
-        )
-        .with_reader(reader)
-        .build();
-
-    opentelemetry::global::set_meter_provider(meter_provider);
+    if let Some(registry) = PROMETHEUS_REGISTRY.get() {
+        let reader = opentelemetry_prometheus::exporter()
+            .with_registry(registry.clone())
+            .build()
+            .context("failed to create Prometheus exporter")?;
+        meter_provider = meter_provider.with_reader(reader);
+    }
+
+    opentelemetry::global::set_meter_provider(meter_provider.build());
 
     Ok(())
 }
+
+#[cfg(all(test, feature = "otel"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encodes_prometheus_metrics() -> anyhow::Result<()> {
+        assert!(encode_prometheus_metrics().is_err());
+        enable_prometheus_metrics();
+        configure_metrics("test", &wasmcloud_core::OtelConfig::default())?;
+
+        let counter = opentelemetry::global::meter("test")
+            .u64_counter("wasmcloud_host.component.invocations")
+            .build();
+        counter.add(2, &[opentelemetry::KeyValue::new("lattice", "default")]);
+        let metrics = encode_prometheus_metrics()?;
+        assert!(metrics.contains("# TYPE wasmcloud_host_component_invocations_total counter\n"));
+        assert!(metrics.contains("wasmcloud_host_component_invocations_total{"));
+        assert!(metrics.contains(r#"lattice="default""#));
+        Ok(())
+    }
+}
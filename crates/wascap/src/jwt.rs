@@ -138,6 +138,15 @@ pub struct Invocation {
     pub invocation_hash: String,
 }
 
+/// The claims metadata corresponding to a revocation list, issued by an account to revoke
+/// components and providers it signed
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct Revocations {
+    /// Issued-at cut-offs in seconds since the epoch, keyed by the subject of the revoked
+    /// components and providers. Claims of a subject issued at or before its cut-off are revoked
+    pub revocations: HashMap<String, u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 pub struct Host {
     /// Optional friendly descriptive name for the host
@@ -295,6 +304,12 @@ impl WascapEntity for Invocation {
     }
 }
 
+impl WascapEntity for Revocations {
+    fn name(&self) -> String {
+        "Revocation list".to_string()
+    }
+}
+
 impl WascapEntity for Host {
     fn name(&self) -> String {
         self.name
@@ -613,6 +628,47 @@ impl Claims<Host> {
     }
 }
 
+impl Claims<Revocations> {
+    /// Creates a new non-expiring Claims wrapper for a revocation list issued by the account
+    /// `issuer`, revoking claims of each subject in `revocations` issued at or before its cut-off
+    #[must_use]
+    pub fn new(issuer: String, revocations: HashMap<String, u64>) -> Claims<Revocations> {
+        Self::with_dates(issuer, None, None, revocations)
+    }
+
+    /// Creates a new Claims wrapper for a revocation list, with optional valid before and expiration dates
+    #[must_use]
+    pub fn with_dates(
+        issuer: String,
+        not_before: Option<u64>,
+        expires: Option<u64>,
+        revocations: HashMap<String, u64>,
+    ) -> Claims<Revocations> {
+        Claims {
+            metadata: Some(Revocations { revocations }),
+            expires,
+            id: nuid::next().to_string(),
+            issued_at: since_the_epoch().as_secs(),
+            subject: issuer.clone(),
+            issuer,
+            not_before,
+            wascap_revision: Some(WASCAP_INTERNAL_REVISION),
+        }
+    }
+
+    /// Returns whether `claims` are revoked by this list, which is the case if they are issued by
+    /// the issuer of the list at or before the cut-off of their subject
+    #[must_use]
+    pub fn revokes<T>(&self, claims: &Claims<T>) -> bool {
+        claims.issuer == self.issuer
+            && self
+                .metadata
+                .as_ref()
+                .and_then(|list| list.revocations.get(&claims.subject))
+                .is_some_and(|cutoff| claims.issued_at <= *cutoff)
+    }
+}
+
 #[derive(Default)]
 pub struct ClaimsBuilder<T> {
     claims: Claims<T>,
@@ -919,7 +975,7 @@ impl Host {
 mod test {
     use super::{Account, Claims, Component, ErrorKind, Host, KeyPair, Operator};
     use crate::jwt::{
-        since_the_epoch, validate_token, CapabilityProvider, ClaimsBuilder, Cluster,
+        since_the_epoch, validate_token, CapabilityProvider, ClaimsBuilder, Cluster, Revocations,
         WASCAP_INTERNAL_REVISION,
     };
     use std::collections::HashMap;
@@ -1447,4 +1503,44 @@ mod test {
             Some(HashMap::from([("test".to_string(), "value".to_string())]))
         );
     }
+
+    #[test]
+    fn revocation_list_revokes_claims() {
+        let account = KeyPair::new_account();
+        let component = KeyPair::new_module();
+        let mut claims = Claims::<Component>::new(
+            "test".to_string(),
+            account.public_key(),
+            component.public_key(),
+            None,
+            false,
+            None,
+            None,
+            None,
+        );
+        claims.issued_at = 100;
+
+        let list = Claims::<Revocations>::new(
+            account.public_key(),
+            HashMap::from([(component.public_key(), 100)]),
+        );
+        let encoded = list.encode(&account).unwrap();
+        assert!(
+            validate_token::<Revocations>(&encoded)
+                .unwrap()
+                .signature_valid
+        );
+        let decoded = Claims::<Revocations>::decode(&encoded).unwrap();
+        assert_eq!(decoded, list);
+        assert!(decoded.revokes(&claims));
+
+        // Claims issued after the cut-off are not revoked
+        claims.issued_at = 101;
+        assert!(!decoded.revokes(&claims));
+
+        // Lists only revoke claims issued by their issuer
+        claims.issued_at = 100;
+        claims.issuer = KeyPair::new_account().public_key();
+        assert!(!decoded.revokes(&claims));
+    }
 }
@@ -11,6 +11,7 @@ use etcetera::AppStrategy;
 use semver::Version;
 use serde_json::json;
 use tracing_subscriber::EnvFilter;
+use wash::lib::cli::cache::CacheCommand;
 use wash::lib::cli::capture::{CaptureCommand, CaptureSubcommand};
 use wash::lib::cli::claims::ClaimsCliCommand;
 use wash::lib::cli::get::GetCommand;
@@ -168,6 +169,7 @@ ________________________________________________________________________________
                 ("link", "Link one component to another on a set of interfaces"),
                 ("call", "Invoke a simple function on a component running in a wasmCloud host"),
                 ("label", "Label (or un-label) a host with a key=value label pair"),
+                ("cache", "List, prune and pre-pull the OCI artifact cache of a host"),
                 (
                     "config",
                     "Create configuration for components, capability providers and links",
@@ -299,6 +301,9 @@ enum CliCommand {
     /// Invoke a simple function on a component running in a wasmCloud host
     #[clap(name = "call")]
     Call(CallCli),
+    /// List, prune and pre-pull the OCI artifact cache of a host
+    #[clap(name = "cache", subcommand)]
+    Cache(CacheCommand),
     /// Capture and debug cluster invocations and state
     #[clap(name = "capture")]
     Capture(CaptureCommand),
@@ -580,6 +585,9 @@ async fn main() {
             common::start_cmd::handle_command(start_cli, output_kind).await
         }
         CliCommand::Stop(stop_cli) => common::stop_cmd::handle_command(stop_cli, output_kind).await,
+        CliCommand::Cache(cache_cli) => {
+            common::cache_cmd::handle_command(cache_cli, output_kind).await
+        }
         CliCommand::Label(label_cli) => {
             common::label_cmd::handle_command(label_cli, output_kind).await
         }
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration;
 
-use crate::lib::app::{load_app_manifest, validate_manifest_file, AppManifest};
+use crate::lib::app::{load_app_manifest, validate_manifest_file_with_config, AppManifest};
 use crate::lib::cli::get::parse_watch_interval;
 use crate::lib::cli::{CliConnectionOpts, CommandOutput, OutputKind};
 use crate::lib::config::WashConnectionOptions;
@@ -174,6 +174,10 @@ pub struct ValidateCommand {
     /// Whether to check image references in the manifest
     #[clap(long)]
     check_image_refs: bool,
+    /// Whether to validate the configuration of providers and their links against the config
+    /// schemas of the providers, using provider archives from local files or the local OCI cache
+    #[clap(long)]
+    check_config: bool,
 }
 
 pub async fn handle_command(
@@ -242,10 +246,13 @@ pub async fn handle_command(
 }
 /// Validate a Wadm manifest file
 async fn handle_validate(cmd: ValidateCommand) -> Result<CommandOutput> {
-    let (_manifest, validation_results) =
-        validate_manifest_file(&cmd.application, cmd.check_image_refs)
-            .await
-            .context("failed to validate Wadm manifest")?;
+    let (_manifest, validation_results) = validate_manifest_file_with_config(
+        &cmd.application,
+        cmd.check_image_refs,
+        cmd.check_config,
+    )
+    .await
+    .context("failed to validate Wadm manifest")?;
     Ok(show_validate_manifest_results(validation_results))
 }
 
@@ -22,6 +22,7 @@ pub async fn invoke(
         interfaces,
         source_config,
         target_config,
+        weighted_targets,
     }: LinkPutCommand,
     output_kind: OutputKind,
 ) -> Result<CommandOutput> {
@@ -30,18 +31,19 @@ pub async fn invoke(
 
     let name = link_name.unwrap_or_else(|| "default".to_string());
 
+    let link = Link::builder()
+        .source_id(&source_id)
+        .target(&target)
+        .name(&name)
+        .wit_namespace(&wit_namespace)
+        .wit_package(&wit_package)
+        .interfaces(interfaces)
+        .source_config(source_config)
+        .target_config(target_config)
+        .weighted_targets(weighted_targets);
     let failure = put_link(
         opts.try_into()?,
-        Link::builder()
-            .source_id(&source_id)
-            .target(&target)
-            .name(&name)
-            .wit_namespace(&wit_namespace)
-            .wit_package(&wit_package)
-            .interfaces(interfaces)
-            .source_config(source_config)
-            .target_config(target_config)
-            .build()
+        link.build()
             .map_err(|e| anyhow!(e).context("failed to build link"))?,
     )
     .await
@@ -0,0 +1,16 @@
+use anyhow::Result;
+
+use crate::lib::cli::{
+    cache::{handle_cache_command, CacheCommand},
+    CommandOutput, OutputKind,
+};
+
+use crate::appearance::spinner::Spinner;
+
+pub async fn handle_command(cmd: CacheCommand, output_kind: OutputKind) -> Result<CommandOutput> {
+    let sp: Spinner = Spinner::new(&output_kind)?;
+    let out = handle_cache_command(cmd).await?;
+    sp.finish_and_clear();
+
+    Ok(out)
+}
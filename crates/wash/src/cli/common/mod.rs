@@ -1,3 +1,4 @@
+pub mod cache_cmd;
 pub mod get_cmd;
 pub mod label_cmd;
 pub mod registry_cmd;
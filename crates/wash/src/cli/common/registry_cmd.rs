@@ -47,6 +47,7 @@ pub async fn registry_pull(
             password: credentials.password().map(String::from),
             insecure: cmd.opts.insecure,
             insecure_skip_tls_verify: cmd.opts.insecure_skip_tls_verify,
+            signature_public_keys: cmd.signature_keys,
         },
     )
     .await?;
@@ -219,6 +220,8 @@ async fn resolve_registry_credentials(registry: &str) -> Result<RegistryCredenti
 
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
+
     use crate::lib::cli::registry::{RegistryCommand, RegistryPullCommand};
     use anyhow::{ensure, Context as _, Result};
     use clap::Parser;
@@ -281,6 +284,8 @@ mod tests {
             "password",
             "--user",
             "user",
+            "--signature-key",
+            "cosign.pub",
         ])
         .context("wash pull with all options failed")?;
         ensure!(matches!(
@@ -289,6 +294,7 @@ mod tests {
                 url,
                 destination,
                 digest,
+                signature_keys,
                 opts,
                 ..
             }) if url == HELLO_WORLD_WASM
@@ -296,6 +302,7 @@ mod tests {
                 && digest == Some("sha256:a17a163afa8447622055deb049587641a9e23243a6cc4411eb33bd4267214cf3".into())
                 && opts.user == Some("user".into())
                 && opts.password == Some("password".into())
+                && signature_keys == [PathBuf::from("cosign.pub")]
         ));
 
         Ok(())
@@ -23,7 +23,11 @@ pub async fn handle_command(
         }
         StopCommand::Host(cmd) => {
             let host_id = &cmd.host_id.to_string();
-            sp.update_spinner_message(format!(" Stopping host {host_id} ... "));
+            if cmd.no_stop {
+                sp.update_spinner_message(format!(" Draining host {host_id} ... "));
+            } else {
+                sp.update_spinner_message(format!(" Stopping host {host_id} ... "));
+            }
             stop_host(cmd).await?
         }
     };
@@ -217,4 +221,45 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    /// Ensures the `--drain` and `--no-stop` flags of the `stop host` subcommand are parsed and
+    /// disabled by default
+    fn test_stop_host_cmd_drain() -> Result<()> {
+        for (args, expected_drain, expected_no_stop) in [
+            (&["ctl", "stop", "host", HOST_ID][..], false, false),
+            (
+                &["ctl", "stop", "host", HOST_ID, "--drain"][..],
+                true,
+                false,
+            ),
+            (
+                &["ctl", "stop", "host", HOST_ID, "--drain", "--no-stop"][..],
+                true,
+                true,
+            ),
+        ] {
+            let cmd: Cmd = Parser::try_parse_from(args)?;
+            match cmd.command {
+                CtlCliCommand::Stop(StopCommand::Host(StopHostCommand {
+                    host_id,
+                    drain,
+                    no_stop,
+                    ..
+                })) => {
+                    assert_eq!(host_id, HOST_ID);
+                    assert_eq!(drain, expected_drain);
+                    assert_eq!(no_stop, expected_no_stop);
+                }
+                cmd => panic!("stop host constructed incorrect command {cmd:?}"),
+            }
+        }
+
+        // The host can only be left running after draining it
+        assert!(
+            <Cmd as Parser>::try_parse_from(["ctl", "stop", "host", HOST_ID, "--no-stop"]).is_err()
+        );
+
+        Ok(())
+    }
 }
@@ -286,6 +286,7 @@ mod test {
                 source_config,
                 target_config,
                 link_name,
+                weighted_targets,
             })) => {
                 assert_eq!(&opts.ctl_host.unwrap(), CTL_HOST);
                 assert_eq!(&opts.ctl_port.unwrap(), CTL_PORT);
@@ -299,6 +300,7 @@ mod test {
                 assert_eq!(interfaces.as_slice(), &["foo".to_string()]);
                 assert!(source_config.is_empty());
                 assert!(target_config.is_empty());
+                assert!(weighted_targets.is_empty());
             }
             cmd => panic!("ctl link put constructed incorrect command {cmd:?}"),
         }
@@ -173,6 +173,7 @@ pub async fn handle_install(
                     password: cmd.oci_auth.password,
                     insecure: cmd.oci_auth.insecure,
                     insecure_skip_tls_verify: cmd.oci_auth.insecure_skip_tls_verify,
+                    ..Default::default()
                 },
             )
             .await
@@ -2,12 +2,14 @@
 //!
 //! This crate is essentially a wrapper around the `wadm_client` crate, and it's recommended to use
 //! that crate directly instead.
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 
 use anyhow::{bail, Context};
 use async_nats::Client;
+use provider_archive::ProviderArchive;
 use regex::Regex;
 use tokio::io::{AsyncRead, AsyncReadExt};
 use tracing::warn;
@@ -15,10 +17,14 @@ use url::Url;
 use wadm_client::Result;
 use wadm_types::api::{ModelSummary, Status, VersionInfo};
 use wadm_types::validation::{validate_manifest, ValidationFailure, ValidationFailureLevel};
-use wadm_types::{CapabilityProperties, ComponentProperties, Manifest, Properties};
+use wadm_types::{
+    CapabilityProperties, ComponentProperties, ConfigProperty, Manifest, Properties, TraitProperty,
+};
+use wasmcloud_core::config_schema::{validate_config, validate_partial_config, ConfigViolation};
 use wasmcloud_core::tls;
 use wasmcloud_core::OciFetcher;
 
+use crate::lib::cli::cached_oci_file;
 use crate::lib::config::DEFAULT_LATTICE;
 
 #[derive(Debug)]
@@ -436,6 +442,17 @@ pub async fn load_app_manifest(source: AppManifestSource) -> anyhow::Result<AppM
 pub async fn validate_manifest_file(
     manifest_file_path: &Path,
     oci_check: bool,
+) -> Result<(Manifest, Vec<ValidationFailure>)> {
+    validate_manifest_file_with_config(manifest_file_path, oci_check, false).await
+}
+
+/// Validate the contents of a manifest file as in [validate_manifest_file] and optionally validate
+/// the configuration of providers and their links against the config schemas of the providers,
+/// see [validate_config_schemas]
+pub async fn validate_manifest_file_with_config(
+    manifest_file_path: &Path,
+    oci_check: bool,
+    config_check: bool,
 ) -> Result<(Manifest, Vec<ValidationFailure>)> {
     let content = tokio::fs::read_to_string(manifest_file_path)
         .await
@@ -464,6 +481,12 @@ pub async fn validate_manifest_file(
         let image_references = extract_image_references(&manifest);
         validate_oci_references(image_references, &mut failures).await;
     }
+    if config_check {
+        let base_dir = manifest_file_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."));
+        validate_config_schemas(&manifest, base_dir, &mut failures).await;
+    }
     Ok((manifest, failures))
 }
 
@@ -508,6 +531,203 @@ pub fn extract_image_references(manifest: &Manifest) -> Vec<String> {
     image_refs
 }
 
+/// Validate the inline configuration of capability providers and their links in a manifest
+/// against the config schemas embedded in the provider archives.
+///
+/// Keys required by a schema may be provided by either the provider or the link configuration, so
+/// they are not required in provider configuration on its own, while link configuration is
+/// validated together with the inline configuration of the provider.
+///
+/// This works offline: provider archives are loaded from local files, relative ones being resolved
+/// from `base_dir`, or from the local OCI cache. Providers whose archives are not available
+/// locally and configuration which is not defined inline in the manifest are reported as warnings
+/// and skipped.
+pub async fn validate_config_schemas(
+    manifest: &Manifest,
+    base_dir: &Path,
+    failures: &mut Vec<ValidationFailure>,
+) {
+    let mut schemas = HashMap::new();
+    for component in &manifest.spec.components {
+        let Properties::Capability {
+            properties: CapabilityProperties {
+                image: Some(image), ..
+            },
+        } = &component.properties
+        else {
+            continue;
+        };
+        match load_provider_config_schema(image, base_dir).await {
+            Ok(Some(schema)) => {
+                schemas.insert(component.name.as_str(), schema);
+            }
+            Ok(None) => {}
+            Err(err) => failures.push(config_failure(
+                ValidationFailureLevel::Warning,
+                format!(
+                    "skipping config validation of provider `{}`: {err:#}",
+                    component.name
+                ),
+            )),
+        }
+    }
+
+    let mut provider_configs = HashMap::new();
+    for component in &manifest.spec.components {
+        let (
+            Some(schema),
+            Properties::Capability {
+                properties: CapabilityProperties { config, .. },
+            },
+        ) = (schemas.get(component.name.as_str()), &component.properties)
+        else {
+            continue;
+        };
+        let context = format!("config of provider `{}`", component.name);
+        if let Some(config) = inline_config(&context, config, failures) {
+            let violations = validate_partial_config(schema, &config);
+            report_config_violations(&context, &component.name, violations, failures);
+            provider_configs.insert(component.name.as_str(), config);
+        }
+    }
+
+    for component in &manifest.spec.components {
+        for link in component
+            .traits
+            .iter()
+            .flatten()
+            .filter_map(|t| match &t.properties {
+                TraitProperty::Link(link) => Some(link),
+                _ => None,
+            })
+        {
+            if let (Some(schema), Some(source)) =
+                (schemas.get(component.name.as_str()), link.source.as_ref())
+            {
+                let context = format!(
+                    "source config of link from `{}` to `{}`",
+                    component.name, link.target.name
+                );
+                validate_link_config_schema(
+                    &context,
+                    &component.name,
+                    schema,
+                    provider_configs.get(component.name.as_str()),
+                    &source.config,
+                    failures,
+                );
+            }
+            if let Some(schema) = schemas.get(link.target.name.as_str()) {
+                let context = format!(
+                    "target config of link from `{}` to `{}`",
+                    component.name, link.target.name
+                );
+                validate_link_config_schema(
+                    &context,
+                    &link.target.name,
+                    schema,
+                    provider_configs.get(link.target.name.as_str()),
+                    &link.target.config,
+                    failures,
+                );
+            }
+        }
+    }
+}
+
+/// Load the config schema of the provider archive referenced by `image` from a local file or the
+/// local OCI cache
+async fn load_provider_config_schema(
+    image: &str,
+    base_dir: &Path,
+) -> anyhow::Result<Option<serde_json::Value>> {
+    let path = match image.strip_prefix("file://") {
+        Some(path) => base_dir.join(path),
+        None => cached_oci_file(image),
+    };
+    if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        bail!("provider archive `{image}` is not available locally, pull it first");
+    }
+    let par = ProviderArchive::try_load_file(&path).await.map_err(|err| {
+        anyhow::anyhow!(
+            "failed to load provider archive `{}`: {err}",
+            path.display()
+        )
+    })?;
+    Ok(par.schema())
+}
+
+/// Merge the inline configuration `config`, recording a warning and returning [None] if any of it
+/// is not defined in the manifest
+fn inline_config(
+    context: &str,
+    config: &[ConfigProperty],
+    failures: &mut Vec<ValidationFailure>,
+) -> Option<HashMap<String, String>> {
+    let mut merged = HashMap::new();
+    for ConfigProperty { name, properties } in config {
+        let Some(properties) = properties else {
+            let msg =
+                format!("skipping validation of {context}: config `{name}` is not in the manifest");
+            failures.push(config_failure(ValidationFailureLevel::Warning, msg));
+            return None;
+        };
+        merged.extend(properties.clone());
+    }
+    Some(merged)
+}
+
+/// Validate the inline link configuration `config` of the provider `provider` together with the
+/// inline `provider_config` against the config `schema` of the provider, recording a failure for
+/// every violation. Keys required by the schema are not enforced if `provider_config` is unknown.
+fn validate_link_config_schema(
+    context: &str,
+    provider: &str,
+    schema: &serde_json::Value,
+    provider_config: Option<&HashMap<String, String>>,
+    config: &[ConfigProperty],
+    failures: &mut Vec<ValidationFailure>,
+) {
+    let Some(config) = inline_config(context, config, failures) else {
+        return;
+    };
+    let violations = if let Some(provider_config) = provider_config {
+        let mut merged = provider_config.clone();
+        merged.extend(config);
+        validate_config(schema, &merged)
+    } else {
+        validate_partial_config(schema, &config)
+    };
+    report_config_violations(context, provider, violations, failures);
+}
+
+/// Record a failure for every violation of the config schema of the provider `provider`
+fn report_config_violations(
+    context: &str,
+    provider: &str,
+    violations: anyhow::Result<Vec<ConfigViolation>>,
+    failures: &mut Vec<ValidationFailure>,
+) {
+    match violations {
+        Ok(violations) => failures.extend(violations.into_iter().map(|violation| {
+            let msg = format!("{context} does not match the schema of `{provider}`: {violation}");
+            config_failure(ValidationFailureLevel::Error, msg)
+        })),
+        Err(err) => failures.push(config_failure(
+            ValidationFailureLevel::Error,
+            format!("failed to validate {context}: {err}"),
+        )),
+    }
+}
+
+/// Build a [`ValidationFailure`] of `level` with `msg`
+fn config_failure(level: ValidationFailureLevel, msg: String) -> ValidationFailure {
+    let mut failure = ValidationFailure::default();
+    failure.level = level;
+    failure.msg = msg;
+    failure
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -614,4 +834,101 @@ mapping:
         ));
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_validate_config_schemas() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let mut par = ProviderArchive::new("kv", "wasmCloud", None, None);
+        par.add_library("x86_64-linux", b"provider")
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        par.set_schema(serde_json::json!({
+            "type": "object",
+            "properties": { "url": { "pattern": "^redis://" } },
+            "required": ["url"],
+        }))
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+        par.write(
+            tmp_dir.path().join("kv.par"),
+            &nkeys::KeyPair::new_account(),
+            &nkeys::KeyPair::new_service(),
+            false,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+        let yaml = r#"
+apiVersion: core.oam.dev/v1beta1
+kind: Application
+metadata:
+  name: kv
+  annotations:
+    version: v0.1.0
+spec:
+  components:
+    - name: counter
+      type: component
+      properties:
+        image: file://counter.wasm
+      traits:
+        - type: link
+          properties:
+            namespace: wasi
+            package: keyvalue
+            interfaces: [store]
+            target:
+              name: kv
+              config:
+                - name: kv-url
+                  properties:
+                    url: http://localhost
+    - name: kv
+      type: capability
+      properties:
+        image: file://kv.par
+        config:
+          - name: external
+"#;
+        let manifest: Manifest = serde_yaml::from_str(yaml)?;
+        let mut failures = Vec::new();
+        validate_config_schemas(&manifest, tmp_dir.path(), &mut failures).await;
+        let msgs: Vec<_> = failures
+            .iter()
+            .map(|f| (f.level.clone(), f.msg.as_str()))
+            .collect();
+        assert_eq!(
+            msgs,
+            [
+                (
+                    ValidationFailureLevel::Warning,
+                    "skipping validation of config of provider `kv`: config `external` is not in the manifest"
+                ),
+                (
+                    ValidationFailureLevel::Error,
+                    "target config of link from `counter` to `kv` does not match the schema of `kv`: `/url`: \"http://localhost\" does not match \"^redis://\""
+                ),
+            ]
+        );
+
+        // Required keys may be provided by either the provider or the link config
+        let yaml = yaml
+            .replace(
+                "- name: external\n",
+                "- name: defaults\n            properties:\n              timeout: '5'\n",
+            )
+            .replace("http://localhost", "redis://localhost");
+        let mut manifest: Manifest = serde_yaml::from_str(&yaml)?;
+        let mut failures = Vec::new();
+        validate_config_schemas(&manifest, tmp_dir.path(), &mut failures).await;
+        assert!(failures.is_empty(), "{failures:?}");
+
+        manifest.spec.components[0].traits = None;
+        validate_config_schemas(&manifest, tmp_dir.path(), &mut failures).await;
+        assert!(failures.is_empty(), "{failures:?}");
+
+        let mut failures = Vec::new();
+        validate_config_schemas(&manifest, &tmp_dir.path().join("missing"), &mut failures).await;
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].level, ValidationFailureLevel::Warning);
+        Ok(())
+    }
 }
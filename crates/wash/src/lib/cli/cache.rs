@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use serde_json::json;
+use wasmcloud_control_interface::CachedArtifact;
+
+use crate::lib::{
+    common::{boxed_err_to_anyhow, find_host_id},
+    config::WashConnectionOptions,
+};
+
+use super::{CliConnectionOpts, CommandOutput};
+
+#[derive(Debug, Clone, Parser)]
+pub enum CacheCommand {
+    /// List the artifacts in the OCI artifact cache of a host
+    #[clap(name = "list", alias = "ls")]
+    List(CacheListCommand),
+
+    /// Remove artifacts from the OCI artifact cache of a host. Artifacts of running components
+    /// and providers are kept unless `--all` is set
+    #[clap(name = "prune")]
+    Prune(CachePruneCommand),
+
+    /// Pull artifacts into the OCI artifact cache of a host ahead of starting them
+    #[clap(name = "pull")]
+    Pull(CachePullCommand),
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct CacheListCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// ID of the host whose cache to list. If a non-ID is provided, the host will be selected based
+    /// on matching the prefix of the ID or the friendly name and will return an error if more than
+    /// one host matches.
+    #[clap(name = "host-id")]
+    pub host_id: String,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct CachePruneCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// ID of the host whose cache to prune. If a non-ID is provided, the host will be selected based
+    /// on matching the prefix of the ID or the friendly name and will return an error if more than
+    /// one host matches.
+    #[clap(name = "host-id")]
+    pub host_id: String,
+
+    /// Also remove the artifacts of running components and providers
+    #[clap(long = "all", default_value = "false")]
+    pub all: bool,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct CachePullCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// ID of the host to pull the artifacts to. If a non-ID is provided, the host will be selected
+    /// based on matching the prefix of the ID or the friendly name and will return an error if more
+    /// than one host matches.
+    #[clap(name = "host-id")]
+    pub host_id: String,
+
+    /// OCI references of the artifacts to pull, e.g. `ghcr.io/wasmcloud/http-server:0.23.0`
+    #[clap(name = "reference", required = true, num_args = 1..)]
+    pub references: Vec<String>,
+
+    /// Amount of time in milliseconds to wait for the host to pull the artifacts
+    #[clap(long = "pull-timeout-ms", default_value_t = 60_000)]
+    pub pull_timeout_ms: u64,
+}
+
+pub async fn handle_cache_command(cmd: CacheCommand) -> Result<CommandOutput> {
+    match cmd {
+        CacheCommand::List(cmd) => handle_cache_list(cmd).await,
+        CacheCommand::Prune(cmd) => handle_cache_prune(cmd).await,
+        CacheCommand::Pull(cmd) => handle_cache_pull(cmd).await,
+    }
+}
+
+async fn handle_cache_list(cmd: CacheListCommand) -> Result<CommandOutput> {
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+
+    let (host_id, _) = find_host_id(&cmd.host_id, &client).await?;
+    let response = client
+        .get_cached_artifacts(&host_id)
+        .await
+        .map_err(boxed_err_to_anyhow)?;
+    if !response.succeeded() {
+        bail!("Operation failed: {}", response.message());
+    }
+    let artifacts = response.into_data().unwrap_or_default();
+
+    let text = if artifacts.is_empty() {
+        format!("No artifacts cached on host {host_id}")
+    } else {
+        artifacts_text(&artifacts)
+    };
+    Ok(artifacts_output(text, &artifacts))
+}
+
+async fn handle_cache_prune(cmd: CachePruneCommand) -> Result<CommandOutput> {
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+
+    let (host_id, _) = find_host_id(&cmd.host_id, &client).await?;
+    let response = client
+        .prune_cache(&host_id, cmd.all)
+        .await
+        .map_err(boxed_err_to_anyhow)?;
+    if !response.succeeded() {
+        bail!("Operation failed: {}", response.message());
+    }
+    let artifacts = response.into_data().unwrap_or_default();
+
+    let size = artifacts.iter().map(CachedArtifact::size).sum::<u64>();
+    let text = format!(
+        "Removed {} cached artifact(s) ({size} bytes) from host {host_id}",
+        artifacts.len()
+    );
+    Ok(artifacts_output(text, &artifacts))
+}
+
+async fn handle_cache_pull(cmd: CachePullCommand) -> Result<CommandOutput> {
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let client = wco.into_ctl_client(None).await?;
+
+    let (host_id, _) = find_host_id(&cmd.host_id, &client).await?;
+    let response = client
+        .pull_artifacts(&host_id, cmd.references, Some(cmd.pull_timeout_ms))
+        .await
+        .map_err(boxed_err_to_anyhow)?;
+    if !response.succeeded() {
+        bail!("Operation failed: {}", response.message());
+    }
+    let artifacts = response.into_data().unwrap_or_default();
+
+    let text = format!(
+        "Host {host_id} cached {} artifact(s)\n{}",
+        artifacts.len(),
+        artifacts_text(&artifacts)
+    );
+    Ok(artifacts_output(text, &artifacts))
+}
+
+/// Format cached artifacts as one line per artifact
+fn artifacts_text(artifacts: &[CachedArtifact]) -> String {
+    artifacts
+        .iter()
+        .map(|artifact| {
+            format!(
+                "{}\t{} bytes\t{}",
+                artifact.digest(),
+                artifact.size(),
+                artifact.references().join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn artifacts_output(text: String, artifacts: &[CachedArtifact]) -> CommandOutput {
+    CommandOutput::new(
+        text,
+        HashMap::from([
+            ("success".into(), json!(true)),
+            ("artifacts".into(), json!(artifacts)),
+        ]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{CacheCommand, CachePruneCommand, CachePullCommand};
+
+    const HOST_ID: &str = "host-id";
+
+    #[derive(Parser, Debug)]
+    struct Cmd {
+        #[clap(subcommand)]
+        command: CacheCommand,
+    }
+
+    #[test]
+    fn test_cache_commands() {
+        let cmd: Cmd = Parser::try_parse_from(["cache", "prune", HOST_ID, "--all"]).unwrap();
+        let CacheCommand::Prune(CachePruneCommand { host_id, all, .. }) = cmd.command else {
+            panic!("expected prune command");
+        };
+        assert_eq!(host_id, HOST_ID);
+        assert!(all);
+
+        let cmd: Cmd = Parser::try_parse_from([
+            "cache",
+            "pull",
+            HOST_ID,
+            "ghcr.io/wasmcloud/http-server:0.23.0",
+            "ghcr.io/wasmcloud/components/http-hello-world-rust:0.1.0",
+        ])
+        .unwrap();
+        let CacheCommand::Pull(CachePullCommand {
+            host_id,
+            references,
+            pull_timeout_ms,
+            ..
+        }) = cmd.command
+        else {
+            panic!("expected pull command");
+        };
+        assert_eq!(host_id, HOST_ID);
+        assert_eq!(
+            references,
+            vec![
+                "ghcr.io/wasmcloud/http-server:0.23.0",
+                "ghcr.io/wasmcloud/components/http-hello-world-rust:0.1.0",
+            ]
+        );
+        assert_eq!(pull_timeout_ms, 60_000);
+
+        assert!(Parser::try_parse_from(["cache", "pull", HOST_ID])
+            .map(|cmd: Cmd| cmd.command)
+            .is_err());
+    }
+}
@@ -98,6 +98,7 @@ pub async fn handle_command(
                 password: command.password.clone(),
                 insecure: command.insecure,
                 insecure_skip_tls_verify: command.insecure_skip_tls_verify,
+                ..Default::default()
             },
         )
         .await?;
@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use clap::Parser;
-use wasmcloud_control_interface::{CtlResponse, Link};
+use wasmcloud_control_interface::{CtlResponse, Link, WeightedTarget};
 
 use crate::lib::{
     cli::CliConnectionOpts, common::boxed_err_to_anyhow, config::WashConnectionOptions,
@@ -76,6 +76,23 @@ pub struct LinkPutCommand {
     /// WIT namespace, package, and interface.
     #[clap(short = 'l', long = "link-name")]
     pub link_name: Option<String>,
+
+    /// Split invocations between several targets in proportion to their weights, specified as
+    /// `<target>=<weight>`. The target of the link must be one of the weighted targets
+    #[clap(long = "weighted-target", value_parser = parse_weighted_target)]
+    pub weighted_targets: Vec<WeightedTarget>,
+}
+
+/// Parse a weighted target specified as `<target>=<weight>`
+fn parse_weighted_target(s: &str) -> Result<WeightedTarget> {
+    let (target, weight) = s
+        .split_once('=')
+        .context("weighted target must be specified as `<target>=<weight>`")?;
+    let target = validate_component_id(target)?;
+    let weight = weight
+        .parse()
+        .with_context(|| format!("invalid weight `{weight}` of target `{target}`"))?;
+    Ok(WeightedTarget::new(&target, weight))
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -63,6 +63,10 @@ pub struct RegistryPullCommand {
     #[clap(long = "allow-latest")]
     pub allow_latest: bool,
 
+    /// Path to a PEM encoded public key, as generated by `cosign generate-key-pair`. If set, the artifact must have a cosign signature valid for one of the keys. This is a repeatable option
+    #[clap(long = "signature-key")]
+    pub signature_keys: Vec<PathBuf>,
+
     #[clap(flatten)]
     pub opts: AuthOpts,
 }
@@ -2,7 +2,7 @@ use anyhow::{anyhow, bail, Context, Result};
 use clap::Parser;
 use std::collections::HashMap;
 use tokio::time::Duration;
-use tracing::error;
+use tracing::{error, warn};
 use wasmcloud_control_interface::HostInventory;
 
 use crate::lib::{
@@ -12,7 +12,10 @@ use crate::lib::{
     config::{host_pid_file, WashConnectionOptions},
     context::default_timeout_ms,
     id::ServerId,
-    wait::{wait_for_provider_stop_event, FindEventOutcome, ProviderStoppedInfo},
+    wait::{
+        wait_for_host_drained_event, wait_for_provider_stop_event, FindEventOutcome,
+        HostDrainedInfo, ProviderStoppedInfo,
+    },
 };
 
 use super::validate_component_id;
@@ -99,6 +102,15 @@ pub struct StopHostCommand {
         default_value_t = default_timeout_ms()
     )]
     pub host_shutdown_timeout: u64,
+
+    /// Drain the host before stopping it. The host stops accepting invocations, waits up to
+    /// `--host-timeout` for in-flight invocations to finish and stops its providers
+    #[clap(long = "drain")]
+    pub drain: bool,
+
+    /// Leave the host running after draining it
+    #[clap(long = "no-stop", requires = "drain")]
+    pub no_stop: bool,
 }
 
 pub async fn handle_stop_provider(cmd: StopProviderCommand) -> Result<CommandOutput> {
@@ -254,19 +266,71 @@ pub async fn handle_stop_component(cmd: StopComponentCommand) -> Result<CommandO
 }
 
 pub async fn stop_host(cmd: StopHostCommand) -> Result<CommandOutput> {
+    let timeout_ms = cmd.opts.timeout_ms;
     let wco: WashConnectionOptions = cmd.opts.try_into()?;
     let client = wco.into_ctl_client(None).await?;
 
+    if cmd.drain {
+        drain_host(&client, &cmd.host_id, cmd.host_shutdown_timeout, timeout_ms).await?;
+        if cmd.no_stop {
+            return Ok(CommandOutput::from_key_and_text(
+                "result",
+                format!("Host {} drained", cmd.host_id),
+            ));
+        }
+    }
+
     let (_, hosts_remain) = stop_hosts(client, Some(&cmd.host_id), false).await?;
     let pid_file_exists = tokio::fs::try_exists(host_pid_file()?).await?;
     if !hosts_remain && pid_file_exists {
         tokio::fs::remove_file(host_pid_file()?).await?;
     }
 
-    Ok(CommandOutput::from_key_and_text(
-        "result",
-        format!("Host {} acknowledged stop request", cmd.host_id),
-    ))
+    let text = if cmd.drain {
+        format!("Host {} drained and acknowledged stop request", cmd.host_id)
+    } else {
+        format!("Host {} acknowledged stop request", cmd.host_id)
+    };
+    Ok(CommandOutput::from_key_and_text("result", text))
+}
+
+/// Drain a host, waiting up to `drain_timeout_ms` for its in-flight invocations to finish and
+/// `timeout_ms` more for it to stop its providers
+pub async fn drain_host(
+    client: &wasmcloud_control_interface::Client,
+    host_id: &str,
+    drain_timeout_ms: u64,
+    timeout_ms: u64,
+) -> Result<()> {
+    let mut receiver = client
+        .events_receiver(vec!["host_drained".to_string()])
+        .await
+        .map_err(boxed_err_to_anyhow)?;
+
+    let host_id = find_host_id(host_id, client).await?.0;
+    let ack = client
+        .drain_host(&host_id, Some(drain_timeout_ms))
+        .await
+        .map_err(boxed_err_to_anyhow)?;
+    if !ack.succeeded() {
+        bail!("Operation failed: {}", ack.message());
+    }
+
+    let event = wait_for_host_drained_event(
+        &mut receiver,
+        Duration::from_millis(drain_timeout_ms.saturating_add(timeout_ms)),
+        host_id.to_string(),
+    )
+    .await?;
+    match event {
+        FindEventOutcome::Success(HostDrainedInfo { timed_out, .. }) => {
+            if timed_out {
+                warn!("host [{host_id}] timed out waiting for in-flight invocations to finish");
+            }
+            Ok(())
+        }
+        FindEventOutcome::Failure(err) => bail!("{}", err),
+    }
 }
 
 async fn find_host_with_provider(
@@ -17,6 +17,7 @@ use provider_archive::ProviderArchive;
 use sha2::Digest;
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
+use wasmcloud_core::signature::SignatureVerifier;
 use wasmcloud_core::tls;
 
 const PROVIDER_ARCHIVE_MEDIA_TYPE: &str = "application/vnd.wasmcloud.provider.archive.layer.v1+par";
@@ -40,6 +41,9 @@ pub struct OciPullOptions {
     pub insecure: bool,
     /// Whether or not OCI registry's certificate will be checked for validity. This will make your HTTPS connections insecure.
     pub insecure_skip_tls_verify: bool,
+    /// Paths to PEM encoded public keys. If any are set, the artifact must have a cosign-compatible
+    /// signature valid for one of them
+    pub signature_public_keys: Vec<PathBuf>,
 }
 
 /// Additional options for pushing an OCI artifact
@@ -147,9 +151,30 @@ pub async fn pull_oci_artifact(image_ref: &Reference, options: OciPullOptions) -
         _ => RegistryAuth::Anonymous,
     };
 
+    // Verify the signature of the current manifest digest, which the pulled artifact must match
+    let verified_digest = if options.signature_public_keys.is_empty() {
+        None
+    } else {
+        let verifier = SignatureVerifier::load(&options.signature_public_keys)?;
+        let (_, digest) = client
+            .pull_manifest(image_ref, &auth)
+            .await
+            .context("failed to fetch OCI manifest")?;
+        verifier
+            .verify(&client, &auth, image_ref, &digest)
+            .await
+            .context("artifact signature verification failed")?;
+        Some(digest)
+    };
+
+    // Pull by the verified digest, so that a tag moved after verification cannot substitute
+    // unverified content
+    let verified_ref = verified_digest
+        .as_ref()
+        .map(|digest| image_ref.clone_with_digest(digest.clone()));
     let image_data = client
         .pull(
-            image_ref,
+            verified_ref.as_ref().unwrap_or(image_ref),
             &auth,
             vec![
                 PROVIDER_ARCHIVE_MEDIA_TYPE,
@@ -160,6 +185,12 @@ pub async fn pull_oci_artifact(image_ref: &Reference, options: OciPullOptions) -
         )
         .await?;
 
+    if let Some(verified_digest) = verified_digest {
+        if image_data.digest.as_deref() != Some(verified_digest.as_str()) {
+            bail!("image digest did not match the digest of its verified signature, aborting")
+        }
+    }
+
     // Reformatting digest in case the sha256: prefix is left off
     let digest = match options.digest {
         Some(d) if d.starts_with("sha256:") => Some(d),
@@ -367,3 +367,45 @@ pub async fn wait_for_component_stop_event(
     let event = find_event(receiver, timeout, check_function).await?;
     Ok(event)
 }
+
+/// Information related to a host drain
+pub struct HostDrainedInfo {
+    pub host_id: String,
+    /// Whether the host stopped waiting for in-flight invocations before they finished
+    pub timed_out: bool,
+}
+
+/// Uses the NATS receiver to read events being published to the wasmCloud lattice event subject, up until the given timeout duration.
+///
+/// If the host drained event is found, the `Ok` variant of the `Result` will be returned, with the `FindEventOutcome` enum
+/// containing whether the host timed out waiting for in-flight invocations.
+///
+/// If the timeout is reached or another error occurs, the `Err` variant of the `Result` will be returned.
+pub async fn wait_for_host_drained_event(
+    receiver: &mut Receiver<Event>,
+    timeout: Duration,
+    host_id: String,
+) -> Result<FindEventOutcome<HostDrainedInfo>> {
+    let check_function = move |event: Event| {
+        let cloud_event = get_wasmbus_event_info(event)?;
+
+        if cloud_event.source != host_id.as_str()
+            || cloud_event.event_type != "com.wasmcloud.lattice.host_drained"
+        {
+            return Ok(EventCheckOutcome::NotApplicable);
+        }
+
+        let timed_out = cloud_event
+            .data
+            .get("timed_out")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or_default();
+        Ok(EventCheckOutcome::Success(HostDrainedInfo {
+            host_id: host_id.as_str().into(),
+            timed_out,
+        }))
+    };
+
+    let event = find_event(receiver, timeout, check_function).await?;
+    Ok(event)
+}
@@ -10,6 +10,7 @@ use anyhow::{bail, Context};
 use clap::{ArgAction, Parser};
 use nkeys::KeyPair;
 use regex::Regex;
+use tokio::task::JoinSet;
 use tokio::time::{timeout, timeout_at};
 use tokio::{select, signal};
 use tracing::{warn, Level as TracingLogLevel};
@@ -17,12 +18,20 @@ use tracing_subscriber::util::SubscriberInitExt as _;
 use url::Url;
 use wasmcloud_core::logging::Level as WasmcloudLogLevel;
 use wasmcloud_core::{OtelConfig, OtelProtocol};
+use wasmcloud_host::event::webhook::{self, WebhookConfig, WebhookEventPublisher};
 use wasmcloud_host::nats::builder::NatsHostBuilder;
 use wasmcloud_host::oci::Config as OciConfig;
+use wasmcloud_host::policy::audit::FileAuditSink;
+use wasmcloud_host::registry::merge_registry_config;
+use wasmcloud_host::store::FileStore;
+use wasmcloud_host::wasmbus::host_config::DEFAULT_COMPILATION_CACHE_SIZE;
 use wasmcloud_host::workload_identity::WorkloadIdentityConfig;
 use wasmcloud_host::WasmbusHostConfig;
-use wasmcloud_host::{nats::connect_nats, wasmbus::Features};
-use wasmcloud_tracing::configure_observability;
+use wasmcloud_host::{
+    nats::connect_nats,
+    wasmbus::{Features, HostBuilder, HostManifest, TenantQuota},
+};
+use wasmcloud_tracing::{configure_observability, enable_prometheus_metrics};
 
 #[derive(Debug, Parser)]
 #[allow(clippy::struct_excessive_bools)]
@@ -202,6 +211,32 @@ struct Args {
         requires = "policy_topic"
     )]
     policy_changes_topic: Option<String>,
+    /// If provided, enables policy checks on start actions and component invocations using a local JSON or YAML rule file, which is reloaded when modified
+    #[clap(
+        long = "policy-file",
+        env = "WASMCLOUD_POLICY_FILE",
+        conflicts_with = "policy_topic"
+    )]
+    policy_file: Option<PathBuf>,
+    /// Interval in seconds at which the file supplied with `policy_file` is checked for changes. Defaults to five seconds.
+    #[clap(
+        long = "policy-file-reload-interval-seconds",
+        env = "WASMCLOUD_POLICY_FILE_RELOAD_INTERVAL",
+        requires = "policy_file",
+        value_parser = parse_duration_secs,
+        hide = true
+    )]
+    policy_file_reload_interval: Option<Duration>,
+    /// If provided, policy decisions are cached for the given number of milliseconds. Cached decisions are overridden or invalidated by messages on `policy_changes_topic`.
+    #[clap(
+        long = "policy-cache-ttl-ms",
+        env = "WASMCLOUD_POLICY_CACHE_TTL",
+        value_parser = parse_duration_millis,
+    )]
+    policy_cache_ttl: Option<Duration>,
+    /// If provided, every policy decision is appended to the given file as a line of JSON
+    #[clap(long = "policy-audit-log", env = "WASMCLOUD_POLICY_AUDIT_LOG")]
+    policy_audit_log: Option<PathBuf>,
     /// If provided, allows to set a custom Max Execution time for the Host in ms.
     #[clap(long = "max-execution-time-ms", default_value = "600000", env = "WASMCLOUD_MAX_EXECUTION_TIME_MS", value_parser = parse_duration_millis)]
     max_execution_time: Duration,
@@ -227,6 +262,75 @@ struct Args {
     )]
     max_core_instances_per_component: u32,
 
+    /// If provided, the fuel consumed by each component invocation is metered and reported in host metrics
+    #[clap(long = "fuel-metering", env = "WASMCLOUD_FUEL_METERING")]
+    fuel_metering: bool,
+
+    /// The maximum amount of fuel a single component invocation may consume before it traps. Enables fuel metering
+    #[clap(long = "max-fuel", env = "WASMCLOUD_MAX_FUEL")]
+    max_fuel: Option<u64>,
+
+    /// If provided, compiled components are cached in the given directory, speeding up subsequent starts of the same components
+    #[clap(
+        long = "compilation-cache-dir",
+        env = "WASMCLOUD_COMPILATION_CACHE_DIR"
+    )]
+    compilation_cache_dir: Option<PathBuf>,
+
+    /// The maximum size in bytes of the compiled component cache (default 1 GiB)
+    #[clap(
+        long = "compilation-cache-max-size-bytes",
+        default_value_t = DEFAULT_COMPILATION_CACHE_SIZE,
+        env = "WASMCLOUD_COMPILATION_CACHE_MAX_SIZE",
+        requires = "compilation_cache_dir"
+    )]
+    compilation_cache_max_size: u64,
+
+    /// If provided, lattice events are POSTed as CloudEvents to the given webhook URLs instead of being published on NATS
+    #[clap(
+        long = "event-webhook-url",
+        env = "WASMCLOUD_EVENT_WEBHOOK_URLS",
+        value_delimiter = ','
+    )]
+    event_webhook_urls: Vec<Url>,
+
+    /// The maximum number of events POSTed to the event webhooks in a single request
+    #[clap(
+        long = "event-webhook-batch-size",
+        default_value_t = webhook::DEFAULT_BATCH_SIZE,
+        env = "WASMCLOUD_EVENT_WEBHOOK_BATCH_SIZE",
+        hide = true
+    )]
+    event_webhook_batch_size: usize,
+
+    /// The maximum number of events buffered for delivery to the event webhooks. Further events are dropped
+    #[clap(
+        long = "event-webhook-queue-size",
+        default_value_t = webhook::DEFAULT_QUEUE_SIZE,
+        env = "WASMCLOUD_EVENT_WEBHOOK_QUEUE_SIZE",
+        hide = true
+    )]
+    event_webhook_queue_size: usize,
+
+    /// The number of times delivery of events to an event webhook is retried before they are dropped
+    #[clap(
+        long = "event-webhook-max-retries",
+        default_value_t = webhook::DEFAULT_MAX_RETRIES,
+        env = "WASMCLOUD_EVENT_WEBHOOK_MAX_RETRIES",
+        hide = true
+    )]
+    event_webhook_max_retries: u32,
+
+    /// The time in milliseconds to wait for buffered events to be delivered to the event webhooks when the host stops
+    #[clap(
+        long = "event-webhook-drain-timeout-ms",
+        default_value = "10000",
+        env = "WASMCLOUD_EVENT_WEBHOOK_DRAIN_TIMEOUT_MS",
+        value_parser = parse_duration_millis,
+        hide = true
+    )]
+    event_webhook_drain_timeout: Duration,
+
     /// If provided, allows setting a custom timeout for requesting policy decisions. Defaults to one second. Requires `policy_topic` to be set.
     #[clap(
         long = "policy-timeout-ms",
@@ -381,6 +485,15 @@ struct Args {
     /// HTTP administration endpoint address
     http_admin: Option<SocketAddr>,
 
+    #[clap(
+        long = "http-admin-token",
+        env = "WASMCLOUD_HTTP_ADMIN_TOKEN",
+        requires = "http_admin"
+    )]
+    /// Bearer token authorizing the write endpoints (scale component, stop host) of the HTTP
+    /// administration endpoint, which are disabled if not set
+    http_admin_token: Option<String>,
+
     #[clap(
         long = "enable-component-auction",
         env = "WASMCLOUD_COMPONENT_AUCTION_ENABLED"
@@ -394,6 +507,113 @@ struct Args {
     )]
     /// Determines whether capability provider auctions should be enabled (defaults to true)
     enable_provider_auction: Option<bool>,
+
+    /// If enabled, invocations sent by components are signed with the host key
+    #[clap(long = "sign-invocations", env = "WASMCLOUD_SIGN_INVOCATIONS")]
+    sign_invocations: bool,
+
+    /// If enabled, invocations of components which are not signed by a trusted host are rejected. This includes all invocations by capability providers
+    #[clap(
+        long = "require-signed-invocations",
+        env = "WASMCLOUD_REQUIRE_SIGNED_INVOCATIONS"
+    )]
+    require_signed_invocations: bool,
+
+    /// Public keys of hosts trusted to sign invocations, in addition to this host
+    #[clap(
+        long = "trusted-invocation-issuer",
+        env = "WASMCLOUD_TRUSTED_INVOCATION_ISSUERS",
+        value_delimiter = ',',
+        requires = "require_signed_invocations"
+    )]
+    trusted_invocation_issuers: Vec<String>,
+
+    /// If enabled, invocations of components running on this host are sent over NATS instead of being dispatched in-process
+    #[clap(
+        long = "disable-local-invocations",
+        env = "WASMCLOUD_DISABLE_LOCAL_INVOCATIONS"
+    )]
+    disable_local_invocations: bool,
+
+    /// Resource quota of a tenant on this host, in the form `tenant:instances=N,memory=BYTES,providers=N`. Components and providers belong to the tenant named by their `wasmcloud.dev/tenant` annotation. This is a repeatable option
+    #[clap(
+        long = "tenant-quota",
+        env = "WASMCLOUD_TENANT_QUOTAS",
+        value_delimiter = ';',
+        value_parser = parse_tenant_quota
+    )]
+    tenant_quotas: Vec<(String, TenantQuota)>,
+
+    /// Public key required to sign artifacts fetched from an OCI registry, in the form `registry=path`, where `path` is a PEM encoded ECDSA P-256 public key as generated by `cosign generate-key-pair`. Artifacts from a registry with keys must have a cosign signature valid for one of them. This is a repeatable option
+    #[clap(
+        long = "oci-signature-key",
+        env = "WASMCLOUD_OCI_SIGNATURE_KEYS",
+        value_delimiter = ',',
+        value_parser = parse_signature_key
+    )]
+    oci_signature_keys: Vec<(String, PathBuf)>,
+
+    /// Directory in which OCI artifacts are cached. Defaults to a directory in the system temporary directory
+    #[clap(long = "oci-cache-dir", env = "WASMCLOUD_OCI_CACHE_DIR")]
+    oci_cache_dir: Option<PathBuf>,
+
+    /// Maximum total size in bytes of cached OCI artifacts. Once exceeded, the least recently used artifacts are evicted from the cache
+    #[clap(long = "oci-cache-max-size", env = "WASMCLOUD_OCI_CACHE_MAX_SIZE")]
+    oci_cache_max_size: Option<u64>,
+
+    /// If enabled, the host runs without connecting to NATS. All invocations are dispatched in-process, only builtin providers can be started and the host is managed through the HTTP administration API or a manifest
+    #[clap(
+        long = "embedded",
+        env = "WASMCLOUD_EMBEDDED",
+        conflicts_with_all = ["policy_topic", "secrets_topic_prefix", "config_service_enabled", "disable_local_invocations"]
+    )]
+    embedded: bool,
+
+    /// Path to a YAML manifest of configuration, components, providers and links to apply to the host once started
+    #[clap(long = "manifest", env = "WASMCLOUD_MANIFEST")]
+    manifest: Option<PathBuf>,
+
+    /// Path to a directory the configuration and lattice data of an embedded host, such as links and component specifications, are persisted in, so that they survive restarts. Kept in memory if not set
+    #[clap(long = "store-dir", env = "WASMCLOUD_STORE_DIR", requires = "embedded")]
+    store_dir: Option<PathBuf>,
+
+    /// JWT of an operator trusted to issue accounts. If set, only components and providers whose issuers chain up to a trusted operator through an account set with `--account-jwt` are started. This is a repeatable option
+    #[clap(
+        long = "trusted-operator-jwt",
+        env = "WASMCLOUD_TRUSTED_OPERATOR_JWTS",
+        value_delimiter = ','
+    )]
+    trusted_operators: Vec<String>,
+
+    /// JWT of an account issuing components and providers, which must be issued by a trusted operator. This is a repeatable option
+    #[clap(
+        long = "account-jwt",
+        env = "WASMCLOUD_ACCOUNT_JWTS",
+        value_delimiter = ',',
+        requires = "trusted_operators"
+    )]
+    trusted_accounts: Vec<String>,
+
+    /// Path to a file containing revocation list JWTs issued by accounts, separated by whitespace. Components and providers revoked by a list are not started, and running instances are stopped when the file is updated
+    #[clap(long = "revocation-list-file", env = "WASMCLOUD_REVOCATION_LIST_FILE")]
+    revocation_list_file: Option<PathBuf>,
+
+    /// Interval in seconds at which the file supplied with `revocation_list_file` is checked for changes. Defaults to five seconds.
+    #[clap(
+        long = "revocation-list-reload-interval-seconds",
+        env = "WASMCLOUD_REVOCATION_LIST_RELOAD_INTERVAL",
+        requires = "revocation_list_file",
+        value_parser = parse_duration_secs,
+        hide = true
+    )]
+    revocation_list_reload_interval: Option<Duration>,
+
+    /// Name of a config in the config store whose values are revocation list JWTs issued by accounts. The config is watched for updates, which stop running instances of revoked components and providers
+    #[clap(
+        long = "revocation-list-config",
+        env = "WASMCLOUD_REVOCATION_LIST_CONFIG"
+    )]
+    revocation_list_config: Option<String>,
 }
 
 const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
@@ -429,6 +649,10 @@ async fn main() -> anyhow::Result<()> {
         ..Default::default()
     };
     let log_level = WasmcloudLogLevel::from(args.log_level);
+    if args.http_admin.is_some() {
+        // Metrics are served by the HTTP administration API
+        enable_prometheus_metrics();
+    }
 
     let _guard = match configure_observability(
         "wasmcloud-host",
@@ -499,6 +723,15 @@ async fn main() -> anyhow::Result<()> {
         oci_registry: args.oci_registry,
         oci_user: args.oci_user,
         oci_password: args.oci_password,
+        signature_public_keys: args.oci_signature_keys.into_iter().fold(
+            HashMap::new(),
+            |mut keys, (registry, path)| {
+                keys.entry(registry).or_insert_with(Vec::new).push(path);
+                keys
+            },
+        ),
+        cache_dir: args.oci_cache_dir,
+        cache_max_size: args.oci_cache_max_size,
     };
 
     let mut labels = args
@@ -538,97 +771,193 @@ async fn main() -> anyhow::Result<()> {
     } else {
         None
     };
-    let ctl_nats = connect_nats(
-        ctl_nats_url.as_str(),
-        ctl_jwt.or_else(|| nats_jwt.clone()).as_ref(),
-        ctl_key.or_else(|| nats_key.clone()),
-        args.ctl_tls,
-        None,
-        workload_identity_config.clone(),
-    )
-    .await
-    .context("failed to establish NATS control connection")?;
-
-    let builder = NatsHostBuilder::new(
-        ctl_nats,
-        Some(args.ctl_topic_prefix),
-        args.lattice.clone(),
-        args.js_domain.clone(),
-        Some(oci_opts.clone()),
-        labels.clone().into_iter().collect(),
-        args.config_service_enabled,
-        args.enable_component_auction.unwrap_or(true),
-        args.enable_provider_auction.unwrap_or(true),
-    )
-    .await?
-    .with_event_publisher(host_key.public_key());
-
-    let builder = if let Some(policy_topic) = args.policy_topic.as_deref() {
-        anyhow::ensure!(
-            validate_nats_subject(policy_topic).is_ok(),
-            "Invalid policy topic"
-        );
-        builder
-            .with_policy_manager(
-                host_key.clone(),
-                labels.clone(),
-                args.policy_topic.clone(),
-                args.policy_timeout_ms,
-                args.policy_changes_topic.clone(),
-            )
-            .await?
-    } else {
-        builder
+    let config = WasmbusHostConfig {
+        lattice: Arc::from(args.lattice.clone()),
+        host_key: host_key.clone(),
+        config_service_enabled: args.config_service_enabled,
+        js_domain: args.js_domain.clone(),
+        labels: labels.clone(),
+        provider_shutdown_delay: Some(args.provider_shutdown_delay),
+        oci_opts: oci_opts.clone(),
+        rpc_nats_url,
+        rpc_timeout: args.rpc_timeout_ms,
+        rpc_jwt: rpc_jwt.or_else(|| nats_jwt.clone()),
+        rpc_key: rpc_key.or_else(|| nats_key.clone()),
+        rpc_tls: args.rpc_tls,
+        allow_file_load: args.allow_file_load,
+        log_level,
+        enable_structured_logging: args.enable_structured_logging,
+        otel_config,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        max_execution_time: args.max_execution_time,
+        max_linear_memory: args.max_linear_memory,
+        max_component_size: args.max_component_size,
+        max_components: args.max_components,
+        max_core_instances_per_component: args.max_core_instances_per_component,
+        fuel_metering: args.fuel_metering,
+        max_fuel: args.max_fuel,
+        compilation_cache_dir: args.compilation_cache_dir,
+        compilation_cache_max_size: args.compilation_cache_max_size,
+        heartbeat_interval: args.heartbeat_interval,
+        experimental_features,
+        http_admin: args.http_admin,
+        http_admin_token: args.http_admin_token,
+        enable_component_auction: args.enable_component_auction.unwrap_or(true),
+        enable_provider_auction: args.enable_provider_auction.unwrap_or(true),
+        sign_invocations: args.sign_invocations,
+        require_signed_invocations: args.require_signed_invocations,
+        trusted_invocation_issuers: args.trusted_invocation_issuers,
+        local_invocations: !args.disable_local_invocations,
+        tenant_quotas: args.tenant_quotas.into_iter().collect(),
+        embedded: args.embedded,
+        trusted_operators: args.trusted_operators,
+        trusted_accounts: args.trusted_accounts,
+        revocation_list_file: args.revocation_list_file,
+        revocation_list_reload_interval: args.revocation_list_reload_interval,
+        revocation_list_config: args.revocation_list_config,
     };
+    let (host_builder, nats_ctl_server) = if args.embedded {
+        let mut registry_config = HashMap::new();
+        merge_registry_config(&mut registry_config, oci_opts).await;
+        let host_builder = HostBuilder::from(config).with_registry_config(registry_config);
+        let host_builder = if let Some(policy_file) = args.policy_file {
+            host_builder
+                .with_local_policy_manager(policy_file, args.policy_file_reload_interval)
+                .await?
+        } else {
+            host_builder
+        };
+        let host_builder = if let Some(ttl) = args.policy_cache_ttl {
+            host_builder.with_policy_cache(ttl)
+        } else {
+            host_builder
+        };
+        let host_builder = if let Some(store_dir) = args.store_dir {
+            let config_store = FileStore::new(store_dir.join("config"))
+                .await
+                .context("failed to open config store")?;
+            let data_store = FileStore::new(store_dir.join("data"))
+                .await
+                .context("failed to open data store")?;
+            host_builder
+                .with_config_store(Some(Arc::new(config_store)))
+                .with_data_store(Some(Arc::new(data_store)))
+        } else {
+            host_builder
+        };
+        (host_builder, None)
+    } else {
+        let ctl_nats = connect_nats(
+            ctl_nats_url.as_str(),
+            ctl_jwt.or_else(|| nats_jwt.clone()).as_ref(),
+            ctl_key.or_else(|| nats_key.clone()),
+            args.ctl_tls,
+            None,
+            workload_identity_config.clone(),
+        )
+        .await
+        .context("failed to establish NATS control connection")?;
+
+        let builder = NatsHostBuilder::new(
+            ctl_nats,
+            Some(args.ctl_topic_prefix),
+            args.lattice.clone(),
+            args.js_domain.clone(),
+            Some(oci_opts.clone()),
+            labels.clone().into_iter().collect(),
+            args.config_service_enabled,
+            args.enable_component_auction.unwrap_or(true),
+            args.enable_provider_auction.unwrap_or(true),
+        )
+        .await?
+        .with_event_publisher(host_key.public_key());
 
-    let builder = if let Some(secrets_topic) = args.secrets_topic_prefix {
-        anyhow::ensure!(
-            validate_nats_subject(&secrets_topic).is_ok(),
-            "Invalid secrets topic"
-        );
-        builder.with_secrets_manager(secrets_topic)?
+        let builder = if let Some(policy_topic) = args.policy_topic.as_deref() {
+            anyhow::ensure!(
+                validate_nats_subject(policy_topic).is_ok(),
+                "Invalid policy topic"
+            );
+            builder
+                .with_policy_manager(
+                    host_key.clone(),
+                    labels.clone(),
+                    args.policy_topic.clone(),
+                    args.policy_timeout_ms,
+                    args.policy_changes_topic.clone(),
+                )
+                .await?
+        } else if let Some(policy_file) = args.policy_file {
+            builder
+                .with_local_policy_manager(policy_file, args.policy_file_reload_interval)
+                .await?
+        } else {
+            builder
+        };
+
+        let builder = if let Some(ttl) = args.policy_cache_ttl {
+            builder
+                .with_policy_cache(ttl, args.policy_changes_topic.clone())
+                .await?
+        } else {
+            builder
+        };
+
+        let builder = if let Some(secrets_topic) = args.secrets_topic_prefix {
+            anyhow::ensure!(
+                validate_nats_subject(&secrets_topic).is_ok(),
+                "Invalid secrets topic"
+            );
+            builder.with_secrets_manager(secrets_topic)?
+        } else {
+            builder
+        };
+
+        let (host_builder, nats_ctl_server) = builder.build(config).await?;
+        (host_builder, Some(nats_ctl_server))
+    };
+    let host_builder = if let Some(policy_audit_log) = args.policy_audit_log {
+        let sink = FileAuditSink::new(policy_audit_log)
+            .await
+            .context("failed to open policy audit log")?;
+        host_builder.with_policy_audit(Arc::new(sink))
     } else {
-        builder
+        host_builder
+    };
+    let host_builder = if args.event_webhook_urls.is_empty() {
+        host_builder
+    } else {
+        let event_publisher = WebhookEventPublisher::new(
+            host_key.public_key(),
+            WebhookConfig {
+                batch_size: args.event_webhook_batch_size,
+                queue_size: args.event_webhook_queue_size,
+                max_retries: args.event_webhook_max_retries,
+                drain_timeout: args.event_webhook_drain_timeout,
+                ..WebhookConfig::new(args.event_webhook_urls)
+            },
+        )
+        .context("failed to create event webhook publisher")?;
+        host_builder.with_event_publisher(Some(Arc::new(event_publisher)))
     };
-
-    let (host_builder, nats_ctl_server) = builder
-        .build(WasmbusHostConfig {
-            lattice: Arc::from(args.lattice.clone()),
-            host_key: host_key.clone(),
-            config_service_enabled: args.config_service_enabled,
-            js_domain: args.js_domain,
-            labels,
-            provider_shutdown_delay: Some(args.provider_shutdown_delay),
-            oci_opts,
-            rpc_nats_url,
-            rpc_timeout: args.rpc_timeout_ms,
-            rpc_jwt: rpc_jwt.or_else(|| nats_jwt.clone()),
-            rpc_key: rpc_key.or_else(|| nats_key.clone()),
-            rpc_tls: args.rpc_tls,
-            allow_file_load: args.allow_file_load,
-            log_level,
-            enable_structured_logging: args.enable_structured_logging,
-            otel_config,
-            version: env!("CARGO_PKG_VERSION").to_string(),
-            max_execution_time: args.max_execution_time,
-            max_linear_memory: args.max_linear_memory,
-            max_component_size: args.max_component_size,
-            max_components: args.max_components,
-            max_core_instances_per_component: args.max_core_instances_per_component,
-            heartbeat_interval: args.heartbeat_interval,
-            experimental_features,
-            http_admin: args.http_admin,
-            enable_component_auction: args.enable_component_auction.unwrap_or(true),
-            enable_provider_auction: args.enable_provider_auction.unwrap_or(true),
-        })
-        .await?;
     let (host, shutdown) = host_builder
         .build()
         .await
         .context("failed to initialize host")?;
 
-    // Start the control interface server
-    let mut ctl = nats_ctl_server.start(host.clone()).await?;
+    // Start the control interface server, unless the host is embedded
+    let mut ctl = if let Some(nats_ctl_server) = nats_ctl_server {
+        nats_ctl_server.start(host.clone()).await?
+    } else {
+        JoinSet::new()
+    };
+
+    if let Some(manifest) = args.manifest {
+        HostManifest::load(manifest)
+            .await?
+            .apply(&host)
+            .await
+            .context("failed to apply host manifest")?;
+    }
 
     #[cfg(unix)]
     let deadline = {
@@ -697,6 +1026,23 @@ fn parse_label(labelpair: &str) -> anyhow::Result<(String, String)> {
     }
 }
 
+fn parse_tenant_quota(arg: &str) -> anyhow::Result<(String, TenantQuota)> {
+    let Some((tenant, quota)) = arg.split_once(':') else {
+        bail!("invalid tenant quota format `{arg}`. Expected `tenant:resource=maximum,...`");
+    };
+    let quota = quota
+        .parse()
+        .with_context(|| format!("invalid quota for tenant `{tenant}`"))?;
+    Ok((tenant.to_string(), quota))
+}
+
+fn parse_signature_key(arg: &str) -> anyhow::Result<(String, PathBuf)> {
+    let Some((registry, path)) = arg.split_once('=') else {
+        bail!("invalid signature key format `{arg}`. Expected `registry=path`");
+    };
+    Ok((registry.to_string(), PathBuf::from(path)))
+}
+
 static JWT_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"-----BEGIN NATS USER JWT-----\n(?<jwt>.*)\n------END NATS USER JWT------").unwrap()
 });
@@ -0,0 +1,52 @@
+#![cfg(feature = "wasmcloud")]
+
+use anyhow::{ensure, Context as _};
+
+use wasmcloud_test_util::host::WasmCloudTestHost;
+
+pub mod common;
+use common::nats::start_nats;
+
+const LATTICE: &str = "cache";
+
+/// Ensure that the host serves OCI artifact cache requests addressed to it over NATS
+#[tokio::test]
+async fn cache_requests() -> anyhow::Result<()> {
+    let (nats_server, nats_url, nats_client) = start_nats(None, true)
+        .await
+        .map(|res| (res.0, res.1, res.2.unwrap()))
+        .context("failed to start NATS")?;
+
+    let ctl_client = wasmcloud_control_interface::ClientBuilder::new(nats_client)
+        .lattice(LATTICE.to_string())
+        .build();
+
+    let host = WasmCloudTestHost::start(&nats_url, LATTICE)
+        .await
+        .context("failed to start test host")?;
+    let host_id = host.host_key().public_key();
+
+    let res = ctl_client
+        .get_cached_artifacts(&host_id)
+        .await
+        .map_err(|e| anyhow::anyhow!(e).context("failed to get cached artifacts"))?;
+    ensure!(res.succeeded(), "{}", res.message());
+    ensure!(res.data().is_some_and(Vec::is_empty));
+
+    let res = ctl_client
+        .prune_cache(&host_id, true)
+        .await
+        .map_err(|e| anyhow::anyhow!(e).context("failed to prune cache"))?;
+    ensure!(res.succeeded(), "{}", res.message());
+
+    // Pulls are answered once complete, including when they fail
+    let res = ctl_client
+        .pull_artifacts(&host_id, vec!["localhost:1/does/not:exist".into()], None)
+        .await
+        .map_err(|e| anyhow::anyhow!(e).context("failed to pull artifacts"))?;
+    ensure!(!res.succeeded(), "pulling a missing artifact should fail");
+
+    host.stop().await.context("failed to stop host")?;
+    nats_server.stop().await.context("failed to stop NATS")?;
+    Ok(())
+}
@@ -15,7 +15,7 @@ use hyper::StatusCode;
 use tokio::try_join;
 use tracing::instrument;
 use tracing_subscriber::prelude::*;
-use wasmcloud_control_interface::CtlResponse;
+use wasmcloud_control_interface::{CtlResponse, Link, WeightedTarget};
 use wasmcloud_core::tls::NativeRootsExt as _;
 use wasmcloud_test_util::lattice::config::assert_config_put;
 use wasmcloud_test_util::lattice::link::assert_remove_link;
@@ -549,6 +549,83 @@ async fn valid_and_invalid() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Ensure that links with weighted targets only accept sticky headers which the host sets on
+/// invocations over the link
+#[instrument(skip_all, ret)]
+#[tokio::test]
+async fn sticky_headers() -> anyhow::Result<()> {
+    _ = tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().compact().without_time())
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+                tracing_subscriber::EnvFilter::new("info,cranelift_codegen=warn,wasmcloud=trace")
+            }),
+        )
+        .try_init();
+
+    let (nats_server, nats_url, nats_client) = start_nats(None, true)
+        .await
+        .map(|res| (res.0, res.1, res.2.unwrap()))
+        .context("failed to start NATS")?;
+
+    // Build client for interacting with the lattice
+    let ctl_client = wasmcloud_control_interface::ClientBuilder::new(nats_client)
+        .lattice(LATTICE.to_string())
+        .build();
+    // Build the host
+    let _host = WasmCloudTestHost::start(&nats_url, LATTICE)
+        .await
+        .context("failed to start test host")?;
+
+    let link = |sticky_header: &str| {
+        Link::builder()
+            .source_id("component_one")
+            .target("component_two")
+            .name("sticky")
+            .wit_namespace(NAMESPACE)
+            .wit_package(PACKAGE)
+            .interfaces(vec!["test1".into()])
+            .weighted_targets(vec![
+                WeightedTarget::new("component_two", 90),
+                WeightedTarget::new("component_three", 10),
+            ])
+            .sticky_header(sticky_header)
+            .build()
+            .map_err(|e| anyhow::anyhow!(e))
+    };
+
+    // Custom headers of incoming invocations are not propagated, so they are rejected
+    let invalid = ctl_client
+        .put_link(link("x-user-id")?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert!(!invalid.succeeded());
+    assert!(
+        invalid
+            .message()
+            .contains("sticky header `x-user-id` is not supported"),
+        "unexpected message: {}",
+        invalid.message()
+    );
+
+    let valid = ctl_client
+        .put_link(link("traceparent")?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert!(valid.succeeded(), "{}", valid.message());
+    let links = ctl_client
+        .get_links()
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?
+        .into_data()
+        .context("missing links")?;
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].sticky_header(), Some("traceparent"));
+
+    nats_server.stop().await.context("failed to stop NATS")?;
+    Ok(())
+}
+
 const NAMESPACE: &str = "wasi";
 const PACKAGE: &str = "tests";
 